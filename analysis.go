@@ -0,0 +1,244 @@
+package main
+
+import (
+	"cmp"
+	"fmt"
+	"math"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+const (
+	positionTolerance    = 1e-3 // mm, positions closer than this are duplicates
+	orientationTolerance = 1e-4
+	spacingTolerance     = 0.01 // relative deviation from the median slice spacing
+)
+
+// getDatasetFloats returns the values of a decimal string element as floats, nil if missing or not numeric
+func getDatasetFloats(dataset dicom.Dataset, t tag.Tag) []float64 {
+	e, err := dataset.FindElementByTag(t)
+	if err != nil {
+		return nil
+	}
+	values := make([]float64, 0)
+	for _, text := range getValueStrings(e) {
+		value, err := strconv.ParseFloat(strings.TrimSpace(text), 64)
+		if err != nil {
+			return nil
+		}
+		values = append(values, value)
+	}
+	return values
+}
+
+// sliceNormal returns the normal of the slice with the given image orientation, the cross product of its row and
+// column direction
+func sliceNormal(orientation []float64) [3]float64 {
+	return [3]float64{
+		orientation[1]*orientation[5] - orientation[2]*orientation[4],
+		orientation[2]*orientation[3] - orientation[0]*orientation[5],
+		orientation[0]*orientation[4] - orientation[1]*orientation[3],
+	}
+}
+
+func dot(a, b [3]float64) float64 {
+	return a[0]*b[0] + a[1]*b[1] + a[2]*b[2]
+}
+
+type slicePosition struct {
+	filename string
+	distance float64 // position along the slice normal
+}
+
+// groupBySeries returns the series instance UIDs in order of their first file and the files per series
+func groupBySeries(datasetsWithFilename []DatasetEntry) ([]string, map[string][]DatasetEntry) {
+	seriesUIDs := make([]string, 0)
+	entriesBySeries := make(map[string][]DatasetEntry)
+	for _, entry := range datasetsWithFilename {
+		seriesUID := getDatasetValue(entry.dataset, tag.SeriesInstanceUID)
+		if _, ok := entriesBySeries[seriesUID]; !ok {
+			seriesUIDs = append(seriesUIDs, seriesUID)
+		}
+		entriesBySeries[seriesUID] = append(entriesBySeries[seriesUID], entry)
+	}
+	return seriesUIDs, entriesBySeries
+}
+
+// getSeriesTitle returns the headline of a series in reports
+func getSeriesTitle(seriesUID string, entries []DatasetEntry) string {
+	first := entries[0].dataset
+	return fmt.Sprintf("Series %s %s (%s) - %d instances", getDatasetValue(first, tag.SeriesNumber),
+		getDatasetValue(first, tag.SeriesDescription), seriesUID, len(entries))
+}
+
+// notEnoughSlices is the finding of series with less than two positioned slices, which isn't a problem by itself
+const notEnoughSlices = "not enough slices for a spacing check"
+
+// checkSliceGeometry sorts the instances of each series by their position along the slice normal and reports
+// inconsistent orientations, non-uniform spacing, gaps and duplicated slices.
+func checkSliceGeometry(datasetsWithFilename []DatasetEntry) string {
+	seriesUIDs, entriesBySeries := groupBySeries(datasetsWithFilename)
+	lines := make([]string, 0)
+	for _, seriesUID := range seriesUIDs {
+		entries := entriesBySeries[seriesUID]
+		lines = append(lines, getSeriesTitle(seriesUID, entries))
+		for _, finding := range checkSeriesGeometry(entries) {
+			lines = append(lines, "  "+finding)
+		}
+		lines = append(lines, "")
+	}
+	return strings.Join(lines, "\n")
+}
+
+// hasGeometryProblems returns true if the slice geometry of a series with image positions is inconsistent. Series
+// without any position like radiographs are fine.
+func hasGeometryProblems(datasetsWithFilename []DatasetEntry) bool {
+	_, entriesBySeries := groupBySeries(datasetsWithFilename)
+	for _, entries := range entriesBySeries {
+		positioned := slices.ContainsFunc(entries, func(entry DatasetEntry) bool {
+			return len(getDatasetFloats(entry.dataset, tag.ImagePositionPatient)) == 3
+		})
+		if !positioned {
+			continue
+		}
+		for _, finding := range checkSeriesGeometry(entries) {
+			if !strings.HasPrefix(finding, "ok - ") && finding != notEnoughSlices {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+func checkSeriesGeometry(entries []DatasetEntry) []string {
+	findings := make([]string, 0)
+	var orientation []float64
+	var normal [3]float64
+	positions := make([]slicePosition, 0)
+	withoutGeometry := 0
+	for _, entry := range entries {
+		position := getDatasetFloats(entry.dataset, tag.ImagePositionPatient)
+		entryOrientation := getDatasetFloats(entry.dataset, tag.ImageOrientationPatient)
+		if len(position) != 3 || len(entryOrientation) != 6 {
+			withoutGeometry++
+			continue
+		}
+		if orientation == nil {
+			orientation = entryOrientation
+			normal = sliceNormal(orientation)
+		} else if !slices.EqualFunc(orientation, entryOrientation, func(a, b float64) bool { return math.Abs(a-b) < orientationTolerance }) {
+			findings = append(findings, fmt.Sprintf("orientation of %s differs: %v instead of %v", entry.filename, entryOrientation, orientation))
+		}
+		distance := dot([3]float64(position), normal)
+		positions = append(positions, slicePosition{entry.filename, distance})
+	}
+	if withoutGeometry > 0 {
+		findings = append(findings, fmt.Sprintf("%d instances without image position/orientation", withoutGeometry))
+	}
+	if len(positions) < 2 {
+		return append(findings, notEnoughSlices)
+	}
+
+	slices.SortStableFunc(positions, func(a, b slicePosition) int {
+		return cmp.Compare(a.distance, b.distance)
+	})
+	spacings := make([]float64, 0)
+	for i := 1; i < len(positions); i++ {
+		spacing := positions[i].distance - positions[i-1].distance
+		if spacing < positionTolerance {
+			findings = append(findings, fmt.Sprintf("duplicated slice position %.3f: %s and %s", positions[i].distance, positions[i-1].filename, positions[i].filename))
+			continue
+		}
+		spacings = append(spacings, spacing)
+	}
+	if len(spacings) == 0 {
+		return findings
+	}
+
+	sortedSpacings := slices.Clone(spacings)
+	slices.Sort(sortedSpacings)
+	// the lower median, so with a single gap among two spacings the regular one is taken as nominal spacing
+	medianSpacing := sortedSpacings[(len(sortedSpacings)-1)/2]
+	for i := 1; i < len(positions); i++ {
+		spacing := positions[i].distance - positions[i-1].distance
+		if spacing < positionTolerance || math.Abs(spacing-medianSpacing) <= spacingTolerance*medianSpacing {
+			continue
+		}
+		if missing := int(math.Round(spacing/medianSpacing)) - 1; missing > 0 {
+			findings = append(findings, fmt.Sprintf("gap of %.3f mm between %s and %s, %d slices missing", spacing, positions[i-1].filename, positions[i].filename, missing))
+		} else {
+			findings = append(findings, fmt.Sprintf("non-uniform spacing of %.3f mm between %s and %s", spacing, positions[i-1].filename, positions[i].filename))
+		}
+	}
+	if len(findings) == 0 {
+		findings = append(findings, fmt.Sprintf("ok - %d slices with uniform spacing of %.3f mm", len(positions), medianSpacing))
+	}
+	return findings
+}
+
+// plotSeriesGeometry draws the slices of the series as seen along their columns: horizontally the position along the
+// normal of the first slice, vertically along its row direction, so each slice is a line from its first to its last
+// column. Gaps, overlaps and tilted or flipped slices stand out.
+func plotSeriesGeometry(entries []DatasetEntry, columns, rows int) string {
+	var normal, rowDirection [3]float64
+	segments := make([][4]float64, 0) // x and y of the start and end of each slice
+	for _, entry := range entries {
+		position := getDatasetFloats(entry.dataset, tag.ImagePositionPatient)
+		orientation := getDatasetFloats(entry.dataset, tag.ImageOrientationPatient)
+		if len(position) != 3 || len(orientation) != 6 {
+			continue
+		}
+		if len(segments) == 0 {
+			normal, rowDirection = sliceNormal(orientation), [3]float64(orientation[:3])
+		}
+		extent := 0.0 // of the slice along its rows
+		spacing := getDatasetFloats(entry.dataset, tag.PixelSpacing)
+		if count, err := strconv.Atoi(getDatasetValue(entry.dataset, tag.Columns)); err == nil && len(spacing) == 2 {
+			extent = float64(count) * spacing[1]
+		}
+		start := [3]float64(position)
+		end := [3]float64{start[0] + orientation[0]*extent, start[1] + orientation[1]*extent, start[2] + orientation[2]*extent}
+		segments = append(segments, [4]float64{dot(start, normal), dot(start, rowDirection), dot(end, normal), dot(end, rowDirection)})
+	}
+	if len(segments) == 0 {
+		return "no slices with image position/orientation"
+	}
+
+	minX, maxX, minY, maxY := math.Inf(1), math.Inf(-1), math.Inf(1), math.Inf(-1)
+	for _, s := range segments {
+		minX, maxX = min(minX, s[0], s[2]), max(maxX, s[0], s[2])
+		minY, maxY = min(minY, s[1], s[3]), max(maxY, s[1], s[3])
+	}
+	if maxX-minX < positionTolerance {
+		minX, maxX = minX-1, maxX+1
+	}
+	if maxY-minY < positionTolerance {
+		minY, maxY = minY-1, maxY+1
+	}
+	canvas := newBrailleCanvas(columns, rows)
+	toDot := func(x, y float64) (int, int) {
+		return int(math.Round((x - minX) / (maxX - minX) * float64(canvas.width-1))),
+			int(math.Round((maxY - y) / (maxY - minY) * float64(canvas.height-1)))
+	}
+	for _, s := range segments {
+		x0, y0 := toDot(s[0], s[1])
+		x1, y1 := toDot(s[2], s[3])
+		canvas.line(x0, y0, x1, y1)
+	}
+	return fmt.Sprintf("%s\n→ along the slice normal %.1f to %.1f mm, ↑ along the rows %.1f to %.1f mm", canvas, minX, maxX, minY, maxY)
+}
+
+// plotSliceGeometry plots the slice geometry of all series
+func plotSliceGeometry(datasetsWithFilename []DatasetEntry, columns, rows int) string {
+	seriesUIDs, entriesBySeries := groupBySeries(datasetsWithFilename)
+	lines := make([]string, 0)
+	for _, seriesUID := range seriesUIDs {
+		entries := entriesBySeries[seriesUID]
+		lines = append(lines, getSeriesTitle(seriesUID, entries), plotSeriesGeometry(entries, columns, rows), "")
+	}
+	return strings.Join(lines, "\n")
+}
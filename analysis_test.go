@@ -0,0 +1,55 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestCheckSeriesGeometry(t *testing.T) {
+	assert := assert.New(t)
+
+	entries := []DatasetEntry{
+		newInstanceFixture(t, 1).entry("1.dcm"),
+		newInstanceFixture(t, 3).entry("3.dcm"),
+		newInstanceFixture(t, 2).entry("2.dcm"),
+	}
+	assert.Equal([]string{"ok - 3 slices with uniform spacing of 1.250 mm"}, checkSeriesGeometry(entries))
+
+	entries = append(entries, newInstanceFixture(t, 6).entry("6.dcm"), newInstanceFixture(t, 7).entry("7.dcm"), newInstanceFixture(t, 7).entry("7b.dcm"))
+	assert.Equal([]string{
+		"duplicated slice position 8.750: 7.dcm and 7b.dcm",
+		"gap of 3.750 mm between 3.dcm and 6.dcm, 2 slices missing",
+	}, checkSeriesGeometry(entries))
+
+	// one gap among two spacings
+	entries = []DatasetEntry{
+		newInstanceFixture(t, 1).entry("1.dcm"), newInstanceFixture(t, 2).entry("2.dcm"), newInstanceFixture(t, 4).entry("4.dcm"),
+	}
+	assert.Equal([]string{"gap of 2.500 mm between 2.dcm and 4.dcm, 1 slices missing"}, checkSeriesGeometry(entries))
+}
+
+func TestHasGeometryProblems(t *testing.T) {
+	assert := assert.New(t)
+
+	entries := []DatasetEntry{newInstanceFixture(t, 1).entry("1.dcm"), newInstanceFixture(t, 2).entry("2.dcm")}
+	assert.False(hasGeometryProblems(entries))
+	assert.False(hasGeometryProblems(entries[:1]))
+	assert.False(hasGeometryProblems([]DatasetEntry{newFixture(t).with(tag.SeriesInstanceUID, []string{"9.9"}).entry("cr.dcm")}))
+	assert.True(hasGeometryProblems(append(entries, newInstanceFixture(t, 5).entry("5.dcm"))))
+}
+
+func TestPlotSeriesGeometry(t *testing.T) {
+	assert := assert.New(t)
+
+	entries := []DatasetEntry{
+		newInstanceFixture(t, 1).entry("1.dcm"),
+		newInstanceFixture(t, 2).entry("2.dcm"),
+		newInstanceFixture(t, 4).entry("4.dcm"),
+	}
+	assert.Equal("\u2847\u2847\u2800\u28b8\n"+
+		"\u2847\u2847\u2800\u28b8\n"+
+		"→ along the slice normal 1.2 to 5.0 mm, ↑ along the rows 0.0 to 256.0 mm", plotSeriesGeometry(entries, 4, 2))
+	assert.Equal("no slices with image position/orientation", plotSeriesGeometry([]DatasetEntry{newFixture(t).entry("x")}, 4, 2))
+}
@@ -0,0 +1,237 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"math/rand/v2"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// removedTags are the identifying attributes the anonymizer removes, a subset of the basic application level
+// confidentiality profile of PS3.15 E.1
+var removedTags = []tag.Tag{
+	tag.InstitutionName, tag.InstitutionAddress, tag.ReferringPhysicianAddress, tag.StationName,
+	tag.InstitutionalDepartmentName, tag.PhysiciansOfRecord, tag.PerformingPhysicianName,
+	tag.NameOfPhysiciansReadingStudy, tag.OperatorsName, tag.PatientBirthTime, tag.OtherPatientIDs,
+	tag.OtherPatientNames, tag.PatientMotherBirthName, tag.PatientAddress, tag.PatientTelephoneNumbers,
+	tag.MilitaryRank, tag.EthnicGroup, tag.Occupation, tag.AdditionalPatientHistory, tag.PatientComments,
+	tag.DeviceSerialNumber, tag.RequestingPhysician,
+}
+
+// emptiedTags are identifying attributes of type 2 in most IODs, they are kept without value
+var emptiedTags = []tag.Tag{
+	tag.PatientName, tag.PatientID, tag.PatientBirthDate, tag.PatientSex, tag.ReferringPhysicianName,
+	tag.AccessionNumber, tag.StudyID,
+}
+
+// replacedUIDs are the UIDs the anonymizer replaces by new ones
+var replacedUIDs = []tag.Tag{
+	tag.MediaStorageSOPInstanceUID, tag.SOPInstanceUID, tag.StudyInstanceUID, tag.SeriesInstanceUID,
+	tag.FrameOfReferenceUID, tag.ReferencedSOPInstanceUID,
+}
+
+// dateShift moves all dates of a patient by the same number of days, so the intervals between their studies are kept
+// as research de-identification usually requires
+type dateShift struct {
+	days      int            // shift of all patients, e.g. -37
+	maxDays   int            // if set, each patient is shifted by a random 1 to maxDays days into the past instead
+	byPatient map[string]int // random shift by original PatientID
+}
+
+// newDateShift returns the shift by the given days or by random days up to maxDays per patient, nil if both are 0
+func newDateShift(days, maxDays int) (*dateShift, error) {
+	switch {
+	case days != 0 && maxDays != 0:
+		return nil, errors.New("dates are shifted either by fixed or by random days")
+	case maxDays < 0:
+		return nil, errors.New("the maximal random shift has to be positive")
+	case days == 0 && maxDays == 0:
+		return nil, nil
+	}
+	return &dateShift{days: days, maxDays: maxDays, byPatient: map[string]int{}}, nil
+}
+
+// patientDays returns the days the dates of the patient are shifted by
+func (s *dateShift) patientDays(patientID string) int {
+	if s.maxDays == 0 {
+		return s.days
+	}
+	days, ok := s.byPatient[patientID]
+	if !ok {
+		days = -1 - rand.IntN(s.maxDays)
+		s.byPatient[patientID] = days
+	}
+	return days
+}
+
+// shiftDate moves the date of a DA or DT value by the given days, the time of a DT value is kept. Dates of DT values
+// with year or month precision are shifted as their first day. Returns false if the value is no valid date.
+func shiftDate(vr, value string, days int) (string, bool) {
+	value = strings.TrimSpace(value)
+	if vr == "DA" {
+		value = strings.ReplaceAll(value, ".", "") // YYYY.MM.DD of ACR-NEMA
+	}
+	if value == "" {
+		return value, true
+	}
+	length := min(len(value)-len(strings.TrimLeft(value, "0123456789")), 8)
+	if (length != 4 && length != 6 && length != 8) || (vr == "DA" && length != len(value)) {
+		return "", false
+	}
+	date, err := time.Parse("20060102", value[:length]+"0101"[:8-length])
+	if err != nil {
+		return "", false
+	}
+	return date.AddDate(0, 0, days).Format("20060102")[:length] + value[length:], true
+}
+
+// anonymizer removes identifying attributes and replaces UIDs by the same new UID in all files it anonymizes, so
+// studies, series and references between the files are kept
+type anonymizer struct {
+	uids  map[string]string // new UID by original one
+	dates *dateShift        // dates are kept if nil
+}
+
+func newAnonymizer() *anonymizer {
+	return &anonymizer{uids: map[string]string{}}
+}
+
+// anonymize removes or empties the identifying attributes and private elements, also in sequence items, replaces the
+// UIDs, shifts the dates if a date shift is set and records the method in DeidentificationMethod
+func (a *anonymizer) anonymize(dataset *dicom.Dataset) error {
+	days := 0
+	if a.dates != nil {
+		days = a.dates.patientDays(getDatasetValue(*dataset, tag.PatientID))
+	}
+	elements, err := a.anonymizeElements(dataset.Elements, days)
+	if err != nil {
+		return err
+	}
+	dataset.Elements = elements
+	// PatientIdentityRemoved isn't set, only a subset of the basic profile is applied and e.g. free text or burned in
+	// annotations may still identify the patient
+	description := "dcmtagger subset of basic profile"
+	if days != 0 {
+		description += ", dates shifted"
+		temporalModified, err := newQueryElement(tag.LongitudinalTemporalInformationModified, "MODIFIED")
+		if err != nil {
+			return err
+		}
+		setElement(dataset, temporalModified)
+	}
+	method, err := newQueryElement(tag.DeidentificationMethod, description)
+	if err != nil {
+		return err
+	}
+	setElement(dataset, method)
+	return nil
+}
+
+// anonymizeElements anonymizes the elements and those of their sequence items, dates are shifted by the given days
+func (a *anonymizer) anonymizeElements(elements []*dicom.Element, days int) ([]*dicom.Element, error) {
+	anonymized := make([]*dicom.Element, 0, len(elements))
+	for _, e := range elements {
+		switch {
+		case e.Tag.Group%2 == 1 || slices.Contains(removedTags, e.Tag):
+			continue
+		case slices.Contains(emptiedTags, e.Tag):
+			e.Value, _ = dicom.NewValue([]string{})
+		case days != 0 && (e.RawValueRepresentation == "DA" || e.RawValueRepresentation == "DT"):
+			dates := make([]string, 0)
+			for _, value := range getValueStrings(e) {
+				if date, ok := shiftDate(e.RawValueRepresentation, value, days); ok {
+					dates = append(dates, date)
+				}
+			}
+			e.Value, _ = dicom.NewValue(dates) // invalid dates are removed rather than leaked
+		case slices.Contains(replacedUIDs, e.Tag):
+			uids := make([]string, 0)
+			for _, uid := range getValueStrings(e) {
+				if _, ok := a.uids[uid]; !ok {
+					a.uids[uid] = newUID()
+				}
+				uids = append(uids, a.uids[uid])
+			}
+			e.Value, _ = dicom.NewValue(uids)
+		case isSequence(e):
+			items := make([][]*dicom.Element, 0)
+			for _, item := range getSequenceItems(e) {
+				anonymizedItem, err := a.anonymizeElements(item, days)
+				if err != nil {
+					return nil, err
+				}
+				items = append(items, anonymizedItem)
+			}
+			if err := setSequenceItems(e, items); err != nil {
+				return nil, err
+			}
+		}
+		anonymized = append(anonymized, e)
+	}
+	return anonymized, nil
+}
+
+// anonymizeFiles writes an anonymized copy of each file of the given file or directory into the output directory,
+// shifting the dates if dates isn't nil. 'written' is called with the paths of each original and its copy.
+func anonymizeFiles(ctx context.Context, input, output string, dates *dateShift,
+	written func(path, anonymizedPath string) error) error {
+	if err := checkOutputDirectory(input, output); err != nil {
+		return err
+	}
+	if err := os.MkdirAll(output, 0o755); err != nil {
+		return err
+	}
+	a := newAnonymizer()
+	a.dates = dates
+	return walkDicomFiles(ctx, input, elementSelection{}, nil, nil, func(entry DatasetEntry) error {
+		if err := a.anonymize(&entry.dataset); err != nil {
+			return err
+		}
+		anonymizedPath := filepath.Join(output, entry.filename)
+		if err := writeDatasetToFile(entry.dataset, anonymizedPath); err != nil {
+			return err
+		}
+		return written(entry.path, anonymizedPath)
+	})
+}
+
+// checkOutputDirectory refuses an output directory that resolves to the input directory or to the directory of the
+// input file, the anonymized copies would overwrite the originals
+func checkOutputDirectory(input, output string) error {
+	inputDir, err := resolvePath(input)
+	if err != nil {
+		return err
+	}
+	if info, err := os.Stat(inputDir); err == nil && !info.IsDir() {
+		inputDir = filepath.Dir(inputDir)
+	}
+	outputDir, err := resolvePath(output)
+	if err != nil {
+		return err
+	}
+	if inputDir == outputDir {
+		return fmt.Errorf("output directory %s is the input directory, the originals would be overwritten", output)
+	}
+	return nil
+}
+
+// resolvePath returns the absolute path with symbolic links resolved, a path that doesn't exist yet is only made
+// absolute
+func resolvePath(path string) (string, error) {
+	absolute, err := filepath.Abs(path)
+	if err != nil {
+		return "", err
+	}
+	if resolved, err := filepath.EvalSymlinks(absolute); err == nil {
+		return resolved, nil
+	}
+	return absolute, nil
+}
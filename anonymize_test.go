@@ -0,0 +1,135 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestAnonymize(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newInstanceFixture(t, 1).
+		with(tag.InstitutionName, []string{"General Hospital"}).
+		withPrivate(0x0009, 0x0010, "LO", []string{"VENDOR"})
+	f.withSequence(tag.ReferencedImageSequence, []*dicom.Element{
+		f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.3.4.5.2"}),
+		f.element(tag.OperatorsName, []string{"Smith"}),
+	})
+	first, second := f.dataset(), newInstanceFixture(t, 2).dataset()
+	studyDate := getDatasetValue(first, tag.StudyDate)
+	a := newAnonymizer()
+	assert.NoError(a.anonymize(&first))
+	assert.NoError(a.anonymize(&second))
+
+	assert.Equal("", getDatasetValue(first, tag.PatientName))
+	assert.Equal("", getDatasetValue(first, tag.PatientID))
+	_, err := first.FindElementByTag(tag.InstitutionName)
+	assert.Error(err)
+	_, err = first.FindElementByTag(tag.Tag{Group: 0x0009, Element: 0x0010})
+	assert.Error(err)
+	_, err = first.FindElementByTag(tag.PatientIdentityRemoved)
+	assert.Error(err)
+	assert.Equal("CT", getDatasetValue(first, tag.Modality))
+	assert.Equal(studyDate, getDatasetValue(first, tag.StudyDate))
+	assert.Equal("dcmtagger subset of basic profile", getDatasetValue(first, tag.DeidentificationMethod))
+
+	// UIDs are replaced by the same new ones in all files, also in references
+	assert.NotEqual("1.2.3.4", getDatasetValue(first, tag.StudyInstanceUID))
+	assert.Equal(getDatasetValue(first, tag.StudyInstanceUID), getDatasetValue(second, tag.StudyInstanceUID))
+	sequence, err := first.FindElementByTag(tag.ReferencedImageSequence)
+	assert.NoError(err)
+	item := getSequenceItems(sequence)[0]
+	assert.Len(item, 1)
+	assert.Equal(getDatasetValue(second, tag.SOPInstanceUID), getValueString(item[0]))
+}
+
+func TestAnonymizeShiftsDates(t *testing.T) {
+	assert := assert.New(t)
+
+	patient := func(id, studyDate string) dicom.Dataset {
+		f := newFixture(t).
+			with(tag.PatientID, []string{id}).
+			with(tag.StudyDate, []string{studyDate}).
+			with(tag.AcquisitionDateTime, []string{"20240301101500.5+0100"})
+		return f.withSequence(tag.ReferencedStudySequence, []*dicom.Element{
+			f.element(tag.InstanceCreationDate, []string{"20240229"}),
+		}).dataset()
+	}
+	dates, err := newDateShift(0, 365)
+	assert.NoError(err)
+	a := newAnonymizer()
+	a.dates = dates
+	first, second, other := patient("A", "20240301"), patient("A", "20240311"), patient("B", "20240301")
+	for _, dataset := range []*dicom.Dataset{&first, &second, &other} {
+		assert.NoError(a.anonymize(dataset))
+	}
+	days := dates.byPatient["A"]
+	assert.Negative(days)
+	assert.GreaterOrEqual(days, -365)
+	assert.Len(dates.byPatient, 2)
+
+	parse := func(dataset dicom.Dataset) time.Time {
+		date, err := time.Parse("20060102", getDatasetValue(dataset, tag.StudyDate))
+		assert.NoError(err)
+		return date
+	}
+	assert.Equal(time.Date(2024, 3, 1, 0, 0, 0, 0, time.UTC).AddDate(0, 0, days), parse(first))
+	assert.Equal(10*24*time.Hour, parse(second).Sub(parse(first)))
+	assert.Equal(parse(first).Format("20060102")+"101500.5+0100", getDatasetValue(first, tag.AcquisitionDateTime))
+	sequence, err := first.FindElementByTag(tag.ReferencedStudySequence)
+	assert.NoError(err)
+	assert.Equal(parse(first).AddDate(0, 0, -1).Format("20060102"), getValueString(getSequenceItems(sequence)[0][0]))
+	assert.Equal("MODIFIED", getDatasetValue(first, tag.LongitudinalTemporalInformationModified))
+	assert.Equal("dcmtagger subset of basic profile, dates shifted", getDatasetValue(first, tag.DeidentificationMethod))
+
+	_, err = newDateShift(-37, 10)
+	assert.Error(err)
+	dates, err = newDateShift(0, 0)
+	assert.NoError(err)
+	assert.Nil(dates)
+}
+
+func TestShiftDate(t *testing.T) {
+	assert := assert.New(t)
+
+	for _, test := range []struct {
+		vr, value, shifted string
+		ok                 bool
+	}{
+		{"DA", "20240301", "20240123", true},
+		{"DA", "2024.03.01", "20240123", true},
+		{"DA", "", "", true},
+		{"DT", "20240301120000", "20240123120000", true},
+		{"DT", "202403", "202401", true},
+		{"DT", "2024", "2023", true},
+		{"DA", "20241301", "", false},
+		{"DA", "2024030", "", false},
+		{"DA", "20240301120000", "", false},
+	} {
+		shifted, ok := shiftDate(test.vr, test.value, -38)
+		assert.Equal(test.ok, ok, test.value)
+		assert.Equal(test.shifted, shifted, test.value)
+	}
+}
+
+func TestCheckOutputDirectory(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	file := filepath.Join(dir, "1.dcm")
+	assert.NoError(os.WriteFile(file, nil, 0o644))
+	link := filepath.Join(t.TempDir(), "link")
+	assert.NoError(os.Symlink(dir, link))
+
+	assert.Error(checkOutputDirectory(dir, dir))
+	assert.Error(checkOutputDirectory(dir, dir+string(filepath.Separator)))
+	assert.Error(checkOutputDirectory(file, dir))
+	assert.Error(checkOutputDirectory(dir, link))
+	assert.NoError(checkOutputDirectory(dir, filepath.Join(dir, "anonymized")))
+}
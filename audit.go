@@ -0,0 +1,56 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/suyashkumar/dicom"
+)
+
+// auditLog appends a line for every operation modifying data to a log file, for traceability of changes. An empty
+// path disables the log.
+type auditLog struct {
+	path string
+	now  func() time.Time
+}
+
+func auditLogPath() string {
+	dir, err := os.UserConfigDir()
+	if err != nil {
+		return ""
+	}
+	return filepath.Join(dir, "dcmtagger", "audit.log")
+}
+
+func newAuditLog(path string) *auditLog {
+	return &auditLog{path: path, now: time.Now}
+}
+
+// record appends the operation with timestamp, affected file and details as tab separated line
+func (a *auditLog) record(operation, file, details string) error {
+	if a.path == "" {
+		return nil
+	}
+	if err := os.MkdirAll(filepath.Dir(a.path), 0o755); err != nil {
+		return err
+	}
+	f, err := os.OpenFile(a.path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0o644)
+	if err != nil {
+		return err
+	}
+	line := strings.Join([]string{a.now().Format(time.RFC3339), operation, file, details}, "\t")
+	if _, err := fmt.Fprintln(f, line); err != nil {
+		f.Close()
+		return err
+	}
+	return f.Close()
+}
+
+// recordEdit logs the change of an element value
+func (a *auditLog) recordEdit(operation, file string, e *dicom.Element, oldValue string) error {
+	details := fmt.Sprintf("(%04x,%04x) %s: '%s' -> '%s'", e.Tag.Group, e.Tag.Element, getTagName(e), oldValue, getValueString(e))
+	return a.record(operation, file, details)
+}
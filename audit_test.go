@@ -0,0 +1,28 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestAuditLog(t *testing.T) {
+	assert := assert.New(t)
+
+	path := filepath.Join(t.TempDir(), "logs", "audit.log")
+	audit := newAuditLog(path)
+	audit.now = func() time.Time { return time.Date(2024, 3, 4, 10, 11, 12, 0, time.UTC) }
+
+	e := newFixture(t).element(tag.Modality, []string{"MR"})
+	assert.NoError(audit.record("save", "copy.dcm", "from 1.dcm"))
+	assert.NoError(audit.recordEdit("edit", "1.dcm", e, "CT"))
+
+	data, err := os.ReadFile(path)
+	assert.NoError(err)
+	assert.Equal("2024-03-04T10:11:12Z\tsave\tcopy.dcm\tfrom 1.dcm\n"+
+		"2024-03-04T10:11:12Z\tedit\t1.dcm\t(0008,0060) Modality: 'CT' -> 'MR'\n", string(data))
+}
@@ -0,0 +1,96 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/rivo/tview"
+)
+
+var benchmarkElementCounts = []int{1_000, 10_000, 100_000}
+
+const benchmarkFileCount = 10
+
+func benchmarkName(elementCount int) string {
+	return fmt.Sprintf("%dk", elementCount/1000)
+}
+
+func BenchmarkParseDicomFiles(b *testing.B) {
+	data, err := os.ReadFile("testdata/test.dcm")
+	if err != nil {
+		b.Fatal(err)
+	}
+	for _, fileCount := range []int{1, 10, 100} {
+		dir := b.TempDir()
+		for i := 0; i < fileCount; i++ {
+			if err := os.WriteFile(filepath.Join(dir, fmt.Sprintf("file%03d.dcm", i)), data, 0o644); err != nil {
+				b.Fatal(err)
+			}
+		}
+		b.Run(fmt.Sprintf("%dfiles", fileCount), func(b *testing.B) {
+			for i := 0; i < b.N; i++ {
+				if _, _, err := parseDicomFiles(context.Background(), dir, elementSelection{}, nil); err != nil {
+					b.Fatal(err)
+				}
+			}
+		})
+	}
+}
+
+func BenchmarkSortTreeByFilename(b *testing.B) {
+	for _, elementCount := range benchmarkElementCounts {
+		datasets := newSyntheticDatasets(b, benchmarkFileCount, elementCount)
+		b.Run(benchmarkName(elementCount), func(b *testing.B) {
+			for i := 0; i < b.N; i++ {
+				sortTreeByFilename("root", tview.NewTreeView(), datasets)
+			}
+		})
+	}
+}
+
+func BenchmarkSortTreeByTags(b *testing.B) {
+	for _, minDiffValuesPerTag := range []int{0, 1} {
+		for _, elementCount := range benchmarkElementCounts {
+			datasets := newSyntheticDatasets(b, benchmarkFileCount, elementCount)
+			b.Run(fmt.Sprintf("minDiff%d/%s", minDiffValuesPerTag, benchmarkName(elementCount)), func(b *testing.B) {
+				for i := 0; i < b.N; i++ {
+					sortTreeByTags("root", tview.NewTreeView(), datasets, minDiffValuesPerTag)
+				}
+			})
+		}
+	}
+}
+
+func BenchmarkCollectAllVisible(b *testing.B) {
+	for _, elementCount := range benchmarkElementCounts {
+		tree, root := sortTreeByFilename("root", tview.NewTreeView(), newSyntheticDatasets(b, benchmarkFileCount, elementCount))
+		root.ExpandAll()
+		b.Run(benchmarkName(elementCount), func(b *testing.B) {
+			for i := 0; i < b.N; i++ {
+				collectAllVisible(tree)
+			}
+		})
+	}
+}
+
+func BenchmarkFindNodeRecursive(b *testing.B) {
+	for _, elementCount := range benchmarkElementCounts {
+		tree, _ := sortTreeByTags("root", tview.NewTreeView(), newSyntheticDatasets(b, benchmarkFileCount, elementCount), 0)
+		b.Run(benchmarkName(elementCount), func(b *testing.B) {
+			for i := 0; i < b.N; i++ {
+				findNodeRecursive(tree, nil, "of file 3")
+			}
+		})
+	}
+}
+
+func BenchmarkGetValueStringLargeBinary(b *testing.B) {
+	e := newBinaryElement(b, 0x0029, 0x1010, 16*1024*1024)
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		getValueString(e)
+	}
+}
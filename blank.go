@@ -0,0 +1,96 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// blankRegion is a rectangle of pixels zeroed by :blank, e.g. a burned-in patient name, in pixels from the top left
+type blankRegion struct {
+	X      int `json:"x"`
+	Y      int `json:"y"`
+	Width  int `json:"width"`
+	Height int `json:"height"`
+}
+
+func (r blankRegion) String() string {
+	return fmt.Sprintf("%d,%d,%d,%d", r.X, r.Y, r.Width, r.Height)
+}
+
+// parseBlankRegions parses regions given as x,y,width,height separated by spaces
+func parseBlankRegions(text string) ([]blankRegion, error) {
+	regions := make([]blankRegion, 0)
+	for _, field := range strings.Fields(text) {
+		parts := strings.Split(field, ",")
+		numbers := make([]int, 0, len(parts))
+		for _, part := range parts {
+			if number, err := strconv.Atoi(part); err == nil && number >= 0 {
+				numbers = append(numbers, number)
+			}
+		}
+		if len(numbers) != 4 || len(parts) != 4 || numbers[2] == 0 || numbers[3] == 0 {
+			return nil, fmt.Errorf("invalid region '%s', expected x,y,width,height", field)
+		}
+		regions = append(regions, blankRegion{numbers[0], numbers[1], numbers[2], numbers[3]})
+	}
+	return regions, nil
+}
+
+// blankPixels zeroes the regions in all frames of the dataset and sets BurnedInAnnotation to NO, it returns the
+// number of pixels zeroed in all frames. Only uncompressed pixel data is supported so far.
+func blankPixels(dataset *dicom.Dataset, regions []blankRegion) (int, error) {
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	if err != nil {
+		return 0, errors.New("no pixel data")
+	}
+	if err := loadLazyValue(e); err != nil {
+		return 0, err
+	}
+	info, ok := e.Value.GetValue().(dicom.PixelDataInfo)
+	if !ok || info.IsEncapsulated {
+		return 0, errors.New("compressed pixel data can't be blanked yet, only uncompressed transfer syntaxes")
+	}
+	blanked := 0
+	for _, f := range info.Frames {
+		native := f.NativeData
+		for _, r := range regions {
+			for y := r.Y; y < min(r.Y+r.Height, native.Rows); y++ {
+				for x := r.X; x < min(r.X+r.Width, native.Cols); x++ {
+					if index := y*native.Cols + x; index < len(native.Data) {
+						clear(native.Data[index])
+						blanked++
+					}
+				}
+			}
+		}
+	}
+	burnedIn, err := newQueryElement(tag.BurnedInAnnotation, "NO")
+	if err != nil {
+		return 0, err
+	}
+	setElement(dataset, burnedIn)
+	return blanked, nil
+}
+
+// blankSeries blanks the regions in all files of the series of the given file, it returns the blanked files
+func blankSeries(datasetsWithFilename []DatasetEntry, entry *DatasetEntry, regions []blankRegion) ([]*DatasetEntry, error) {
+	seriesUID := getDatasetValue(entry.dataset, tag.SeriesInstanceUID)
+	blanked := make([]*DatasetEntry, 0)
+	for i := range datasetsWithFilename {
+		other := &datasetsWithFilename[i]
+		if pickKey(other) != pickKey(entry) &&
+			(seriesUID == "" || getDatasetValue(other.dataset, tag.SeriesInstanceUID) != seriesUID) {
+			continue
+		}
+		if _, err := blankPixels(&other.dataset, regions); err != nil {
+			return blanked, fmt.Errorf("%s: %w", other.filename, err)
+		}
+		blanked = append(blanked, other)
+	}
+	return blanked, nil
+}
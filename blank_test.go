@@ -0,0 +1,68 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestParseBlankRegions(t *testing.T) {
+	assert := assert.New(t)
+
+	regions, err := parseBlankRegions(" 0,0,512,40  10,480,100,32")
+	assert.NoError(err)
+	assert.Equal([]blankRegion{{0, 0, 512, 40}, {10, 480, 100, 32}}, regions)
+	assert.Equal("10,480,100,32", regions[1].String())
+
+	regions, err = parseBlankRegions("")
+	assert.NoError(err)
+	assert.Empty(regions)
+	for _, invalid := range []string{"0,0,512", "0,0,0,40", "-1,0,5,5", "a,0,5,5", "0,0,5,5,5"} {
+		_, err = parseBlankRegions(invalid)
+		assert.Error(err, invalid)
+	}
+}
+
+// newImageFixture returns a series instance with a 4x3 frame of pixels numbered from 1
+func newImageFixture(t *testing.T, instanceNumber int) *fixture {
+	data := make([][]int, 0)
+	for i := range 12 {
+		data = append(data, []int{i + 1})
+	}
+	return newInstanceFixture(t, instanceNumber).
+		with(tag.PixelData, dicom.PixelDataInfo{Frames: []frame.Frame{{
+			NativeData: frame.NativeFrame{Data: data, Rows: 3, Cols: 4, BitsPerSample: 16},
+		}}})
+}
+
+func TestBlankSeries(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newImageFixture(t, 1).entry("1.dcm"),
+		newImageFixture(t, 2).entry("2.dcm"),
+		newFixture(t).with(tag.SeriesInstanceUID, []string{"9.9"}).entry("other.dcm"),
+	}
+	blanked, err := blankSeries(datasets, &datasets[1], []blankRegion{{X: 2, Y: 1, Width: 10, Height: 1}})
+	assert.NoError(err)
+	assert.Len(blanked, 2)
+	for _, entry := range blanked {
+		e, err := entry.dataset.FindElementByTag(tag.PixelData)
+		assert.NoError(err)
+		pixels := make([]int, 0)
+		for _, pixel := range e.Value.GetValue().(dicom.PixelDataInfo).Frames[0].NativeData.Data {
+			pixels = append(pixels, pixel[0])
+		}
+		assert.Equal([]int{1, 2, 3, 4, 5, 6, 0, 0, 9, 10, 11, 12}, pixels)
+		assert.Equal("NO", getDatasetValue(entry.dataset, tag.BurnedInAnnotation))
+	}
+
+	_, err = blankPixels(&datasets[2].dataset, []blankRegion{{0, 0, 1, 1}})
+	assert.Error(err)
+	encapsulated := newInstanceFixture(t, 3).with(tag.PixelData, dicom.PixelDataInfo{IsEncapsulated: true}).dataset()
+	_, err = blankPixels(&encapsulated, []blankRegion{{0, 0, 1, 1}})
+	assert.ErrorContains(err, "compressed")
+}
@@ -0,0 +1,62 @@
+package main
+
+import (
+	"math"
+	"strings"
+)
+
+// brailleDots are the bits of the braille pattern dots by column and row within a character cell
+var brailleDots = [2][4]rune{{0x01, 0x02, 0x04, 0x40}, {0x08, 0x10, 0x20, 0x80}}
+
+// brailleCanvas draws with braille characters, each character cell holds 2x4 dots
+type brailleCanvas struct {
+	width, height int // in dots
+	cells         [][]rune
+}
+
+func newBrailleCanvas(columns, rows int) *brailleCanvas {
+	cells := make([][]rune, rows)
+	for i := range cells {
+		cells[i] = make([]rune, columns)
+	}
+	return &brailleCanvas{width: 2 * columns, height: 4 * rows, cells: cells}
+}
+
+// set sets the dot, dots outside the canvas are ignored. y grows downwards.
+func (c *brailleCanvas) set(x, y int) {
+	if x < 0 || y < 0 || x >= c.width || y >= c.height {
+		return
+	}
+	c.cells[y/4][x/2] |= brailleDots[x%2][y%4]
+}
+
+// line draws a line between the dots
+func (c *brailleCanvas) line(x0, y0, x1, y1 int) {
+	steps := max(abs(x1-x0), abs(y1-y0))
+	for i := 0; i <= steps; i++ {
+		t := 0.0
+		if steps > 0 {
+			t = float64(i) / float64(steps)
+		}
+		c.set(int(math.Round(float64(x0)+t*float64(x1-x0))), int(math.Round(float64(y0)+t*float64(y1-y0))))
+	}
+}
+
+func (c *brailleCanvas) String() string {
+	lines := make([]string, 0, len(c.cells))
+	for _, row := range c.cells {
+		var line strings.Builder
+		for _, dots := range row {
+			line.WriteRune(0x2800 + dots)
+		}
+		lines = append(lines, line.String())
+	}
+	return strings.Join(lines, "\n")
+}
+
+func abs(v int) int {
+	if v < 0 {
+		return -v
+	}
+	return v
+}
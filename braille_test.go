@@ -0,0 +1,21 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestBrailleCanvas(t *testing.T) {
+	assert := assert.New(t)
+
+	canvas := newBrailleCanvas(2, 1)
+	canvas.line(0, 0, 0, 3)
+	canvas.set(3, 3)
+	canvas.set(4, 0) // outside
+	assert.Equal("⡇⢀", canvas.String())
+
+	canvas = newBrailleCanvas(2, 2)
+	canvas.line(0, 0, 3, 7)
+	assert.Equal("\u28a3\u2800\n\u2800\u28a3", canvas.String())
+}
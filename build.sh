@@ -1,2 +1 @@
-go build -ldflags "-X main.version=`git describe --tags`"
-
+go build -ldflags "-X main.version=`git describe --tags`" && ./dcmtagger man > dcmtagger.1
@@ -0,0 +1,132 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// checksumEntry is a line of a manifest in the format of sha256sum, so manifests can also be checked with
+// 'sha256sum -c'
+type checksumEntry struct {
+	sum  string
+	name string // path relative to the directory of the files
+}
+
+func sha256File(path string) (string, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return "", err
+	}
+	defer file.Close()
+	hash := sha256.New()
+	if _, err := io.Copy(hash, file); err != nil {
+		return "", err
+	}
+	return hex.EncodeToString(hash.Sum(nil)), nil
+}
+
+// newManifest computes the checksums of the files, named relative to dir
+func newManifest(dir string, paths []string) ([]checksumEntry, error) {
+	entries := make([]checksumEntry, 0, len(paths))
+	for _, path := range paths {
+		sum, err := sha256File(path)
+		if err != nil {
+			return nil, err
+		}
+		name, err := filepath.Rel(dir, path)
+		if err != nil {
+			name = path
+		}
+		entries = append(entries, checksumEntry{sum, filepath.ToSlash(name)})
+	}
+	return entries, nil
+}
+
+// manifestOfEntries computes the checksums of the files of the entries, named by their filename
+func manifestOfEntries(ctx context.Context, datasetsWithFilename []DatasetEntry, progress func(done, total int)) ([]checksumEntry, error) {
+	entries := make([]checksumEntry, 0, len(datasetsWithFilename))
+	for i, entry := range datasetsWithFilename {
+		if err := ctx.Err(); err != nil {
+			return nil, err
+		}
+		if progress != nil {
+			progress(i, len(datasetsWithFilename))
+		}
+		sum, err := sha256File(entry.path)
+		if err != nil {
+			return nil, err
+		}
+		entries = append(entries, checksumEntry{sum, entry.filename})
+	}
+	return entries, nil
+}
+
+func formatManifest(entries []checksumEntry) string {
+	var b strings.Builder
+	for _, entry := range entries {
+		fmt.Fprintf(&b, "%s  %s\n", entry.sum, entry.name)
+	}
+	return b.String()
+}
+
+func writeManifest(filename string, entries []checksumEntry) error {
+	return os.WriteFile(filename, []byte(formatManifest(entries)), 0o644)
+}
+
+// readManifest reads a manifest written by writeManifest or sha256sum, also in its binary mode marking names with '*'
+func readManifest(filename string) ([]checksumEntry, error) {
+	file, err := os.Open(filename)
+	if err != nil {
+		return nil, err
+	}
+	defer file.Close()
+	entries := make([]checksumEntry, 0)
+	scanner := bufio.NewScanner(file)
+	for line := 1; scanner.Scan(); line++ {
+		text := strings.TrimRight(scanner.Text(), "\r")
+		if text == "" {
+			continue
+		}
+		sum, name, ok := strings.Cut(text, " ")
+		if _, err := hex.DecodeString(sum); !ok || err != nil || len(sum) != 2*sha256.Size || len(name) < 2 {
+			return nil, fmt.Errorf("%s:%d: invalid checksum line", filename, line)
+		}
+		entries = append(entries, checksumEntry{strings.ToLower(sum), name[1:]})
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	if len(entries) == 0 {
+		return nil, errors.New("no checksums in " + filename)
+	}
+	return entries, nil
+}
+
+// verifyManifest checks the files of the manifest in dir, it returns a line per file with OK, FAILED or MISSING like
+// 'sha256sum -c' and true if any file isn't OK
+func verifyManifest(dir string, entries []checksumEntry) ([]string, bool) {
+	lines := make([]string, 0, len(entries))
+	failed := false
+	for _, entry := range entries {
+		result := "OK"
+		sum, err := sha256File(filepath.Join(dir, filepath.FromSlash(entry.name)))
+		if errors.Is(err, os.ErrNotExist) {
+			result = "MISSING"
+		} else if err != nil {
+			result = "FAILED " + err.Error()
+		} else if sum != entry.sum {
+			result = "FAILED"
+		}
+		failed = failed || result != "OK"
+		lines = append(lines, entry.name+": "+result)
+	}
+	return lines, failed
+}
@@ -0,0 +1,66 @@
+package main
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestManifest(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	assert.NoError(os.WriteFile(filepath.Join(dir, "a.dcm"), []byte("abc"), 0o644))
+	assert.NoError(os.WriteFile(filepath.Join(dir, "b.dcm"), []byte(""), 0o644))
+	entries, err := newManifest(dir, []string{filepath.Join(dir, "a.dcm"), filepath.Join(dir, "b.dcm")})
+	assert.NoError(err)
+	assert.Equal("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  a.dcm\n"+
+		"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  b.dcm\n", formatManifest(entries))
+
+	manifest := filepath.Join(t.TempDir(), "SHA256SUMS")
+	assert.NoError(writeManifest(manifest, entries))
+	read, err := readManifest(manifest)
+	assert.NoError(err)
+	assert.Equal(entries, read)
+	lines, failed := verifyManifest(dir, read)
+	assert.False(failed)
+	assert.Equal([]string{"a.dcm: OK", "b.dcm: OK"}, lines)
+
+	assert.NoError(os.WriteFile(filepath.Join(dir, "a.dcm"), []byte("abd"), 0o644))
+	assert.NoError(os.Remove(filepath.Join(dir, "b.dcm")))
+	lines, failed = verifyManifest(dir, read)
+	assert.True(failed)
+	assert.Equal([]string{"a.dcm: FAILED", "b.dcm: MISSING"}, lines)
+
+	assert.NoError(os.WriteFile(manifest, []byte("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad *a.dcm\n"), 0o644))
+	read, err = readManifest(manifest)
+	assert.NoError(err)
+	assert.Equal([]checksumEntry{{"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", "a.dcm"}}, read)
+	assert.NoError(os.WriteFile(manifest, []byte("abc  a.dcm\n"), 0o644))
+	_, err = readManifest(manifest)
+	assert.Error(err)
+}
+
+func TestManifestOfEntries(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	path := filepath.Join(dir, "a.dcm")
+	assert.NoError(os.WriteFile(path, []byte("abc"), 0o644))
+	datasets := []DatasetEntry{{filename: "a.dcm", path: path}}
+	progress := make([]int, 0)
+	entries, err := manifestOfEntries(context.Background(), datasets, func(done, total int) {
+		progress = append(progress, done, total)
+	})
+	assert.NoError(err)
+	assert.Equal([]checksumEntry{{"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad", "a.dcm"}}, entries)
+	assert.Equal([]int{0, 1}, progress)
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	_, err = manifestOfEntries(ctx, datasets, nil)
+	assert.ErrorIs(err, context.Canceled)
+}
@@ -0,0 +1,490 @@
+package main
+
+import (
+	"cmp"
+	"context"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+
+	"github.com/alexflint/go-arg"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// args are the subcommands with the options shared by all of them. Without subcommand 'browse' is run, see
+// withDefaultCommand.
+type args struct {
+	Browse      *browseCmd      `arg:"subcommand:browse" help:"Show the files in the UI, the default without command"`
+	Dump        *dumpCmd        `arg:"subcommand:dump" help:"Write the elements of all files to stdout"`
+	Diff        *diffCmd        `arg:"subcommand:diff" help:"Write a report of the elements differing between the files to stdout"`
+	Anonymize   *anonymizeCmd   `arg:"subcommand:anonymize" help:"Write copies of the files without identifying attributes"`
+	Send        *sendCmd        `arg:"subcommand:send" help:"Send the files to a remote AE"`
+	Query       *queryCmd       `arg:"subcommand:query" help:"Query a modality worklist or the index and print the matches"`
+	Validate    *validateCmd    `arg:"subcommand:validate" help:"Check the files for missing required attributes and the slice geometry"`
+	Checksum    *checksumCmd    `arg:"subcommand:checksum" help:"Print a SHA-256 manifest of the files or verify them against one"`
+	UpdateDict  *updateDictCmd  `arg:"subcommand:update-dict" help:"Download the dictionary of the DICOM standard, so elements added since the release resolve to names"`
+	Completions *completionsCmd `arg:"subcommand:completions" help:"Print the completion script of a shell"`
+	Man         *manCmd         `arg:"subcommand:man" help:"Print the man page"`
+	CommonOptions
+}
+
+func (args) Version() string { return "Version " + version }
+
+// CommonOptions are the options of all subcommands
+type CommonOptions struct {
+	Config   string `arg:"--config" help:"Config file [default: config.json in the user config directory]"`
+	AuditLog string `arg:"--audit-log" help:"File all modifications are logged to [default: audit.log in the user config directory]"`
+	Verbose  int    `arg:"-v,--verbose" help:"Log more, -v informational and -vv debug messages, shown with tl"`
+	LogFile  string `arg:"--log-file" help:"Append log messages to the given file"`
+}
+
+// ReadOptions select the files and elements read of the input
+type ReadOptions struct {
+	Query     string `arg:"--query" help:"Only files matching the query like 'Modality==\"MR\" && SliceThickness<1.0', see :query"`
+	Tags      string `arg:"--tags" help:"Read only the given comma separated tags like PatientName,0020000E,(0008,0060)"`
+	ReadUntil string `arg:"--read-until" help:"Stop reading files before the given tag like PixelData or 7FE00010"`
+	Modality  string `arg:"--modality" help:"Only files of the given comma separated modalities like CT,MR, skipped while reading a directory"`
+	SOPClass  string `arg:"--sop-class" help:"Only files of the given comma separated SOP class UIDs, skipped while reading a directory"`
+	NoFollow  bool   `arg:"--no-follow-symlinks" help:"Skip symbolic links in a directory instead of reading their targets"`
+}
+
+// parse returns the element selection and query of the options, a nil query without --query
+func (o ReadOptions) parse() (elementSelection, *query, error) {
+	selection := elementSelection{skipSymlinks: o.NoFollow}
+	var err error
+	if o.Tags != "" {
+		if selection.tags, err = parseTagList(o.Tags); err != nil {
+			return selection, nil, fmt.Errorf("invalid --tags: %w", err)
+		}
+	}
+	if o.ReadUntil != "" {
+		readUntil, err := parseTagName(o.ReadUntil)
+		if err != nil {
+			return selection, nil, fmt.Errorf("invalid --read-until: %w", err)
+		}
+		selection.readUntil = &readUntil
+	}
+	if o.Modality != "" {
+		selection.modalities = strings.Split(strings.ToUpper(strings.ReplaceAll(o.Modality, " ", "")), ",")
+		selection.keep(tag.Modality)
+	}
+	if o.SOPClass != "" {
+		selection.sopClasses = strings.Split(strings.ReplaceAll(o.SOPClass, " ", ""), ",")
+		for _, uid := range selection.sopClasses {
+			if err := validateValue(tag.SOPClassUID, "UI", uid); err != nil {
+				return selection, nil, fmt.Errorf("invalid --sop-class: %w", err)
+			}
+		}
+		selection.keep(tag.SOPClassUID)
+	}
+	var q *query
+	if o.Query != "" {
+		if q, err = parseQuery(o.Query); err != nil {
+			return selection, nil, fmt.Errorf("invalid query: %w", err)
+		}
+	}
+	return selection, q, nil
+}
+
+// NetworkOptions are the AE titles of network operations
+type NetworkOptions struct {
+	AET       string `arg:"--aet" default:"DCMTAGGER" help:"Own AE title for network operations"`
+	CalledAET string `arg:"--called-aet" default:"ANY-SCP" help:"AE title of the remote AE for network operations"`
+}
+
+type browseCmd struct {
+	Input       string   `arg:"positional" help:"The DICOM input file or directory"`
+	Tabs        []string `arg:"positional" help:"Further DICOM input files or directories, each shown in its own tab"`
+	ScrollOff   int      `arg:"--scrolloff" default:"3" help:"Minimal number of lines kept visible above and below the selection"`
+	Index       bool     `arg:"--index" help:"Keep a persistent index of the key tags of the input files in the user cache directory, unchanged files are taken from it on reopening and read when expanded"`
+	Hide        []string `arg:"--hide" help:"Hide elements of the given kinds: retired, group-length, private"`
+	MWL         string   `arg:"--mwl" help:"Show the modality worklist of the SCP at host:port or with a configured AE name instead of files"`
+	MWLModality string   `arg:"--mwl-modality" help:"Modality of the scheduled procedure steps to query"`
+	MWLStation  string   `arg:"--mwl-station" help:"Scheduled station AE title of the procedure steps to query"`
+	MWLDate     string   `arg:"--mwl-date" help:"Start date or date range like 20240301-20240305 of the procedure steps to query"`
+	Listen      int      `arg:"--listen" help:"Start a storage SCP on the given port, received objects are added to the tree"`
+	Spool       string   `arg:"--spool" help:"Directory received objects are written to [default: a new temporary directory]"`
+	NoColor     bool     `arg:"--no-color" help:"Don't use colors, the selection is marked with ►, also set by the NO_COLOR environment variable"`
+	Theme       string   `arg:"--theme" help:"Colors of the UI, supported: default, high-contrast, no-color"`
+	Session     string   `arg:"--session" help:"Restore the tabs, trees, notes and filters of a session saved with :session instead of giving inputs"`
+	Sort        string   `arg:"--sort" help:"Tree layout to start with: filename, tag, diff, study, size, time, natural or instance, like the keys 1 to 8 [default: filename]"`
+	Expand      *int     `arg:"--expand" help:"Number of tree levels expanded below the root after loading, e.g. 1 shows the groups of the files [default: 0]"`
+	Privacy     bool     `arg:"--privacy" help:"Start in privacy mode, which masks identifying values like names, IDs and dates, toggled with tx"`
+	GroupBy     string   `arg:"--group-by" help:"Group the files of the filename trees in folders by the values of the comma separated tags, e.g. StudyDate,StudyDescription"`
+	ReadOptions
+	NetworkOptions
+}
+
+type dumpCmd struct {
+	Input  string `arg:"positional,required" help:"The DICOM input file or directory"`
+	Format string `arg:"--format" default:"jsonl" help:"Output format, supported: jsonl"`
+	ReadOptions
+}
+
+type diffCmd struct {
+	Input  string `arg:"positional,required" help:"The DICOM input file or directory"`
+	Other  string `arg:"positional" help:"Second directory, its instances are matched with the ones of the input by SOPInstanceUID"`
+	Format string `arg:"--format" help:"Output format, supported: html for one input, text for two [default: html, text for two inputs]"`
+	Quiet  bool   `arg:"-q,--quiet" help:"Write no report, only exit with 1 if values differ"`
+	ReadOptions
+}
+
+type anonymizeCmd struct {
+	Input       string `arg:"positional,required" help:"The DICOM input file or directory"`
+	Output      string `arg:"-o,--output,required" help:"Directory the anonymized copies are written to"`
+	ShiftDates  int    `arg:"--shift-dates" help:"Shift all dates by the given days, e.g. --shift-dates=-37, keeping the intervals between studies"`
+	RandomShift int    `arg:"--random-shift" help:"Shift the dates of each patient by random 1 to the given days into the past"`
+}
+
+type sendCmd struct {
+	Input string `arg:"positional,required" help:"The DICOM file or directory to send"`
+	To    string `arg:"--to,required" help:"Configured AE name or host:port of the storage SCP"`
+	NetworkOptions
+}
+
+type queryCmd struct {
+	Input       string `arg:"positional" help:"The DICOM directory searched with --find"`
+	Find        string `arg:"--find" help:"Print the files with the given key tag value like Modality=CT using the index"`
+	MWL         string `arg:"--mwl" help:"Query the modality worklist SCP at host:port or with a configured AE name"`
+	MWLModality string `arg:"--mwl-modality" help:"Modality of the scheduled procedure steps to query"`
+	MWLStation  string `arg:"--mwl-station" help:"Scheduled station AE title of the procedure steps to query"`
+	MWLDate     string `arg:"--mwl-date" help:"Start date or date range like 20240301-20240305 of the procedure steps to query"`
+	Quiet       bool   `arg:"-q,--quiet" help:"Print no matches, only exit with 1 if there are none"`
+	NetworkOptions
+}
+
+type validateCmd struct {
+	Input string `arg:"positional,required" help:"The DICOM input file or directory"`
+	Quiet bool   `arg:"-q,--quiet" help:"Print no results, only exit with 1 if there are problems"`
+}
+
+type checksumCmd struct {
+	Input  string `arg:"positional,required" help:"The file or directory of the files"`
+	Output string `arg:"-o,--output" help:"Write the manifest to the given file instead of stdout"`
+	Verify string `arg:"--verify" help:"Check the files against the given manifest instead of printing one"`
+	Quiet  bool   `arg:"-q,--quiet" help:"Print no results of --verify, only exit with 1 if files differ or are missing"`
+}
+
+type updateDictCmd struct {
+	Source string `arg:"positional" help:"URL or file of the DocBook XML of PS3.6, the current edition if not given"`
+}
+
+type completionsCmd struct {
+	Shell string `arg:"positional,required" help:"Shell of the script, supported: bash, zsh, fish"`
+}
+
+type manCmd struct{}
+
+// commandNames are the names of the subcommands of args
+func commandNames() []string {
+	names := make([]string, 0)
+	for _, command := range commandSpecs(args{}) {
+		names = append(names, command.name)
+	}
+	return names
+}
+
+// withDefaultCommand inserts 'browse' in front of the arguments unless they start with a subcommand or ask for help
+// or version, so 'dcmtagger <dir>' keeps working. Options shared by all commands are accepted after the command.
+func withDefaultCommand(arguments []string) []string {
+	if len(arguments) > 0 && (slices.Contains(commandNames(), arguments[0]) ||
+		slices.Contains([]string{"-h", "--help", "--version"}, arguments[0])) {
+		return arguments
+	}
+	return append([]string{"browse"}, arguments...)
+}
+
+// exit codes of the batch commands, so they can gate scripts checking DICOM data
+const (
+	exitFindings = 1 // validate found problems, diff differing values, query no matches, checksum mismatches
+	exitError    = 2
+)
+
+// parseArgs parses the arguments into dest, it prints the help or version and exits with 0 if asked for them and
+// prints the usage and exits with exitError on invalid arguments
+func parseArgs(dest *args, arguments []string) *arg.Parser {
+	p, err := arg.NewParser(arg.Config{}, dest)
+	if err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(exitError)
+	}
+	switch err := p.Parse(arguments); {
+	case errors.Is(err, arg.ErrHelp):
+		p.WriteHelp(os.Stdout)
+		os.Exit(0)
+	case errors.Is(err, arg.ErrVersion):
+		fmt.Println(dest.Version())
+		os.Exit(0)
+	case err != nil:
+		failUsage(p, err.Error())
+	}
+	return p
+}
+
+// failUsage prints the usage and the error of invalid arguments, also of combinations found invalid after parsing,
+// and exits with exitError. Unlike arg.Parser.Fail it keeps the exit codes of the batch commands.
+func failUsage(p *arg.Parser, message string) {
+	p.WriteUsage(os.Stderr)
+	fmt.Fprintln(os.Stderr, "error:", message)
+	os.Exit(exitError)
+}
+
+// runBatchCommand runs the subcommand not starting the UI, it returns true if validate found problems, diff differing
+// values, query no matches or checksum --verify mismatching files
+func runBatchCommand(p *arg.Parser, args args, cfg config, audit *auditLog) (bool, error) {
+	ctx := context.Background()
+	switch {
+	case args.Dump != nil:
+		selection, q, err := args.Dump.parse()
+		if err != nil {
+			failUsage(p, err.Error())
+		}
+		if args.Dump.Format != "jsonl" {
+			failUsage(p, "Unsupported dump format: " + args.Dump.Format)
+		}
+		if err := writeJSONLDump(ctx, os.Stdout, args.Dump.Input, selection, q); err != nil {
+			return false, fmt.Errorf("writing dump: %w", err)
+		}
+	case args.Diff != nil:
+		selection, q, err := args.Diff.parse()
+		if err != nil {
+			failUsage(p, err.Error())
+		}
+		if args.Diff.Other != "" {
+			if cmp.Or(args.Diff.Format, "text") != "text" {
+				failUsage(p, "Unsupported report format for two inputs: " + args.Diff.Format)
+			}
+			return diffDirectories(ctx, args.Diff.Input, args.Diff.Other, selection, q, args.Diff.Quiet)
+		}
+		if cmp.Or(args.Diff.Format, "html") != "html" {
+			failUsage(p, "Unsupported report format: " + args.Diff.Format)
+		}
+		datasets, skipped, err := parseDicomFiles(ctx, args.Diff.Input, selection, nil)
+		if err != nil {
+			return false, fmt.Errorf("reading files: %w", err)
+		}
+		printSkippedFiles(skipped)
+		report := newComparisonReport(args.Diff.Input, q.filter(datasets))
+		if !args.Diff.Quiet {
+			if err := htmlReportTemplate.Execute(os.Stdout, report); err != nil {
+				return false, fmt.Errorf("writing report: %w", err)
+			}
+		}
+		return report.differs(), nil
+	case args.Anonymize != nil:
+		dates, err := newDateShift(args.Anonymize.ShiftDates, args.Anonymize.RandomShift)
+		if err != nil {
+			failUsage(p, err.Error())
+		}
+		err = anonymizeFiles(ctx, args.Anonymize.Input, args.Anonymize.Output, dates, func(path, anonymizedPath string) error {
+			fmt.Println(anonymizedPath)
+			return audit.record("anonymize", anonymizedPath, "from "+path)
+		})
+		if err != nil {
+			return false, fmt.Errorf("anonymizing: %w", err)
+		}
+	case args.Send != nil:
+		ae, err := cfg.resolveAE(args.Send.To, args.Send.AET, args.Send.CalledAET)
+		if err != nil {
+			failUsage(p, err.Error())
+		}
+		tlsConfig, err := ae.TLS.clientConfig()
+		if err != nil {
+			return false, fmt.Errorf("reading TLS certificates: %w", err)
+		}
+		paths, err := listFiles(args.Send.Input)
+		if err == nil {
+			err = sendFiles(ctx, ae, paths, tlsConfig, nil, nil)
+		}
+		if err != nil {
+			return false, fmt.Errorf("sending to %s: %w", ae.Name, err)
+		}
+		for _, path := range paths {
+			if err := audit.record("send", path, "to "+ae.String()); err != nil {
+				return false, fmt.Errorf("writing audit log: %w", err)
+			}
+		}
+		fmt.Printf("Sent %d files to %s\n", len(paths), ae.Name)
+	case args.Query != nil:
+		return runQuery(p, args.Query, cfg)
+	case args.Checksum != nil:
+		return runChecksum(args.Checksum)
+	case args.UpdateDict != nil:
+		source, path := cmp.Or(args.UpdateDict.Source, part6URL), dictionaryPath()
+		if path == "" {
+			return false, errors.New("no user config directory for the dictionary")
+		}
+		count, added, err := updateDictionary(ctx, source, path)
+		if err != nil {
+			return false, fmt.Errorf("updating dictionary: %w", err)
+		}
+		fmt.Printf("Wrote %d elements to %s, %d of them unknown to the built-in dictionary\n", count, path, added)
+	case args.Validate != nil:
+		datasets, skipped, err := parseDicomFiles(ctx, args.Validate.Input, elementSelection{}, nil)
+		if err != nil {
+			return false, fmt.Errorf("reading files: %w", err)
+		}
+		printSkippedFiles(skipped)
+		if !args.Validate.Quiet {
+			conformance, err := checkSeriesConformance(ctx, datasets, nil)
+			if err != nil {
+				return false, err
+			}
+			fmt.Printf("Required attributes\n\n%s\nSlice geometry\n\n%s", conformance, checkSliceGeometry(datasets))
+		}
+		return hasConformanceProblems(datasets) || hasGeometryProblems(datasets), nil
+	}
+	return false, nil
+}
+
+// runQuery prints the files with a key tag value found with the index or the worklist items matching the query, it
+// returns true if nothing matches
+func runQuery(p *arg.Parser, cmd *queryCmd, cfg config) (bool, error) {
+	ctx := context.Background()
+	if cmd.Find != "" {
+		keyword, value, ok := strings.Cut(cmd.Find, "=")
+		if !ok || !slices.ContainsFunc(indexedTags, func(t tag.Tag) bool { return tagKeyword(t) == keyword }) {
+			failUsage(p, "--find needs an indexed key tag and value like Modality=CT")
+		}
+		if cmd.Input == "" {
+			failUsage(p, "--find needs the DICOM input directory")
+		}
+		index, err := loadIndex(indexPath(cmd.Input))
+		if err == nil {
+			var skipped []skippedFile
+			_, skipped, err = index.update(ctx, cmd.Input, nil)
+			printSkippedFiles(skipped)
+		}
+		if err == nil {
+			err = index.save()
+		}
+		if err != nil {
+			return false, fmt.Errorf("updating index: %w", err)
+		}
+		paths := index.find(keyword, value)
+		if !cmd.Quiet {
+			for _, path := range paths {
+				fmt.Println(path)
+			}
+		}
+		return len(paths) == 0, nil
+	}
+	if cmd.MWL == "" {
+		failUsage(p, "query needs --find or --mwl")
+	}
+	ae, err := cfg.resolveAE(cmd.MWL, cmd.AET, cmd.CalledAET)
+	if err != nil {
+		failUsage(p, err.Error())
+	}
+	tlsConfig, err := ae.TLS.clientConfig()
+	if err != nil {
+		return false, fmt.Errorf("reading TLS certificates: %w", err)
+	}
+	query := worklistQuery{modality: cmd.MWLModality, stationAET: cmd.MWLStation, date: cmd.MWLDate}
+	entries, err := queryWorklist(ctx, ae, query, tlsConfig, nil)
+	if err != nil {
+		return false, fmt.Errorf("querying worklist: %w", err)
+	}
+	if !cmd.Quiet {
+		if err := writeJSONLEntries(os.Stdout, entries); err != nil {
+			return false, err
+		}
+	}
+	return len(entries) == 0, nil
+}
+
+// diffDirectories prints the instances differing between the two directories, it returns true if any differ or are
+// missing on one side
+func diffDirectories(ctx context.Context, a, b string, selection elementSelection, q *query, quiet bool) (bool, error) {
+	datasets := make([][]DatasetEntry, 0, 2)
+	for _, dir := range []string{a, b} {
+		entries, skipped, err := parseDicomFiles(ctx, dir, selection, nil)
+		if err != nil {
+			return false, fmt.Errorf("reading files: %w", err)
+		}
+		printSkippedFiles(skipped)
+		datasets = append(datasets, q.filter(entries))
+	}
+	d := newDirectoryDiff(a, b, datasets[0], datasets[1])
+	if !quiet {
+		fmt.Print(d)
+	}
+	return d.differs(), nil
+}
+
+// runChecksum prints or writes the manifest of the files or verifies them against one, it returns true if files
+// differ or are missing
+func runChecksum(cmd *checksumCmd) (bool, error) {
+	dir := cmd.Input
+	if info, err := os.Stat(cmd.Input); err == nil && !info.IsDir() {
+		dir = filepath.Dir(cmd.Input)
+	}
+	if cmd.Verify != "" {
+		entries, err := readManifest(cmd.Verify)
+		if err != nil {
+			return false, fmt.Errorf("reading manifest: %w", err)
+		}
+		lines, failed := verifyManifest(dir, entries)
+		if !cmd.Quiet {
+			for _, line := range lines {
+				fmt.Println(line)
+			}
+		}
+		return failed, nil
+	}
+	paths, err := listFiles(cmd.Input)
+	if err != nil {
+		return false, err
+	}
+	if cmd.Output != "" {
+		output, _ := filepath.Abs(cmd.Output)
+		paths = slices.DeleteFunc(paths, func(path string) bool {
+			abs, _ := filepath.Abs(path)
+			return abs == output // an earlier manifest in the directory
+		})
+	}
+	entries, err := newManifest(dir, paths)
+	if err != nil {
+		return false, fmt.Errorf("computing checksums: %w", err)
+	}
+	if cmd.Output != "" {
+		return false, writeManifest(cmd.Output, entries)
+	}
+	fmt.Print(formatManifest(entries))
+	return false, nil
+}
+
+// listFiles returns the file or the files of the directory, without subdirectories
+func listFiles(path string) ([]string, error) {
+	info, err := os.Stat(path)
+	if err != nil {
+		return nil, err
+	}
+	if !info.IsDir() {
+		return []string{path}, nil
+	}
+	files, err := os.ReadDir(path)
+	if err != nil {
+		return nil, err
+	}
+	paths := make([]string, 0)
+	for _, f := range files {
+		if !f.IsDir() {
+			paths = append(paths, filepath.Join(path, f.Name()))
+		}
+	}
+	if len(paths) == 0 {
+		return nil, errors.New("no files in " + path)
+	}
+	return paths, nil
+}
+
+// printSkippedFiles lists the files which weren't read on stderr, so the report on stdout stays unchanged
+func printSkippedFiles(skipped []skippedFile) {
+	for _, file := range skipped {
+		fmt.Fprintf(os.Stderr, "skipped %s: %s\n", file.path, file.reason)
+	}
+}
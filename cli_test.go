@@ -0,0 +1,44 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestWithDefaultCommand(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal([]string{"browse", "images"}, withDefaultCommand([]string{"images"}))
+	assert.Equal([]string{"browse", "--theme", "no-color", "images"}, withDefaultCommand([]string{"--theme", "no-color", "images"}))
+	assert.Equal([]string{"browse"}, withDefaultCommand(nil))
+	assert.Equal([]string{"dump", "images"}, withDefaultCommand([]string{"dump", "images"}))
+	assert.Equal([]string{"--help"}, withDefaultCommand([]string{"--help"}))
+}
+
+func TestReadOptionsParse(t *testing.T) {
+	assert := assert.New(t)
+
+	selection, q, err := ReadOptions{Tags: "Modality", ReadUntil: "PixelData"}.parse()
+	assert.NoError(err)
+	assert.Len(selection.tags, 1)
+	assert.NotNil(selection.readUntil)
+	assert.Nil(q)
+
+	_, q, err = ReadOptions{Query: `Modality=="CT"`}.parse()
+	assert.NoError(err)
+	assert.NotNil(q)
+
+	_, _, err = ReadOptions{ReadUntil: "NoSuchTag"}.parse()
+	assert.ErrorContains(err, "invalid --read-until")
+
+	selection, _, err = ReadOptions{Tags: "PatientName", Modality: "ct, MR", SOPClass: "1.2.840.10008.5.1.4.1.1.2"}.parse()
+	assert.NoError(err)
+	assert.Equal([]string{"CT", "MR"}, selection.modalities)
+	assert.Equal([]string{"1.2.840.10008.5.1.4.1.1.2"}, selection.sopClasses)
+	assert.Equal([]tag.Tag{tag.PatientName, tag.Modality, tag.SOPClassUID}, selection.tags)
+
+	_, _, err = ReadOptions{SOPClass: "CT Image Storage"}.parse()
+	assert.ErrorContains(err, "invalid --sop-class")
+}
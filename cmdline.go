@@ -0,0 +1,107 @@
+package main
+
+import (
+	"strconv"
+	"strings"
+
+	"github.com/rivo/tview"
+)
+
+const maxKillRingEntries = 10
+
+// prompts of the command line modes, e.g. search or command
+var cmdlinePrompts = []string{"s/", "/", "?", ":"}
+
+// setCmdlineText shows the prompt like '/' or ':' the text starts with as label and the rest as editable text, so the
+// prompt can't be edited or deleted by word motions
+func setCmdlineText(cmdline *tview.InputField, text string) {
+	prompt := ""
+	for _, cmdlinePrompt := range cmdlinePrompts {
+		if strings.HasPrefix(text, cmdlinePrompt) {
+			prompt = cmdlinePrompt
+			break
+		}
+	}
+	cmdline.SetLabel(prompt).SetText(text[len(prompt):])
+}
+
+// getCmdlineText returns the command line text including the prompt
+func getCmdlineText(cmdline *tview.InputField) string {
+	return cmdline.GetLabel() + cmdline.GetText()
+}
+
+// killRing collects the text deleted with ctrl + w/k/u in the command line, which can be inserted again with ctrl + y.
+// The input field doesn't expose the deleted text, so it is determined from the text before and after the deletion.
+type killRing struct {
+	entries    []string
+	textBefore *string // text before a pending deletion
+}
+
+func (k *killRing) startKill(text string) {
+	k.textBefore = &text
+}
+
+// finishKill stores the text deleted since startKill, it is called from the changed handler of the input field
+func (k *killRing) finishKill(text string) {
+	if k.textBefore == nil {
+		return
+	}
+	if killed := getRemovedText(*k.textBefore, text); killed != "" {
+		k.entries = append(k.entries, killed)
+		if len(k.entries) > maxKillRingEntries {
+			k.entries = k.entries[1:]
+		}
+	}
+	k.textBefore = nil
+}
+
+// yank inserts the last deleted text at the cursor position by feeding it as key events to the input field
+func (k *killRing) yank(cmdline *tview.InputField) {
+	if len(k.entries) == 0 {
+		return
+	}
+	insertText(cmdline, k.entries[len(k.entries)-1])
+}
+
+// getRemovedText returns the part of 'before' which is missing in 'after', assuming one contiguous deletion
+func getRemovedText(before, after string) string {
+	b, a := []rune(before), []rune(after)
+	if len(b) <= len(a) {
+		return ""
+	}
+	prefix := 0
+	for prefix < len(a) && b[prefix] == a[prefix] {
+		prefix++
+	}
+	suffix := 0
+	for suffix < len(a)-prefix && b[len(b)-1-suffix] == a[len(a)-1-suffix] {
+		suffix++
+	}
+	return string(b[prefix : len(b)-suffix])
+}
+
+// toggleSearchCaseSensitivity appends '\C' to force a case sensitive search, or removes it if already present
+func toggleSearchCaseSensitivity(searchText string) string {
+	if strings.Contains(searchText, `\C`) {
+		return strings.ReplaceAll(searchText, `\C`, "")
+	}
+	return searchText + `\C`
+}
+
+// toggleSearchWholeWord surrounds the search text with '\<' and '\>' to match whole words only, or removes them
+func toggleSearchWholeWord(searchText string) string {
+	if strings.HasPrefix(searchText, `\<`) && strings.HasSuffix(searchText, `\>`) && len(searchText) >= 4 {
+		return searchText[2 : len(searchText)-2]
+	}
+	return `\<` + searchText + `\>`
+}
+
+// parseRowCommand returns the row of a go-to-line command like ":42", false for other commands
+func parseRowCommand(cmdlineText string) (int, bool) {
+	digits, ok := strings.CutPrefix(cmdlineText, ":")
+	if !ok || digits == "" || strings.TrimLeft(digits, "0123456789") != "" {
+		return 0, false
+	}
+	row, err := strconv.Atoi(digits)
+	return row, err == nil
+}
@@ -0,0 +1,28 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestGetRemovedText(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal("world", getRemovedText("hello world", "hello "))
+	assert.Equal("hello ", getRemovedText("hello world", "world"))
+	assert.Equal("", getRemovedText("hello", "hello"))
+	assert.Equal("ä", getRemovedText("äöü", "öü"))
+}
+
+func TestParseRowCommand(t *testing.T) {
+	assert := assert.New(t)
+
+	row, ok := parseRowCommand(":42")
+	assert.True(ok)
+	assert.Equal(42, row)
+	for _, text := range []string{":", ":q", ":4a", ":-1", "42", ":+3"} {
+		_, ok = parseRowCommand(text)
+		assert.False(ok, text)
+	}
+}
@@ -0,0 +1,218 @@
+package main
+
+import (
+	"bytes"
+	"cmp"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"image"
+	"image/color"
+	"image/jpeg"
+	"strconv"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// transfer syntaxes
+const (
+	implicitVRLittleEndian = "1.2.840.10008.1.2"
+	explicitVRLittleEndian = "1.2.840.10008.1.2.1"
+	jpegBaseline           = "1.2.840.10008.1.2.4.50"
+	jpegExtended           = "1.2.840.10008.1.2.4.51"
+	jpegLossless           = "1.2.840.10008.1.2.4.57"
+	jpegLosslessSV1        = "1.2.840.10008.1.2.4.70"
+	jpegLSLossless         = "1.2.840.10008.1.2.4.80"
+	jpegLSNearLossless     = "1.2.840.10008.1.2.4.81"
+	jpeg2000Lossless       = "1.2.840.10008.1.2.4.90"
+	jpeg2000               = "1.2.840.10008.1.2.4.91"
+	rleLossless            = "1.2.840.10008.1.2.5"
+)
+
+// compressedSyntax names the codec of a compressed transfer syntax and the build tag compiling it in, if any
+type compressedSyntax struct {
+	codec    string
+	buildTag string
+}
+
+var compressedSyntaxes = map[string]compressedSyntax{
+	jpegBaseline:       {"JPEG baseline", ""},
+	jpegExtended:       {"JPEG extended", ""},
+	jpegLossless:       {"JPEG lossless", ""},
+	jpegLosslessSV1:    {"JPEG lossless", ""},
+	jpegLSLossless:     {"JPEG-LS", "charls"},
+	jpegLSNearLossless: {"JPEG-LS", "charls"},
+	jpeg2000Lossless:   {"JPEG 2000", "openjpeg"},
+	jpeg2000:           {"JPEG 2000", "openjpeg"},
+	rleLossless:        {"RLE", ""},
+}
+
+// frameDecoder decodes a frame of encapsulated pixel data to its samples, the dataset gives the image pixel attributes
+type frameDecoder func(data []byte, dataset dicom.Dataset) (frame.NativeFrame, error)
+
+// frameDecoders are the codecs compiled in by transfer syntax, those needing native libraries register themselves
+// when built with their tag
+var frameDecoders = map[string]frameDecoder{
+	jpegBaseline: decodeJPEGFrame,
+	jpegExtended: decodeJPEGFrame, // 8 bit only
+	rleLossless:  decodeRLEFrame,
+}
+
+// decodedFrame returns the samples of a frame of the pixel data, native or decoded with the codec of the transfer
+// syntax. Of huge files only this frame is read.
+func decodedFrame(dataset dicom.Dataset, index int) (frame.NativeFrame, error) {
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	if err != nil {
+		return frame.NativeFrame{}, errors.New("no pixel data")
+	}
+	data, ok := lazyFrame(e, index)
+	if !ok {
+		if err := loadLazyValue(e); err != nil {
+			return frame.NativeFrame{}, err
+		}
+		info, ok := e.Value.GetValue().(dicom.PixelDataInfo)
+		if !ok || index >= len(info.Frames) {
+			return frame.NativeFrame{}, fmt.Errorf("no frame %d", index+1)
+		}
+		if !info.IsEncapsulated {
+			return info.Frames[index].NativeData, nil
+		}
+		data = info.Frames[index].EncapsulatedData.Data
+	}
+	transferSyntax := getDatasetValue(dataset, tag.TransferSyntaxUID)
+	decode, ok := frameDecoders[transferSyntax]
+	if ok {
+		return decode(data, dataset)
+	}
+	syntax, ok := compressedSyntaxes[transferSyntax]
+	switch {
+	case !ok:
+		return frame.NativeFrame{}, fmt.Errorf("no codec for transfer syntax %s", cmp.Or(transferSyntax, "unknown"))
+	case syntax.buildTag == "":
+		return frame.NativeFrame{}, fmt.Errorf("%s isn't supported yet", syntax.codec)
+	}
+	return frame.NativeFrame{}, fmt.Errorf("%s decoding isn't compiled in, build with -tags %s", syntax.codec, syntax.buildTag)
+}
+
+// imagePixelAttributes are the attributes describing the samples of the pixel data
+type imagePixelAttributes struct {
+	rows, columns   int
+	samplesPerPixel int
+	bitsAllocated   int
+	signed          bool
+}
+
+func newImagePixelAttributes(dataset dicom.Dataset) imagePixelAttributes {
+	value := func(t tag.Tag, fallback int) int {
+		if n, err := strconv.Atoi(getDatasetValue(dataset, t)); err == nil {
+			return n
+		}
+		return fallback
+	}
+	return imagePixelAttributes{
+		rows:            value(tag.Rows, 0),
+		columns:         value(tag.Columns, 0),
+		samplesPerPixel: value(tag.SamplesPerPixel, 1),
+		bitsAllocated:   value(tag.BitsAllocated, 8),
+		signed:          value(tag.PixelRepresentation, 0) == 1,
+	}
+}
+
+// imageFrame converts a decoded image to samples, gray images to one sample, others to RGB
+func imageFrame(img image.Image) frame.NativeFrame {
+	bounds := img.Bounds()
+	f := frame.NativeFrame{Data: make([][]int, 0, bounds.Dx()*bounds.Dy()), Rows: bounds.Dy(), Cols: bounds.Dx(), BitsPerSample: 8}
+	for y := bounds.Min.Y; y < bounds.Max.Y; y++ {
+		for x := bounds.Min.X; x < bounds.Max.X; x++ {
+			switch img := img.(type) {
+			case *image.Gray:
+				f.Data = append(f.Data, []int{int(img.GrayAt(x, y).Y)})
+			case *image.Gray16:
+				f.BitsPerSample = 16
+				f.Data = append(f.Data, []int{int(img.Gray16At(x, y).Y)})
+			default:
+				c := color.RGBAModel.Convert(img.At(x, y)).(color.RGBA)
+				f.Data = append(f.Data, []int{int(c.R), int(c.G), int(c.B)})
+			}
+		}
+	}
+	return f
+}
+
+// decodeJPEGFrame decodes JPEG baseline and 8 bit extended frames, YBR color is converted to RGB
+func decodeJPEGFrame(data []byte, dataset dicom.Dataset) (frame.NativeFrame, error) {
+	img, err := jpeg.Decode(bytes.NewReader(data))
+	if err != nil {
+		return frame.NativeFrame{}, fmt.Errorf("decoding JPEG: %w", err)
+	}
+	return imageFrame(img), nil
+}
+
+// decodeRLEFrame decodes a frame of RLE lossless pixel data, with one PackBits segment per byte of each sample from
+// the most significant byte on
+func decodeRLEFrame(data []byte, dataset dicom.Dataset) (frame.NativeFrame, error) {
+	attributes := newImagePixelAttributes(dataset)
+	bytesPerSample := attributes.bitsAllocated / 8
+	pixels := attributes.rows * attributes.columns
+	if len(data) < 64 {
+		return frame.NativeFrame{}, errors.New("no RLE header")
+	}
+	segments := int(binary.LittleEndian.Uint32(data))
+	if segments != attributes.samplesPerPixel*bytesPerSample || segments == 0 || segments > 15 {
+		return frame.NativeFrame{}, fmt.Errorf("%d RLE segments for %d samples of %d bits", segments,
+			attributes.samplesPerPixel, attributes.bitsAllocated)
+	}
+	f := frame.NativeFrame{Data: make([][]int, pixels), Rows: attributes.rows, Cols: attributes.columns,
+		BitsPerSample: attributes.bitsAllocated}
+	for i := range f.Data {
+		f.Data[i] = make([]int, attributes.samplesPerPixel)
+	}
+	for segment := range segments {
+		start := int(binary.LittleEndian.Uint32(data[4+4*segment:]))
+		end := len(data)
+		if segment+1 < segments {
+			end = int(binary.LittleEndian.Uint32(data[8+4*segment:]))
+		}
+		if start < 64 || start > end || end > len(data) {
+			return frame.NativeFrame{}, fmt.Errorf("invalid offset of RLE segment %d", segment+1)
+		}
+		decoded := unpackBits(data[start:end], pixels)
+		if len(decoded) < pixels {
+			return frame.NativeFrame{}, fmt.Errorf("RLE segment %d too short", segment+1)
+		}
+		sample, shift := segment/bytesPerSample, 8*(bytesPerSample-1-segment%bytesPerSample)
+		for i, b := range decoded[:pixels] {
+			f.Data[i][sample] |= int(b) << shift
+		}
+	}
+	if attributes.signed && attributes.bitsAllocated < 64 {
+		for _, pixel := range f.Data {
+			for i, sample := range pixel {
+				if sample >= 1<<(attributes.bitsAllocated-1) {
+					pixel[i] = sample - 1<<attributes.bitsAllocated
+				}
+			}
+		}
+	}
+	return f, nil
+}
+
+// unpackBits decodes a PackBits segment up to the given number of bytes
+func unpackBits(data []byte, size int) []byte {
+	decoded := make([]byte, 0, size)
+	for i := 0; i < len(data) && len(decoded) < size; {
+		n := int(int8(data[i]))
+		i++
+		switch {
+		case n >= 0:
+			decoded = append(decoded, data[i:min(i+n+1, len(data))]...)
+			i += n + 1
+		case n > -128 && i < len(data):
+			decoded = append(decoded, bytes.Repeat(data[i:i+1], 1-n)...)
+			i++
+		}
+	}
+	return decoded
+}
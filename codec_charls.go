@@ -0,0 +1,81 @@
+//go:build charls || pixels
+
+package main
+
+/*
+#cgo pkg-config: charls
+#include <stdlib.h>
+#include <charls/charls.h>
+
+// decode_jpegls decodes a JPEG-LS frame to a newly allocated buffer of samples interleaved by pixel, returns the error
+// code of CharLS
+static charls_jpegls_errc decode_jpegls(const void *data, size_t size, charls_frame_info *info, void **samples,
+	size_t *samples_size) {
+	charls_jpegls_decoder *decoder = charls_jpegls_decoder_create();
+	charls_jpegls_errc error = charls_jpegls_decoder_set_source_buffer(decoder, data, size);
+	if (!error) {
+		error = charls_jpegls_decoder_read_header(decoder);
+	}
+	if (!error) {
+		error = charls_jpegls_decoder_get_frame_info(decoder, info);
+	}
+	if (!error) {
+		error = charls_jpegls_decoder_get_destination_size(decoder, 0, samples_size);
+	}
+	if (!error) {
+		*samples = malloc(*samples_size);
+		error = charls_jpegls_decoder_decode_to_buffer(decoder, *samples, *samples_size, 0);
+	}
+	charls_jpegls_decoder_destroy(decoder);
+	return error;
+}
+*/
+import "C"
+
+import (
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"unsafe"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+)
+
+func init() {
+	frameDecoders[jpegLSLossless] = decodeJPEGLSFrame
+	frameDecoders[jpegLSNearLossless] = decodeJPEGLSFrame
+}
+
+// decodeJPEGLSFrame decodes a JPEG-LS frame with CharLS, color components are expected interleaved by pixel
+func decodeJPEGLSFrame(data []byte, dataset dicom.Dataset) (frame.NativeFrame, error) {
+	buffer := C.CBytes(data)
+	defer C.free(buffer)
+	var info C.charls_frame_info
+	var samples unsafe.Pointer
+	var size C.size_t
+	errc := C.decode_jpegls(buffer, C.size_t(len(data)), &info, &samples, &size)
+	defer C.free(samples)
+	if errc != 0 {
+		return frame.NativeFrame{}, fmt.Errorf("decoding JPEG-LS failed: %s", C.GoString(C.charls_get_error_message(errc)))
+	}
+	decoded := unsafe.Slice((*byte)(samples), int(size))
+	width, height, components := int(info.width), int(info.height), int(info.component_count)
+	bytesPerSample := (int(info.bits_per_sample) + 7) / 8
+	if len(decoded) < width*height*components*bytesPerSample {
+		return frame.NativeFrame{}, errors.New("decoded JPEG-LS frame too short")
+	}
+	f := frame.NativeFrame{Data: make([][]int, width*height), Rows: height, Cols: width, BitsPerSample: int(info.bits_per_sample)}
+	for i := range f.Data {
+		f.Data[i] = make([]int, components)
+		for c := range components {
+			offset := (i*components + c) * bytesPerSample
+			if bytesPerSample == 1 {
+				f.Data[i][c] = int(decoded[offset])
+			} else {
+				f.Data[i][c] = int(binary.LittleEndian.Uint16(decoded[offset:]))
+			}
+		}
+	}
+	return f, nil
+}
@@ -0,0 +1,121 @@
+//go:build openjpeg || pixels
+
+package main
+
+/*
+#cgo pkg-config: libopenjp2
+#include <stdlib.h>
+#include <string.h>
+#include <openjpeg.h>
+
+typedef struct {
+	const unsigned char *data;
+	OPJ_SIZE_T size;
+	OPJ_SIZE_T offset;
+} memory_stream;
+
+static OPJ_SIZE_T memory_read(void *buffer, OPJ_SIZE_T n, void *user_data) {
+	memory_stream *s = user_data;
+	if (s->offset >= s->size) {
+		return (OPJ_SIZE_T)-1;
+	}
+	if (n > s->size - s->offset) {
+		n = s->size - s->offset;
+	}
+	memcpy(buffer, s->data + s->offset, n);
+	s->offset += n;
+	return n;
+}
+
+static OPJ_OFF_T memory_skip(OPJ_OFF_T n, void *user_data) {
+	memory_stream *s = user_data;
+	if (n < 0 || (OPJ_SIZE_T)n > s->size - s->offset) {
+		return -1;
+	}
+	s->offset += n;
+	return n;
+}
+
+static OPJ_BOOL memory_seek(OPJ_OFF_T offset, void *user_data) {
+	memory_stream *s = user_data;
+	if (offset < 0 || (OPJ_SIZE_T)offset > s->size) {
+		return OPJ_FALSE;
+	}
+	s->offset = offset;
+	return OPJ_TRUE;
+}
+
+// decode_j2k decodes a JPEG 2000 codestream, the image is NULL on errors
+static opj_image_t *decode_j2k(const unsigned char *data, size_t size) {
+	memory_stream s = {data, size, 0};
+	opj_dparameters_t parameters;
+	opj_image_t *image = NULL;
+	opj_codec_t *codec = opj_create_decompress(OPJ_CODEC_J2K);
+	opj_stream_t *stream = opj_stream_create(OPJ_J2K_STREAM_CHUNK_SIZE, OPJ_TRUE);
+	opj_set_default_decoder_parameters(&parameters);
+	opj_stream_set_user_data(stream, &s, NULL);
+	opj_stream_set_user_data_length(stream, size);
+	opj_stream_set_read_function(stream, memory_read);
+	opj_stream_set_skip_function(stream, memory_skip);
+	opj_stream_set_seek_function(stream, memory_seek);
+	if (!opj_setup_decoder(codec, &parameters) || !opj_read_header(stream, codec, &image) ||
+		!opj_decode(codec, stream, image) || !opj_end_decompress(codec, stream)) {
+		opj_image_destroy(image);
+		image = NULL;
+	}
+	opj_stream_destroy(stream);
+	opj_destroy_codec(codec);
+	return image;
+}
+
+static opj_image_comp_t *image_component(opj_image_t *image, int index) {
+	return &image->comps[index];
+}
+*/
+import "C"
+
+import (
+	"errors"
+	"unsafe"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+)
+
+func init() {
+	frameDecoders[jpeg2000Lossless] = decodeJ2KFrame
+	frameDecoders[jpeg2000] = decodeJ2KFrame
+}
+
+// decodeJ2KFrame decodes a JPEG 2000 frame with OpenJPEG, the components are expected in full resolution
+func decodeJ2KFrame(data []byte, dataset dicom.Dataset) (frame.NativeFrame, error) {
+	if len(data) == 0 {
+		return frame.NativeFrame{}, errors.New("empty JPEG 2000 frame")
+	}
+	buffer := C.CBytes(data)
+	defer C.free(buffer)
+	image := C.decode_j2k((*C.uchar)(buffer), C.size_t(len(data)))
+	if image == nil {
+		return frame.NativeFrame{}, errors.New("decoding JPEG 2000 failed")
+	}
+	defer C.opj_image_destroy(image)
+
+	components := make([][]C.OPJ_INT32, image.numcomps)
+	first := C.image_component(image, 0)
+	width, height := int(first.w), int(first.h)
+	for i := range components {
+		component := C.image_component(image, C.int(i))
+		if int(component.w) != width || int(component.h) != height {
+			return frame.NativeFrame{}, errors.New("subsampled JPEG 2000 components aren't supported")
+		}
+		components[i] = unsafe.Slice(component.data, width*height)
+	}
+	f := frame.NativeFrame{Data: make([][]int, width*height), Rows: height, Cols: width, BitsPerSample: int(first.prec)}
+	for i := range f.Data {
+		f.Data[i] = make([]int, len(components))
+		for c, component := range components {
+			f.Data[i][c] = int(component[i])
+		}
+	}
+	return f, nil
+}
@@ -0,0 +1,73 @@
+package main
+
+import (
+	"bytes"
+	"encoding/binary"
+	"image"
+	"image/jpeg"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestDecodedFrame(t *testing.T) {
+	assert := assert.New(t)
+
+	encapsulated := func(transferSyntax string, rows, columns, bitsAllocated, pixelRepresentation int, data []byte) dicom.Dataset {
+		return newFixture(t).
+			with(tag.TransferSyntaxUID, []string{transferSyntax}).
+			with(tag.SamplesPerPixel, []int{1}).
+			with(tag.Rows, []int{rows}).
+			with(tag.Columns, []int{columns}).
+			with(tag.BitsAllocated, []int{bitsAllocated}).
+			with(tag.PixelRepresentation, []int{pixelRepresentation}).
+			with(tag.PixelData, dicom.PixelDataInfo{IsEncapsulated: true, Frames: []frame.Frame{
+				{Encapsulated: true, EncapsulatedData: frame.EncapsulatedFrame{Data: data}},
+			}}).
+			dataset()
+	}
+	rle := func(segments ...[]byte) []byte {
+		header := binary.LittleEndian.AppendUint32(nil, uint32(len(segments)))
+		offset := 64
+		for _, segment := range segments {
+			header = binary.LittleEndian.AppendUint32(header, uint32(offset))
+			offset += len(segment)
+		}
+		return append(append(header, make([]byte, 64-len(header))...), bytes.Join(segments, nil)...)
+	}
+
+	// literal run of 3 bytes and replicate run of 2
+	f, err := decodedFrame(encapsulated(rleLossless, 1, 5, 8, 0, rle([]byte{0x02, 10, 20, 30, 0xff, 40})), 0)
+	assert.NoError(err)
+	assert.Equal([][]int{{10}, {20}, {30}, {40}, {40}}, f.Data)
+	assert.Equal(5, f.Cols)
+
+	// most significant bytes first, 0xfffe is -2
+	f, err = decodedFrame(encapsulated(rleLossless, 1, 2, 16, 1, rle([]byte{0x01, 0xff, 0x01}, []byte{0x01, 0xfe, 0x2c})), 0)
+	assert.NoError(err)
+	assert.Equal([][]int{{-2}, {300}}, f.Data)
+
+	_, err = decodedFrame(encapsulated(rleLossless, 1, 8, 8, 0, rle([]byte{0x02, 10, 20, 30})), 0)
+	assert.EqualError(err, "RLE segment 1 too short")
+
+	gray := image.NewGray(image.Rect(0, 0, 8, 8))
+	for i := range gray.Pix {
+		gray.Pix[i] = 200
+	}
+	var encoded bytes.Buffer
+	assert.NoError(jpeg.Encode(&encoded, gray, nil))
+	f, err = decodedFrame(encapsulated(jpegBaseline, 8, 8, 8, 0, encoded.Bytes()), 0)
+	assert.NoError(err)
+	assert.Equal(8, f.Rows)
+	assert.InDelta(200, f.Data[0][0], 2)
+
+	if _, ok := frameDecoders[jpeg2000]; !ok {
+		_, err = decodedFrame(encapsulated(jpeg2000, 8, 8, 8, 0, []byte{0xff, 0x4f}), 0)
+		assert.EqualError(err, "JPEG 2000 decoding isn't compiled in, build with -tags openjpeg")
+	}
+	_, err = decodedFrame(encapsulated(jpegLossless, 8, 8, 8, 0, nil), 0)
+	assert.EqualError(err, "JPEG lossless isn't supported yet")
+}
@@ -0,0 +1,195 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"os"
+	"strings"
+
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// encapsulatedPixelData are the items of encapsulated pixel data as stored, without decompressing them
+type encapsulatedPixelData struct {
+	offsets   []uint32 // basic offset table, empty if the encoder left it out
+	fragments [][]byte
+}
+
+// rawPixelData returns the encoded value of the pixel data element of the file, of huge files from their mapped file.
+// The dicom library drops the basic offset table, so the file is read again.
+func rawPixelData(entry *DatasetEntry) ([]byte, error) {
+	e, err := entry.dataset.FindElementByTag(tag.PixelData)
+	if err != nil {
+		return nil, errors.New("no pixel data")
+	}
+	if raw := lazyRawValue(e); raw != nil {
+		return raw, nil
+	}
+	data, err := os.ReadFile(entry.path)
+	if err != nil {
+		return nil, err
+	}
+	offset, err := findElementOffset(bufio.NewReader(bytes.NewReader(data)), tag.PixelData)
+	if err != nil {
+		return nil, err
+	}
+	scanner := &elementScanner{r: bufio.NewReader(bytes.NewReader(data[offset:])), explicit: true}
+	if t, _, _, err := scanner.readHeader(); err != nil || t != tag.PixelData {
+		return nil, errors.New("no pixel data")
+	}
+	return data[offset+scanner.offset:], nil
+}
+
+// parseEncapsulatedPixelData splits the encoded value into the basic offset table and the fragments
+func parseEncapsulatedPixelData(raw []byte) (encapsulatedPixelData, error) {
+	var pixelData encapsulatedPixelData
+	for offset, first := 0, true; offset+8 <= len(raw); first = false {
+		t := tag.Tag{Group: binary.LittleEndian.Uint16(raw[offset:]), Element: binary.LittleEndian.Uint16(raw[offset+2:])}
+		length := int(binary.LittleEndian.Uint32(raw[offset+4:]))
+		offset += 8
+		if t == sequenceDelimiterTag {
+			return pixelData, nil
+		}
+		if t != itemTag || offset+length > len(raw) {
+			return pixelData, fmt.Errorf("invalid item at offset %d", offset-8)
+		}
+		item := raw[offset : offset+length]
+		offset += length
+		if !first {
+			pixelData.fragments = append(pixelData.fragments, item)
+			continue
+		}
+		for i := 0; i+4 <= len(item); i += 4 {
+			pixelData.offsets = append(pixelData.offsets, binary.LittleEndian.Uint32(item[i:]))
+		}
+	}
+	return pixelData, errors.New("no sequence delimiter, not encapsulated")
+}
+
+// jpegProcesses are the names of the start of frame markers of JPEG and JPEG-LS
+var jpegProcesses = map[byte]string{
+	0xc0: "JPEG baseline", 0xc1: "JPEG extended", 0xc2: "JPEG progressive", 0xc3: "JPEG lossless",
+	0xc5: "JPEG differential sequential", 0xc6: "JPEG differential progressive", 0xc7: "JPEG differential lossless",
+	0xc9: "JPEG extended arithmetic", 0xca: "JPEG progressive arithmetic", 0xcb: "JPEG lossless arithmetic",
+	0xf7: "JPEG-LS",
+}
+
+// describeJPEG returns the process, dimensions, precision and components from the start of frame segment
+func describeJPEG(data []byte) (string, error) {
+	for offset := 2; offset+4 <= len(data); {
+		if data[offset] != 0xff {
+			return "", fmt.Errorf("no JPEG marker at offset %d", offset)
+		}
+		marker := data[offset+1]
+		length := int(binary.BigEndian.Uint16(data[offset+2:]))
+		if process, ok := jpegProcesses[marker]; ok {
+			if offset+10 > len(data) {
+				break
+			}
+			segment := data[offset+4:]
+			return fmt.Sprintf("%s, %dx%d, %d bit, %d components", process, binary.BigEndian.Uint16(segment[3:]),
+				binary.BigEndian.Uint16(segment[1:]), segment[0], segment[5]), nil
+		}
+		if marker == 0xda {
+			break // scan data without frame header
+		}
+		offset += 2 + length
+	}
+	return "", errors.New("no JPEG frame header")
+}
+
+// j2kProgressionOrders are the progression orders of the COD marker by their value
+var j2kProgressionOrders = []string{"LRCP", "RLCP", "RPCL", "PCRL", "CPRL"}
+
+// describeJ2K returns the dimensions, precision and components from the SIZ marker and the progression order, layers,
+// decomposition levels and wavelet from the COD marker of a JPEG 2000 codestream
+func describeJ2K(data []byte) (string, error) {
+	parts := make([]string, 0)
+	for offset := 2; offset+4 <= len(data); {
+		marker := binary.BigEndian.Uint16(data[offset:])
+		length := int(binary.BigEndian.Uint16(data[offset+2:]))
+		if offset+2+length > len(data) {
+			break
+		}
+		segment := data[offset+4 : offset+2+length]
+		switch {
+		case marker == 0xff51 && len(segment) >= 39: // SIZ
+			width := binary.BigEndian.Uint32(segment[2:]) - binary.BigEndian.Uint32(segment[10:])
+			height := binary.BigEndian.Uint32(segment[6:]) - binary.BigEndian.Uint32(segment[14:])
+			precision, signed := segment[36]&0x7f+1, ""
+			if segment[36]&0x80 != 0 {
+				signed = " signed"
+			}
+			parts = append(parts, fmt.Sprintf("JPEG 2000, %dx%d, %d bit%s, %d components, tiles %dx%d", width, height,
+				precision, signed, binary.BigEndian.Uint16(segment[34:]), binary.BigEndian.Uint32(segment[18:]),
+				binary.BigEndian.Uint32(segment[22:])))
+		case marker == 0xff52 && len(segment) >= 10: // COD
+			order := fmt.Sprint(segment[1])
+			if int(segment[1]) < len(j2kProgressionOrders) {
+				order = j2kProgressionOrders[segment[1]]
+			}
+			wavelet := "9-7 irreversible"
+			if segment[9] == 1 {
+				wavelet = "5-3 reversible"
+			}
+			parts = append(parts, fmt.Sprintf("progression %s, %d layers, %d decompositions, %s wavelet", order,
+				binary.BigEndian.Uint16(segment[2:]), segment[5], wavelet))
+		case marker == 0xff90 || marker == 0xff93: // first tile part or its data
+			offset = len(data)
+			continue
+		}
+		offset += 2 + length
+	}
+	if len(parts) == 0 {
+		return "", errors.New("no JPEG 2000 size marker")
+	}
+	return strings.Join(parts, ", "), nil
+}
+
+// describeCodestream returns the parameters of the codestream starting in the fragment, decoded from its headers
+func describeCodestream(fragment []byte) string {
+	var description string
+	var err error
+	switch {
+	case bytes.HasPrefix(fragment, []byte{0xff, 0xd8}):
+		description, err = describeJPEG(fragment)
+	case bytes.HasPrefix(fragment, []byte{0xff, 0x4f, 0xff, 0x51}):
+		description, err = describeJ2K(fragment)
+	case len(fragment) >= 64 && binary.LittleEndian.Uint32(fragment) <= 15:
+		return fmt.Sprintf("RLE, %d segments", binary.LittleEndian.Uint32(fragment))
+	default:
+		return "unknown codestream"
+	}
+	if err != nil {
+		return err.Error()
+	}
+	return description
+}
+
+func (p encapsulatedPixelData) String() string {
+	var b strings.Builder
+	if len(p.offsets) == 0 {
+		b.WriteString("Basic offset table: empty\n")
+	} else {
+		fmt.Fprintf(&b, "Basic offset table: %d offsets\n", len(p.offsets))
+		for i, offset := range p.offsets {
+			fmt.Fprintf(&b, "  frame %d at %d\n", i+1, offset)
+		}
+	}
+	total := 0
+	for _, fragment := range p.fragments {
+		total += len(fragment)
+	}
+	fmt.Fprintf(&b, "\n%d fragments, %d bytes\n", len(p.fragments), total)
+	for i, fragment := range p.fragments {
+		fmt.Fprintf(&b, "  %d: %d bytes", i+1, len(fragment))
+		if description := describeCodestream(fragment); !strings.HasPrefix(description, "unknown") || i == 0 {
+			fmt.Fprintf(&b, " - %s", description)
+		}
+		b.WriteString("\n")
+	}
+	return b.String()
+}
@@ -0,0 +1,61 @@
+package main
+
+import (
+	"encoding/binary"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestParseEncapsulatedPixelData(t *testing.T) {
+	assert := assert.New(t)
+
+	item := func(group, element uint16, value []byte) []byte {
+		header := binary.LittleEndian.AppendUint16(nil, group)
+		header = binary.LittleEndian.AppendUint16(header, element)
+		return append(binary.LittleEndian.AppendUint32(header, uint32(len(value))), value...)
+	}
+	offsets := binary.LittleEndian.AppendUint32(binary.LittleEndian.AppendUint32(nil, 0), 12)
+	raw := item(0xfffe, 0xe000, offsets)
+	raw = append(raw, item(0xfffe, 0xe000, []byte{0xff, 0xd8, 0xff, 0xd9})...)
+	raw = append(raw, item(0xfffe, 0xe000, []byte{1, 2})...)
+	raw = append(raw, item(0xfffe, 0xe0dd, nil)...)
+
+	pixelData, err := parseEncapsulatedPixelData(raw)
+	assert.NoError(err)
+	assert.Equal([]uint32{0, 12}, pixelData.offsets)
+	assert.Equal([][]byte{{0xff, 0xd8, 0xff, 0xd9}, {1, 2}}, pixelData.fragments)
+	assert.Contains(pixelData.String(), "Basic offset table: 2 offsets")
+	assert.Contains(pixelData.String(), "2 fragments, 6 bytes")
+
+	pixelData, err = parseEncapsulatedPixelData(item(0xfffe, 0xe000, nil))
+	assert.Error(err)
+	assert.Empty(pixelData.offsets)
+	assert.Contains(pixelData.String(), "Basic offset table: empty")
+}
+
+func TestDescribeCodestream(t *testing.T) {
+	assert := assert.New(t)
+
+	// SOI, APP0 with 2 bytes of data, SOF1 with 12 bit, 512 rows, 256 columns and 1 component
+	jpeg := []byte{0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 'J', 'F',
+		0xff, 0xc1, 0x00, 0x0b, 12, 0x02, 0x00, 0x01, 0x00, 1, 1, 0x11, 0}
+	assert.Equal("JPEG extended, 256x512, 12 bit, 1 components", describeCodestream(jpeg))
+	assert.Equal("no JPEG frame header", describeCodestream(jpeg[:8]))
+
+	siz := []byte{0xff, 0x4f, 0xff, 0x51, 0x00, 0x29, 0x00, 0x00}
+	for _, value := range []uint32{640, 480, 0, 0, 640, 480, 0, 0} { // size, offset, tile size, tile offset
+		siz = binary.BigEndian.AppendUint32(siz, value)
+	}
+	siz = append(siz, 0x00, 0x01, 0x8f, 1, 1) // 1 component, 16 bit signed, no subsampling
+	cod := []byte{0xff, 0x52, 0x00, 0x0c, 0x00, 0x02, 0x00, 0x03, 0x00, 5, 4, 4, 0, 1}
+	sot := []byte{0xff, 0x90, 0x00, 0x0a}
+	j2k := append(append(siz, cod...), sot...)
+	assert.Equal("JPEG 2000, 640x480, 16 bit signed, 1 components, tiles 640x480, progression RPCL, 3 layers, 5 decompositions, 5-3 reversible wavelet",
+		describeCodestream(j2k))
+
+	rle := make([]byte, 64)
+	rle[0] = 3
+	assert.Equal("RLE, 3 segments", describeCodestream(rle))
+	assert.Equal("unknown codestream", describeCodestream([]byte{1, 2}))
+}
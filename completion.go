@@ -0,0 +1,223 @@
+package main
+
+import (
+	"fmt"
+	"reflect"
+	"slices"
+	"strings"
+)
+
+// flagSpec is a command line flag as declared by the go-arg struct tags, for completions and the man page
+type flagSpec struct {
+	long       string
+	short      string
+	help       string
+	defaultVal string
+	takesValue bool
+	choices    []string // values listed as 'supported: a, b' in the help text
+}
+
+// commandSpec is a subcommand as declared by the go-arg struct tags
+type commandSpec struct {
+	name string
+	help string
+}
+
+// commandSpecs returns the subcommands of the given go-arg struct in declaration order
+func commandSpecs(v any) []commandSpec {
+	var commands []commandSpec
+	t := reflect.TypeOf(v)
+	for i := 0; i < t.NumField(); i++ {
+		field := t.Field(i)
+		if name, ok := strings.CutPrefix(field.Tag.Get("arg"), "subcommand:"); ok {
+			commands = append(commands, commandSpec{name, field.Tag.Get("help")})
+		}
+	}
+	return commands
+}
+
+// flagSpecs returns the flags of the given go-arg struct, its embedded structs and subcommands in declaration order,
+// each flag once, including the flags go-arg adds itself
+func flagSpecs(v any) []flagSpec {
+	specs := appendFlagSpecs(nil, reflect.TypeOf(v))
+	return append(specs,
+		flagSpec{long: "help", short: "h", help: "Display this help and exit"},
+		flagSpec{long: "version", help: "Display version and exit"})
+}
+
+func appendFlagSpecs(specs []flagSpec, t reflect.Type) []flagSpec {
+	for i := 0; i < t.NumField(); i++ {
+		field := t.Field(i)
+		argTag := field.Tag.Get("arg")
+		if field.Anonymous && field.Type.Kind() == reflect.Struct {
+			specs = appendFlagSpecs(specs, field.Type)
+			continue
+		}
+		if strings.HasPrefix(argTag, "subcommand:") {
+			specs = appendFlagSpecs(specs, field.Type.Elem())
+			continue
+		}
+		if argTag == "" || argTag == "-" || strings.HasPrefix(argTag, "positional") {
+			continue
+		}
+		spec := flagSpec{help: field.Tag.Get("help"), defaultVal: field.Tag.Get("default")}
+		for _, name := range strings.Split(argTag, ",") {
+			if strings.HasPrefix(name, "--") {
+				spec.long = name[2:]
+			} else if strings.HasPrefix(name, "-") {
+				spec.short = name[1:]
+			}
+		}
+		if slices.ContainsFunc(specs, func(s flagSpec) bool { return s.long == spec.long }) {
+			continue
+		}
+		// -v and -vv are counted by expandVerbosityFlags and don't take a value
+		spec.takesValue = field.Type.Kind() != reflect.Bool && spec.short != "v"
+		if _, supported, ok := strings.Cut(spec.help, "supported: "); ok {
+			for _, choice := range strings.Split(supported, ",") {
+				spec.choices = append(spec.choices, strings.TrimSpace(choice))
+			}
+		}
+		specs = append(specs, spec)
+	}
+	return specs
+}
+
+// completionScript returns the completion script of the given shell: bash, zsh or fish
+func completionScript(shell string, commands []commandSpec, specs []flagSpec) (string, error) {
+	switch shell {
+	case "bash":
+		return bashCompletion(commands, specs), nil
+	case "zsh":
+		return zshCompletion(commands, specs), nil
+	case "fish":
+		return fishCompletion(commands, specs), nil
+	}
+	return "", fmt.Errorf("unknown shell '%s', supported: bash, zsh, fish", shell)
+}
+
+func commandList(commands []commandSpec) string {
+	names := make([]string, 0, len(commands))
+	for _, command := range commands {
+		names = append(names, command.name)
+	}
+	return strings.Join(names, " ")
+}
+
+func bashCompletion(commands []commandSpec, specs []flagSpec) string {
+	var flags, cases strings.Builder
+	for _, spec := range specs {
+		flags.WriteString(" --" + spec.long)
+		if spec.short != "" {
+			flags.WriteString(" -" + spec.short)
+		}
+		if len(spec.choices) > 0 {
+			fmt.Fprintf(&cases, "        --%s) COMPREPLY=($(compgen -W \"%s\" -- \"$cur\")); return;;\n", spec.long,
+				strings.Join(spec.choices, " "))
+		}
+	}
+	return fmt.Sprintf(`_dcmtagger() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}"
+    case "$prev" in
+%s    esac
+    if [[ $COMP_CWORD -eq 1 && "$cur" != -* ]]; then
+        COMPREPLY=($(compgen -W "%s" -- "$cur"))
+    fi
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "%s" -- "$cur"))
+    else
+        COMPREPLY+=($(compgen -f -- "$cur"))
+    fi
+}
+complete -o filenames -F _dcmtagger dcmtagger
+`, cases.String(), commandList(commands), strings.TrimSpace(flags.String()))
+}
+
+func zshCompletion(commands []commandSpec, specs []flagSpec) string {
+	escape := strings.NewReplacer(`'`, `'\''`, "[", `\[`, "]", `\]`, ":", `\:`).Replace
+	var b strings.Builder
+	b.WriteString("#compdef dcmtagger\n\n_arguments \\\n")
+	for _, spec := range specs {
+		value := ""
+		if len(spec.choices) > 0 {
+			value = fmt.Sprintf(":%s:(%s)", spec.long, strings.Join(spec.choices, " "))
+		} else if spec.takesValue {
+			value = fmt.Sprintf(":%s:_files", spec.long)
+		}
+		if spec.short != "" {
+			fmt.Fprintf(&b, "  '(-%s --%s)'{-%s,--%s}'[%s]%s' \\\n", spec.short, spec.long, spec.short, spec.long,
+				escape(spec.help), value)
+		} else {
+			fmt.Fprintf(&b, "  '--%s[%s]%s' \\\n", spec.long, escape(spec.help), value)
+		}
+	}
+	fmt.Fprintf(&b, "  '1:command or input:{_alternative \"commands:command:(%s)\" \"files:input:_files\"}' \\\n",
+		commandList(commands))
+	b.WriteString("  '*:input:_files'\n")
+	return b.String()
+}
+
+func fishCompletion(commands []commandSpec, specs []flagSpec) string {
+	escape := strings.NewReplacer(`\`, `\\`, `'`, `\'`).Replace
+	var b strings.Builder
+	for _, command := range commands {
+		fmt.Fprintf(&b, "complete -c dcmtagger -n '__fish_use_subcommand' -a %s -d '%s'\n", command.name,
+			escape(command.help))
+	}
+	for _, spec := range specs {
+		fmt.Fprintf(&b, "complete -c dcmtagger -l %s", spec.long)
+		if spec.short != "" {
+			fmt.Fprintf(&b, " -s %s", spec.short)
+		}
+		if len(spec.choices) > 0 {
+			fmt.Fprintf(&b, " -x -a '%s'", strings.Join(spec.choices, " "))
+		} else if spec.takesValue {
+			b.WriteString(" -r")
+		}
+		fmt.Fprintf(&b, " -d '%s'\n", escape(spec.help))
+	}
+	return b.String()
+}
+
+// manPage returns the man page in roff format with the commands, options and the key bindings of the help text
+func manPage(commands []commandSpec, specs []flagSpec, version string) string {
+	escape := func(text string) string {
+		text = strings.NewReplacer(`\`, `\e`, "-", `\-`).Replace(text)
+		if strings.HasPrefix(text, ".") || strings.HasPrefix(text, "'") {
+			text = `\&` + text
+		}
+		return text
+	}
+	var b strings.Builder
+	fmt.Fprintf(&b, ".TH DCMTAGGER 1 \"\" \"dcmtagger %s\" \"User Commands\"\n", escape(version))
+	b.WriteString(".SH NAME\ndcmtagger \\- a simple DICOM tag viewer\n")
+	b.WriteString(".SH SYNOPSIS\n.B dcmtagger\n[\\fICOMMAND\\fR] [\\fIOPTIONS\\fR] [\\fIINPUT\\fR]\n")
+	b.WriteString(".SH DESCRIPTION\nShows the elements of the DICOM file or the files in the directory \\fIINPUT\\fR ")
+	b.WriteString("as a tree, unless another command is given.\n")
+	b.WriteString(".SH COMMANDS\n")
+	for _, command := range commands {
+		fmt.Fprintf(&b, ".TP\n\\fB%s\\fR\n%s\n", escape(command.name), escape(command.help))
+	}
+	b.WriteString(".SH OPTIONS\n")
+	for _, spec := range specs {
+		b.WriteString(".TP\n")
+		if spec.short != "" {
+			fmt.Fprintf(&b, "\\fB\\-%s\\fR, ", escape(spec.short))
+		}
+		fmt.Fprintf(&b, "\\fB\\-\\-%s\\fR", escape(spec.long))
+		if spec.takesValue {
+			fmt.Fprintf(&b, " \\fI%s\\fR", strings.ToUpper(spec.long))
+		}
+		b.WriteString("\n" + escape(spec.help))
+		if spec.defaultVal != "" {
+			fmt.Fprintf(&b, " [default: %s]", escape(spec.defaultVal))
+		}
+		b.WriteString("\n")
+	}
+	b.WriteString(".SH KEYS\n.nf\n")
+	for _, line := range strings.Split(strings.TrimSpace(helpText), "\n") {
+		b.WriteString(escape(line) + "\n")
+	}
+	b.WriteString(".fi\n")
+	return b.String()
+}
@@ -0,0 +1,77 @@
+package main
+
+import (
+	"slices"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestFlagSpecs(t *testing.T) {
+	assert := assert.New(t)
+
+	specs := flagSpecs(args{})
+	assert.Equal(flagSpec{long: "scrolloff", help: "Minimal number of lines kept visible above and below the selection",
+		defaultVal: "3", takesValue: true}, specs[0])
+	theme := specs[slices.IndexFunc(specs, func(s flagSpec) bool { return s.long == "theme" })]
+	assert.Equal([]string{"default", "high-contrast", "no-color"}, theme.choices)
+	verbose := specs[slices.IndexFunc(specs, func(s flagSpec) bool { return s.long == "verbose" })]
+	assert.Equal("v", verbose.short)
+	assert.False(verbose.takesValue)
+	formats := slices.DeleteFunc(slices.Clone(specs), func(s flagSpec) bool { return s.long != "format" })
+	assert.Len(formats, 1)
+	assert.Equal("help", specs[len(specs)-2].long)
+
+	commands := commandSpecs(args{})
+	assert.Equal(commandSpec{"browse", "Show the files in the UI, the default without command"}, commands[0])
+	assert.Equal([]string{"browse", "dump", "diff", "anonymize", "send", "query", "validate", "checksum", "update-dict",
+		"completions", "man"},
+		commandNames())
+}
+
+func TestCompletionScript(t *testing.T) {
+	assert := assert.New(t)
+
+	commands := []commandSpec{{"browse", "Show the files"}, {"dump", "Write the elements"}}
+	specs := []flagSpec{
+		{long: "theme", help: "Colors of the UI [x]", takesValue: true, choices: []string{"default", "no-color"}},
+		{long: "verbose", short: "v", help: "Log more"},
+		{long: "config", help: "Config file", takesValue: true},
+	}
+	bash, err := completionScript("bash", commands, specs)
+	assert.NoError(err)
+	assert.Contains(bash, `--theme) COMPREPLY=($(compgen -W "default no-color" -- "$cur")); return;;`)
+	assert.Contains(bash, `compgen -W "browse dump" -- "$cur"`)
+	assert.Contains(bash, `compgen -W "--theme --verbose -v --config"`)
+
+	zsh, err := completionScript("zsh", commands, specs)
+	assert.NoError(err)
+	assert.Contains(zsh, `'--theme[Colors of the UI \[x\]]:theme:(default no-color)' \`)
+	assert.Contains(zsh, `'(-v --verbose)'{-v,--verbose}'[Log more]' \`)
+	assert.Contains(zsh, `'--config[Config file]:config:_files' \`)
+	assert.Contains(zsh, `'1:command or input:{_alternative "commands:command:(browse dump)" "files:input:_files"}' \`)
+
+	fish, err := completionScript("fish", commands, specs)
+	assert.NoError(err)
+	assert.Contains(fish, "complete -c dcmtagger -l theme -x -a 'default no-color' -d 'Colors of the UI [x]'\n")
+	assert.Contains(fish, "complete -c dcmtagger -l verbose -s v -d 'Log more'\n")
+	assert.Contains(fish, "complete -c dcmtagger -l config -r -d 'Config file'\n")
+	assert.Contains(fish, "complete -c dcmtagger -n '__fish_use_subcommand' -a dump -d 'Write the elements'\n")
+
+	_, err = completionScript("tcsh", commands, specs)
+	assert.EqualError(err, "unknown shell 'tcsh', supported: bash, zsh, fish")
+}
+
+func TestManPage(t *testing.T) {
+	assert := assert.New(t)
+
+	commands := []commandSpec{{"dump", "Write the elements"}}
+	specs := []flagSpec{{long: "scrolloff", help: "Lines kept visible", defaultVal: "3", takesValue: true},
+		{long: "verbose", short: "v", help: "Log more"}}
+	page := manPage(commands, specs, "v1.2")
+	assert.Contains(page, ".TH DCMTAGGER 1 \"\" \"dcmtagger v1.2\" \"User Commands\"\n")
+	assert.Contains(page, ".TP\n\\fB\\-\\-scrolloff\\fR \\fISCROLLOFF\\fR\nLines kept visible [default: 3]\n")
+	assert.Contains(page, ".TP\n\\fB\\-v\\fR, \\fB\\-\\-verbose\\fR\nLog more\n")
+	assert.Contains(page, ".SH COMMANDS\n.TP\n\\fBdump\\fR\nWrite the elements\n")
+	assert.Contains(page, ".SH KEYS\n.nf\nNavigation\n")
+}
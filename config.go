@@ -0,0 +1,204 @@
+package main
+
+import (
+	"cmp"
+	"crypto/tls"
+	"crypto/x509"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io/fs"
+	"net"
+	"os"
+	"path/filepath"
+	"slices"
+	"strconv"
+	"strings"
+)
+
+// config are the settings read from the config file, see README.md for the format
+type config struct {
+	TLS     *tlsSettings    `json:"tls"`     // TLS for all network connections if set
+	AEs     []remoteAE      `json:"aes"`     // named remote AEs, usable instead of host and port
+	Tree    treeStyle       `json:"tree"`    // line graphics and icons of the tree
+	Startup startupSettings `json:"startup"` // tree layout and expansion the UI starts with
+	Blank   []blankRegion   `json:"blank"`   // pixel regions :blank zeroes if none are given
+	Privacy []string        `json:"privacy"` // keywords of the elements masked in privacy mode, defaults if empty
+}
+
+// startupSettings are the tree layout the UI starts with and the number of levels expanded below the root after
+// loading, overridden by --sort and --expand
+type startupSettings struct {
+	Sort   string `json:"sort"`
+	Expand int    `json:"expand"`
+}
+
+// sortModeNames are the names of the sort modes for --sort, in the order of their keys 1 to 8
+var sortModeNames = []string{"filename", "tag", "diff", "study", "size", "time", "natural", "instance"}
+
+// startup returns the key of the sort mode and the number of expanded levels to start with, the given options
+// override the config, a nil expand keeps the configured one
+func (c config) startup(sort string, expand *int) (rune, int, error) {
+	name := cmp.Or(sort, c.Startup.Sort, sortModeNames[0])
+	index := slices.Index(sortModeNames, name)
+	if index < 0 {
+		return 0, 0, fmt.Errorf("unknown sort mode '%s', supported: %s", name, strings.Join(sortModeNames, ", "))
+	}
+	levels := c.Startup.Expand
+	if expand != nil {
+		levels = *expand
+	}
+	if levels < 0 {
+		return 0, 0, fmt.Errorf("invalid number of expanded levels %d", levels)
+	}
+	return rune('1' + index), levels, nil
+}
+
+// remoteAE are the connection parameters of a remote application entity
+type remoteAE struct {
+	Name       string       `json:"name"`
+	Host       string       `json:"host"`
+	Port       int          `json:"port"`
+	CalledAET  string       `json:"calledAET"`
+	CallingAET string       `json:"callingAET"` // own AE title, the one given with --aet if empty
+	TLS        *tlsSettings `json:"tls"`        // overrides the global TLS settings
+}
+
+func (ae remoteAE) address() string {
+	return net.JoinHostPort(ae.Host, strconv.Itoa(ae.Port))
+}
+
+func (ae remoteAE) String() string {
+	return fmt.Sprintf("%s - %s@%s", ae.Name, ae.CalledAET, ae.address())
+}
+
+// resolveAE returns the remote AE with the given name from the config, or one for the given host:port with the given
+// AE titles. AE titles and TLS settings missing in the config are taken from the arguments and global settings.
+func (c config) resolveAE(nameOrAddress, callingAET, calledAET string) (remoteAE, error) {
+	ae := remoteAE{Name: nameOrAddress, CalledAET: calledAET}
+	found := false
+	for _, configured := range c.AEs {
+		if configured.Name == nameOrAddress {
+			ae, found = configured, true
+		}
+	}
+	if !found {
+		host, port, err := net.SplitHostPort(nameOrAddress)
+		if err != nil {
+			return ae, fmt.Errorf("'%s' is neither a configured AE nor host:port", nameOrAddress)
+		}
+		ae.Host = host
+		if ae.Port, err = strconv.Atoi(port); err != nil {
+			return ae, fmt.Errorf("invalid port in '%s'", nameOrAddress)
+		}
+	}
+	if ae.CallingAET == "" {
+		ae.CallingAET = callingAET
+	}
+	if ae.CalledAET == "" {
+		ae.CalledAET = calledAET
+	}
+	if ae.TLS == nil {
+		ae.TLS = c.TLS
+	}
+	return ae, nil
+}
+
+// tlsSettings are the certificate files for TLS secured associations, all in PEM format
+type tlsSettings struct {
+	CA       string `json:"ca"`       // trusted CAs, the system ones if empty
+	Cert     string `json:"cert"`     // own certificate, required for the storage SCP, optional client certificate
+	Key      string `json:"key"`      // private key of the own certificate
+	Insecure bool   `json:"insecure"` // skip verification of the peer certificate, for testing only
+}
+
+func configPath() string {
+	dir, err := os.UserConfigDir()
+	if err != nil {
+		return ""
+	}
+	return filepath.Join(dir, "dcmtagger", "config.json")
+}
+
+// loadConfig reads the config file, a missing file results in the default config
+func loadConfig(path string) (config, error) {
+	var c config
+	data, err := os.ReadFile(path)
+	if errors.Is(err, fs.ErrNotExist) || path == "" {
+		return c, nil
+	}
+	if err != nil {
+		return c, err
+	}
+	if err := json.Unmarshal(data, &c); err != nil {
+		return c, fmt.Errorf("invalid config file %s: %w", path, err)
+	}
+	return c, nil
+}
+
+func (s *tlsSettings) certificates() ([]tls.Certificate, error) {
+	if s.Cert == "" {
+		return nil, nil
+	}
+	certificate, err := tls.LoadX509KeyPair(s.Cert, s.Key)
+	if err != nil {
+		return nil, err
+	}
+	return []tls.Certificate{certificate}, nil
+}
+
+func (s *tlsSettings) certPool() (*x509.CertPool, error) {
+	if s.CA == "" {
+		return nil, nil
+	}
+	data, err := os.ReadFile(s.CA)
+	if err != nil {
+		return nil, err
+	}
+	pool := x509.NewCertPool()
+	if !pool.AppendCertsFromPEM(data) {
+		return nil, fmt.Errorf("no certificates found in %s", s.CA)
+	}
+	return pool, nil
+}
+
+// clientConfig returns the TLS config for outgoing associations, nil if TLS isn't configured
+func (s *tlsSettings) clientConfig() (*tls.Config, error) {
+	if s == nil {
+		return nil, nil
+	}
+	certificates, err := s.certificates()
+	if err != nil {
+		return nil, err
+	}
+	pool, err := s.certPool()
+	if err != nil {
+		return nil, err
+	}
+	return &tls.Config{Certificates: certificates, RootCAs: pool, InsecureSkipVerify: s.Insecure}, nil
+}
+
+// serverConfig returns the TLS config for the storage SCP, nil if TLS isn't configured. If CAs are given, clients
+// have to authenticate with a certificate signed by them.
+func (s *tlsSettings) serverConfig() (*tls.Config, error) {
+	if s == nil {
+		return nil, nil
+	}
+	certificates, err := s.certificates()
+	if err != nil {
+		return nil, err
+	}
+	if len(certificates) == 0 {
+		return nil, errors.New("TLS for the storage SCP needs a certificate")
+	}
+	pool, err := s.certPool()
+	if err != nil {
+		return nil, err
+	}
+	serverConfig := &tls.Config{Certificates: certificates}
+	if pool != nil {
+		serverConfig.ClientCAs = pool
+		serverConfig.ClientAuth = tls.RequireAndVerifyClientCert
+	}
+	return serverConfig, nil
+}
@@ -0,0 +1,90 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestLoadConfig(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	c, err := loadConfig(filepath.Join(dir, "missing.json"))
+	assert.NoError(err)
+	assert.Nil(c.TLS)
+
+	path := filepath.Join(dir, "config.json")
+	os.WriteFile(path, []byte(`{"tls": {"ca": "ca.pem", "insecure": true}}`), 0o600)
+	c, err = loadConfig(path)
+	assert.NoError(err)
+	assert.Equal(&tlsSettings{CA: "ca.pem", Insecure: true}, c.TLS)
+
+	os.WriteFile(path, []byte(`{"tls": `), 0o600)
+	_, err = loadConfig(path)
+	assert.Error(err)
+}
+
+func TestResolveAE(t *testing.T) {
+	assert := assert.New(t)
+
+	global := &tlsSettings{Insecure: true}
+	own := &tlsSettings{CA: "ca.pem"}
+	c := config{TLS: global, AEs: []remoteAE{
+		{Name: "ARCHIVE", Host: "pacs", Port: 11112, CalledAET: "ARCHIVE"},
+		{Name: "RIS", Host: "ris", Port: 104, CallingAET: "CT01", TLS: own},
+	}}
+
+	ae, err := c.resolveAE("ARCHIVE", "DCMTAGGER", "ANY-SCP")
+	assert.NoError(err)
+	assert.Equal("pacs:11112", ae.address())
+	assert.Equal("DCMTAGGER", ae.CallingAET)
+	assert.Equal("ARCHIVE", ae.CalledAET)
+	assert.Same(global, ae.TLS)
+
+	ae, err = c.resolveAE("RIS", "DCMTAGGER", "ANY-SCP")
+	assert.NoError(err)
+	assert.Equal("CT01", ae.CallingAET)
+	assert.Equal("ANY-SCP", ae.CalledAET)
+	assert.Same(own, ae.TLS)
+
+	ae, err = c.resolveAE("localhost:4242", "DCMTAGGER", "ANY-SCP")
+	assert.NoError(err)
+	assert.Equal("localhost", ae.Host)
+	assert.Equal(4242, ae.Port)
+	assert.Equal("ANY-SCP", ae.CalledAET)
+
+	_, err = c.resolveAE("UNKNOWN", "DCMTAGGER", "ANY-SCP")
+	assert.Error(err)
+	_, err = c.resolveAE("localhost:port", "DCMTAGGER", "ANY-SCP")
+	assert.Error(err)
+}
+
+func TestStartup(t *testing.T) {
+	assert := assert.New(t)
+
+	mode, levels, err := config{}.startup("", nil)
+	assert.NoError(err)
+	assert.Equal('1', mode)
+	assert.Equal(0, levels)
+
+	c := config{Startup: startupSettings{Sort: "study", Expand: 2}}
+	mode, levels, err = c.startup("", nil)
+	assert.NoError(err)
+	assert.Equal('4', mode)
+	assert.Equal(2, levels)
+
+	none := 0
+	mode, levels, err = c.startup("diff", &none)
+	assert.NoError(err)
+	assert.Equal('3', mode)
+	assert.Equal(0, levels)
+
+	_, _, err = c.startup("tags", nil)
+	assert.EqualError(err, "unknown sort mode 'tags', supported: filename, tag, diff, study, size, time, natural, instance")
+	negative := -1
+	_, _, err = c.startup("", &negative)
+	assert.Error(err)
+}
@@ -0,0 +1,267 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"slices"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// module are the attributes of an IOD module which have to be present with a value (type 1) or may be empty (type 2)
+type module struct {
+	type1 []tag.Tag
+	type2 []tag.Tag
+}
+
+// modules commonly used by image IODs, see PS3.3 C.7 and C.12
+var (
+	commonModules = module{
+		type1: []tag.Tag{tag.SOPClassUID, tag.SOPInstanceUID, tag.StudyInstanceUID, tag.SeriesInstanceUID, tag.Modality},
+		type2: []tag.Tag{tag.PatientName, tag.PatientID, tag.PatientBirthDate, tag.PatientSex, tag.StudyDate, tag.StudyTime,
+			tag.ReferringPhysicianName, tag.StudyID, tag.AccessionNumber, tag.SeriesNumber, tag.Manufacturer, tag.InstanceNumber},
+	}
+	imagePixelModule = module{
+		type1: []tag.Tag{tag.SamplesPerPixel, tag.PhotometricInterpretation, tag.Rows, tag.Columns, tag.BitsAllocated,
+			tag.BitsStored, tag.HighBit, tag.PixelRepresentation, tag.PixelData},
+	}
+	imagePlaneModule = module{
+		type1: []tag.Tag{tag.PixelSpacing, tag.ImageOrientationPatient, tag.ImagePositionPatient, tag.FrameOfReferenceUID},
+		type2: []tag.Tag{tag.SliceThickness, tag.PositionReferenceIndicator},
+	}
+)
+
+// checklist are the required attributes of the IOD of a SOP class
+type checklist struct {
+	name    string
+	modules []module
+}
+
+var checklists = map[string]checklist{
+	"1.2.840.10008.5.1.4.1.1.2": {"CT Image", []module{commonModules, imagePixelModule, imagePlaneModule, {
+		type1: []tag.Tag{tag.ImageType, tag.RescaleIntercept, tag.RescaleSlope},
+		type2: []tag.Tag{tag.KVP, tag.AcquisitionNumber},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.4": {"MR Image", []module{commonModules, imagePixelModule, imagePlaneModule, {
+		type1: []tag.Tag{tag.ImageType, tag.ScanningSequence, tag.SequenceVariant},
+		type2: []tag.Tag{tag.ScanOptions, tag.MRAcquisitionType, tag.EchoTime, tag.EchoTrainLength},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.128": {"PET Image", []module{commonModules, imagePixelModule, imagePlaneModule, {
+		type1: []tag.Tag{tag.SeriesType, tag.Units, tag.CountsSource, tag.DecayCorrection, tag.ImageType},
+		type2: []tag.Tag{tag.CorrectedImage},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.6.1": {"US Image", []module{commonModules, imagePixelModule, {
+		type2: []tag.Tag{tag.ImageType},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.3.1": {"US Multi-frame Image", []module{commonModules, imagePixelModule, {
+		type1: []tag.Tag{tag.NumberOfFrames, tag.FrameIncrementPointer},
+		type2: []tag.Tag{tag.ImageType},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.1": {"CR Image", []module{commonModules, imagePixelModule, {
+		type2: []tag.Tag{tag.BodyPartExamined, tag.ViewPosition},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.1.1": {"DX Image", []module{commonModules, imagePixelModule, {
+		type1: []tag.Tag{tag.ImageType, tag.PresentationIntentType, tag.RescaleIntercept, tag.RescaleSlope},
+		type2: []tag.Tag{tag.ImagerPixelSpacing},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.1.2": {"Digital Mammography Image", []module{commonModules, imagePixelModule, {
+		type1: []tag.Tag{tag.ImageType, tag.PresentationIntentType, tag.ImageLaterality},
+		type2: []tag.Tag{tag.ImagerPixelSpacing},
+	}}},
+	"1.2.840.10008.5.1.4.1.1.7": {"Secondary Capture Image", []module{commonModules, imagePixelModule, {
+		type1: []tag.Tag{tag.ConversionType},
+	}}},
+}
+
+// conformanceResult are the required attributes a file lacks according to the checklist of its SOP class
+type conformanceResult struct {
+	checklist string
+	missing   []tag.Tag
+	empty     []tag.Tag // type 1 attributes without value
+}
+
+// isEmptyElement returns true if the element has no value, binary values only if their length is zero
+func isEmptyElement(e *dicom.Element) bool {
+	switch e.Value.ValueType() {
+	case dicom.Strings, dicom.Ints, dicom.Floats:
+		return strings.TrimSpace(strings.Join(getValueStrings(e), "")) == ""
+	case dicom.Sequences:
+		return len(getSequenceItems(e)) == 0
+	}
+	return e.ValueLength == 0
+}
+
+// checkConformance checks the dataset against the checklist of its SOP class, false if there is no checklist for it
+func checkConformance(dataset dicom.Dataset) (conformanceResult, bool) {
+	list, ok := checklists[getDatasetValue(dataset, tag.SOPClassUID)]
+	if !ok {
+		return conformanceResult{}, false
+	}
+	result := conformanceResult{checklist: list.name}
+	for _, m := range list.modules {
+		for _, t := range m.type1 {
+			if e, err := dataset.FindElementByTag(t); err != nil {
+				result.missing = append(result.missing, t)
+			} else if t != tag.PixelData && isEmptyElement(e) {
+				result.empty = append(result.empty, t)
+			}
+		}
+		for _, t := range m.type2 {
+			if _, err := dataset.FindElementByTag(t); err != nil {
+				result.missing = append(result.missing, t)
+			}
+		}
+	}
+	return result, true
+}
+
+func (r conformanceResult) String() string {
+	if len(r.missing) == 0 && len(r.empty) == 0 {
+		return r.checklist + " ✓"
+	}
+	parts := []string{r.checklist}
+	if len(r.missing) > 0 {
+		parts = append(parts, fmt.Sprintf("%d missing", len(r.missing)))
+	}
+	if len(r.empty) > 0 {
+		parts = append(parts, fmt.Sprintf("%d empty", len(r.empty)))
+	}
+	return strings.Join(parts, ", ")
+}
+
+// details lists the required attributes the file lacks or which have no value, shown in the details of the file node
+func (r conformanceResult) details() string {
+	lines := []string{"Checklist " + r.checklist + ":"}
+	for _, t := range r.missing {
+		lines = append(lines, fmt.Sprintf("  missing (%04x,%04x) %s", t.Group, t.Element, tagKeyword(t)))
+	}
+	for _, t := range r.empty {
+		lines = append(lines, fmt.Sprintf("  empty (%04x,%04x) %s", t.Group, t.Element, tagKeyword(t)))
+	}
+	if len(lines) == 1 {
+		lines = append(lines, "  all required attributes present")
+	}
+	return strings.Join(lines, "\n")
+}
+
+// hasConformanceProblems returns true if a file lacks required attributes of the checklist of its SOP class
+func hasConformanceProblems(datasetsWithFilename []DatasetEntry) bool {
+	return slices.ContainsFunc(datasetsWithFilename, func(entry DatasetEntry) bool {
+		result, ok := checkConformance(entry.dataset)
+		return ok && (len(result.missing) > 0 || len(result.empty) > 0)
+	})
+}
+
+// markConformance appends the conformance check result to all file nodes below the given node
+func markConformance(root *tview.TreeNode) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if entry, ok := node.GetReference().(*DatasetEntry); ok && !entry.indexed { // only the key tags are known
+			if result, ok := checkConformance(entry.dataset); ok {
+				node.SetText(fmt.Sprintf("%s [%s]", node.GetText(), result))
+			}
+		}
+		return true
+	})
+}
+
+// conformanceIssues are the files lacking required attributes and the required elements without value, so ]w and [w
+// can jump between them. Files are keyed like picked ones since the trees reference copies of the entries if a query
+// is active.
+type conformanceIssues struct {
+	files    map[string]bool
+	elements map[*dicom.Element]bool
+}
+
+func newConformanceIssues(datasetsWithFilename []DatasetEntry) conformanceIssues {
+	issues := conformanceIssues{map[string]bool{}, map[*dicom.Element]bool{}}
+	for i := range datasetsWithFilename {
+		entry := &datasetsWithFilename[i]
+		result, ok := checkConformance(entry.dataset)
+		if !ok || entry.indexed {
+			continue
+		}
+		if len(result.missing) > 0 {
+			issues.files[pickKey(entry)] = true
+		}
+		for _, t := range result.empty {
+			if e, err := entry.dataset.FindElementByTag(t); err == nil {
+				issues.elements[e] = true
+			}
+		}
+	}
+	return issues
+}
+
+// isIssue returns true if the node is the one of a file lacking required attributes or of an empty required element
+func (c conformanceIssues) isIssue(node *tview.TreeNode) bool {
+	switch ref := node.GetReference().(type) {
+	case *DatasetEntry:
+		return c.files[pickKey(ref)]
+	case *dicom.Element:
+		return c.elements[ref]
+	}
+	return false
+}
+
+// checkSeriesConformance summarizes per series how many files lack which required attributes, the progress counts series
+func checkSeriesConformance(ctx context.Context, datasetsWithFilename []DatasetEntry, progress func(done, total int)) (string, error) {
+	seriesUIDs, entriesBySeries := groupBySeries(datasetsWithFilename)
+	lines := make([]string, 0)
+	for i, seriesUID := range seriesUIDs {
+		if err := ctx.Err(); err != nil {
+			return "", err
+		}
+		if progress != nil {
+			progress(i, len(seriesUIDs))
+		}
+		entries := entriesBySeries[seriesUID]
+		lines = append(lines, getSeriesTitle(seriesUID, entries))
+		missing, empty := map[tag.Tag]int{}, map[tag.Tag]int{}
+		checklistNames, unchecked := make([]string, 0), 0
+		for _, entry := range entries {
+			result, ok := checkConformance(entry.dataset)
+			if !ok {
+				unchecked++
+				continue
+			}
+			if !slices.Contains(checklistNames, result.checklist) {
+				checklistNames = append(checklistNames, result.checklist)
+			}
+			for _, t := range result.missing {
+				missing[t]++
+			}
+			for _, t := range result.empty {
+				empty[t]++
+			}
+		}
+		if len(checklistNames) > 0 {
+			lines = append(lines, "  checked as "+strings.Join(checklistNames, ", "))
+		}
+		if unchecked > 0 {
+			lines = append(lines, fmt.Sprintf("  %d instances of SOP classes without checklist", unchecked))
+		}
+		lines = append(lines, formatTagCounts("missing", missing, len(entries))...)
+		lines = append(lines, formatTagCounts("empty", empty, len(entries))...)
+		if len(checklistNames) > 0 && len(missing) == 0 && len(empty) == 0 {
+			lines = append(lines, "  ok - all required attributes present")
+		}
+		lines = append(lines, "")
+	}
+	return strings.Join(lines, "\n"), nil
+}
+
+func formatTagCounts(status string, counts map[tag.Tag]int, total int) []string {
+	tags := make([]tag.Tag, 0, len(counts))
+	for t := range counts {
+		tags = append(tags, t)
+	}
+	slices.SortFunc(tags, compareTags)
+	lines := make([]string, 0)
+	for _, t := range tags {
+		lines = append(lines, fmt.Sprintf("  %s (%04x,%04x) %s in %d of %d instances", status, t.Group, t.Element, tagKeyword(t), counts[t], total))
+	}
+	return lines
+}
@@ -0,0 +1,94 @@
+package main
+
+import (
+	"context"
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestCheckConformance(t *testing.T) {
+	assert := assert.New(t)
+
+	result, ok := checkConformance(newInstanceFixture(t, 1).with(tag.ImageType, []string{""}).dataset())
+	assert.True(ok)
+	assert.Equal("CT Image", result.checklist)
+	assert.Contains(result.missing, tag.PixelData)
+	assert.Contains(result.missing, tag.KVP)
+	assert.NotContains(result.missing, tag.Rows)
+	assert.NotContains(result.missing, tag.ImageType)
+	assert.Equal([]tag.Tag{tag.ImageType}, result.empty)
+	assert.Contains(result.String(), "CT Image, ")
+	assert.Contains(result.String(), "1 empty")
+	assert.Contains(result.details(), "Checklist CT Image:\n")
+	assert.Contains(result.details(), "  missing (0018,0060) KVP\n")
+	assert.Contains(result.details(), "  empty (0008,0008) ImageType")
+
+	complete := newFixture(t).
+		with(tag.SOPClassUID, []string{"1.2.840.10008.5.1.4.1.1.7"}).
+		with(tag.ConversionType, []string{"WSD"})
+	for _, m := range []module{commonModules, imagePixelModule} {
+		for _, required := range append(m.type1, m.type2...) {
+			if required != tag.SOPClassUID {
+				complete.withPrivate(required.Group, required.Element, "LO", []string{"x"})
+			}
+		}
+	}
+	result, ok = checkConformance(complete.dataset())
+	assert.True(ok)
+	assert.Equal("Secondary Capture Image ✓", result.String())
+	assert.Equal("Checklist Secondary Capture Image:\n  all required attributes present", result.details())
+
+	_, ok = checkConformance(newFixture(t).with(tag.SOPClassUID, []string{"1.2.3"}).dataset())
+	assert.False(ok)
+}
+
+func TestConformanceSummaryAndMarks(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newInstanceFixture(t, 1).entry("1.dcm"),
+		newInstanceFixture(t, 2).entry("2.dcm"),
+		newFixture(t).with(tag.SeriesInstanceUID, []string{"9.9"}).entry("other.dcm"),
+	}
+	assert.True(hasConformanceProblems(datasets))
+	assert.False(hasConformanceProblems(datasets[2:]))
+	summary, err := checkSeriesConformance(context.Background(), datasets, nil)
+	assert.NoError(err)
+	assert.Contains(summary, "checked as CT Image")
+	assert.Contains(summary, "missing (7fe0,0010) PixelData in 2 of 2 instances")
+	assert.Contains(summary, "1 instances of SOP classes without checklist")
+
+	_, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	markConformance(root)
+	assert.Contains(root.GetChildren()[0].GetText(), "1.dcm [CT Image, ")
+	assert.Equal("other.dcm", root.GetChildren()[2].GetText())
+}
+
+func TestConformanceIssues(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newFixture(t).with(tag.SeriesInstanceUID, []string{"9.9"}).entry("other.dcm"),
+		newInstanceFixture(t, 1).with(tag.ImageType, []string{""}).entry("1.dcm"),
+	}
+	imageType, err := datasets[1].dataset.FindElementByTag(tag.ImageType)
+	assert.NoError(err)
+	issues := newConformanceIssues(datasets)
+
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	assert.False(issues.isIssue(root.GetChildren()[0]))
+	assert.True(issues.isIssue(root.GetChildren()[1]))
+
+	tree.SetCurrentNode(root)
+	assert.True(jumpToMatchingNode(tree, false, issues.isIssue))
+	assert.Same(root.GetChildren()[1], tree.GetCurrentNode())
+	assert.True(jumpToMatchingNode(tree, false, issues.isIssue))
+	assert.Same(imageType, tree.GetCurrentNode().GetReference())
+	assert.True(jumpToMatchingNode(tree, true, issues.isIssue))
+	assert.Same(root.GetChildren()[1], tree.GetCurrentNode())
+
+	assert.False(jumpToMatchingNode(tree, false, newConformanceIssues(datasets[:1]).isIssue))
+}
@@ -0,0 +1,78 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"runtime/debug"
+	"sync"
+	"time"
+
+	"github.com/gdamore/tcell/v2"
+)
+
+// the screen restored before a crash is reported, nil while the UI isn't running
+var (
+	crashMutex  sync.Mutex
+	crashScreen tcell.Screen
+)
+
+func setCrashScreen(screen tcell.Screen) {
+	crashMutex.Lock()
+	defer crashMutex.Unlock()
+	crashScreen = screen
+}
+
+// crashLogPath returns the file crashes are written to in the user's cache directory
+func crashLogPath() string {
+	dir, err := os.UserCacheDir()
+	if err != nil {
+		return ""
+	}
+	return filepath.Join(dir, "dcmtagger", "crash.log")
+}
+
+// recoverCrash reports a panic and exits instead of leaving the terminal in raw mode, it has to be deferred at the
+// start of every goroutine
+func recoverCrash() {
+	if p := recover(); p != nil {
+		reportCrash(p, debug.Stack())
+	}
+}
+
+// reportCrash restores the terminal, appends the panic with its stack to the crash log and exits. Goroutines
+// panicking at the same time wait for the exit.
+func reportCrash(p any, stack []byte) {
+	crashMutex.Lock()
+	if crashScreen != nil {
+		crashScreen.Fini()
+		popTerminalTitle()
+	}
+	fmt.Fprintf(os.Stderr, "dcmtagger crashed: %v\n", p)
+	path := crashLogPath()
+	if err := writeCrashLog(path, p, stack, time.Now()); err != nil {
+		fmt.Fprintf(os.Stderr, "%s\n", stack)
+	} else {
+		fmt.Fprintf(os.Stderr, "Details are written to %s, please attach them when reporting the crash\n", path)
+	}
+	os.Exit(2)
+}
+
+// writeCrashLog appends the panic with version, time and stack trace to the crash log
+func writeCrashLog(path string, p any, stack []byte, now time.Time) error {
+	if path == "" {
+		return fmt.Errorf("no crash log")
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return err
+	}
+	file, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0o644)
+	if err != nil {
+		return err
+	}
+	_, err = fmt.Fprintf(file, "%s dcmtagger %s: %v\n%s\n", now.Format(time.RFC3339), version, p, stack)
+	if closeErr := file.Close(); err == nil {
+		err = closeErr
+	}
+	return err
+}
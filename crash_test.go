@@ -0,0 +1,29 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestWriteCrashLog(t *testing.T) {
+	assert := assert.New(t)
+
+	path := filepath.Join(t.TempDir(), "dcmtagger", "crash.log")
+	now := time.Date(2024, 3, 4, 10, 11, 12, 0, time.UTC)
+	assert.NoError(writeCrashLog(path, "index out of range", []byte("goroutine 1 [running]:\nmain.main()"), now))
+	assert.NoError(writeCrashLog(path, "nil map", []byte("goroutine 7 [running]:"), now))
+
+	data, err := os.ReadFile(path)
+	assert.NoError(err)
+	lines := strings.Split(string(data), "\n")
+	assert.Equal("2024-03-04T10:11:12Z dcmtagger unknown: index out of range", lines[0])
+	assert.Equal("goroutine 1 [running]:", lines[1])
+	assert.Contains(string(data), "dcmtagger unknown: nil map\ngoroutine 7 [running]:")
+
+	assert.Error(writeCrashLog("", "panic", nil, now))
+}
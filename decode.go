@@ -0,0 +1,134 @@
+package main
+
+import (
+	"encoding/binary"
+	"fmt"
+	"math"
+	"strconv"
+	"strings"
+	"unicode"
+	"unicode/utf16"
+
+	"github.com/suyashkumar/dicom"
+)
+
+// number of values of an array reading shown, the rest is elided
+const decodedValuesShown = 16
+
+// decoding is one interpretation of the raw bytes of a value
+type decoding struct {
+	name  string
+	value string
+}
+
+// canTryDecode returns true for the elements :decode interprets, i.e. unknown and private ones
+func canTryDecode(e *dicom.Element) bool {
+	return e.RawValueRepresentation == "UN" || e.Tag.Group%2 == 1
+}
+
+// isPlausibleText returns true if the text is not empty and all runes are printable or whitespace
+func isPlausibleText(text string) bool {
+	return text != "" && strings.IndexFunc(text, func(r rune) bool {
+		return !unicode.IsPrint(r) && !unicode.IsSpace(r)
+	}) < 0
+}
+
+// isPlausibleFloat returns true if the value is zero or has a magnitude usual for measurements, random bytes mostly
+// result in tiny, huge or not finite floats
+func isPlausibleFloat(v float64) bool {
+	return v == 0 || (math.Abs(v) >= 1e-6 && math.Abs(v) <= 1e9)
+}
+
+// formatDecodedValues joins the first values, the count is added if values are elided
+func formatDecodedValues(values []string) string {
+	if len(values) <= decodedValuesShown {
+		return strings.Join(values, `\`)
+	}
+	return fmt.Sprintf(`%s\… (%d values)`, strings.Join(values[:decodedValuesShown], `\`), len(values))
+}
+
+// decodeArray reads the data as array of values of the given size, nil if the length doesn't fit or a value isn't
+// plausible
+func decodeArray(data []byte, size int, read func(b []byte) (string, bool)) []string {
+	if len(data) == 0 || len(data)%size != 0 {
+		return nil
+	}
+	values := make([]string, 0, len(data)/size)
+	for i := 0; i < len(data); i += size {
+		value, ok := read(data[i : i+size])
+		if !ok {
+			return nil
+		}
+		values = append(values, value)
+	}
+	return values
+}
+
+// tryDecode interprets the bytes of a value of unknown encoding, e.g. a vendor blob, as text, little endian floats and
+// integers, and returns the plausible readings
+func tryDecode(data []byte) []decoding {
+	decodings := make([]decoding, 0)
+	ascii := strings.TrimRight(string(data), "\x00 ")
+	isASCII := isPlausibleText(ascii) && strings.IndexFunc(ascii, func(r rune) bool { return r > unicode.MaxASCII }) < 0
+	if isASCII {
+		decodings = append(decodings, decoding{"ASCII", strconv.Quote(ascii)})
+	}
+	if !isASCII && len(data)%2 == 0 {
+		units := make([]uint16, 0, len(data)/2)
+		for i := 0; i < len(data); i += 2 {
+			units = append(units, binary.LittleEndian.Uint16(data[i:]))
+		}
+		if text := strings.TrimRight(string(utf16.Decode(units)), "\x00 "); isPlausibleText(text) {
+			decodings = append(decodings, decoding{"UTF-16", strconv.Quote(text)})
+		}
+	}
+
+	readFloat := func(v float64, bitSize int) (string, bool) {
+		return strconv.FormatFloat(v, 'g', -1, bitSize), isPlausibleFloat(v)
+	}
+	arrays := []struct {
+		name string
+		size int
+		read func(b []byte) (string, bool)
+	}{
+		{"float32", 4, func(b []byte) (string, bool) {
+			return readFloat(float64(math.Float32frombits(binary.LittleEndian.Uint32(b))), 32)
+		}},
+		{"float64", 8, func(b []byte) (string, bool) {
+			return readFloat(math.Float64frombits(binary.LittleEndian.Uint64(b)), 64)
+		}},
+		{"int16", 2, func(b []byte) (string, bool) {
+			return strconv.Itoa(int(int16(binary.LittleEndian.Uint16(b)))), true
+		}},
+		{"uint16", 2, func(b []byte) (string, bool) {
+			return strconv.Itoa(int(binary.LittleEndian.Uint16(b))), true
+		}},
+		{"int32", 4, func(b []byte) (string, bool) {
+			return strconv.Itoa(int(int32(binary.LittleEndian.Uint32(b)))), true
+		}},
+	}
+	for _, array := range arrays {
+		if values := decodeArray(data, array.size, array.read); values != nil {
+			name := fmt.Sprintf("%s[%d]", array.name, len(values))
+			decodings = append(decodings, decoding{name, formatDecodedValues(values)})
+		}
+	}
+	return decodings
+}
+
+// formatDecodings lists the plausible readings of the value of the element for the detail pane
+func formatDecodings(e *dicom.Element) string {
+	data, err := getRawValue(e)
+	if err != nil {
+		return "Can't decode: " + err.Error()
+	}
+	decodings := tryDecode(data)
+	if len(decodings) == 0 {
+		return fmt.Sprintf("No plausible reading of %d bytes", len(data))
+	}
+	lines := []string{fmt.Sprintf("Possible readings of %d bytes, numbers little endian:", len(data))}
+	for _, d := range decodings {
+		lines = append(lines, fmt.Sprintf("  %s: %s", d.name, d.value))
+	}
+	return strings.Join(lines, "\n")
+}
@@ -0,0 +1,59 @@
+package main
+
+import (
+	"encoding/binary"
+	"math"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestTryDecode(t *testing.T) {
+	assert := assert.New(t)
+
+	readings := func(data []byte) map[string]string {
+		m := map[string]string{}
+		for _, d := range tryDecode(data) {
+			m[d.name] = d.value
+		}
+		return m
+	}
+
+	text := readings([]byte("SIEMENS CSA\x00"))
+	assert.Equal(`"SIEMENS CSA"`, text["ASCII"])
+	assert.NotContains(text, "UTF-16")
+	assert.NotContains(text, "float32[3]")
+
+	assert.Equal(`"Ω-Öl"`, readings([]byte{0xa9, 0x03, '-', 0, 0xd6, 0, 'l', 0})["UTF-16"])
+
+	floats := make([]byte, 0)
+	for _, v := range []float32{1.5, -0.25, 120} {
+		floats = binary.LittleEndian.AppendUint32(floats, math.Float32bits(v))
+	}
+	numbers := readings(floats)
+	assert.Equal(`1.5\-0.25\120`, numbers["float32[3]"])
+	assert.NotContains(numbers, "ASCII")
+	assert.Contains(numbers, "int32[3]")
+	assert.Contains(numbers, "int16[6]")
+
+	ints := make([]byte, 0)
+	for i := range 20 {
+		ints = binary.LittleEndian.AppendUint16(ints, uint16(i+1000))
+	}
+	assert.Equal(`1000\1001\1002\1003\1004\1005\1006\1007\1008\1009\1010\1011\1012\1013\1014\1015\… (20 values)`,
+		readings(ints)["uint16[20]"])
+
+	assert.Empty(tryDecode(nil))
+}
+
+func TestFormatDecodings(t *testing.T) {
+	assert := assert.New(t)
+
+	blob, err := newFixture(t).withPrivate(0x0029, 0x1010, "UN", []byte("CSA1")).dataset().
+		FindElementByTag(tag.Tag{Group: 0x0029, Element: 0x1010})
+	assert.NoError(err)
+	assert.True(canTryDecode(blob))
+	assert.Contains(formatDecodings(blob), "Possible readings of 4 bytes, numbers little endian:\n  ASCII: \"CSA1\"")
+	assert.False(canTryDecode(newFixture(t).element(tag.PatientName, []string{"Doe^John"})))
+}
@@ -0,0 +1,167 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// getValueStrings returns the single values of a multi-valued element as strings
+func getValueStrings(e *dicom.Element) []string {
+	switch e.Value.ValueType() {
+	case dicom.Strings:
+		return e.Value.GetValue().([]string)
+	case dicom.Ints:
+		values := make([]string, 0)
+		for _, v := range e.Value.GetValue().([]int) {
+			values = append(values, strconv.Itoa(v))
+		}
+		return values
+	case dicom.Floats:
+		values := make([]string, 0)
+		for _, v := range e.Value.GetValue().([]float64) {
+			values = append(values, strconv.FormatFloat(v, 'g', -1, 64))
+		}
+		return values
+	}
+	return []string{getValueString(e)}
+}
+
+// getDatasetValue returns the values of the given tag joined by '\', empty if the tag doesn't exist
+func getDatasetValue(dataset dicom.Dataset, t tag.Tag) string {
+	e, err := dataset.FindElementByTag(t)
+	if err != nil {
+		return ""
+	}
+	return strings.TrimSpace(strings.Join(getValueStrings(e), "\\"))
+}
+
+// getFileSummary composes a compact overview of the most important attributes of a file
+func getFileSummary(entry *DatasetEntry) string {
+	value := func(t tag.Tag) string {
+		return getDatasetValue(entry.dataset, t)
+	}
+
+	lines := []string{entry.path, ""}
+	add := func(label, text string) {
+		if text != "" {
+			lines = append(lines, fmt.Sprintf("%-12s %s", label+":", text))
+		}
+	}
+	add("Modality", value(tag.Modality))
+	add("Body part", value(tag.BodyPartExamined))
+	add("Series", strings.TrimSpace(value(tag.SeriesNumber)+" "+value(tag.SeriesDescription)))
+	add("Instance", value(tag.InstanceNumber))
+	if rows, columns := value(tag.Rows), value(tag.Columns); rows != "" && columns != "" {
+		add("Size", rows+" × "+columns)
+	}
+	if spacing := strings.Split(value(tag.PixelSpacing), "\\"); len(spacing) == 2 {
+		add("Spacing", spacing[0]+" × "+spacing[1]+" mm")
+	}
+	acquisition := value(tag.AcquisitionDateTime)
+	if acquisition == "" {
+		acquisition = strings.TrimSpace(value(tag.AcquisitionDate) + " " + value(tag.AcquisitionTime))
+	}
+	add("Acquired", acquisition)
+
+	return strings.Join(lines, "\n")
+}
+
+// getElementDetails shows the element with its untruncated value
+func getElementDetails(e *dicom.Element) string {
+	lines := []string{
+		fmt.Sprintf("%-12s %s", "Tag:", e.Tag.String()),
+		fmt.Sprintf("%-12s %s", "Name:", getTagName(e)),
+		fmt.Sprintf("%-12s %s", "VR:", e.RawValueRepresentation),
+		fmt.Sprintf("%-12s %d", "Length:", e.ValueLength),
+	}
+	if values := getValueStrings(e); isNumericVR(e.RawValueRepresentation) && len(values) > 1 {
+		lines = append(lines, fmt.Sprintf("%-12s %s", "VM:", formatVM(e.Tag, len(values))))
+		for i, line := range formatIndexedValues(values) {
+			label := ""
+			if i == 0 {
+				label = "Values:"
+			}
+			lines = append(lines, fmt.Sprintf("%-12s %s", label, line))
+		}
+	} else {
+		lines = append(lines, fmt.Sprintf("%-12s %s", "Value:", strings.Join(values, "\\")))
+	}
+	if text, ok := formatDateTimes(e); ok {
+		lines = append(lines, fmt.Sprintf("%-12s %s", "Formatted:", text))
+	}
+	if e.RawValueRepresentation == "PN" {
+		label := "Components:"
+		for _, value := range getValueStrings(e) {
+			for _, line := range parsePersonName(value).details() {
+				lines = append(lines, fmt.Sprintf("%-12s %s", label, line))
+				label = ""
+			}
+		}
+	}
+	return strings.Join(lines, "\n")
+}
+
+// numericVRs are the value representations of numbers
+var numericVRs = []string{"DS", "IS", "FL", "FD", "SL", "SS", "UL", "US", "SV", "UV"}
+
+func isNumericVR(vr string) bool {
+	return slices.Contains(numericVRs, vr)
+}
+
+// formatIndexedValues returns a line per value with its index, the values aligned at the decimal point
+func formatIndexedValues(values []string) []string {
+	integerWidth := func(value string) int {
+		if i := strings.IndexAny(value, ".eE"); i >= 0 {
+			return i
+		}
+		return len(value)
+	}
+	widest := 0
+	for _, value := range values {
+		widest = max(widest, integerWidth(strings.TrimSpace(value)))
+	}
+	indexWidth := len(fmt.Sprint(len(values) - 1))
+	lines := make([]string, 0, len(values))
+	for i, value := range values {
+		value = strings.TrimSpace(value)
+		lines = append(lines, fmt.Sprintf("[%*d] %s%s", indexWidth, i, strings.Repeat(" ", widest-integerWidth(value)), value))
+	}
+	return lines
+}
+
+// formatVM returns the number of values, followed by the value multiplicity of the dictionary if it doesn't allow it
+func formatVM(t tag.Tag, count int) string {
+	info, err := findTag(t)
+	if err != nil || info.VM == "" || matchesVM(info.VM, count) {
+		return fmt.Sprint(count)
+	}
+	return fmt.Sprintf("%d, expected %s", count, info.VM)
+}
+
+// matchesVM returns true if the number of values is allowed by the value multiplicity like 6, 1-3, 1-n or 2-2n
+func matchesVM(vm string, count int) bool {
+	low, high, isRange := strings.Cut(vm, "-")
+	minimum, err := strconv.Atoi(low)
+	if err != nil {
+		return true // unknown notation
+	}
+	if !isRange {
+		return count == minimum
+	}
+	if step, ok := strings.CutSuffix(high, "n"); ok {
+		multiple := 1
+		if step != "" {
+			if multiple, err = strconv.Atoi(step); err != nil {
+				return true
+			}
+		}
+		return count >= minimum && count%multiple == 0
+	}
+	maximum, err := strconv.Atoi(high)
+	return err != nil || (count >= minimum && count <= maximum)
+}
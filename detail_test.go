@@ -0,0 +1,41 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestFormatIndexedValues(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal([]string{"[0]   1", "[1] -10.5", "[2]   0.25"}, formatIndexedValues([]string{"1", "-10.5", " 0.25 "}))
+	lines := formatIndexedValues(make([]string, 11))
+	assert.Equal("[ 0] ", lines[0])
+	assert.Equal("[10] ", lines[10])
+}
+
+func TestMatchesVM(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.True(matchesVM("6", 6))
+	assert.False(matchesVM("6", 5))
+	assert.True(matchesVM("1-3", 3))
+	assert.False(matchesVM("1-3", 4))
+	assert.True(matchesVM("1-n", 7))
+	assert.True(matchesVM("2-2n", 4))
+	assert.False(matchesVM("2-2n", 3))
+	assert.True(matchesVM("unknown", 3))
+}
+
+func TestNumericElementDetails(t *testing.T) {
+	assert := assert.New(t)
+
+	e := newFixture(t).element(tag.ImageOrientationPatient, []string{"1", "0", "0", "0", "1", "0"})
+	assert.Contains(getElementDetails(e), "VM:          6\nValues:      [0] 1\n             [1] 0\n")
+	e = newFixture(t).element(tag.ImageOrientationPatient, []string{"1", "0", "0", "0", "1"})
+	assert.Contains(getElementDetails(e), "VM:          5, expected 6\n")
+	e = newFixture(t).element(tag.SliceThickness, []string{"1.25"})
+	assert.Contains(getElementDetails(e), "Value:       1.25")
+}
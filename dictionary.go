@@ -0,0 +1,191 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"encoding/xml"
+	"errors"
+	"fmt"
+	"io"
+	"io/fs"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// part6URL is the machine-readable dictionary of the current edition of the standard, PS3.6 in DocBook XML
+const part6URL = "https://dicom.nema.org/medical/dicom/current/source/docbook/part06/part06.xml"
+
+// dictionaryEntry is a data element of the dictionary of the standard, as stored in the user dictionary file
+type dictionaryEntry struct {
+	Tag     string `json:"tag"` // like (0008,0001)
+	Keyword string `json:"keyword"`
+	VR      string `json:"vr"`
+	VM      string `json:"vm"`
+	Retired bool   `json:"retired,omitempty"`
+}
+
+// userDictionary are the elements of the dictionary downloaded with update-dict, loaded once at startup. They take
+// precedence over the dictionary of the dicom library, so elements added to the standard since then resolve to names.
+var userDictionary = map[tag.Tag]dictionaryEntry{}
+
+func dictionaryPath() string {
+	dir, err := os.UserConfigDir()
+	if err != nil {
+		return ""
+	}
+	return filepath.Join(dir, "dcmtagger", "dictionary.json")
+}
+
+// findTag looks the tag up in the user dictionary, then in the one of the dicom library
+func findTag(t tag.Tag) (tag.Info, error) {
+	if entry, ok := userDictionary[t]; ok {
+		return tag.Info{Tag: t, VR: entry.VR, Name: entry.Keyword, VM: entry.VM}, nil
+	}
+	return tag.Find(t)
+}
+
+// findTagByName looks the keyword up in the dictionary of the dicom library, then in the user dictionary
+func findTagByName(keyword string) (tag.Info, error) {
+	info, err := tag.FindByName(keyword)
+	if err == nil {
+		return info, nil
+	}
+	for t, entry := range userDictionary {
+		if entry.Keyword == keyword {
+			return tag.Info{Tag: t, VR: entry.VR, Name: entry.Keyword, VM: entry.VM}, nil
+		}
+	}
+	return info, err
+}
+
+// loadDictionary reads the user dictionary, a missing file results in an empty one
+func loadDictionary(path string) (map[tag.Tag]dictionaryEntry, error) {
+	dictionary := map[tag.Tag]dictionaryEntry{}
+	data, err := os.ReadFile(path)
+	if errors.Is(err, fs.ErrNotExist) || path == "" {
+		return dictionary, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	var entries []dictionaryEntry
+	if err := json.Unmarshal(data, &entries); err != nil {
+		return nil, fmt.Errorf("invalid dictionary file %s: %w", path, err)
+	}
+	for _, entry := range entries {
+		if !isTagLiteral(entry.Tag) {
+			return nil, fmt.Errorf("invalid tag '%s' in dictionary file %s", entry.Tag, path)
+		}
+		t, _ := parseQueryTag(entry.Tag)
+		dictionary[t] = entry
+	}
+	return dictionary, nil
+}
+
+// parsePart6 reads the data elements from the registry tables of PS3.6 in DocBook XML. Their rows are tag, name,
+// keyword, VR, VM and an optional RET. Repeating groups like (60xx,0010) are left out, they have no single tag, as
+// well as elements without keyword like the item delimiters.
+func parsePart6(r io.Reader) ([]dictionaryEntry, error) {
+	decoder := xml.NewDecoder(r)
+	entries := make([]dictionaryEntry, 0)
+	var cells []string
+	var cell *strings.Builder
+	for {
+		token, err := decoder.Token()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			return nil, fmt.Errorf("invalid dictionary XML: %w", err)
+		}
+		switch token := token.(type) {
+		case xml.StartElement:
+			switch token.Name.Local {
+			case "tr":
+				cells = make([]string, 0)
+			case "td":
+				cell = &strings.Builder{}
+			}
+		case xml.CharData:
+			if cell != nil {
+				cell.Write(token)
+			}
+		case xml.EndElement:
+			switch token.Name.Local {
+			case "td":
+				if cell != nil {
+					cells = append(cells, strings.TrimSpace(strings.ReplaceAll(cell.String(), "\u200b", "")))
+				}
+				cell = nil
+			case "tr":
+				if len(cells) >= 5 && isTagLiteral(cells[0]) && cells[2] != "" {
+					entries = append(entries, dictionaryEntry{
+						Tag:     strings.ToUpper(cells[0]),
+						Keyword: cells[2],
+						VR:      cells[3],
+						VM:      cells[4],
+						Retired: len(cells) > 5 && strings.HasPrefix(cells[5], "RET"),
+					})
+				}
+				cells = nil
+			}
+		}
+	}
+	if len(entries) == 0 {
+		return nil, errors.New("no data elements found, is it the XML of PS3.6?")
+	}
+	return entries, nil
+}
+
+// openDictionarySource opens the URL or file of the dictionary
+func openDictionarySource(ctx context.Context, source string) (io.ReadCloser, error) {
+	if !strings.HasPrefix(source, "http://") && !strings.HasPrefix(source, "https://") {
+		return os.Open(source)
+	}
+	request, err := http.NewRequestWithContext(ctx, http.MethodGet, source, nil)
+	if err != nil {
+		return nil, err
+	}
+	response, err := http.DefaultClient.Do(request)
+	if err != nil {
+		return nil, err
+	}
+	if response.StatusCode != http.StatusOK {
+		response.Body.Close()
+		return nil, fmt.Errorf("downloading %s: %s", source, response.Status)
+	}
+	return response.Body, nil
+}
+
+// updateDictionary reads the dictionary from the URL or file and writes it as user dictionary to the given path. It
+// returns the number of elements and of those unknown to the dictionary of the dicom library.
+func updateDictionary(ctx context.Context, source, path string) (int, int, error) {
+	r, err := openDictionarySource(ctx, source)
+	if err != nil {
+		return 0, 0, err
+	}
+	defer r.Close()
+	entries, err := parsePart6(r)
+	if err != nil {
+		return 0, 0, err
+	}
+	added := 0
+	for _, entry := range entries {
+		t, _ := parseQueryTag(entry.Tag)
+		if _, err := tag.Find(t); err != nil {
+			added++
+		}
+	}
+	data, err := json.MarshalIndent(entries, "", "  ")
+	if err != nil {
+		return 0, 0, err
+	}
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		return 0, 0, err
+	}
+	return len(entries), added, os.WriteFile(path, append(data, '\n'), 0o644)
+}
@@ -0,0 +1,83 @@
+package main
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// part6Excerpt has the layout of the registry tables of PS3.6 in DocBook XML
+const part6Excerpt = `<?xml version="1.0" encoding="utf-8"?>
+<book xmlns="http://docbook.org/ns/docbook">
+<table xml:id="table_6-1"><thead><tr><th><para>Tag</para></th><th><para>Name</para></th><th><para>Keyword</para></th>
+<th><para>VR</para></th><th><para>VM</para></th><th><para/></th></tr></thead>
+<tbody>
+<tr><td><para>(0044,ab00)</para></td><td><para>Retired Future Element</para></td>
+<td><para>Retired&#8203;Future&#8203;Element</para></td>
+<td><para>UL</para></td><td><para>1</para></td><td><para>RET</para></td></tr>
+<tr><td><para>(0010,0010)</para></td><td><para>Patient's Name</para></td><td><para>PatientName</para></td>
+<td><para>PN</para></td><td><para>1</para></td><td><para/></td></tr>
+<tr><td><para>(0044,ab01)</para></td><td><para>Future Element</para></td><td><para>FutureElement</para></td>
+<td><para>US or SS</para></td><td><para>1-n</para></td><td><para/></td></tr>
+<tr><td><para>(60xx,0010)</para></td><td><para>Overlay Rows</para></td><td><para>OverlayRows</para></td>
+<td><para>US</para></td><td><para>1</para></td><td><para/></td></tr>
+<tr><td><para>(FFFE,E000)</para></td><td><para>Item</para></td><td><para/></td>
+<td><para>See Note</para></td><td><para>1</para></td><td><para/></td></tr>
+</tbody></table>
+</book>`
+
+func TestParsePart6(t *testing.T) {
+	assert := assert.New(t)
+
+	entries, err := parsePart6(strings.NewReader(part6Excerpt))
+	assert.NoError(err)
+	assert.Equal([]dictionaryEntry{
+		{Tag: "(0044,AB00)", Keyword: "RetiredFutureElement", VR: "UL", VM: "1", Retired: true},
+		{Tag: "(0010,0010)", Keyword: "PatientName", VR: "PN", VM: "1"},
+		{Tag: "(0044,AB01)", Keyword: "FutureElement", VR: "US or SS", VM: "1-n"},
+	}, entries)
+
+	_, err = parsePart6(strings.NewReader("<book/>"))
+	assert.Error(err)
+}
+
+func TestUpdateDictionary(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	source, path := filepath.Join(dir, "part06.xml"), filepath.Join(dir, "dcmtagger", "dictionary.json")
+	assert.NoError(os.WriteFile(source, []byte(part6Excerpt), 0o644))
+	count, added, err := updateDictionary(context.Background(), source, path)
+	assert.NoError(err)
+	assert.Equal(3, count)
+	assert.Equal(2, added)
+
+	dictionary, err := loadDictionary(path)
+	assert.NoError(err)
+	assert.Len(dictionary, 3)
+	future := tag.Tag{Group: 0x0044, Element: 0xab01}
+	assert.Equal("FutureElement", dictionary[future].Keyword)
+
+	defer func(previous map[tag.Tag]dictionaryEntry) { userDictionary = previous }(userDictionary)
+	userDictionary = dictionary
+	info, err := findTag(future)
+	assert.NoError(err)
+	assert.Equal("FutureElement", info.Name)
+	assert.Equal("US", getImplicitVR(future))
+	info, err = findTagByName("FutureElement")
+	assert.NoError(err)
+	assert.Equal(future, info.Tag)
+	assert.True(isRetiredTag(tag.Tag{Group: 0x0044, Element: 0xab00}))
+
+	dictionary, err = loadDictionary(filepath.Join(dir, "missing.json"))
+	assert.NoError(err)
+	assert.Empty(dictionary)
+	assert.NoError(os.WriteFile(path, []byte(`[{"tag": "0044ab01"}]`), 0o644))
+	_, err = loadDictionary(path)
+	assert.Error(err)
+}
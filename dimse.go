@@ -0,0 +1,474 @@
+//go:build !nonet
+
+package main
+
+import (
+	"context"
+	"crypto/tls"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"strings"
+	"time"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// Minimal implementation of the DICOM upper layer protocol (PS3.8) and the DIMSE services (PS3.7) needed for the
+// network features. Only implicit VR little endian is proposed for queries.
+
+// networking tells if the DICOM network features are compiled in, they are left out with -tags nonet
+const networking = true
+
+const (
+	pduAssociateRQ byte = 0x01
+	pduAssociateAC byte = 0x02
+	pduAssociateRJ byte = 0x03
+	pduDataTF      byte = 0x04
+	pduReleaseRQ   byte = 0x05
+	pduReleaseRP   byte = 0x06
+	pduAbort       byte = 0x07
+)
+
+const (
+	applicationContextUID = "1.2.840.10008.3.1.1.1"
+
+	defaultMaxPDULength  = 16384
+	maxReceivedPDULength = 64 << 20 // larger PDUs are rejected as broken
+	dialTimeout          = 10 * time.Second
+)
+
+// DIMSE command fields
+const (
+	commandCStoreRQ  = 0x0001
+	commandCStoreRSP = 0x8001
+	commandCFindRQ   = 0x0020
+	commandCFindRSP  = 0x8020
+	commandCEchoRQ   = 0x0030
+	commandCEchoRSP  = 0x8030
+)
+
+// DIMSE status values
+const (
+	statusSuccess        = 0x0000
+	statusPending        = 0xff00
+	statusPendingWarning = 0xff01
+	statusCannotProcess  = 0xc000
+)
+
+// value of CommandDataSetType if no dataset follows the command
+const noDataset = 0x0101
+
+type presentationContext struct {
+	id               byte
+	abstractSyntax   string
+	transferSyntaxes []string // proposed ones in the request, the accepted one in the response
+	result           byte     // 0 if accepted
+}
+
+// associateParams are the parameters of an A-ASSOCIATE-RQ or A-ASSOCIATE-AC PDU
+type associateParams struct {
+	calledAET    string
+	callingAET   string
+	contexts     []presentationContext
+	maxPDULength uint32 // maximal PDU length the sender can receive, 0 for unlimited
+}
+
+func appendItem(buf []byte, itemType byte, data []byte) []byte {
+	buf = append(buf, itemType, 0)
+	buf = binary.BigEndian.AppendUint16(buf, uint16(len(data)))
+	return append(buf, data...)
+}
+
+// encode returns the PDU data of an association request or, with 'accept', of the acceptance
+func (p associateParams) encode(accept bool) []byte {
+	data := make([]byte, 68)
+	binary.BigEndian.PutUint16(data, 1) // protocol version
+	copy(data[4:20], fmt.Sprintf("%-16s", p.calledAET))
+	copy(data[20:36], fmt.Sprintf("%-16s", p.callingAET))
+	data = appendItem(data, 0x10, []byte(applicationContextUID))
+	for _, pc := range p.contexts {
+		item := []byte{pc.id, 0, 0, 0}
+		if accept {
+			item[2] = pc.result
+			transferSyntax := implicitVRLittleEndian
+			if len(pc.transferSyntaxes) > 0 {
+				transferSyntax = pc.transferSyntaxes[0]
+			}
+			item = appendItem(item, 0x40, []byte(transferSyntax))
+			data = appendItem(data, 0x21, item)
+		} else {
+			item = appendItem(item, 0x30, []byte(pc.abstractSyntax))
+			for _, transferSyntax := range pc.transferSyntaxes {
+				item = appendItem(item, 0x40, []byte(transferSyntax))
+			}
+			data = appendItem(data, 0x20, item)
+		}
+	}
+	userInfo := appendItem(nil, 0x51, binary.BigEndian.AppendUint32(nil, p.maxPDULength))
+	userInfo = appendItem(userInfo, 0x52, []byte(implementationClassUID))
+	userInfo = appendItem(userInfo, 0x55, []byte(implementationVersion))
+	return appendItem(data, 0x50, userInfo)
+}
+
+type pduItem struct {
+	itemType byte
+	data     []byte
+}
+
+func splitItems(data []byte) ([]pduItem, error) {
+	items := make([]pduItem, 0)
+	for len(data) > 0 {
+		if len(data) < 4 {
+			return nil, errors.New("truncated PDU item")
+		}
+		length := int(binary.BigEndian.Uint16(data[2:]))
+		if len(data) < 4+length {
+			return nil, errors.New("truncated PDU item")
+		}
+		items = append(items, pduItem{data[0], data[4 : 4+length]})
+		data = data[4+length:]
+	}
+	return items, nil
+}
+
+// decodeAssociate parses the PDU data of an association request or acceptance
+func decodeAssociate(data []byte) (associateParams, error) {
+	var p associateParams
+	if len(data) < 68 {
+		return p, errors.New("truncated association PDU")
+	}
+	p.calledAET = strings.TrimSpace(string(data[4:20]))
+	p.callingAET = strings.TrimSpace(string(data[20:36]))
+	items, err := splitItems(data[68:])
+	if err != nil {
+		return p, err
+	}
+	for _, item := range items {
+		switch item.itemType {
+		case 0x20, 0x21:
+			if len(item.data) < 4 {
+				return p, errors.New("truncated presentation context")
+			}
+			pc := presentationContext{id: item.data[0], result: item.data[2]}
+			subItems, err := splitItems(item.data[4:])
+			if err != nil {
+				return p, err
+			}
+			for _, subItem := range subItems {
+				switch subItem.itemType {
+				case 0x30:
+					pc.abstractSyntax = trimUID(subItem.data)
+				case 0x40:
+					pc.transferSyntaxes = append(pc.transferSyntaxes, trimUID(subItem.data))
+				}
+			}
+			p.contexts = append(p.contexts, pc)
+		case 0x50:
+			subItems, err := splitItems(item.data)
+			if err != nil {
+				return p, err
+			}
+			for _, subItem := range subItems {
+				if subItem.itemType == 0x51 && len(subItem.data) >= 4 {
+					p.maxPDULength = binary.BigEndian.Uint32(subItem.data)
+				}
+			}
+		}
+	}
+	return p, nil
+}
+
+func writePDU(w io.Writer, pduType byte, data []byte) error {
+	pdu := []byte{pduType, 0}
+	pdu = binary.BigEndian.AppendUint32(pdu, uint32(len(data)))
+	_, err := w.Write(append(pdu, data...))
+	return err
+}
+
+func readPDU(r io.Reader) (byte, []byte, error) {
+	header := make([]byte, 6)
+	if _, err := io.ReadFull(r, header); err != nil {
+		return 0, nil, err
+	}
+	length := binary.BigEndian.Uint32(header[2:])
+	if length > maxReceivedPDULength {
+		return 0, nil, fmt.Errorf("PDU length %d exceeds the maximum", length)
+	}
+	data := make([]byte, length)
+	if _, err := io.ReadFull(r, data); err != nil {
+		return 0, nil, err
+	}
+	return header[0], data, nil
+}
+
+// association is an established association, either requested by us or accepted from a peer
+type association struct {
+	conn         net.Conn
+	maxPDULength uint32                // maximal length of PDUs sent to the peer, 0 for unlimited
+	contexts     []presentationContext // accepted presentation contexts with abstract and transfer syntax
+	messageID    uint16
+}
+
+// dialAssociation connects to the remote AE, secured by TLS if a TLS config is given, and negotiates the given
+// presentation contexts, the negotiation is recorded in the protocol log. Closing the context aborts the connection.
+func dialAssociation(ctx context.Context, address, callingAET, calledAET string, contexts []presentationContext, tlsConfig *tls.Config, netLog *protocolLog) (*association, error) {
+	dialer := &net.Dialer{Timeout: dialTimeout}
+	var conn net.Conn
+	var err error
+	if tlsConfig != nil {
+		conn, err = (&tls.Dialer{NetDialer: dialer, Config: tlsConfig}).DialContext(ctx, "tcp", address)
+	} else {
+		conn, err = dialer.DialContext(ctx, "tcp", address)
+	}
+	if err != nil {
+		netLog.addNegotiation(address, associateParams{calledAET: calledAET, callingAET: callingAET, contexts: contexts}, nil, err)
+		return nil, err
+	}
+	context.AfterFunc(ctx, func() { conn.Close() })
+
+	request := associateParams{calledAET: calledAET, callingAET: callingAET, contexts: contexts, maxPDULength: defaultMaxPDULength}
+	if err := writePDU(conn, pduAssociateRQ, request.encode(false)); err != nil {
+		conn.Close()
+		return nil, err
+	}
+	pduType, data, err := readPDU(conn)
+	if err != nil {
+		netLog.addNegotiation(address, request, nil, err)
+		conn.Close()
+		return nil, err
+	}
+	switch pduType {
+	case pduAssociateAC:
+		accepted, err := decodeAssociate(data)
+		if err != nil {
+			netLog.addNegotiation(address, request, nil, err)
+			conn.Close()
+			return nil, err
+		}
+		netLog.addNegotiation(address, request, &accepted, nil)
+		a := &association{conn: conn, maxPDULength: accepted.maxPDULength}
+		for _, pc := range accepted.contexts {
+			for _, proposed := range contexts {
+				if pc.id == proposed.id && pc.result == 0 && len(pc.transferSyntaxes) > 0 {
+					pc.abstractSyntax = proposed.abstractSyntax
+					a.contexts = append(a.contexts, pc)
+				}
+			}
+		}
+		return a, nil
+	case pduAssociateRJ:
+		conn.Close()
+		err := errors.New("association rejected")
+		if len(data) >= 4 {
+			err = fmt.Errorf("association rejected: result %d, source %d, reason %d", data[1], data[2], data[3])
+		}
+		netLog.addNegotiation(address, request, nil, err)
+		return nil, err
+	default:
+		conn.Close()
+		err := fmt.Errorf("unexpected PDU type %d in association negotiation", pduType)
+		netLog.addNegotiation(address, request, nil, err)
+		return nil, err
+	}
+}
+
+// context returns the accepted presentation context for the abstract syntax
+func (a *association) context(abstractSyntax string) (presentationContext, error) {
+	for _, pc := range a.contexts {
+		if pc.abstractSyntax == abstractSyntax {
+			return pc, nil
+		}
+	}
+	return presentationContext{}, fmt.Errorf("no presentation context accepted for %s", abstractSyntax)
+}
+
+// sendPDVs sends the command or dataset fragmented to fit into the maximal PDU length of the peer
+func (a *association) sendPDVs(pcID byte, isCommand bool, data []byte) error {
+	maxFragment := len(data)
+	if a.maxPDULength != 0 {
+		maxFragment = max(int(a.maxPDULength)-6, 1) // minus PDV item header
+	}
+	for {
+		n := min(len(data), maxFragment)
+		var control byte
+		if isCommand {
+			control |= 0x01
+		}
+		if n == len(data) {
+			control |= 0x02 // last fragment
+		}
+		pdv := binary.BigEndian.AppendUint32(nil, uint32(n+2))
+		pdv = append(pdv, pcID, control)
+		pdv = append(pdv, data[:n]...)
+		if err := writePDU(a.conn, pduDataTF, pdv); err != nil {
+			return err
+		}
+		data = data[n:]
+		if control&0x02 != 0 {
+			return nil
+		}
+	}
+}
+
+// dimseMessage is a received DIMSE message
+type dimseMessage struct {
+	pcID    byte
+	command []*dicom.Element
+	dataset []byte // encoded in the transfer syntax of the presentation context, nil if there is none
+}
+
+func (m dimseMessage) commandInt(t tag.Tag) int {
+	for _, e := range m.command {
+		if e.Tag == t && e.Value.ValueType() == dicom.Ints {
+			if values := e.Value.GetValue().([]int); len(values) > 0 {
+				return values[0]
+			}
+		}
+	}
+	return -1
+}
+
+func (m dimseMessage) commandString(t tag.Tag) string {
+	for _, e := range m.command {
+		if e.Tag == t && e.Value.ValueType() == dicom.Strings {
+			return strings.Join(e.Value.GetValue().([]string), `\`)
+		}
+	}
+	return ""
+}
+
+// send sends a DIMSE message, the dataset may be nil
+func (a *association) send(pcID byte, command []byte, dataset []byte) error {
+	if err := a.sendPDVs(pcID, true, command); err != nil {
+		return err
+	}
+	if dataset == nil {
+		return nil
+	}
+	return a.sendPDVs(pcID, false, dataset)
+}
+
+// receive reads the next DIMSE message, io.EOF is returned if the peer released the association
+func (a *association) receive() (dimseMessage, error) {
+	var message dimseMessage
+	var command []byte
+	commandDone, datasetDone := false, false
+	for {
+		pduType, data, err := readPDU(a.conn)
+		if err != nil {
+			return message, err
+		}
+		switch pduType {
+		case pduDataTF:
+		case pduReleaseRQ:
+			writePDU(a.conn, pduReleaseRP, make([]byte, 4))
+			a.conn.Close()
+			return message, io.EOF
+		case pduAbort:
+			a.conn.Close()
+			return message, errors.New("association aborted by peer")
+		default:
+			return message, fmt.Errorf("unexpected PDU type %d", pduType)
+		}
+
+		for len(data) > 0 {
+			if len(data) < 6 {
+				return message, errors.New("truncated PDV item")
+			}
+			length := int(binary.BigEndian.Uint32(data))
+			if length < 2 || len(data) < 4+length {
+				return message, errors.New("invalid PDV item length")
+			}
+			message.pcID = data[4]
+			control, value := data[5], data[6:4+length]
+			if control&0x01 != 0 {
+				command = append(command, value...)
+				commandDone = control&0x02 != 0
+			} else {
+				message.dataset = append(message.dataset, value...)
+				datasetDone = control&0x02 != 0
+			}
+			data = data[4+length:]
+		}
+
+		if commandDone && message.command == nil {
+			message.command, err = decodeImplicit(command)
+			if err != nil {
+				return message, err
+			}
+		}
+		if commandDone && (datasetDone || message.commandInt(tagCommandDataSetType) == noDataset) {
+			return message, nil
+		}
+	}
+}
+
+// release releases the association and closes the connection
+func (a *association) release() error {
+	defer a.conn.Close()
+	if err := writePDU(a.conn, pduReleaseRQ, make([]byte, 4)); err != nil {
+		return err
+	}
+	pduType, _, err := readPDU(a.conn)
+	if err != nil {
+		return err
+	}
+	if pduType != pduReleaseRP {
+		return fmt.Errorf("unexpected PDU type %d on release", pduType)
+	}
+	return nil
+}
+
+func (a *association) nextMessageID() uint16 {
+	a.messageID++
+	return a.messageID
+}
+
+// find sends a C-FIND request with the identifier and returns the identifiers of all matches
+func (a *association) find(ctx context.Context, abstractSyntax string, identifier []*dicom.Element) ([][]*dicom.Element, error) {
+	pc, err := a.context(abstractSyntax)
+	if err != nil {
+		return nil, err
+	}
+	dataset, err := encodeImplicit(identifier)
+	if err != nil {
+		return nil, err
+	}
+	command := encodeCommand(
+		commandValue{tagAffectedSOPClassUID, abstractSyntax},
+		commandValue{tagCommandField, uint16(commandCFindRQ)},
+		commandValue{tagMessageID, a.nextMessageID()},
+		commandValue{tagPriority, uint16(0)},
+		commandValue{tagCommandDataSetType, uint16(0)},
+	)
+	if err := a.send(pc.id, command, dataset); err != nil {
+		return nil, err
+	}
+
+	matches := make([][]*dicom.Element, 0)
+	for {
+		if err := ctx.Err(); err != nil {
+			return matches, err
+		}
+		response, err := a.receive()
+		if err != nil {
+			return matches, err
+		}
+		switch status := response.commandInt(tagStatus); status {
+		case statusSuccess:
+			return matches, nil
+		case statusPending, statusPendingWarning:
+			match, err := decodeImplicit(response.dataset)
+			if err != nil {
+				return matches, err
+			}
+			matches = append(matches, match)
+		default:
+			return matches, fmt.Errorf("C-FIND failed with status 0x%04x", status)
+		}
+	}
+}
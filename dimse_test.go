@@ -0,0 +1,110 @@
+//go:build !nonet
+
+package main
+
+import (
+	"context"
+	"net"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestImplicitRoundTrip(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t)
+	item := []*dicom.Element{f.element(tag.Modality, []string{"CT"}), f.element(tag.ScheduledProcedureStepID, []string{"SPS1"})}
+	elements := []*dicom.Element{
+		f.element(tag.PatientName, []string{"Doe^John"}),
+		f.element(tag.Rows, []int{512}),
+		f.element(tag.SOPInstanceUID, []string{"1.2.3"}),
+		f.element(tag.ScheduledProcedureStepSequence, [][]*dicom.Element{item}),
+	}
+	data, err := encodeImplicit(elements)
+	assert.NoError(err)
+	decoded, err := decodeImplicit(data)
+	assert.NoError(err)
+
+	assert.Len(decoded, 4)
+	assert.Equal("1.2.3", getValueString(decoded[0])) // sorted by tag
+	assert.Equal("Doe^John", getValueString(decoded[1]))
+	assert.Equal([]int{512}, decoded[2].Value.GetValue())
+	items := decoded[3].Value.GetValue().([]*dicom.SequenceItemValue)
+	assert.Len(items, 1)
+	assert.Equal("SPS1", getValueString(items[0].GetValue().([]*dicom.Element)[1]))
+}
+
+// serveWorklist accepts one association and answers the C-FIND request with the given matches
+func serveWorklist(t *testing.T, listener net.Listener, matches [][]*dicom.Element, queries chan<- []*dicom.Element) {
+	conn, err := listener.Accept()
+	if err != nil {
+		t.Error(err)
+		return
+	}
+	defer conn.Close()
+	_, data, err := readPDU(conn)
+	if err != nil {
+		t.Error(err)
+		return
+	}
+	request, err := decodeAssociate(data)
+	if err != nil {
+		t.Error(err)
+		return
+	}
+	writePDU(conn, pduAssociateAC, request.encode(true))
+
+	a := &association{conn: conn, maxPDULength: request.maxPDULength}
+	query, err := a.receive()
+	if err != nil {
+		t.Error(err)
+		return
+	}
+	identifier, _ := decodeImplicit(query.dataset)
+	queries <- identifier
+	for _, match := range matches {
+		dataset, _ := encodeImplicit(match)
+		command := encodeCommand(
+			commandValue{tagCommandField, uint16(commandCFindRSP)},
+			commandValue{tagStatus, uint16(statusPending)},
+			commandValue{tagCommandDataSetType, uint16(0)},
+		)
+		a.send(query.pcID, command, dataset)
+	}
+	command := encodeCommand(
+		commandValue{tagCommandField, uint16(commandCFindRSP)},
+		commandValue{tagStatus, uint16(statusSuccess)},
+		commandValue{tagCommandDataSetType, uint16(noDataset)},
+	)
+	a.send(query.pcID, command, nil)
+	a.receive() // release
+}
+
+func TestQueryWorklist(t *testing.T) {
+	assert := assert.New(t)
+
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer listener.Close()
+	f := newFixture(t)
+	match := []*dicom.Element{f.element(tag.PatientName, []string{"Doe^John"}), f.element(tag.AccessionNumber, []string{"A1"})}
+	queries := make(chan []*dicom.Element, 1)
+	go serveWorklist(t, listener, [][]*dicom.Element{match}, queries)
+
+	entries, err := queryWorklist(context.Background(), remoteAE{Host: "127.0.0.1", Port: listener.Addr().(*net.TCPAddr).Port, CallingAET: "SCU", CalledAET: "WORKLIST"}, worklistQuery{modality: "CT"}, nil, nil)
+	assert.NoError(err)
+	assert.Len(entries, 1)
+	assert.Equal("001 Doe^John A1", entries[0].filename)
+
+	identifier := <-queries
+	stepSequence := dicom.Dataset{Elements: identifier}
+	e, err := stepSequence.FindElementByTag(tag.ScheduledProcedureStepSequence)
+	assert.NoError(err)
+	step := e.Value.GetValue().([]*dicom.SequenceItemValue)[0].GetValue().([]*dicom.Element)
+	assert.Equal("CT", getDatasetValue(dicom.Dataset{Elements: step}, tag.Modality))
+}
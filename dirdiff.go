@@ -0,0 +1,111 @@
+package main
+
+import (
+	"fmt"
+	"path/filepath"
+	"strings"
+
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// instanceDiff are the top level elements of an instance differing between the two directories
+type instanceDiff struct {
+	key  string // SOPInstanceUID or for files without one the path relative to the directory
+	a, b *DatasetEntry
+	rows []comparisonRow
+}
+
+// directoryDiff compares the instances of two directories, e.g. of a study before and after processing, matched by
+// their SOPInstanceUID
+type directoryDiff struct {
+	a, b         string
+	onlyA, onlyB []*DatasetEntry
+	differing    []instanceDiff
+	identical    int
+}
+
+// instanceKey returns the SOPInstanceUID of the entry, the path relative to the directory if it has none
+func instanceKey(dir string, entry *DatasetEntry) string {
+	if uid := getDatasetValue(entry.dataset, tag.SOPInstanceUID); uid != "" {
+		return uid
+	}
+	if rel, err := filepath.Rel(dir, entry.path); err == nil {
+		return rel
+	}
+	return entry.filename
+}
+
+func newDirectoryDiff(a, b string, datasetsA, datasetsB []DatasetEntry) directoryDiff {
+	d := directoryDiff{a: a, b: b}
+	entriesB := map[string]*DatasetEntry{}
+	for i := range datasetsB {
+		entriesB[instanceKey(b, &datasetsB[i])] = &datasetsB[i]
+	}
+	matched := map[string]bool{}
+	for i := range datasetsA {
+		entryA := &datasetsA[i]
+		key := instanceKey(a, entryA)
+		entryB, ok := entriesB[key]
+		if !ok {
+			d.onlyA = append(d.onlyA, entryA)
+			continue
+		}
+		matched[key] = true
+		rows := make([]comparisonRow, 0)
+		for _, row := range newComparisonReport(key, []DatasetEntry{*entryA, *entryB}).Rows {
+			if row.Differs {
+				rows = append(rows, row)
+			}
+		}
+		if len(rows) == 0 {
+			d.identical++
+		} else {
+			d.differing = append(d.differing, instanceDiff{key, entryA, entryB, rows})
+		}
+	}
+	for i := range datasetsB {
+		if !matched[instanceKey(b, &datasetsB[i])] {
+			d.onlyB = append(d.onlyB, &datasetsB[i])
+		}
+	}
+	return d
+}
+
+// differs returns true if instances differ or are missing on one side
+func (d directoryDiff) differs() bool {
+	return len(d.onlyA) > 0 || len(d.onlyB) > 0 || len(d.differing) > 0
+}
+
+// formatComparisonValue returns the value of the row in the file with the index, quoted to show whitespace
+func formatComparisonValue(row comparisonRow, index int) string {
+	if !row.Present[index] {
+		return "missing"
+	}
+	return fmt.Sprintf("%q", row.Values[index])
+}
+
+func (d directoryDiff) String() string {
+	var b strings.Builder
+	fmt.Fprintf(&b, "Comparing %s with %s\n\n", d.a, d.b)
+	fmt.Fprintf(&b, "%d instances in both, %d identical, %d differing, %d only in %s, %d only in %s\n",
+		d.identical+len(d.differing), d.identical, len(d.differing), len(d.onlyA), d.a, len(d.onlyB), d.b)
+	for _, side := range []struct {
+		dir     string
+		entries []*DatasetEntry
+	}{{d.a, d.onlyA}, {d.b, d.onlyB}} {
+		if len(side.entries) == 0 {
+			continue
+		}
+		fmt.Fprintf(&b, "\nOnly in %s:\n", side.dir)
+		for _, entry := range side.entries {
+			fmt.Fprintf(&b, "  %s  %s\n", instanceKey(side.dir, entry), entry.path)
+		}
+	}
+	for _, instance := range d.differing {
+		fmt.Fprintf(&b, "\n%s  %s  %s\n", instance.key, instance.a.path, instance.b.path)
+		for _, row := range instance.rows {
+			fmt.Fprintf(&b, "  %s %s: %s → %s\n", row.Tag, row.Name, formatComparisonValue(row, 0), formatComparisonValue(row, 1))
+		}
+	}
+	return b.String()
+}
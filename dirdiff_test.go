@@ -0,0 +1,60 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestDirectoryDiff(t *testing.T) {
+	assert := assert.New(t)
+
+	entry := func(f *fixture, path string) DatasetEntry {
+		e := f.entry(path)
+		e.path = path
+		return e
+	}
+	before := []DatasetEntry{
+		entry(newInstanceFixture(t, 1), "before/1.dcm"),
+		entry(newInstanceFixture(t, 2), "before/2.dcm"),
+		entry(newInstanceFixture(t, 3), "before/3.dcm"),
+		entry(newFixture(t).with(tag.PatientName, []string{"Doe^John"}), "before/sr/no-uid.dcm"),
+	}
+	after := []DatasetEntry{
+		entry(newInstanceFixture(t, 1), "after/1.dcm"),
+		entry(newInstanceFixture(t, 2), "after/2.dcm"),
+		entry(newInstanceFixture(t, 4), "after/4.dcm"),
+		entry(newFixture(t).with(tag.PatientName, []string{"Doe^John"}), "after/sr/no-uid.dcm"),
+	}
+	for attribute, value := range map[tag.Tag]string{tag.PatientName: "ANON", tag.PatientID: "X"} {
+		e, _ := after[1].dataset.FindElementByTag(attribute)
+		e.Value, _ = dicom.NewValue([]string{value})
+	}
+	d := newDirectoryDiff("before", "after", before, after)
+	assert.True(d.differs())
+	assert.Equal(2, d.identical)
+	assert.Len(d.onlyA, 1)
+	assert.Equal("before/3.dcm", d.onlyA[0].path)
+	assert.Len(d.onlyB, 1)
+	assert.Equal("after/4.dcm", d.onlyB[0].path)
+	assert.Len(d.differing, 1)
+	assert.Equal("1.2.3.4.5.2", d.differing[0].key)
+	assert.Equal(`Comparing before with after
+
+3 instances in both, 2 identical, 1 differing, 1 only in before, 1 only in after
+
+Only in before:
+  1.2.3.4.5.3  before/3.dcm
+
+Only in after:
+  1.2.3.4.5.4  after/4.dcm
+
+1.2.3.4.5.2  before/2.dcm  after/2.dcm
+  (0010,0010) PatientName: "Doe^John" → "ANON"
+  (0010,0020) PatientID: "12345" → "X"
+`, d.String())
+
+	assert.False(newDirectoryDiff("before", "after", before[:2], before[:2]).differs())
+}
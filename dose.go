@@ -0,0 +1,171 @@
+package main
+
+import (
+	"encoding/csv"
+	"fmt"
+	"os"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+const xRayRadiationDoseSR = "1.2.840.10008.5.1.4.1.1.88.67"
+
+// concept codes (scheme DCM) of the X-Ray Radiation Dose SR CT templates TID 10011 and following, see PS3.16
+const (
+	codeCTAcquisition   = "113819"
+	codeProtocol        = "125203"
+	codeTargetRegion    = "123014"
+	codeAcquisitionType = "113820"
+	codeCTDIvol         = "113830"
+	codeDLP             = "113838"
+	codeTotalDLP        = "113813"
+)
+
+// doseEvent is an irradiation event of a CT dose report
+type doseEvent struct {
+	protocol        string
+	targetRegion    string
+	acquisitionType string
+	ctdiVol         string // mGy
+	dlp             string // mGy.cm
+}
+
+// doseReport are the irradiation events and the accumulated DLP of a radiation dose SR file
+type doseReport struct {
+	file     string
+	events   []doseEvent
+	totalDLP string
+}
+
+func findItemElement(item []*dicom.Element, t tag.Tag) *dicom.Element {
+	for _, e := range item {
+		if e.Tag == t {
+			return e
+		}
+	}
+	return nil
+}
+
+// codeOf returns the given attribute of the first item of the code sequence of the content item
+func codeOf(item []*dicom.Element, sequence, attribute tag.Tag) string {
+	e := findItemElement(item, sequence)
+	if e == nil || !isSequence(e) || len(getSequenceItems(e)) == 0 {
+		return ""
+	}
+	if value := findItemElement(getSequenceItems(e)[0], attribute); value != nil {
+		return strings.Join(getValueStrings(value), `\`)
+	}
+	return ""
+}
+
+// contentValue returns the value of a TEXT, CODE or NUM content item, numbers without unit
+func contentValue(item []*dicom.Element) string {
+	if e := findItemElement(item, tag.TextValue); e != nil {
+		return strings.Join(getValueStrings(e), `\`)
+	}
+	if meaning := codeOf(item, tag.ConceptCodeSequence, tag.CodeMeaning); meaning != "" {
+		return meaning
+	}
+	return codeOf(item, tag.MeasuredValueSequence, tag.NumericValue)
+}
+
+// contentItems returns the items of the content sequence of a content item or the document
+func contentItems(elements []*dicom.Element) [][]*dicom.Element {
+	if e := findItemElement(elements, tag.ContentSequence); e != nil && isSequence(e) {
+		return getSequenceItems(e)
+	}
+	return nil
+}
+
+// collectDoseEvent fills the event with the values found below the content item, e.g. in the CT Dose container
+func collectDoseEvent(item []*dicom.Element, event *doseEvent) {
+	for _, child := range contentItems(item) {
+		switch codeOf(child, tag.ConceptNameCodeSequence, tag.CodeValue) {
+		case codeProtocol:
+			event.protocol = contentValue(child)
+		case codeTargetRegion:
+			event.targetRegion = contentValue(child)
+		case codeAcquisitionType:
+			event.acquisitionType = contentValue(child)
+		case codeCTDIvol:
+			event.ctdiVol = contentValue(child)
+		case codeDLP:
+			event.dlp = contentValue(child)
+		default:
+			collectDoseEvent(child, event)
+		}
+	}
+}
+
+func collectDoseReport(items [][]*dicom.Element, report *doseReport) {
+	for _, item := range items {
+		switch codeOf(item, tag.ConceptNameCodeSequence, tag.CodeValue) {
+		case codeCTAcquisition:
+			var event doseEvent
+			collectDoseEvent(item, &event)
+			report.events = append(report.events, event)
+		case codeTotalDLP:
+			report.totalDLP = contentValue(item)
+		default:
+			collectDoseReport(contentItems(item), report)
+		}
+	}
+}
+
+// newDoseReports extracts the CT irradiation events of all radiation dose SR files
+func newDoseReports(datasetsWithFilename []DatasetEntry) []doseReport {
+	reports := make([]doseReport, 0)
+	for _, entry := range datasetsWithFilename {
+		if getDatasetValue(entry.dataset, tag.SOPClassUID) != xRayRadiationDoseSR {
+			continue
+		}
+		report := doseReport{file: entry.filename}
+		collectDoseReport(contentItems(entry.dataset.Elements), &report)
+		reports = append(reports, report)
+	}
+	return reports
+}
+
+var doseColumns = []string{"File", "Event", "Protocol", "Target region", "Type", "CTDIvol [mGy]", "DLP [mGy.cm]"}
+
+func doseRows(reports []doseReport) [][]string {
+	rows := make([][]string, 0)
+	for _, report := range reports {
+		for i, event := range report.events {
+			rows = append(rows, []string{report.file, fmt.Sprint(i + 1), event.protocol, event.targetRegion,
+				event.acquisitionType, event.ctdiVol, event.dlp})
+		}
+		if report.totalDLP != "" {
+			rows = append(rows, []string{report.file, "total", "", "", "", "", report.totalDLP})
+		}
+	}
+	return rows
+}
+
+// formatDoseReports returns a table of the irradiation events of all dose reports with aligned columns
+func formatDoseReports(reports []doseReport) string {
+	if len(reports) == 0 {
+		return "no CT radiation dose reports"
+	}
+	return formatTable(append([][]string{doseColumns}, doseRows(reports)...))
+}
+
+// exportDoseReports writes the irradiation events of all dose reports as CSV file
+func exportDoseReports(filename string, reports []doseReport) error {
+	file, err := os.Create(filename)
+	if err != nil {
+		return err
+	}
+	w := csv.NewWriter(file)
+	err = w.Write(doseColumns)
+	if err == nil {
+		err = w.WriteAll(doseRows(reports))
+	}
+	if err != nil {
+		file.Close()
+		return err
+	}
+	return file.Close()
+}
@@ -0,0 +1,65 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// contentItem creates a content item with the concept name code and the given value elements
+func contentItem(f *fixture, code string, value ...*dicom.Element) []*dicom.Element {
+	concept := f.element(tag.ConceptNameCodeSequence, [][]*dicom.Element{{
+		f.element(tag.CodeValue, []string{code}),
+		f.element(tag.CodingSchemeDesignator, []string{"DCM"}),
+	}})
+	return append([]*dicom.Element{concept}, value...)
+}
+
+func numItem(f *fixture, code, value string) []*dicom.Element {
+	return contentItem(f, code, f.element(tag.MeasuredValueSequence, [][]*dicom.Element{{
+		f.element(tag.NumericValue, []string{value}),
+	}}))
+}
+
+func TestNewDoseReports(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t).with(tag.SOPClassUID, []string{xRayRadiationDoseSR})
+	ctDose := contentItem(f, "113829", f.element(tag.ContentSequence, [][]*dicom.Element{
+		numItem(f, codeCTDIvol, "12.5"),
+		numItem(f, codeDLP, "480"),
+	}))
+	acquisition := contentItem(f, codeCTAcquisition, f.element(tag.ContentSequence, [][]*dicom.Element{
+		contentItem(f, codeProtocol, f.element(tag.TextValue, []string{"Thorax"})),
+		contentItem(f, codeAcquisitionType, f.element(tag.ConceptCodeSequence, [][]*dicom.Element{{
+			f.element(tag.CodeMeaning, []string{"Spiral Acquisition"}),
+		}})),
+		ctDose,
+	}))
+	accumulated := contentItem(f, "113811", f.element(tag.ContentSequence, [][]*dicom.Element{
+		numItem(f, codeTotalDLP, "480"),
+	}))
+	f.withSequence(tag.ContentSequence, accumulated, acquisition)
+	datasets := []DatasetEntry{f.entry("dose.dcm"), newInstanceFixture(t, 1).entry("1.dcm")}
+
+	reports := newDoseReports(datasets)
+	assert.Equal([]doseReport{{file: "dose.dcm", totalDLP: "480", events: []doseEvent{
+		{protocol: "Thorax", acquisitionType: "Spiral Acquisition", ctdiVol: "12.5", dlp: "480"},
+	}}}, reports)
+	assert.Equal("File      Event  Protocol  Target region  Type                CTDIvol [mGy]  DLP [mGy.cm]\n"+
+		"dose.dcm  1      Thorax                   Spiral Acquisition  12.5           480\n"+
+		"dose.dcm  total                                                              480", formatDoseReports(reports))
+	assert.Equal("no CT radiation dose reports", formatDoseReports(nil))
+
+	path := filepath.Join(t.TempDir(), "dose.csv")
+	assert.NoError(exportDoseReports(path, reports))
+	csv, err := os.ReadFile(path)
+	assert.NoError(err)
+	assert.Equal("File,Event,Protocol,Target region,Type,CTDIvol [mGy],DLP [mGy.cm]\n"+
+		"dose.dcm,1,Thorax,,Spiral Acquisition,12.5,480\n"+
+		"dose.dcm,total,,,,,480\n", string(csv))
+}
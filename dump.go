@@ -0,0 +1,83 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// dumpFile is the JSON object written per file by --dump
+type dumpFile struct {
+	Path     string        `json:"path"`
+	Size     int64         `json:"size"`
+	Elements []dumpElement `json:"elements"`
+}
+
+// dumpElement is one element of a dumped file. Binary values are left out, only their length is given.
+type dumpElement struct {
+	Tag    string          `json:"tag"`
+	VR     string          `json:"vr"`
+	Name   string          `json:"name,omitempty"`
+	Length uint32          `json:"length"`
+	Value  []string        `json:"value,omitempty"`
+	Items  [][]dumpElement `json:"items,omitempty"`
+}
+
+func newDumpElements(elements []*dicom.Element) []dumpElement {
+	dumped := make([]dumpElement, 0, len(elements))
+	for _, e := range elements {
+		d := dumpElement{
+			Tag:    fmt.Sprintf("%04x,%04x", e.Tag.Group, e.Tag.Element),
+			VR:     e.RawValueRepresentation,
+			Name:   getTagName(e),
+			Length: e.ValueLength,
+		}
+		switch e.Value.ValueType() {
+		case dicom.Sequences:
+			for _, item := range e.Value.GetValue().([]*dicom.SequenceItemValue) {
+				d.Items = append(d.Items, newDumpElements(item.GetValue().([]*dicom.Element)))
+			}
+		case dicom.Strings, dicom.Ints, dicom.Floats:
+			d.Value = getValueStrings(e)
+		}
+		dumped = append(dumped, d)
+	}
+	return dumped
+}
+
+// writeJSONLDump writes one JSON object per file of the given file or directory matching the query as soon as it is
+// parsed, with the selected elements. Pixel data isn't read unless another tag to stop at is selected and no dataset
+// is kept after it is written, so arbitrary large archives can be dumped.
+func writeJSONLDump(ctx context.Context, w io.Writer, path string, selection elementSelection, q *query) error {
+	encoder := json.NewEncoder(w)
+	if selection.readUntil == nil {
+		pixelData := tag.PixelData
+		selection.readUntil = &pixelData
+	}
+	return walkDicomFiles(ctx, path, selection, nil, nil, func(entry DatasetEntry) error {
+		if q != nil && !q.expr.match(entry.dataset) {
+			return nil
+		}
+		return encoder.Encode(dumpFile{entry.path, entry.size, newDumpElements(entry.dataset.Elements)})
+	})
+}
+
+// writeJSONLEntries writes one JSON object per entry like writeJSONLDump, entries not read from a file like worklist
+// items with their name as path
+func writeJSONLEntries(w io.Writer, entries []DatasetEntry) error {
+	encoder := json.NewEncoder(w)
+	for _, entry := range entries {
+		path := entry.path
+		if path == "" {
+			path = entry.filename
+		}
+		if err := encoder.Encode(dumpFile{path, entry.size, newDumpElements(entry.dataset.Elements)}); err != nil {
+			return err
+		}
+	}
+	return nil
+}
@@ -0,0 +1,55 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestWriteJSONLDump(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	for i := 1; i <= 2; i++ {
+		f := newInstanceFixture(t, i)
+		f.withSequence(tag.ReferencedImageSequence, []*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.3"})})
+		f.writeFile(filepath.Join(dir, fmt.Sprintf("%d.dcm", i)))
+	}
+
+	var out strings.Builder
+	assert.NoError(writeJSONLDump(context.Background(), &out, dir, elementSelection{}, nil))
+	lines := strings.Split(strings.TrimSuffix(out.String(), "\n"), "\n")
+	assert.Len(lines, 2)
+
+	for i, line := range lines {
+		var file dumpFile
+		assert.NoError(json.Unmarshal([]byte(line), &file))
+		assert.Equal(filepath.Join(dir, fmt.Sprintf("%d.dcm", i+1)), file.Path)
+		assert.Greater(file.Size, int64(132))
+		found := map[string]dumpElement{}
+		for _, e := range file.Elements {
+			found[e.Tag] = e
+		}
+		assert.Equal([]string{fmt.Sprintf("1.2.3.4.5.%d", i+1)}, found["0008,0018"].Value)
+		assert.Equal("SOPInstanceUID", found["0008,0018"].Name)
+		assert.Equal([]string{"512"}, found["0028,0010"].Value)
+		assert.Len(found["0008,1140"].Items, 1)
+		assert.Equal([]string{"1.2.3"}, found["0008,1140"].Items[0][0].Value)
+	}
+
+	assert.Error(writeJSONLDump(context.Background(), &out, filepath.Join(dir, "missing"), elementSelection{}, nil))
+
+	q, err := parseQuery("InstanceNumber==2")
+	assert.NoError(err)
+	out.Reset()
+	assert.NoError(writeJSONLDump(context.Background(), &out, dir, elementSelection{}, q))
+	assert.Equal(1, strings.Count(out.String(), "\n"))
+	assert.Contains(out.String(), "2.dcm")
+}
@@ -0,0 +1,139 @@
+package main
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+)
+
+// hashElements writes the tags and values of the elements including sequence items and pixel data to w, string
+// values without padding, so datasets differing only in their encoding are written the same
+func hashElements(w io.Writer, elements []*dicom.Element, includeMeta bool) {
+	var buffer []byte
+	for _, e := range elements {
+		if e.Tag.Group == 0x0002 && !includeMeta {
+			continue
+		}
+		fmt.Fprintf(w, "(%04x,%04x)", e.Tag.Group, e.Tag.Element)
+		if writeLazyRawValue(w, e) {
+			continue // pixel data of a huge file not read yet, its encoding only differs from other huge files rarely
+		}
+		switch value := e.Value.GetValue().(type) {
+		case []string:
+			for _, v := range value {
+				fmt.Fprintf(w, "%q", strings.TrimRight(v, " \x00"))
+			}
+		case []int, []float64:
+			fmt.Fprint(w, value)
+		case []byte:
+			w.Write(value)
+		case []*dicom.SequenceItemValue:
+			for _, item := range value {
+				io.WriteString(w, "{")
+				hashElements(w, item.GetValue().([]*dicom.Element), includeMeta)
+				io.WriteString(w, "}")
+			}
+		case dicom.PixelDataInfo:
+			for _, f := range value.Frames {
+				if f.Encapsulated {
+					w.Write(f.EncapsulatedData.Data)
+					continue
+				}
+				for _, pixel := range f.NativeData.Data {
+					buffer = buffer[:0]
+					for _, sample := range pixel {
+						buffer = strconv.AppendInt(append(buffer, ' '), int64(sample), 10)
+					}
+					w.Write(buffer)
+				}
+			}
+		}
+	}
+}
+
+// datasetHash returns the SHA-256 of the elements of the dataset, the file meta information only if includeMeta is set
+func datasetHash(dataset dicom.Dataset, includeMeta bool) string {
+	hash := sha256.New()
+	hashElements(hash, dataset.Elements, includeMeta)
+	return hex.EncodeToString(hash.Sum(nil))
+}
+
+// duplicateClusters groups the paths of the entries with the same key, only groups of more than one file in the order
+// of their first file
+func duplicateClusters(ctx context.Context, datasetsWithFilename []DatasetEntry, key func(entry DatasetEntry) (string, error), progress func(done int)) ([][]string, error) {
+	keys := make([]string, 0)
+	clusters := map[string][]string{}
+	for i, entry := range datasetsWithFilename {
+		if err := ctx.Err(); err != nil {
+			return nil, err
+		}
+		progress(i)
+		k, err := key(entry)
+		if err != nil {
+			return nil, err
+		}
+		if _, ok := clusters[k]; !ok {
+			keys = append(keys, k)
+		}
+		clusters[k] = append(clusters[k], entry.path)
+	}
+	duplicates := make([][]string, 0)
+	for _, k := range keys {
+		if len(clusters[k]) > 1 {
+			duplicates = append(duplicates, clusters[k])
+		}
+	}
+	return duplicates, nil
+}
+
+// formatDuplicates lists the clusters of byte-identical files and of files with identical datasets but differing
+// bytes, e.g. by transfer syntax or padding. The file meta information (group 0002) is only compared with includeMeta.
+// Progress counts every entry twice, once for hashing the file and once for hashing the dataset.
+func formatDuplicates(ctx context.Context, datasetsWithFilename []DatasetEntry, includeMeta bool, progress func(done, total int)) (string, error) {
+	total := 2 * len(datasetsWithFilename)
+	report := func(offset int) func(done int) {
+		return func(done int) {
+			if progress != nil {
+				progress(offset+done, total)
+			}
+		}
+	}
+	identicalFiles, err := duplicateClusters(ctx, datasetsWithFilename, func(entry DatasetEntry) (string, error) {
+		return sha256File(entry.path)
+	}, report(0))
+	if err != nil {
+		return "", err
+	}
+	identicalDatasets, err := duplicateClusters(ctx, datasetsWithFilename, func(entry DatasetEntry) (string, error) {
+		return datasetHash(entry.dataset, includeMeta), nil
+	}, report(len(datasetsWithFilename)))
+	if err != nil {
+		return "", err
+	}
+	identicalDatasets = slices.DeleteFunc(identicalDatasets, func(paths []string) bool {
+		return slices.ContainsFunc(identicalFiles, func(files []string) bool { return slices.Equal(files, paths) })
+	})
+
+	format := func(clusters [][]string) string {
+		if len(clusters) == 0 {
+			return "none\n"
+		}
+		var b strings.Builder
+		for _, paths := range clusters {
+			fmt.Fprintf(&b, "%d files: %s\n", len(paths), strings.Join(paths, ", "))
+		}
+		return b.String()
+	}
+	title := "Identical datasets with differing bytes, file meta information ignored"
+	if includeMeta {
+		title = "Identical datasets with differing bytes"
+	}
+	return "Byte-identical files\n\n" + format(identicalFiles) + "\n" + title + "\n\n" + format(identicalDatasets), nil
+}
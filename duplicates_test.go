@@ -0,0 +1,59 @@
+package main
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestFormatDuplicates(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	write := func(name, content string) string {
+		path := filepath.Join(dir, name)
+		assert.NoError(os.WriteFile(path, []byte(content), 0o644))
+		return path
+	}
+	entry := func(name, content string, f *fixture) DatasetEntry {
+		e := f.entry(name)
+		e.path = write(name, content)
+		return e
+	}
+	entries := []DatasetEntry{
+		entry("a.dcm", "a", newInstanceFixture(t, 1)),
+		entry("b.dcm", "a", newInstanceFixture(t, 1)),
+		entry("c.dcm", "c", newInstanceFixture(t, 2).with(tag.TransferSyntaxUID, []string{"1.2.840.10008.1.2"})),
+		entry("d.dcm", "d", newInstanceFixture(t, 2).with(tag.TransferSyntaxUID, []string{"1.2.840.10008.1.2.1"})),
+		entry("e.dcm", "e", newInstanceFixture(t, 3)),
+	}
+	report, err := formatDuplicates(context.Background(), entries, false, nil)
+	assert.NoError(err)
+	assert.Equal("Byte-identical files\n\n"+
+		"2 files: "+filepath.Join(dir, "a.dcm")+", "+filepath.Join(dir, "b.dcm")+"\n\n"+
+		"Identical datasets with differing bytes, file meta information ignored\n\n"+
+		"2 files: "+filepath.Join(dir, "c.dcm")+", "+filepath.Join(dir, "d.dcm")+"\n", report)
+
+	report, err = formatDuplicates(context.Background(), entries, true, nil)
+	assert.NoError(err)
+	assert.Contains(report, "Identical datasets with differing bytes\n\nnone\n")
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	_, err = formatDuplicates(ctx, entries, false, nil)
+	assert.ErrorIs(err, context.Canceled)
+}
+
+func TestDatasetHash(t *testing.T) {
+	assert := assert.New(t)
+
+	padded := newFixture(t).with(tag.PatientName, []string{"Doe^John "}).dataset()
+	unpadded := newFixture(t).with(tag.PatientName, []string{"Doe^John"}).dataset()
+	other := newFixture(t).with(tag.PatientName, []string{"Doe^Jane"}).dataset()
+	assert.Equal(datasetHash(padded, false), datasetHash(unpadded, false))
+	assert.NotEqual(datasetHash(padded, false), datasetHash(other, false))
+}
@@ -0,0 +1,46 @@
+package main
+
+import (
+	"slices"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// marker appended to the text of nodes of elements edited in this session
+const editMarker = " ±"
+
+// editedElements are the elements changed in this session by editing, importing or propagating values, so ]c and [c
+// can jump between them like between the hunks of a diff
+type editedElements map[*dicom.Element]bool
+
+// isEdited returns true if the node references an edited element
+func (m editedElements) isEdited(node *tview.TreeNode) bool {
+	e, ok := node.GetReference().(*dicom.Element)
+	return ok && m[e]
+}
+
+// markNodes appends the edit marker to the nodes of edited elements below the given node not marked yet
+func (m editedElements) markNodes(root *tview.TreeNode) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if m.isEdited(node) && !strings.HasSuffix(node.GetText(), editMarker) {
+			node.SetText(node.GetText() + editMarker)
+		}
+		return true
+	})
+}
+
+// addByTag adds the top level elements with the tag of the files with the given paths, e.g. the copies of a propagated
+// element
+func (m editedElements) addByTag(datasetsWithFilename []DatasetEntry, paths []string, t tag.Tag) {
+	for i := range datasetsWithFilename {
+		if !slices.Contains(paths, datasetsWithFilename[i].path) {
+			continue
+		}
+		if e, err := datasetsWithFilename[i].dataset.FindElementByTag(t); err == nil {
+			m[e] = true
+		}
+	}
+}
@@ -0,0 +1,41 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestEditedElements(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	datasets[0].path, datasets[1].path = "file1.dcm", "file2.dcm"
+	edited := editedElements{}
+	patientName, err := datasets[0].dataset.FindElementByTag(tag.PatientName)
+	assert.NoError(err)
+	edited[patientName] = true
+	edited.addByTag(datasets, []string{"file2.dcm"}, tag.InstanceNumber)
+	assert.Len(edited, 2)
+
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	edited.markNodes(root)
+	edited.markNodes(root)
+	node := findElementNode(root, patientName)
+	assert.Equal(getElementText(patientName)+editMarker, node.GetText())
+
+	tree.SetCurrentNode(root)
+	assert.True(jumpToMatchingNode(tree, false, edited.isEdited))
+	assert.Same(node, tree.GetCurrentNode())
+	assert.True(getParent(tree, node).IsExpanded())
+	assert.True(jumpToMatchingNode(tree, false, edited.isEdited))
+	assert.Contains(tree.GetCurrentNode().GetText(), "InstanceNumber")
+	assert.True(jumpToMatchingNode(tree, false, edited.isEdited))
+	assert.Same(node, tree.GetCurrentNode())
+	assert.True(jumpToMatchingNode(tree, true, edited.isEdited))
+	assert.Contains(tree.GetCurrentNode().GetText(), "InstanceNumber")
+
+	assert.False(jumpToMatchingNode(tree, false, editedElements{}.isEdited))
+}
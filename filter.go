@@ -0,0 +1,163 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// displayFilter hides elements of the given kinds from the tree
+type displayFilter struct {
+	hideRetired     bool
+	hideGroupLength bool
+	hidePrivate     bool
+}
+
+// newDisplayFilter creates the filter from the names given with --hide
+func newDisplayFilter(hide []string) (displayFilter, error) {
+	var filter displayFilter
+	for _, name := range hide {
+		switch name {
+		case "retired":
+			filter.hideRetired = true
+		case "group-length":
+			filter.hideGroupLength = true
+		case "private":
+			filter.hidePrivate = true
+		default:
+			return filter, fmt.Errorf("unknown element kind '%s', supported: retired, group-length, private", name)
+		}
+	}
+	return filter, nil
+}
+
+// names returns the element kinds hidden by the filter as accepted by newDisplayFilter
+func (f displayFilter) names() []string {
+	names := make([]string, 0)
+	if f.hideRetired {
+		names = append(names, "retired")
+	}
+	if f.hideGroupLength {
+		names = append(names, "group-length")
+	}
+	if f.hidePrivate {
+		names = append(names, "private")
+	}
+	return names
+}
+
+func (f displayFilter) String() string {
+	hidden := make([]string, 0)
+	if f.hideRetired {
+		hidden = append(hidden, "retired")
+	}
+	if f.hideGroupLength {
+		hidden = append(hidden, "group length")
+	}
+	if f.hidePrivate {
+		hidden = append(hidden, "private")
+	}
+	if len(hidden) == 0 {
+		return "all elements shown"
+	}
+	return "hiding " + strings.Join(hidden, ", ") + " elements"
+}
+
+func (f displayFilter) hides(e *dicom.Element) bool {
+	return (f.hideRetired && isRetiredTag(e.Tag)) ||
+		(f.hideGroupLength && e.Tag.Element == 0x0000) ||
+		(f.hidePrivate && e.Tag.Group%2 == 1)
+}
+
+// retiredTags lists retired elements still found in older files. The dictionary of the dicom library doesn't mark
+// retired elements, so only these, the retired curve groups and those of the user dictionary are detected.
+var retiredTags = map[tag.Tag]bool{
+	{Group: 0x0008, Element: 0x0001}: true, // Length to End
+	{Group: 0x0008, Element: 0x0010}: true, // Recognition Code
+	{Group: 0x0008, Element: 0x0024}: true, // Overlay Date
+	{Group: 0x0008, Element: 0x0025}: true, // Curve Date
+	{Group: 0x0008, Element: 0x0034}: true, // Overlay Time
+	{Group: 0x0008, Element: 0x0035}: true, // Curve Time
+	{Group: 0x0008, Element: 0x0040}: true, // Data Set Type
+	{Group: 0x0008, Element: 0x0041}: true, // Data Set Subtype
+	{Group: 0x0008, Element: 0x0042}: true, // Nuclear Medicine Series Type
+	{Group: 0x0008, Element: 0x1000}: true, // Network ID
+	{Group: 0x0008, Element: 0x2110}: true, // Lossy Image Compression (Retired)
+	{Group: 0x0010, Element: 0x1000}: true, // Other Patient IDs
+	{Group: 0x0018, Element: 0x4000}: true, // Acquisition Comments
+	{Group: 0x0020, Element: 0x0030}: true, // Image Position
+	{Group: 0x0020, Element: 0x0035}: true, // Image Orientation
+	{Group: 0x0020, Element: 0x0050}: true, // Location
+	{Group: 0x0020, Element: 0x0070}: true, // Image Geometry Type
+	{Group: 0x0020, Element: 0x1000}: true, // Series in Study
+	{Group: 0x0020, Element: 0x1001}: true, // Acquisitions in Series
+	{Group: 0x0020, Element: 0x1020}: true, // Reference
+	{Group: 0x0020, Element: 0x3401}: true, // Modifying Device ID
+	{Group: 0x0020, Element: 0x3402}: true, // Modified Image ID
+	{Group: 0x0020, Element: 0x3403}: true, // Modified Image Date
+	{Group: 0x0020, Element: 0x3404}: true, // Modifying Device Manufacturer
+	{Group: 0x0020, Element: 0x3405}: true, // Modified Image Time
+	{Group: 0x0020, Element: 0x3406}: true, // Modified Image Description
+	{Group: 0x0020, Element: 0x5000}: true, // Original Image Identification
+	{Group: 0x0020, Element: 0x5002}: true, // Original Image Identification Nomenclature
+	{Group: 0x0028, Element: 0x0005}: true, // Image Dimensions
+	{Group: 0x0028, Element: 0x0040}: true, // Image Format
+	{Group: 0x0028, Element: 0x0050}: true, // Manipulated Image
+	{Group: 0x0028, Element: 0x0060}: true, // Compression Code
+	{Group: 0x0028, Element: 0x0200}: true, // Image Location
+	{Group: 0x0028, Element: 0x1080}: true, // Gray Scale
+	{Group: 0x0028, Element: 0x1100}: true, // Gray Lookup Table Descriptor
+	{Group: 0x0028, Element: 0x1200}: true, // Gray Lookup Table Data
+	{Group: 0x0028, Element: 0x4000}: true, // Image Presentation Comments
+	{Group: 0x0032, Element: 0x000a}: true, // Study Status ID
+	{Group: 0x0032, Element: 0x000c}: true, // Study Priority ID
+	{Group: 0x0032, Element: 0x0012}: true, // Study ID Issuer
+	{Group: 0x0032, Element: 0x0032}: true, // Study Verified Date
+	{Group: 0x0032, Element: 0x0033}: true, // Study Verified Time
+	{Group: 0x0032, Element: 0x0034}: true, // Study Read Date
+	{Group: 0x0032, Element: 0x0035}: true, // Study Read Time
+	{Group: 0x0032, Element: 0x1000}: true, // Scheduled Study Start Date
+	{Group: 0x0032, Element: 0x1001}: true, // Scheduled Study Start Time
+	{Group: 0x0032, Element: 0x1010}: true, // Scheduled Study Stop Date
+	{Group: 0x0032, Element: 0x1011}: true, // Scheduled Study Stop Time
+	{Group: 0x0032, Element: 0x1020}: true, // Scheduled Study Location
+	{Group: 0x0032, Element: 0x1021}: true, // Scheduled Study Location AE Title
+	{Group: 0x0032, Element: 0x1030}: true, // Reason for Study
+	{Group: 0x0032, Element: 0x1040}: true, // Study Arrival Date
+	{Group: 0x0032, Element: 0x1041}: true, // Study Arrival Time
+	{Group: 0x0032, Element: 0x1050}: true, // Study Completion Date
+	{Group: 0x0032, Element: 0x1051}: true, // Study Completion Time
+	{Group: 0x0032, Element: 0x4000}: true, // Study Comments
+}
+
+func isRetiredTag(t tag.Tag) bool {
+	return retiredTags[t] || userDictionary[t].Retired || t.Group&0xff00 == 0x5000 // curves
+}
+
+// hideFilteredElements removes the element nodes hidden by the filter below the given node, together with branches
+// left empty. The number of hidden elements is appended to the text of each branch containing hidden elements, the
+// total number is returned.
+func hideFilteredElements(node *tview.TreeNode, filter displayFilter) int {
+	hidden := 0
+	children := make([]*tview.TreeNode, 0)
+	for _, child := range node.GetChildren() {
+		if e, ok := child.GetReference().(*dicom.Element); ok && filter.hides(e) {
+			hidden++
+			continue
+		}
+		childHidden := hideFilteredElements(child, filter)
+		hidden += childHidden
+		if childHidden > 0 && len(child.GetChildren()) == 0 {
+			continue // all elements of the branch are hidden
+		}
+		children = append(children, child)
+	}
+	if hidden > 0 {
+		node.SetChildren(children)
+		node.SetText(fmt.Sprintf("%s (%d hidden)", node.GetText(), hidden))
+	}
+	return hidden
+}
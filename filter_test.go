@@ -0,0 +1,35 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestHideFilteredElements(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newFixture(t).
+			with(tag.Modality, []string{"CT"}).
+			with(tag.ImagePositionPatient, []string{"0", "0", "0"}).
+			withPrivate(0x0020, 0x0030, "DS", []string{"0", "0", "0"}). // retired Image Position
+			withPrivate(0x0029, 0x1010, "LO", []string{"private"}).
+			entry("file1.dcm"),
+	}
+	_, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+
+	filter, err := newDisplayFilter([]string{"retired", "private"})
+	assert.NoError(err)
+	assert.Equal(2, hideFilteredElements(root, filter))
+	assert.Equal("root (2 hidden)", root.GetText())
+	fileNode := root.GetChildren()[0]
+	assert.Equal("file1.dcm (2 hidden)", fileNode.GetText())
+	assert.Len(fileNode.GetChildren(), 2) // private group removed
+	assert.Equal("0020 (1 hidden)", fileNode.GetChildren()[1].GetText())
+
+	_, err = newDisplayFilter([]string{"unknown"})
+	assert.Error(err)
+}
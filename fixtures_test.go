@@ -0,0 +1,169 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// fixture builds small in-memory datasets for tests, so no real patient data has to be shipped with the repository.
+type fixture struct {
+	tb       testing.TB
+	elements []*dicom.Element
+}
+
+func newFixture(tb testing.TB) *fixture {
+	return &fixture{tb: tb}
+}
+
+// newInstanceFixture creates a fixture with the usual patient, study, series and instance attributes of a CT image.
+func newInstanceFixture(tb testing.TB, instanceNumber int) *fixture {
+	return newFixture(tb).
+		with(tag.SOPClassUID, []string{"1.2.840.10008.5.1.4.1.1.2"}).
+		with(tag.SOPInstanceUID, []string{fmt.Sprintf("1.2.3.4.5.%d", instanceNumber)}).
+		with(tag.StudyDate, []string{"20210304"}).
+		with(tag.Modality, []string{"CT"}).
+		with(tag.PatientName, []string{"Doe^John"}).
+		with(tag.PatientID, []string{"12345"}).
+		with(tag.SliceThickness, []string{"1.25"}).
+		with(tag.StudyInstanceUID, []string{"1.2.3.4"}).
+		with(tag.SeriesInstanceUID, []string{"1.2.3.4.5"}).
+		with(tag.InstanceNumber, []string{fmt.Sprint(instanceNumber)}).
+		with(tag.ImagePositionPatient, []string{"0", "0", fmt.Sprint(1.25 * float64(instanceNumber))}).
+		with(tag.ImageOrientationPatient, []string{"1", "0", "0", "0", "1", "0"}).
+		with(tag.Rows, []int{512}).
+		with(tag.Columns, []int{512}).
+		with(tag.PixelSpacing, []string{"0.5", "0.5"})
+}
+
+// with adds a standard element, the VR is taken from the dictionary.
+func (f *fixture) with(t tag.Tag, data interface{}) *fixture {
+	e, err := dicom.NewElement(t, data)
+	if err != nil {
+		f.tb.Fatalf("creating element %v: %v", t, err)
+	}
+	return f.withElement(e)
+}
+
+// withPrivate adds an element with explicit VR, e.g. for private tags not contained in the dictionary.
+func (f *fixture) withPrivate(group, element uint16, vr string, data interface{}) *fixture {
+	value, err := dicom.NewValue(data)
+	if err != nil {
+		f.tb.Fatalf("creating value for (%04x,%04x): %v", group, element, err)
+	}
+	return f.withElement(&dicom.Element{
+		Tag:                    tag.Tag{Group: group, Element: element},
+		RawValueRepresentation: vr,
+		ValueLength:            valueLength(data),
+		Value:                  value,
+	})
+}
+
+func valueLength(data interface{}) uint32 {
+	switch v := data.(type) {
+	case []string:
+		return uint32(len(strings.Join(v, "\\")))
+	case []byte:
+		return uint32(len(v))
+	}
+	return 0
+}
+
+// withSequence adds a sequence element with one item per given element list.
+func (f *fixture) withSequence(t tag.Tag, items ...[]*dicom.Element) *fixture {
+	return f.with(t, items)
+}
+
+func (f *fixture) withElement(e *dicom.Element) *fixture {
+	f.elements = append(f.elements, e)
+	return f
+}
+
+// element creates a single standard element, e.g. for sequence items.
+func (f *fixture) element(t tag.Tag, data interface{}) *dicom.Element {
+	e, err := dicom.NewElement(t, data)
+	if err != nil {
+		f.tb.Fatalf("creating element %v: %v", t, err)
+	}
+	return e
+}
+
+// dataset returns the elements sorted by tag, like they are read from a file.
+func (f *fixture) dataset() dicom.Dataset {
+	elements := append([]*dicom.Element{}, f.elements...)
+	sort.SliceStable(elements, func(i, j int) bool {
+		if elements[i].Tag.Group != elements[j].Tag.Group {
+			return elements[i].Tag.Group < elements[j].Tag.Group
+		}
+		return elements[i].Tag.Element < elements[j].Tag.Element
+	})
+	return dicom.Dataset{Elements: elements}
+}
+
+func (f *fixture) entry(filename string) DatasetEntry {
+	return DatasetEntry{filename, f.dataset(), filename, 0, time.Time{}, false, false}
+}
+
+// writeFile writes the dataset as part 10 file in implicit VR little endian
+func (f *fixture) writeFile(path string) {
+	dataset := f.dataset()
+	encoded, err := encodeImplicit(dataset.Elements)
+	if err != nil {
+		f.tb.Fatal(err)
+	}
+	sopClass, sopInstance := getDatasetValue(dataset, tag.SOPClassUID), getDatasetValue(dataset, tag.SOPInstanceUID)
+	if err := writeReceivedFile(path, sopClass, sopInstance, implicitVRLittleEndian, "TEST", encoded); err != nil {
+		f.tb.Fatal(err)
+	}
+}
+
+func newBinaryElement(tb testing.TB, group, element uint16, length int) *dicom.Element {
+	value, err := dicom.NewValue(make([]byte, length))
+	if err != nil {
+		tb.Fatal(err)
+	}
+	return &dicom.Element{
+		Tag:                    tag.Tag{Group: group, Element: element},
+		RawValueRepresentation: "OB",
+		ValueLength:            uint32(length),
+		Value:                  value,
+	}
+}
+
+// newSyntheticDatasets creates 'fileCount' datasets with 'elementCount' elements in total, every tenth element has a
+// different value per file.
+func newSyntheticDatasets(tb testing.TB, fileCount, elementCount int) []DatasetEntry {
+	entries := make([]DatasetEntry, 0, fileCount)
+	for f := 0; f < fileCount; f++ {
+		fx := newFixture(tb)
+		for i := 0; i < elementCount/fileCount; i++ {
+			text := fmt.Sprintf("value %d", i)
+			if i%10 == 0 {
+				text = fmt.Sprintf("value %d of file %d", i, f)
+			}
+			fx.withPrivate(uint16(0x0011+2*(i/256)), uint16(0x1000+i%256), "LO", []string{text})
+		}
+		entries = append(entries, fx.entry(fmt.Sprintf("file%03d.dcm", f)))
+	}
+	return entries
+}
+
+// writeBrokenFile writes a truncated copy of the test file, which fails to parse.
+func writeBrokenFile(tb testing.TB, dir, filename string) string {
+	data, err := os.ReadFile("testdata/test.dcm")
+	if err != nil {
+		tb.Fatal(err)
+	}
+	path := filepath.Join(dir, filename)
+	if err := os.WriteFile(path, data[:300], 0o644); err != nil {
+		tb.Fatal(err)
+	}
+	return path
+}
@@ -0,0 +1,107 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"slices"
+	"strings"
+
+	"github.com/mattn/go-runewidth"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// alwaysShownFrameAttributes are shown in the per-frame table even if they are the same in all frames
+var alwaysShownFrameAttributes = []tag.Tag{tag.ImagePositionPatient, tag.DiffusionBValue, tag.NominalCardiacTriggerDelayTime}
+
+// frameTable pivots the per-frame functional groups of an enhanced multi-frame object into one row per frame with a
+// column per attribute
+type frameTable struct {
+	columns []string
+	rows    [][]string
+	hidden  int // attributes left out as they are the same in all frames
+}
+
+// frameAttributes collects the values of the non-sequence attributes of a frame item by their tag path, also in
+// nested sequences, in the order of the item
+func frameAttributes(elements []*dicom.Element, path string, keys *[]string, values map[string]string, leaves map[string]tag.Tag) {
+	for _, e := range elements {
+		key := fmt.Sprintf("%s/%04x%04x", path, e.Tag.Group, e.Tag.Element)
+		if isSequence(e) {
+			for _, item := range getSequenceItems(e) {
+				frameAttributes(item, key, keys, values, leaves)
+			}
+			continue
+		}
+		if _, ok := leaves[key]; !ok {
+			*keys = append(*keys, key)
+			leaves[key] = e.Tag
+		}
+		values[key] = strings.Join(getValueStrings(e), `\`)
+	}
+}
+
+// newFrameTable creates the table of the per-frame functional groups of the dataset. Only attributes varying between
+// the frames and alwaysShownFrameAttributes get a column.
+func newFrameTable(dataset dicom.Dataset) (frameTable, error) {
+	sequence, err := dataset.FindElementByTag(tag.PerFrameFunctionalGroupsSequence)
+	if err != nil || !isSequence(sequence) {
+		return frameTable{}, errors.New("no per-frame functional groups")
+	}
+	keys := make([]string, 0)
+	leaves := map[string]tag.Tag{}
+	frames := make([]map[string]string, 0)
+	for _, item := range getSequenceItems(sequence) {
+		values := map[string]string{}
+		frameAttributes(item, "", &keys, values, leaves)
+		frames = append(frames, values)
+	}
+
+	table := frameTable{columns: []string{"Frame"}}
+	shown := make([]string, 0)
+	for _, key := range keys {
+		varies := slices.ContainsFunc(frames, func(values map[string]string) bool { return values[key] != frames[0][key] })
+		if varies || slices.Contains(alwaysShownFrameAttributes, leaves[key]) {
+			shown = append(shown, key)
+			table.columns = append(table.columns, tagKeyword(leaves[key]))
+		} else {
+			table.hidden++
+		}
+	}
+	for i, values := range frames {
+		row := []string{fmt.Sprint(i + 1)}
+		for _, key := range shown {
+			row = append(row, values[key])
+		}
+		table.rows = append(table.rows, row)
+	}
+	return table, nil
+}
+
+// String formats the table with aligned columns
+func (t frameTable) String() string {
+	header := fmt.Sprintf("%d frames, %d attributes the same in all frames not shown\n\n", len(t.rows), t.hidden)
+	return header + formatTable(append([][]string{t.columns}, t.rows...)) + "\n"
+}
+
+// formatTable aligns the cells of the rows in columns separated by two spaces
+func formatTable(rows [][]string) string {
+	widths := make([]int, 0)
+	for _, row := range rows {
+		for i, cell := range row {
+			if i == len(widths) {
+				widths = append(widths, 0)
+			}
+			widths[i] = max(widths[i], runewidth.StringWidth(cell))
+		}
+	}
+	lines := make([]string, 0, len(rows))
+	for _, row := range rows {
+		cells := make([]string, len(row))
+		for i, cell := range row {
+			cells[i] = runewidth.FillRight(cell, widths[i])
+		}
+		lines = append(lines, strings.TrimRight(strings.Join(cells, "  "), " "))
+	}
+	return strings.Join(lines, "\n")
+}
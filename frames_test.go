@@ -0,0 +1,41 @@
+package main
+
+import (
+	"fmt"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestNewFrameTable(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t)
+	frames := make([][]*dicom.Element, 0)
+	for i := 0; i < 3; i++ {
+		frames = append(frames, []*dicom.Element{
+			f.element(tag.FrameContentSequence, [][]*dicom.Element{{
+				f.element(tag.StackID, []string{"1"}),
+				f.element(tag.InStackPositionNumber, []int{i + 1}),
+			}}),
+			f.element(tag.PlanePositionSequence, [][]*dicom.Element{{
+				f.element(tag.ImagePositionPatient, []string{"0", "0", fmt.Sprint(i * 5)}),
+			}}),
+		})
+	}
+	table, err := newFrameTable(f.withSequence(tag.PerFrameFunctionalGroupsSequence, frames...).dataset())
+	assert.NoError(err)
+	assert.Equal([]string{"Frame", "InStackPositionNumber", "ImagePositionPatient"}, table.columns)
+	assert.Equal([]string{"3", "3", `0\0\10`}, table.rows[2])
+	assert.Equal(1, table.hidden)
+	assert.Equal("3 frames, 1 attributes the same in all frames not shown\n\n"+
+		"Frame  InStackPositionNumber  ImagePositionPatient\n"+
+		"1      1                      0\\0\\0\n"+
+		"2      2                      0\\0\\5\n"+
+		"3      3                      0\\0\\10\n", table.String())
+
+	_, err = newFrameTable(newInstanceFixture(t, 1).dataset())
+	assert.EqualError(err, "no per-frame functional groups")
+}
@@ -5,6 +5,7 @@ go 1.22
 require (
 	github.com/alexflint/go-arg v1.4.3
 	github.com/gdamore/tcell/v2 v2.5.4
+	github.com/mattn/go-runewidth v0.0.14
 	github.com/rivo/tview v0.0.0-20230104153304-892d1a2eb0da
 	github.com/stretchr/testify v1.8.1
 	github.com/suyashkumar/dicom v1.0.5
@@ -15,7 +16,6 @@ require (
 	github.com/davecgh/go-spew v1.1.1 // indirect
 	github.com/gdamore/encoding v1.0.0 // indirect
 	github.com/lucasb-eyer/go-colorful v1.2.0 // indirect
-	github.com/mattn/go-runewidth v0.0.14 // indirect
 	github.com/pmezard/go-difflib v1.0.0 // indirect
 	github.com/rivo/uniseg v0.4.3 // indirect
 	golang.org/x/sys v0.4.0 // indirect
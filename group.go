@@ -0,0 +1,95 @@
+package main
+
+import "fmt"
+
+// groupNames describe the standard groups by the kind of their attributes, see PS3.6
+var groupNames = map[uint16]string{
+	0x0002: "File Meta Information",
+	0x0004: "Directory Structure",
+	0x0008: "Identifying",
+	0x0010: "Patient",
+	0x0012: "Clinical Trial",
+	0x0014: "NDE",
+	0x0018: "Acquisition",
+	0x0020: "Relationship",
+	0x0022: "Ophthalmology",
+	0x0024: "Visual Field",
+	0x0028: "Image Presentation",
+	0x0032: "Study",
+	0x0038: "Visit",
+	0x003a: "Waveform",
+	0x0040: "Procedure",
+	0x0042: "Encapsulated Document",
+	0x0044: "Product",
+	0x0046: "Lens",
+	0x0048: "Whole Slide Microscopy",
+	0x0050: "Device",
+	0x0052: "Intravascular OCT",
+	0x0054: "Nuclear Medicine",
+	0x0060: "Histogram",
+	0x0062: "Segmentation",
+	0x0064: "Deformable Registration",
+	0x0066: "Surface",
+	0x0068: "Implant",
+	0x0070: "Presentation State",
+	0x0072: "Hanging Protocol",
+	0x0074: "Treatment Delivery",
+	0x0076: "Implant Assembly",
+	0x0078: "Implant Template",
+	0x0080: "Surface Scan",
+	0x0082: "Clinical Trial",
+	0x0088: "Storage",
+	0x0100: "Authorization",
+	0x0400: "Digital Signature",
+	0x2000: "Print",
+	0x2010: "Print",
+	0x2020: "Print",
+	0x2030: "Print",
+	0x2040: "Print",
+	0x2050: "Print",
+	0x2100: "Print",
+	0x2110: "Print",
+	0x2120: "Print",
+	0x2130: "Print",
+	0x2200: "Media Creation",
+	0x3002: "RT Image",
+	0x3004: "RT Dose",
+	0x3006: "RT Structure Set",
+	0x3008: "RT Treatment Record",
+	0x300a: "RT Plan",
+	0x300c: "RT Relationship",
+	0x300e: "RT Approval",
+	0x4000: "Text",
+	0x4008: "Results",
+	0x4010: "Security Screening",
+	0x4ffe: "MAC Parameters",
+	0x5200: "Functional Groups",
+	0x5400: "Waveform Data",
+	0x5600: "Spectroscopy",
+	0x7fe0: "Pixel Data",
+	0xfffa: "Digital Signatures",
+	0xfffc: "Padding",
+	0xfffe: "Item Delimitation",
+}
+
+// getGroupName returns the description of the group, curve and overlay groups are repeating groups with even numbers
+// from xx00 to xx1e
+func getGroupName(group uint16) string {
+	switch {
+	case group%2 == 1:
+		return "Private"
+	case group >= 0x5000 && group <= 0x501e:
+		return "Curve"
+	case group >= 0x6000 && group <= 0x601e:
+		return "Overlay"
+	}
+	return groupNames[group]
+}
+
+// getGroupText returns the text of a group node, the group number with its description if known
+func getGroupText(group uint16) string {
+	if name := getGroupName(group); name != "" {
+		return fmt.Sprintf("%04x – %s", group, name)
+	}
+	return fmt.Sprintf("%04x", group)
+}
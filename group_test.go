@@ -0,0 +1,17 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestGetGroupText(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal("0008 – Identifying", getGroupText(0x0008))
+	assert.Equal("7fe0 – Pixel Data", getGroupText(0x7fe0))
+	assert.Equal("0029 – Private", getGroupText(0x0029))
+	assert.Equal("6002 – Overlay", getGroupText(0x6002))
+	assert.Equal("6020", getGroupText(0x6020))
+}
@@ -0,0 +1,80 @@
+package main
+
+import (
+	"cmp"
+	"fmt"
+	"slices"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// fileGrouping nests the file nodes of the filename trees in virtual folders by the values of its tags, one folder
+// level per tag, e.g. by StudyDate and StudyDescription for a big unsorted directory
+type fileGrouping []tag.Info
+
+// newFileGrouping parses the comma separated keywords of --group-by
+func newFileGrouping(keywords string) (fileGrouping, error) {
+	var grouping fileGrouping
+	for _, keyword := range strings.Split(keywords, ",") {
+		if keyword = strings.TrimSpace(keyword); keyword == "" {
+			continue
+		}
+		info, err := findTagByName(keyword)
+		if err != nil {
+			return nil, fmt.Errorf("unknown keyword '%s' in --group-by", keyword)
+		}
+		grouping = append(grouping, info)
+	}
+	return grouping, nil
+}
+
+func (g fileGrouping) String() string {
+	names := make([]string, len(g))
+	for i, info := range g {
+		names[i] = info.Name
+	}
+	return strings.Join(names, ", ")
+}
+
+// apply moves the file nodes below the root into collapsed folder nodes sorted by value, the files keep their order.
+// Like the study and series nodes of the hierarchy tree the folders show the statistics of their files.
+func (g fileGrouping) apply(root *tview.TreeNode) {
+	if len(g) == 0 {
+		return
+	}
+	fileNodes := root.GetChildren()
+	root.ClearChildren()
+	g.group(root, fileNodes, 0)
+}
+
+func (g fileGrouping) group(parent *tview.TreeNode, fileNodes []*tview.TreeNode, level int) {
+	if level == len(g) {
+		parent.SetChildren(fileNodes)
+		return
+	}
+	byValue := map[string][]*tview.TreeNode{}
+	for _, fileNode := range fileNodes {
+		entry, ok := fileNode.GetReference().(*DatasetEntry)
+		if !ok {
+			continue
+		}
+		value := strings.TrimSpace(getDatasetValue(entry.dataset, g[level].Tag))
+		byValue[value] = append(byValue[value], fileNode)
+	}
+	values := make([]string, 0, len(byValue))
+	for value := range byValue {
+		values = append(values, value)
+	}
+	slices.Sort(values)
+	for _, value := range values {
+		stats := &hierarchyStats{label: g[level].Name + " " + cmp.Or(value, "(empty)")}
+		for _, fileNode := range byValue[value] {
+			stats.add(fileNode.GetReference().(*DatasetEntry))
+		}
+		folder := tview.NewTreeNode(stats.String()).SetSelectable(true).SetReference(stats).SetExpanded(false)
+		parent.AddChild(folder)
+		g.group(folder, byValue[value], level+1)
+	}
+}
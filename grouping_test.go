@@ -0,0 +1,60 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestFileGrouping(t *testing.T) {
+	assert := assert.New(t)
+
+	_, err := newFileGrouping("StudyDate,NoSuchKeyword")
+	assert.Error(err)
+	grouping, err := newFileGrouping("")
+	assert.NoError(err)
+	assert.Empty(grouping)
+	grouping, err = newFileGrouping("StudyDate, Modality")
+	assert.NoError(err)
+	assert.Equal("StudyDate, Modality", grouping.String())
+
+	file := func(studyDate, modality string) *fixture {
+		f := newFixture(t).with(tag.Modality, []string{modality})
+		if studyDate != "" {
+			f.with(tag.StudyDate, []string{studyDate})
+		}
+		return f
+	}
+	entries := []DatasetEntry{
+		file("20210305", "CT").entry("a.dcm"),
+		file("20210304", "CT").entry("b.dcm"),
+		file("20210304", "MR").entry("c.dcm"),
+		file("", "CT").entry("d.dcm"),
+	}
+	tree, root := sortTreeByFilename("dir", tview.NewTreeView(), entries)
+	grouping.apply(root)
+	assert.Same(root, tree.GetRoot())
+
+	texts := func(nodes []*tview.TreeNode) []string {
+		result := make([]string, len(nodes))
+		for i, node := range nodes {
+			result[i] = node.GetText()
+		}
+		return result
+	}
+	dates := root.GetChildren()
+	assert.Len(dates, 3)
+	assert.Contains(dates[0].GetText(), "StudyDate (empty) - 1 instances")
+	assert.Contains(dates[1].GetText(), "StudyDate 20210304 - 2 instances")
+	assert.Contains(dates[2].GetText(), "StudyDate 20210305 - 1 instances")
+	assert.False(dates[1].IsExpanded())
+	modalities := dates[1].GetChildren()
+	assert.Len(modalities, 2)
+	assert.Contains(modalities[0].GetText(), "Modality CT")
+	assert.Contains(modalities[1].GetText(), "Modality MR")
+	assert.Equal([]string{"b.dcm"}, texts(modalities[0].GetChildren()))
+	assert.Equal([]string{"c.dcm"}, texts(modalities[1].GetChildren()))
+	assert.Equal([]string{"d.dcm"}, texts(dates[0].GetChildren()[0].GetChildren()))
+}
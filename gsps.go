@@ -0,0 +1,303 @@
+package main
+
+import (
+	"cmp"
+	"errors"
+	"fmt"
+	"math"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+const grayscaleSoftcopyPresentationState = "1.2.840.10008.5.1.4.1.1.11.1"
+
+// psGraphic is a graphic or text annotation of a presentation state, in pixel coordinates of the image
+type psGraphic struct {
+	text   string       // empty for graphics
+	points [][2]float64 // the anchor of texts, the points of graphics in drawing order
+	closed bool         // the last point is connected to the first, e.g. for polygons
+}
+
+// presentationState is the part of a grayscale softcopy presentation state applied by the preview
+type presentationState struct {
+	entry        *DatasetEntry
+	window       []float64 // center and width of the VOI window, nil if there is none
+	shutter      string    // RECTANGULAR, CIRCULAR or empty
+	shutterRect  [4]float64
+	shutterRound [3]float64 // center column and row and radius
+	graphics     []psGraphic
+}
+
+// itemFloats returns the numeric values of the attribute in the item
+func itemFloats(item []*dicom.Element, t tag.Tag) []float64 {
+	e := findItemElement(item, t)
+	if e == nil {
+		return nil
+	}
+	values := make([]float64, 0)
+	for _, text := range getValueStrings(e) {
+		value, err := strconv.ParseFloat(strings.TrimSpace(text), 64)
+		if err != nil {
+			return nil
+		}
+		values = append(values, value)
+	}
+	return values
+}
+
+// referencesImage returns true if the presentation state applies to the SOP instance
+func referencesImage(dataset dicom.Dataset, uid string) bool {
+	for _, series := range sequenceItems(dataset.Elements, tag.ReferencedSeriesSequence) {
+		for _, image := range sequenceItems(series, tag.ReferencedImageSequence) {
+			if itemValue(image, tag.ReferencedSOPInstanceUID) == uid {
+				return true
+			}
+		}
+	}
+	return false
+}
+
+// findPresentationStates returns the presentation states of the loaded files referencing the image
+func findPresentationStates(image dicom.Dataset, datasetsWithFilename []DatasetEntry) []presentationState {
+	uid := getDatasetValue(image, tag.SOPInstanceUID)
+	states := make([]presentationState, 0)
+	for i := range datasetsWithFilename {
+		entry := &datasetsWithFilename[i]
+		if uid != "" && getDatasetValue(entry.dataset, tag.SOPClassUID) == grayscaleSoftcopyPresentationState &&
+			referencesImage(entry.dataset, uid) {
+			states = append(states, newPresentationState(entry, image))
+		}
+	}
+	return states
+}
+
+// newPresentationState reads the VOI window, shutter and annotations applying to the image
+func newPresentationState(entry *DatasetEntry, image dicom.Dataset) presentationState {
+	ps := presentationState{entry: entry}
+	uid := getDatasetValue(image, tag.SOPInstanceUID)
+	// items without referenced images apply to all images of the presentation state
+	appliesToImage := func(item []*dicom.Element) bool {
+		references := sequenceItems(item, tag.ReferencedImageSequence)
+		for _, reference := range references {
+			if itemValue(reference, tag.ReferencedSOPInstanceUID) == uid {
+				return true
+			}
+		}
+		return len(references) == 0
+	}
+	for _, item := range sequenceItems(entry.dataset.Elements, tag.SoftcopyVOILUTSequence) {
+		center, width := itemFloats(item, tag.WindowCenter), itemFloats(item, tag.WindowWidth)
+		if appliesToImage(item) && len(center) > 0 && len(width) > 0 {
+			ps.window = []float64{center[0], width[0]}
+			break
+		}
+	}
+
+	shapes := getDatasetValue(entry.dataset, tag.ShutterShape)
+	left, right := getDatasetFloats(entry.dataset, tag.ShutterLeftVerticalEdge), getDatasetFloats(entry.dataset, tag.ShutterRightVerticalEdge)
+	upper, lower := getDatasetFloats(entry.dataset, tag.ShutterUpperHorizontalEdge), getDatasetFloats(entry.dataset, tag.ShutterLowerHorizontalEdge)
+	center, radius := getDatasetFloats(entry.dataset, tag.CenterOfCircularShutter), getDatasetFloats(entry.dataset, tag.RadiusOfCircularShutter)
+	switch {
+	case strings.Contains(shapes, "RECTANGULAR") && len(left) > 0 && len(right) > 0 && len(upper) > 0 && len(lower) > 0:
+		ps.shutter, ps.shutterRect = "RECTANGULAR", [4]float64{left[0], right[0], upper[0], lower[0]}
+	case strings.Contains(shapes, "CIRCULAR") && len(center) > 1 && len(radius) > 0:
+		ps.shutter, ps.shutterRound = "CIRCULAR", [3]float64{center[1], center[0], radius[0]} // center is row\column
+	}
+
+	columns, rows := getDatasetFloats(image, tag.Columns), getDatasetFloats(image, tag.Rows)
+	for _, annotation := range sequenceItems(entry.dataset.Elements, tag.GraphicAnnotationSequence) {
+		if !appliesToImage(annotation) {
+			continue
+		}
+		// DISPLAY coordinates are fractions of the image area
+		scale := func(units string, point [2]float64) [2]float64 {
+			if units == "DISPLAY" && len(columns) > 0 && len(rows) > 0 {
+				return [2]float64{point[0] * columns[0], point[1] * rows[0]}
+			}
+			return point
+		}
+		for _, text := range sequenceItems(annotation, tag.TextObjectSequence) {
+			anchor := itemFloats(text, tag.AnchorPoint)
+			units := itemValue(text, tag.AnchorPointAnnotationUnits)
+			if len(anchor) < 2 {
+				anchor = itemFloats(text, tag.BoundingBoxTopLeftHandCorner)
+				units = itemValue(text, tag.BoundingBoxAnnotationUnits)
+			}
+			if len(anchor) >= 2 {
+				ps.graphics = append(ps.graphics, psGraphic{text: itemValue(text, tag.UnformattedTextValue),
+					points: [][2]float64{scale(units, [2]float64{anchor[0], anchor[1]})}})
+			}
+		}
+		for _, graphic := range sequenceItems(annotation, tag.GraphicObjectSequence) {
+			data := itemFloats(graphic, tag.GraphicData)
+			g := psGraphic{closed: itemValue(graphic, tag.GraphicType) == "POLYLINE" && len(data) > 4 &&
+				data[0] == data[len(data)-2] && data[1] == data[len(data)-1]}
+			for i := 0; i+1 < len(data); i += 2 {
+				g.points = append(g.points, scale(itemValue(graphic, tag.GraphicAnnotationUnits), [2]float64{data[i], data[i+1]}))
+			}
+			if itemValue(graphic, tag.GraphicType) == "CIRCLE" && len(g.points) == 2 {
+				g.points, g.closed = circlePoints(g.points[0], g.points[1]), true
+			}
+			if len(g.points) > 0 {
+				ps.graphics = append(ps.graphics, g)
+			}
+		}
+	}
+	return ps
+}
+
+// circlePoints approximates the circle around the center through the point by a polygon
+func circlePoints(center, point [2]float64) [][2]float64 {
+	radius := math.Hypot(point[0]-center[0], point[1]-center[1])
+	points := make([][2]float64, 0, 24)
+	for i := 0; i < 24; i++ {
+		angle := 2 * math.Pi * float64(i) / 24
+		points = append(points, [2]float64{center[0] + radius*math.Cos(angle), center[1] + radius*math.Sin(angle)})
+	}
+	return points
+}
+
+// transformations lists what the presentation state changes, like "VOI window 40/400"
+func (ps presentationState) transformations() []string {
+	lines := make([]string, 0)
+	if ps.window != nil {
+		lines = append(lines, fmt.Sprintf("VOI window %g/%g", ps.window[0], ps.window[1]))
+	}
+	switch ps.shutter {
+	case "RECTANGULAR":
+		lines = append(lines, fmt.Sprintf("rectangular shutter columns %g-%g, rows %g-%g", ps.shutterRect[0], ps.shutterRect[1],
+			ps.shutterRect[2], ps.shutterRect[3]))
+	case "CIRCULAR":
+		lines = append(lines, fmt.Sprintf("circular shutter at %g,%g radius %g", ps.shutterRound[0], ps.shutterRound[1], ps.shutterRound[2]))
+	}
+	texts := 0
+	for _, g := range ps.graphics {
+		if g.text != "" {
+			texts++
+		}
+	}
+	if len(ps.graphics) > 0 {
+		lines = append(lines, fmt.Sprintf("%d graphic annotations, %d texts", len(ps.graphics)-texts, texts))
+	}
+	return lines
+}
+
+// formatPresentationStates lists the presentation states with what they change, one per line
+func formatPresentationStates(states []presentationState) string {
+	lines := []string{"Presentation states:"}
+	for _, ps := range states {
+		lines = append(lines, fmt.Sprintf("  %s: %s", ps.entry.filename, cmp.Or(strings.Join(ps.transformations(), ", "), "nothing applied")))
+	}
+	return strings.Join(lines, "\n")
+}
+
+// shuttered returns true if the pixel at the 1-based column and row is hidden by the shutter
+func (ps presentationState) shuttered(column, row float64) bool {
+	switch ps.shutter {
+	case "RECTANGULAR":
+		return column < ps.shutterRect[0] || column > ps.shutterRect[1] || row < ps.shutterRect[2] || row > ps.shutterRect[3]
+	case "CIRCULAR":
+		return math.Hypot(column-ps.shutterRound[0], row-ps.shutterRound[1]) > ps.shutterRound[2]
+	}
+	return false
+}
+
+// renderPreview draws the first frame of the image with shades, windowed like the image defines it or with the
+// presentation state applied if given. Compressed frames are decoded with the codecs compiled in.
+func renderPreview(image dicom.Dataset, ps *presentationState, columns, rows int) (string, error) {
+	f, err := decodedFrame(image, 0)
+	if err != nil {
+		return "", err
+	}
+	if f.Rows == 0 || f.Cols == 0 || len(f.Data) < f.Rows*f.Cols {
+		return "", errors.New("no pixels")
+	}
+	slope, intercept := 1.0, 0.0
+	if values := getDatasetFloats(image, tag.RescaleSlope); len(values) > 0 && values[0] != 0 {
+		slope = values[0]
+	}
+	if values := getDatasetFloats(image, tag.RescaleIntercept); len(values) > 0 {
+		intercept = values[0]
+	}
+	low, high := math.Inf(1), math.Inf(-1)
+	for _, pixel := range f.Data {
+		low, high = min(low, slope*float64(pixel[0])+intercept), max(high, slope*float64(pixel[0])+intercept)
+	}
+	center, width := getDatasetFloats(image, tag.WindowCenter), getDatasetFloats(image, tag.WindowWidth)
+	if ps != nil && ps.window != nil {
+		center, width = ps.window[:1], ps.window[1:]
+	}
+	if len(center) > 0 && len(width) > 0 && width[0] > 0 {
+		low, high = center[0]-width[0]/2, center[0]+width[0]/2
+	}
+
+	cells := make([][]rune, rows)
+	for row := range cells {
+		cells[row] = make([]rune, columns)
+		for column := range cells[row] {
+			x0, x1 := column*f.Cols/columns, max((column+1)*f.Cols/columns, column*f.Cols/columns+1)
+			y0, y1 := row*f.Rows/rows, max((row+1)*f.Rows/rows, row*f.Rows/rows+1)
+			if ps != nil && ps.shuttered(float64(x0+x1)/2+0.5, float64(y0+y1)/2+0.5) {
+				cells[row][column] = ' '
+				continue
+			}
+			sum, count := 0.0, 0
+			for y := y0; y < min(y1, f.Rows); y++ {
+				for x := x0; x < min(x1, f.Cols); x++ {
+					sum += slope*float64(f.Data[y*f.Cols+x][0]) + intercept
+					count++
+				}
+			}
+			brightness := 1.0
+			if high > low {
+				brightness = (sum/float64(max(count, 1)) - low) / (high - low)
+			}
+			cells[row][column] = shades[min(max(int(brightness*float64(len(shades))), 0), len(shades)-1)]
+		}
+	}
+	if ps != nil {
+		// pixel coordinates are 1-based with the center of the first pixel at 0.5
+		cell := func(point [2]float64) (int, int) {
+			return int((point[0] - 0.5) * float64(columns) / float64(f.Cols)), int((point[1] - 0.5) * float64(rows) / float64(f.Rows))
+		}
+		set := func(column, row int, r rune) {
+			if row >= 0 && row < rows && column >= 0 && column < columns {
+				cells[row][column] = r
+			}
+		}
+		for _, g := range ps.graphics {
+			if g.text != "" {
+				column, row := cell(g.points[0])
+				for i, r := range []rune(g.text) {
+					set(column+i, row, r)
+				}
+				continue
+			}
+			points := g.points
+			if g.closed {
+				points = append(points[:len(points):len(points)], points[0])
+			}
+			for i := range points {
+				x0, y0 := cell(points[i])
+				set(x0, y0, '+')
+				if i == 0 {
+					continue
+				}
+				x1, y1 := cell(points[i-1])
+				steps := max(abs(x1-x0), abs(y1-y0))
+				for step := 1; step < steps; step++ {
+					t := float64(step) / float64(steps)
+					set(int(math.Round(float64(x0)+t*float64(x1-x0))), int(math.Round(float64(y0)+t*float64(y1-y0))), '·')
+				}
+			}
+		}
+	}
+	lines := make([]string, rows)
+	for row := range cells {
+		lines[row] = string(cells[row])
+	}
+	return strings.Join(lines, "\n"), nil
+}
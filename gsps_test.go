@@ -0,0 +1,67 @@
+package main
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestPresentationState(t *testing.T) {
+	assert := assert.New(t)
+
+	image := newFixture(t).
+		with(tag.SOPInstanceUID, []string{"1.2.3.1"}).
+		with(tag.Rows, []int{2}).
+		with(tag.Columns, []int{4}).
+		with(tag.WindowCenter, []string{"50"}).
+		with(tag.WindowWidth, []string{"100"}).
+		with(tag.PixelData, dicom.PixelDataInfo{Frames: []frame.Frame{{
+			NativeData: frame.NativeFrame{Data: [][]int{{0}, {30}, {60}, {100}, {0}, {30}, {60}, {100}}, Rows: 2, Cols: 4, BitsPerSample: 16},
+		}}}).
+		entry("image.dcm")
+	f := newFixture(t)
+	reference := [][]*dicom.Element{{f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.3.1"})}}
+	gsps := f.with(tag.SOPClassUID, []string{grayscaleSoftcopyPresentationState}).
+		withSequence(tag.ReferencedSeriesSequence, []*dicom.Element{f.element(tag.ReferencedImageSequence, reference)}).
+		withSequence(tag.SoftcopyVOILUTSequence, []*dicom.Element{
+			f.element(tag.WindowCenter, []string{"30"}),
+			f.element(tag.WindowWidth, []string{"60"}),
+		}).
+		with(tag.ShutterShape, []string{"RECTANGULAR"}).
+		with(tag.ShutterLeftVerticalEdge, []string{"2"}).
+		with(tag.ShutterRightVerticalEdge, []string{"4"}).
+		with(tag.ShutterUpperHorizontalEdge, []string{"1"}).
+		with(tag.ShutterLowerHorizontalEdge, []string{"2"}).
+		withSequence(tag.GraphicAnnotationSequence, []*dicom.Element{
+			f.element(tag.TextObjectSequence, [][]*dicom.Element{{
+				f.element(tag.UnformattedTextValue, []string{"AB"}),
+				f.element(tag.AnchorPoint, []float64{2.5, 1.5}),
+				f.element(tag.AnchorPointAnnotationUnits, []string{"PIXEL"}),
+			}}),
+		}).
+		entry("ps.dcm")
+	datasets := []DatasetEntry{image, gsps, newInstanceFixture(t, 1).entry("ct.dcm")}
+
+	states := findPresentationStates(image.dataset, datasets)
+	assert.Len(states, 1)
+	ps := states[0]
+	assert.Equal([]float64{30, 60}, ps.window)
+	assert.Equal([]string{"VOI window 30/60", "rectangular shutter columns 2-4, rows 1-2", "0 graphic annotations, 1 texts"},
+		ps.transformations())
+	assert.Equal("Presentation states:\n  ps.dcm: VOI window 30/60, rectangular shutter columns 2-4, rows 1-2, 0 graphic annotations, 1 texts",
+		formatPresentationStates(states))
+	assert.Empty(findPresentationStates(datasets[2].dataset, datasets))
+
+	text, err := renderPreview(image.dataset, nil, 4, 2)
+	assert.NoError(err)
+	assert.Equal(" ░▓█\n ░▓█", text)
+	text, err = renderPreview(image.dataset, &ps, 4, 2)
+	assert.NoError(err)
+	lines := strings.Split(text, "\n")
+	assert.Equal(" ▒██", lines[0]) // the shutter hides the first column, the window is 0 to 60
+	assert.Equal(" ▒AB", lines[1])
+}
@@ -0,0 +1,169 @@
+package main
+
+import (
+	"fmt"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// hierarchyStats aggregates the instances of a study or series node in the hierarchy tree
+type hierarchyStats struct {
+	label            string
+	instanceCount    int
+	totalSize        int64
+	firstAcquisition string
+	lastAcquisition  string
+	instanceNumbers  []int
+}
+
+func (s *hierarchyStats) add(entry *DatasetEntry) {
+	s.instanceCount++
+	s.totalSize += entry.size
+	if acquisition := getAcquisitionDateTime(entry.dataset); acquisition != "" {
+		if s.firstAcquisition == "" || acquisition < s.firstAcquisition {
+			s.firstAcquisition = acquisition
+		}
+		if acquisition > s.lastAcquisition {
+			s.lastAcquisition = acquisition
+		}
+	}
+	if instanceNumber, err := strconv.Atoi(getDatasetValue(entry.dataset, tag.InstanceNumber)); err == nil {
+		s.instanceNumbers = append(s.instanceNumbers, instanceNumber)
+	}
+}
+
+// missingInstanceNumbers returns the gaps between the smallest and largest instance number as ranges, e.g. "5, 7-9"
+func (s *hierarchyStats) missingInstanceNumbers() string {
+	numbers := slices.Clone(s.instanceNumbers)
+	slices.Sort(numbers)
+	numbers = slices.Compact(numbers)
+	ranges := make([]string, 0)
+	for i := 1; i < len(numbers); i++ {
+		first, last := numbers[i-1]+1, numbers[i]-1
+		if first == last {
+			ranges = append(ranges, strconv.Itoa(first))
+		} else if first < last {
+			ranges = append(ranges, fmt.Sprintf("%d-%d", first, last))
+		}
+	}
+	return strings.Join(ranges, ", ")
+}
+
+func (s *hierarchyStats) String() string {
+	text := fmt.Sprintf("%s - %d instances, %s", s.label, s.instanceCount, formatSize(s.totalSize))
+	if s.firstAcquisition != "" {
+		text += fmt.Sprintf(", %s - %s", s.firstAcquisition, s.lastAcquisition)
+	}
+	if missing := s.missingInstanceNumbers(); missing != "" {
+		text += ", missing " + missing
+	}
+	return text
+}
+
+func (s *hierarchyStats) details() string {
+	lines := []string{
+		s.label,
+		"",
+		fmt.Sprintf("%-12s %d", "Instances:", s.instanceCount),
+		fmt.Sprintf("%-12s %s", "Size:", formatSize(s.totalSize)),
+		fmt.Sprintf("%-12s %s", "First:", s.firstAcquisition),
+		fmt.Sprintf("%-12s %s", "Last:", s.lastAcquisition),
+		fmt.Sprintf("%-12s %s", "Missing:", s.missingInstanceNumbers()),
+	}
+	return strings.Join(lines, "\n")
+}
+
+// getAcquisitionDateTime returns the acquisition or content date and time in DT format, which sorts chronologically
+func getAcquisitionDateTime(dataset dicom.Dataset) string {
+	if dateTime := getDatasetValue(dataset, tag.AcquisitionDateTime); dateTime != "" {
+		return dateTime
+	}
+	if date := getDatasetValue(dataset, tag.AcquisitionDate); date != "" {
+		return date + getDatasetValue(dataset, tag.AcquisitionTime)
+	}
+	if date := getDatasetValue(dataset, tag.ContentDate); date != "" {
+		return date + getDatasetValue(dataset, tag.ContentTime)
+	}
+	return ""
+}
+
+func formatSize(size int64) string {
+	const unit = 1024
+	if size < unit {
+		return fmt.Sprintf("%d B", size)
+	}
+	div, exp := int64(unit), 0
+	for n := size / unit; n >= unit; n /= unit {
+		div *= unit
+		exp++
+	}
+	return fmt.Sprintf("%.1f %ciB", float64(size)/float64(div), "KMGTPE"[exp])
+}
+
+// sortTreeByHierarchy arranges the files by study and series, with aggregated statistics on the study and series nodes
+func sortTreeByHierarchy(rootDir string, tree *tview.TreeView, datasetsWithFilename []DatasetEntry) (*tview.TreeView, *tview.TreeNode) {
+	if tree.GetRoot() != nil {
+		tree.GetRoot().ClearChildren()
+	}
+	root := tview.NewTreeNode(rootDir).SetSelectable(true)
+	tree.SetRoot(root).SetCurrentNode(root)
+
+	studyNodesByUID := make(map[string]*tview.TreeNode)
+	seriesNodesByUID := make(map[string]*tview.TreeNode)
+	for i := range datasetsWithFilename {
+		entry := &datasetsWithFilename[i]
+		value := func(t tag.Tag) string {
+			return getDatasetValue(entry.dataset, t)
+		}
+
+		studyUID := value(tag.StudyInstanceUID)
+		studyNode, ok := studyNodesByUID[studyUID]
+		if !ok {
+			label := strings.TrimSpace(fmt.Sprintf("Study %s %s", value(tag.StudyDate), value(tag.StudyDescription)))
+			studyNode = tview.NewTreeNode(label).SetSelectable(true).SetReference(&hierarchyStats{label: label})
+			root.AddChild(studyNode)
+			studyNodesByUID[studyUID] = studyNode
+		}
+		studyNode.GetReference().(*hierarchyStats).add(entry)
+
+		seriesUID := studyUID + "/" + value(tag.SeriesInstanceUID)
+		seriesNode, ok := seriesNodesByUID[seriesUID]
+		if !ok {
+			label := strings.TrimSpace(fmt.Sprintf("Series %s %s (%s)", value(tag.SeriesNumber), value(tag.SeriesDescription), value(tag.Modality)))
+			seriesNode = tview.NewTreeNode(label).SetSelectable(true).SetReference(&hierarchyStats{label: label})
+			studyNode.AddChild(seriesNode)
+			seriesNodesByUID[seriesUID] = seriesNode
+		}
+		seriesNode.GetReference().(*hierarchyStats).add(entry)
+
+		fileNode := tview.NewTreeNode(entry.filename).SetSelectable(true).SetReference(entry)
+		addElementNodes(fileNode, entry.dataset)
+		seriesNode.AddChild(fileNode)
+	}
+
+	// texts contain the statistics, so they are set after all instances are added
+	for _, studyNode := range root.GetChildren() {
+		studyNode.SetText(studyNode.GetReference().(*hierarchyStats).String())
+		for _, seriesNode := range studyNode.GetChildren() {
+			seriesNode.SetText(seriesNode.GetReference().(*hierarchyStats).String())
+		}
+	}
+
+	return tree, root
+}
+
+// collapseAllFiles collapses all file nodes recursively, so only the hierarchy above the files is visible
+func collapseAllFiles(root *tview.TreeNode) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if isFileNode(node) {
+			node.CollapseAll()
+			return false
+		}
+		return true
+	})
+}
@@ -0,0 +1,37 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestSortTreeByHierarchy(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newInstanceFixture(t, 1).entry("1.dcm"),
+		newInstanceFixture(t, 2).entry("2.dcm"),
+		newInstanceFixture(t, 5).entry("5.dcm"),
+		newInstanceFixture(t, 7).entry("7.dcm"),
+	}
+	_, root := sortTreeByHierarchy("root", tview.NewTreeView(), datasets)
+
+	assert.Len(root.GetChildren(), 1)
+	studyNode := root.GetChildren()[0]
+	assert.Len(studyNode.GetChildren(), 1)
+	seriesNode := studyNode.GetChildren()[0]
+	assert.Len(seriesNode.GetChildren(), 4)
+	stats := seriesNode.GetReference().(*hierarchyStats)
+	assert.Equal(4, stats.instanceCount)
+	assert.Equal("3-4, 6", stats.missingInstanceNumbers())
+}
+
+func TestFormatSize(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal("512 B", formatSize(512))
+	assert.Equal("1.5 KiB", formatSize(1536))
+	assert.Equal("2.0 MiB", formatSize(2*1024*1024))
+}
@@ -0,0 +1,182 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"math"
+	"slices"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// histogramBlocks are the bar characters by eighths of a character cell
+var histogramBlocks = []rune(" ▁▂▃▄▅▆▇█")
+
+// pixelHistogram counts the stored pixel values of all native frames of a file, samples of color images together
+type pixelHistogram struct {
+	values []int // distinct values in ascending order
+	counts []int // per distinct value
+	total  int
+	// storedMin and storedMax is the value range representable with BitsStored
+	storedMin, storedMax int
+	// windowLow and windowHigh are the bounds of the first VOI window as stored values, NaN if there is none
+	windowLow, windowHigh float64
+}
+
+// newPixelHistogram counts the pixel values of the dataset, encapsulated pixel data is not supported
+func newPixelHistogram(dataset dicom.Dataset) (*pixelHistogram, error) {
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	if err != nil {
+		return nil, errors.New("no pixel data")
+	}
+	if err := loadLazyValue(e); err != nil {
+		return nil, err
+	}
+	info, ok := e.Value.GetValue().(dicom.PixelDataInfo)
+	if !ok || info.IsEncapsulated {
+		return nil, errors.New("no native pixel data")
+	}
+	occurrences := map[int]int{}
+	total := 0
+	for _, f := range info.Frames {
+		for _, pixel := range f.NativeData.Data {
+			for _, sample := range pixel {
+				occurrences[sample]++
+				total++
+			}
+		}
+	}
+	if total == 0 {
+		return nil, errors.New("no pixels")
+	}
+
+	h := &pixelHistogram{total: total, windowLow: math.NaN(), windowHigh: math.NaN()}
+	for value := range occurrences {
+		h.values = append(h.values, value)
+	}
+	slices.Sort(h.values)
+	for _, value := range h.values {
+		h.counts = append(h.counts, occurrences[value])
+	}
+
+	bitsStored := 16
+	if bits := getDatasetFloats(dataset, tag.BitsStored); len(bits) > 0 && bits[0] > 0 && bits[0] < 32 {
+		bitsStored = int(bits[0])
+	}
+	if getDatasetValue(dataset, tag.PixelRepresentation) == "1" {
+		h.storedMin, h.storedMax = -1<<(bitsStored-1), 1<<(bitsStored-1)-1
+	} else {
+		h.storedMin, h.storedMax = 0, 1<<bitsStored-1
+	}
+
+	center, width := getDatasetFloats(dataset, tag.WindowCenter), getDatasetFloats(dataset, tag.WindowWidth)
+	if len(center) > 0 && len(width) > 0 {
+		slope, intercept := 1.0, 0.0
+		if values := getDatasetFloats(dataset, tag.RescaleSlope); len(values) > 0 && values[0] != 0 {
+			slope = values[0]
+		}
+		if values := getDatasetFloats(dataset, tag.RescaleIntercept); len(values) > 0 {
+			intercept = values[0]
+		}
+		h.windowLow = (center[0] - width[0]/2 - intercept) / slope
+		h.windowHigh = (center[0] + width[0]/2 - intercept) / slope
+		if h.windowLow > h.windowHigh {
+			h.windowLow, h.windowHigh = h.windowHigh, h.windowLow
+		}
+	}
+	return h, nil
+}
+
+func (h *pixelHistogram) minValue() int {
+	return h.values[0]
+}
+
+func (h *pixelHistogram) maxValue() int {
+	return h.values[len(h.values)-1]
+}
+
+// binWidth returns the width of the bins, so that the value range fits into the given number of columns
+func (h *pixelHistogram) binWidth(columns int) int {
+	return max(1, (h.maxValue()-h.minValue()+columns)/columns)
+}
+
+// bins returns the pixel counts of the bins
+func (h *pixelHistogram) bins(columns int) []int {
+	binWidth := h.binWidth(columns)
+	bins := make([]int, (h.maxValue()-h.minValue())/binWidth+1)
+	for i, value := range h.values {
+		bins[(value-h.minValue())/binWidth] += h.counts[i]
+	}
+	return bins
+}
+
+// column returns the bin of the value, clamped to the bins
+func (h *pixelHistogram) column(value float64, columns int) int {
+	bin := int(math.Floor((value - float64(h.minValue())) / float64(h.binWidth(columns))))
+	return min(max(bin, 0), (h.maxValue()-h.minValue())/h.binWidth(columns))
+}
+
+// chart draws the bars with a logarithmic height, so that single values like the background don't hide the rest.
+// Below the bars the VOI window is marked by '[' and ']' and the cursor bin by '^', if cursor isn't negative.
+func (h *pixelHistogram) chart(columns, rows, cursor int) string {
+	bins := h.bins(columns)
+	highest := math.Log1p(float64(slices.Max(bins)))
+	lines := make([]string, 0, rows+2)
+	for row := rows - 1; row >= 0; row-- {
+		var line strings.Builder
+		for _, count := range bins {
+			eighths := int(math.Round(math.Log1p(float64(count))/highest*float64(8*rows))) - 8*row
+			if count > 0 && row == 0 {
+				eighths = max(eighths, 1) // single pixels stay visible
+			}
+			line.WriteRune(histogramBlocks[min(max(eighths, 0), 8)])
+		}
+		lines = append(lines, line.String())
+	}
+
+	markers := []rune(strings.Repeat(" ", len(bins)))
+	if !math.IsNaN(h.windowLow) {
+		markers[h.column(h.windowLow, columns)] = '['
+		markers[h.column(h.windowHigh, columns)] = ']'
+	}
+	if cursor >= 0 && cursor < len(bins) {
+		markers[cursor] = '^'
+	}
+	lines = append(lines, strings.TrimRight(string(markers), " "))
+	low, high := fmt.Sprint(h.minValue()), fmt.Sprint(h.maxValue())
+	lines = append(lines, low+strings.Repeat(" ", max(1, len(bins)-len(low)-len(high)))+high)
+	return strings.Join(lines, "\n")
+}
+
+// readout describes the bin at the cursor
+func (h *pixelHistogram) readout(columns, cursor int) string {
+	bins := h.bins(columns)
+	cursor = min(max(cursor, 0), len(bins)-1)
+	low := h.minValue() + cursor*h.binWidth(columns)
+	high := min(low+h.binWidth(columns)-1, h.maxValue())
+	count := bins[cursor]
+	if low == high {
+		return fmt.Sprintf("%d: %d pixels (%.1f%%)", low, count, 100*float64(count)/float64(h.total))
+	}
+	return fmt.Sprintf("%d to %d: %d pixels (%.1f%%)", low, high, count, 100*float64(count)/float64(h.total))
+}
+
+// summary describes the value range and warns of empty images and of values clipped at the limits of the stored range
+func (h *pixelHistogram) summary() string {
+	lines := []string{fmt.Sprintf("%d pixels, values %d to %d, %d distinct", h.total, h.minValue(), h.maxValue(), len(h.values))}
+	if len(h.values) == 1 {
+		lines = append(lines, fmt.Sprintf("empty image, all pixels %d", h.minValue()))
+		return strings.Join(lines, "\n")
+	}
+	// zero is the usual background of unsigned images rather than clipping
+	if h.minValue() == h.storedMin && h.storedMin != 0 {
+		lines = append(lines, fmt.Sprintf("%.1f%% clipped at minimum %d", 100*float64(h.counts[0])/float64(h.total), h.storedMin))
+	}
+	if h.maxValue() == h.storedMax {
+		count := h.counts[len(h.counts)-1]
+		lines = append(lines, fmt.Sprintf("%.1f%% clipped at maximum %d", 100*float64(count)/float64(h.total), h.storedMax))
+	}
+	return strings.Join(lines, "\n")
+}
@@ -0,0 +1,54 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func newPixelFixture(t *testing.T, pixels ...int) *fixture {
+	data := make([][]int, 0, len(pixels))
+	for _, pixel := range pixels {
+		data = append(data, []int{pixel})
+	}
+	return newInstanceFixture(t, 1).
+		with(tag.BitsStored, []int{12}).
+		with(tag.PixelRepresentation, []int{0}).
+		with(tag.PixelData, dicom.PixelDataInfo{Frames: []frame.Frame{{
+			NativeData: frame.NativeFrame{Data: data, Rows: 1, Cols: len(pixels), BitsPerSample: 16},
+		}}})
+}
+
+func TestPixelHistogram(t *testing.T) {
+	assert := assert.New(t)
+
+	h, err := newPixelHistogram(newPixelFixture(t, 0, 0, 0, 1, 5, 9, 4095).
+		with(tag.WindowCenter, []string{"40"}).
+		with(tag.WindowWidth, []string{"20"}).
+		with(tag.RescaleIntercept, []string{"-1024"}).
+		dataset())
+	assert.NoError(err)
+	assert.Equal([]int{0, 1, 5, 9, 4095}, h.values)
+	assert.Equal([]int{3, 1, 1, 1, 1}, h.counts)
+	assert.Equal(1054.0, h.windowLow)
+	assert.Equal(1074.0, h.windowHigh)
+	assert.Equal(410, h.binWidth(10))
+	assert.Equal([]int{6, 0, 0, 0, 0, 0, 0, 0, 0, 1}, h.bins(10))
+	assert.Equal("0 to 409: 6 pixels (85.7%)", h.readout(10, 0))
+	assert.Equal("3690 to 4095: 1 pixels (14.3%)", h.readout(10, 20))
+	assert.Equal("7 pixels, values 0 to 4095, 5 distinct\n14.3% clipped at maximum 4095", h.summary())
+	assert.Equal("█        ▃\n"+
+		"^ ]\n"+
+		"0     4095", h.chart(10, 1, 0))
+
+	h, err = newPixelHistogram(newPixelFixture(t, 7, 7).dataset())
+	assert.NoError(err)
+	assert.Equal("2 pixels, values 7 to 7, 1 distinct\nempty image, all pixels 7", h.summary())
+	assert.Equal("7: 2 pixels (100.0%)", h.readout(10, 0))
+
+	_, err = newPixelHistogram(newInstanceFixture(t, 1).dataset())
+	assert.Error(err)
+}
@@ -0,0 +1,105 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+)
+
+const maxHistoryEntries = 500
+
+// history keeps the previously entered searches and commands, persisted in the user's config directory
+type history struct {
+	path    string
+	entries []string
+	index   int    // current position while navigating, len(entries) if not navigating
+	search  string // text searched for with ctrl + r
+}
+
+func historyPath() string {
+	dir, err := os.UserConfigDir()
+	if err != nil {
+		return ""
+	}
+	return filepath.Join(dir, "dcmtagger", "history")
+}
+
+// loadHistory reads the history from the given file, an empty path keeps the history in memory only
+func loadHistory(path string) *history {
+	h := &history{path: path, entries: make([]string, 0)}
+	if data, err := os.ReadFile(path); err == nil && path != "" {
+		for _, line := range strings.Split(string(data), "\n") {
+			if line != "" {
+				h.entries = append(h.entries, line)
+			}
+		}
+	}
+	h.reset()
+	return h
+}
+
+func (h *history) reset() {
+	h.index = len(h.entries)
+	h.search = ""
+}
+
+// add appends the entry as newest one, removing older duplicates, and saves the history
+func (h *history) add(text string) error {
+	h.entries = slices.DeleteFunc(h.entries, func(entry string) bool { return entry == text })
+	h.entries = append(h.entries, text)
+	if len(h.entries) > maxHistoryEntries {
+		h.entries = h.entries[len(h.entries)-maxHistoryEntries:]
+	}
+	h.reset()
+	return h.save()
+}
+
+func (h *history) save() error {
+	if h.path == "" {
+		return nil
+	}
+	if err := os.MkdirAll(filepath.Dir(h.path), 0o755); err != nil {
+		return err
+	}
+	return os.WriteFile(h.path, []byte(strings.Join(h.entries, "\n")+"\n"), 0o644)
+}
+
+// previous moves to the previous entry starting with the given prefix, e.g. '/' for searches, returns false if there is
+// none
+func (h *history) previous(prefix string) (string, bool) {
+	for i := h.index - 1; i >= 0; i-- {
+		if strings.HasPrefix(h.entries[i], prefix) {
+			h.index = i
+			return h.entries[i], true
+		}
+	}
+	return "", false
+}
+
+// next moves to the next entry starting with the given prefix, after the newest one the plain prefix is returned
+func (h *history) next(prefix string) string {
+	for i := h.index + 1; i < len(h.entries); i++ {
+		if strings.HasPrefix(h.entries[i], prefix) {
+			h.index = i
+			return h.entries[i]
+		}
+	}
+	h.index = len(h.entries)
+	return prefix
+}
+
+// reverseSearch moves to the previous entry containing the text given on the first call, like ctrl + r in a shell.
+// Subsequent calls continue searching further back until reset is called.
+func (h *history) reverseSearch(text string) (string, bool) {
+	if h.search == "" {
+		h.search = text
+	}
+	for i := h.index - 1; i >= 0; i-- {
+		if strings.Contains(h.entries[i], h.search) {
+			h.index = i
+			return h.entries[i], true
+		}
+	}
+	return "", false
+}
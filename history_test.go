@@ -0,0 +1,37 @@
+package main
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestHistory(t *testing.T) {
+	assert := assert.New(t)
+
+	path := filepath.Join(t.TempDir(), "history")
+	h := loadHistory(path)
+	assert.NoError(h.add("/patient"))
+	assert.NoError(h.add(":slicecheck"))
+	assert.NoError(h.add("/study"))
+	assert.NoError(h.add("/patient"))
+
+	h = loadHistory(path)
+	assert.Equal([]string{":slicecheck", "/study", "/patient"}, h.entries)
+
+	entry, ok := h.previous("/")
+	assert.True(ok)
+	assert.Equal("/patient", entry)
+	entry, _ = h.previous("/")
+	assert.Equal("/study", entry)
+	_, ok = h.previous("/")
+	assert.False(ok)
+	assert.Equal("/patient", h.next("/"))
+	assert.Equal("/", h.next("/"))
+
+	h.reset()
+	entry, ok = h.reverseSearch("slice")
+	assert.True(ok)
+	assert.Equal(":slicecheck", entry)
+}
@@ -0,0 +1,270 @@
+package main
+
+import (
+	"cmp"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"slices"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// Encoding and decoding of datasets in implicit VR little endian, as used for DIMSE commands and query identifiers.
+// The VR of decoded elements is taken from the dictionary.
+
+var (
+	itemTag              = tag.Tag{Group: 0xfffe, Element: 0xe000}
+	itemDelimiterTag     = tag.Tag{Group: 0xfffe, Element: 0xe00d}
+	sequenceDelimiterTag = tag.Tag{Group: 0xfffe, Element: 0xe0dd}
+)
+
+// command elements, which aren't part of the dictionary
+var (
+	tagCommandGroupLength        = tag.Tag{Group: 0x0000, Element: 0x0000}
+	tagAffectedSOPClassUID       = tag.Tag{Group: 0x0000, Element: 0x0002}
+	tagCommandField              = tag.Tag{Group: 0x0000, Element: 0x0100}
+	tagMessageID                 = tag.Tag{Group: 0x0000, Element: 0x0110}
+	tagMessageIDBeingRespondedTo = tag.Tag{Group: 0x0000, Element: 0x0120}
+	tagPriority                  = tag.Tag{Group: 0x0000, Element: 0x0700}
+	tagCommandDataSetType        = tag.Tag{Group: 0x0000, Element: 0x0800}
+	tagStatus                    = tag.Tag{Group: 0x0000, Element: 0x0900}
+	tagAffectedSOPInstanceUID    = tag.Tag{Group: 0x0000, Element: 0x1000}
+)
+
+var commandVRs = map[tag.Tag]string{
+	tagCommandGroupLength:        "UL",
+	tagAffectedSOPClassUID:       "UI",
+	tagCommandField:              "US",
+	tagMessageID:                 "US",
+	tagMessageIDBeingRespondedTo: "US",
+	tagPriority:                  "US",
+	tagCommandDataSetType:        "US",
+	tagStatus:                    "US",
+	tagAffectedSOPInstanceUID:    "UI",
+	{Group: 0x0000, Element: 0x0003}: "UI", // Requested SOP Class UID
+	{Group: 0x0000, Element: 0x0600}: "AE", // Move Destination
+	{Group: 0x0000, Element: 0x0902}: "LO", // Error Comment
+	{Group: 0x0000, Element: 0x1001}: "UI", // Requested SOP Instance UID
+	{Group: 0x0000, Element: 0x1030}: "AE", // Move Originator Application Entity Title
+	{Group: 0x0000, Element: 0x1031}: "US", // Move Originator Message ID
+}
+
+// getImplicitVR returns the VR of the tag from the dictionary, UN if unknown
+func getImplicitVR(t tag.Tag) string {
+	if vr, ok := commandVRs[t]; ok {
+		return vr
+	}
+	if t.Element == 0x0000 {
+		return "UL" // group length
+	}
+	info, err := findTag(t)
+	if err != nil || len(info.VR) < 2 {
+		return "UN"
+	}
+	return info.VR[:2] // first one of ambiguous VRs like "US or SS"
+}
+
+// newEmptyValue returns an empty value of the type the dicom library uses for the VR
+func newEmptyValue(vr string) (dicom.Value, error) {
+	switch vr {
+	case "US", "SS", "UL", "SL", "AT":
+		return dicom.NewValue([]int{})
+	case "FL", "FD":
+		return dicom.NewValue([]float64{})
+	case "OB", "OW", "OF", "OD", "OL", "UN":
+		return dicom.NewValue([]byte{})
+	}
+	return dicom.NewValue([]string{})
+}
+
+func newDecodedElement(t tag.Tag, vr string, data []byte) (*dicom.Element, error) {
+	value, err := newEmptyValue(vr)
+	if err != nil {
+		return nil, err
+	}
+	e := &dicom.Element{Tag: t, RawValueRepresentation: vr, Value: value}
+	if value.ValueType() == dicom.Strings {
+		data = []byte(strings.TrimRight(string(data), " \x00"))
+	}
+	if err := setRawValue(e, data); err != nil {
+		return nil, err
+	}
+	return e, nil
+}
+
+// decodeImplicit decodes all elements of the data
+func decodeImplicit(data []byte) ([]*dicom.Element, error) {
+	elements, _, err := decodeImplicitElements(data, false)
+	return elements, err
+}
+
+// decodeImplicitElements decodes elements until the end of the data or, with 'untilItemDelimiter', until the item
+// delimiter of an item with undefined length. Returns the elements and the number of bytes consumed.
+func decodeImplicitElements(data []byte, untilItemDelimiter bool) ([]*dicom.Element, int, error) {
+	elements := make([]*dicom.Element, 0)
+	pos := 0
+	for pos < len(data) {
+		if len(data)-pos < 8 {
+			return nil, 0, errors.New("truncated element header")
+		}
+		t := tag.Tag{Group: binary.LittleEndian.Uint16(data[pos:]), Element: binary.LittleEndian.Uint16(data[pos+2:])}
+		length := binary.LittleEndian.Uint32(data[pos+4:])
+		pos += 8
+		if t == itemDelimiterTag && untilItemDelimiter {
+			return elements, pos, nil
+		}
+
+		vr := getImplicitVR(t)
+		if vr == "SQ" {
+			items, n, err := decodeImplicitSequence(data[pos:], length)
+			if err != nil {
+				return nil, 0, err
+			}
+			pos += n
+			value, err := dicom.NewValue(items)
+			if err != nil {
+				return nil, 0, err
+			}
+			elements = append(elements, &dicom.Element{Tag: t, RawValueRepresentation: vr, ValueLength: length, Value: value})
+			continue
+		}
+		if length == undefinedLength || int(length) > len(data)-pos {
+			return nil, 0, fmt.Errorf("invalid length %d of element (%04x,%04x)", length, t.Group, t.Element)
+		}
+		e, err := newDecodedElement(t, vr, data[pos:pos+int(length)])
+		if err != nil {
+			return nil, 0, err
+		}
+		pos += int(length)
+		elements = append(elements, e)
+	}
+	if untilItemDelimiter {
+		return nil, 0, errors.New("missing item delimiter")
+	}
+	return elements, pos, nil
+}
+
+// decodeImplicitSequence decodes the items of a sequence with the given length, which may be undefined
+func decodeImplicitSequence(data []byte, length uint32) ([][]*dicom.Element, int, error) {
+	items := make([][]*dicom.Element, 0)
+	end := len(data)
+	if length != undefinedLength {
+		if int(length) > len(data) {
+			return nil, 0, errors.New("truncated sequence")
+		}
+		end = int(length)
+	}
+	pos := 0
+	for pos < end {
+		if end-pos < 8 {
+			return nil, 0, errors.New("truncated item header")
+		}
+		t := tag.Tag{Group: binary.LittleEndian.Uint16(data[pos:]), Element: binary.LittleEndian.Uint16(data[pos+2:])}
+		itemLength := binary.LittleEndian.Uint32(data[pos+4:])
+		pos += 8
+		if t == sequenceDelimiterTag {
+			return items, pos, nil
+		}
+		if t != itemTag {
+			return nil, 0, fmt.Errorf("unexpected element (%04x,%04x) in sequence", t.Group, t.Element)
+		}
+		if itemLength == undefinedLength {
+			item, n, err := decodeImplicitElements(data[pos:end], true)
+			if err != nil {
+				return nil, 0, err
+			}
+			items = append(items, item)
+			pos += n
+		} else {
+			if int(itemLength) > end-pos {
+				return nil, 0, errors.New("truncated item")
+			}
+			item, _, err := decodeImplicitElements(data[pos:pos+int(itemLength)], false)
+			if err != nil {
+				return nil, 0, err
+			}
+			items = append(items, item)
+			pos += int(itemLength)
+		}
+	}
+	if length == undefinedLength {
+		return nil, 0, errors.New("missing sequence delimiter")
+	}
+	return items, pos, nil
+}
+
+func appendElementHeader(buf []byte, t tag.Tag, length uint32) []byte {
+	buf = binary.LittleEndian.AppendUint16(buf, t.Group)
+	buf = binary.LittleEndian.AppendUint16(buf, t.Element)
+	return binary.LittleEndian.AppendUint32(buf, length)
+}
+
+// encodeImplicit encodes the elements sorted by tag, sequences and items with explicit length
+func encodeImplicit(elements []*dicom.Element) ([]byte, error) {
+	elements = slices.Clone(elements)
+	slices.SortFunc(elements, func(a, b *dicom.Element) int {
+		return cmp.Or(cmp.Compare(a.Tag.Group, b.Tag.Group), cmp.Compare(a.Tag.Element, b.Tag.Element))
+	})
+	buf := make([]byte, 0)
+	for _, e := range elements {
+		if e.Value.ValueType() == dicom.Sequences {
+			sequence := make([]byte, 0)
+			for _, item := range e.Value.GetValue().([]*dicom.SequenceItemValue) {
+				itemData, err := encodeImplicit(item.GetValue().([]*dicom.Element))
+				if err != nil {
+					return nil, err
+				}
+				sequence = appendElementHeader(sequence, itemTag, uint32(len(itemData)))
+				sequence = append(sequence, itemData...)
+			}
+			buf = appendElementHeader(buf, e.Tag, uint32(len(sequence)))
+			buf = append(buf, sequence...)
+			continue
+		}
+
+		value, err := getRawValue(e)
+		if err != nil {
+			return nil, err
+		}
+		if len(value)%2 == 1 {
+			padding := byte(' ')
+			if e.RawValueRepresentation == "UI" || e.Value.ValueType() == dicom.Bytes {
+				padding = 0
+			}
+			value = append(slices.Clone(value), padding)
+		}
+		buf = appendElementHeader(buf, e.Tag, uint32(len(value)))
+		buf = append(buf, value...)
+	}
+	return buf, nil
+}
+
+// commandValue is an element of a DIMSE command, either a UID string or an unsigned short
+type commandValue struct {
+	tag   tag.Tag
+	value any
+}
+
+// encodeCommand encodes the command elements, preceded by the command group length
+func encodeCommand(values ...commandValue) []byte {
+	data := make([]byte, 0)
+	for _, v := range values {
+		switch value := v.value.(type) {
+		case string:
+			if len(value)%2 == 1 {
+				value += "\x00"
+			}
+			data = appendElementHeader(data, v.tag, uint32(len(value)))
+			data = append(data, value...)
+		case uint16:
+			data = appendElementHeader(data, v.tag, 2)
+			data = binary.LittleEndian.AppendUint16(data, value)
+		}
+	}
+	command := appendElementHeader(nil, tagCommandGroupLength, 4)
+	command = binary.LittleEndian.AppendUint32(command, uint32(len(data)))
+	return append(command, data...)
+}
@@ -0,0 +1,208 @@
+package main
+
+import (
+	"cmp"
+	"context"
+	"crypto/sha256"
+	"encoding/gob"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// indexedTags are the key tags stored per file in the index
+var indexedTags = []tag.Tag{
+	tag.PatientName, tag.PatientID, tag.PatientBirthDate, tag.AccessionNumber, tag.StudyInstanceUID, tag.StudyDate,
+	tag.StudyDescription, tag.SeriesInstanceUID, tag.SeriesNumber, tag.SeriesDescription, tag.Modality,
+	tag.SOPClassUID, tag.SOPInstanceUID, tag.InstanceNumber, tag.SliceThickness, tag.Manufacturer,
+}
+
+// indexedFile are the key tags of a file by keyword, with size and modification time to detect changed files
+type indexedFile struct {
+	Size    int64
+	ModTime time.Time
+	Tags    map[string]string
+}
+
+// fileIndex is a persistent index of the key tags of all files of a directory, so files don't have to be parsed again
+// to query them
+type fileIndex struct {
+	path  string
+	Files map[string]indexedFile // by file path
+}
+
+// indexPath returns the index file of the given input in the user's cache directory
+func indexPath(input string) string {
+	dir, err := os.UserCacheDir()
+	if err != nil {
+		return ""
+	}
+	abs, err := filepath.Abs(input)
+	if err != nil {
+		return ""
+	}
+	hash := sha256.Sum256([]byte(abs))
+	return filepath.Join(dir, "dcmtagger", "index", hex.EncodeToString(hash[:8])+".gob")
+}
+
+// loadIndex reads the index from the given file, a missing or outdated file results in an empty index. An empty path
+// keeps the index in memory only.
+func loadIndex(path string) (*fileIndex, error) {
+	index := &fileIndex{path: path, Files: map[string]indexedFile{}}
+	if path == "" {
+		return index, nil
+	}
+	file, err := os.Open(path)
+	if errors.Is(err, fs.ErrNotExist) {
+		return index, nil
+	}
+	if err != nil {
+		return nil, err
+	}
+	defer file.Close()
+	if err := gob.NewDecoder(file).Decode(index); err != nil {
+		return &fileIndex{path: path, Files: map[string]indexedFile{}}, nil
+	}
+	return index, nil
+}
+
+// save writes the index to a temporary file first, so an interrupted save doesn't leave a broken index
+func (i *fileIndex) save() error {
+	if i.path == "" {
+		return nil
+	}
+	if err := os.MkdirAll(filepath.Dir(i.path), 0o755); err != nil {
+		return err
+	}
+	file, err := os.CreateTemp(filepath.Dir(i.path), "index-*.tmp")
+	if err != nil {
+		return err
+	}
+	if err := gob.NewEncoder(file).Encode(i); err != nil {
+		file.Close()
+		os.Remove(file.Name())
+		return err
+	}
+	if err := file.Close(); err != nil {
+		os.Remove(file.Name())
+		return err
+	}
+	return os.Rename(file.Name(), i.path)
+}
+
+// add stores the key tags of the parsed dataset
+func (i *fileIndex) add(path string, info fs.FileInfo, dataset dicom.Dataset) {
+	tags := map[string]string{}
+	for _, t := range indexedTags {
+		if value := getDatasetValue(dataset, t); value != "" {
+			tags[tagKeyword(t)] = value
+		}
+	}
+	i.Files[path] = indexedFile{Size: info.Size(), ModTime: info.ModTime(), Tags: tags}
+}
+
+// isCurrent returns true if the file is indexed and wasn't changed since
+func (i *fileIndex) isCurrent(path string, info fs.FileInfo) bool {
+	indexed, ok := i.Files[path]
+	return ok && indexed.Size == info.Size() && indexed.ModTime.Equal(info.ModTime())
+}
+
+// entry returns the entry of a file which wasn't changed since it was indexed, with a dataset of its key tags, false
+// for new and changed files or without index
+func (i *fileIndex) entry(path, filename string, info fs.FileInfo) (DatasetEntry, bool) {
+	if i == nil || !i.isCurrent(path, info) {
+		return DatasetEntry{}, false
+	}
+	elements := make([]*dicom.Element, 0, len(i.Files[path].Tags))
+	for keyword, value := range i.Files[path].Tags {
+		tagInfo, err := findTagByName(keyword)
+		if err != nil {
+			return DatasetEntry{}, false
+		}
+		e, err := dicom.NewElement(tagInfo.Tag, strings.Split(value, "\\"))
+		if err != nil {
+			return DatasetEntry{}, false
+		}
+		elements = append(elements, e)
+	}
+	slices.SortFunc(elements, func(a, b *dicom.Element) int {
+		return cmp.Or(cmp.Compare(a.Tag.Group, b.Tag.Group), cmp.Compare(a.Tag.Element, b.Tag.Element))
+	})
+	return DatasetEntry{filename, dicom.Dataset{Elements: elements}, path, info.Size(), info.ModTime(), true, true}, true
+}
+
+// update parses all new and changed DICOM files of the given file or directory and removes the vanished ones. Other
+// files are skipped like when loading the directory. Returns the number of parsed files and the skipped files.
+func (i *fileIndex) update(ctx context.Context, input string, progress func(done, total int)) (int, []skippedFile, error) {
+	pixelData := tag.PixelData
+	parsed := 0
+	found := map[string]bool{}
+	skipped := make([]skippedFile, 0)
+	err := walkIndexedDicomFiles(ctx, input, elementSelection{readUntil: &pixelData}, i, progress,
+		func(file skippedFile) { skipped = append(skipped, file) },
+		func(entry DatasetEntry) error {
+			found[entry.path] = true
+			if !entry.indexed {
+				parsed++
+			}
+			return nil
+		})
+	if err != nil {
+		return parsed, skipped, err
+	}
+	for path := range i.Files {
+		if !found[path] {
+			delete(i.Files, path)
+		}
+	}
+	return parsed, skipped, nil
+}
+
+// find returns the sorted paths of all files whose key tag with the given keyword has the given value
+func (i *fileIndex) find(keyword, value string) []string {
+	paths := make([]string, 0)
+	for path, indexed := range i.Files {
+		if indexed.Tags[keyword] == value {
+			paths = append(paths, path)
+		}
+	}
+	slices.Sort(paths)
+	return paths
+}
+
+// withoutIndexed returns the entries of the files which have been read, leaving out those with only the key tags of
+// the index, and the number of entries left out
+func withoutIndexed(entries []DatasetEntry) ([]DatasetEntry, int) {
+	read := make([]DatasetEntry, 0, len(entries))
+	for _, entry := range entries {
+		if !entry.indexed {
+			read = append(read, entry)
+		}
+	}
+	return read, len(entries) - len(read)
+}
+
+// indexedStatus tells that the given number of files only taken from the index were left out, empty if none were
+func indexedStatus(count int) string {
+	if count == 0 {
+		return ""
+	}
+	return fmt.Sprintf(" - %d files only indexed left out, expand them to read them", count)
+}
+
+// tagKeyword returns the dictionary keyword of the tag, e.g. PatientName
+func tagKeyword(t tag.Tag) string {
+	if info, err := findTag(t); err == nil {
+		return info.Name
+	}
+	return fmt.Sprintf("%04x,%04x", t.Group, t.Element)
+}
@@ -0,0 +1,105 @@
+package main
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestFileIndex(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	first, second := filepath.Join(dir, "1.dcm"), filepath.Join(dir, "2.dcm")
+	newInstanceFixture(t, 1).writeFile(first)
+	newInstanceFixture(t, 2).writeFile(second)
+	indexFile := filepath.Join(t.TempDir(), "index.gob")
+
+	index, err := loadIndex(indexFile)
+	assert.NoError(err)
+	parsed, _, err := index.update(context.Background(), dir, nil)
+	assert.NoError(err)
+	assert.Equal(2, parsed)
+	assert.Equal([]string{first, second}, index.find("Modality", "CT"))
+	assert.Equal("1.2.3.4.5.2", index.Files[second].Tags["SOPInstanceUID"])
+	assert.NoError(index.save())
+
+	index, err = loadIndex(indexFile)
+	assert.NoError(err)
+	parsed, _, _, err = index.update(context.Background(), dir, nil)
+	assert.NoError(err)
+	assert.Equal(0, parsed, "unchanged files aren't parsed again")
+	assert.Equal([]string{first, second}, index.find("Modality", "CT"))
+
+	newFixture(t).
+		with(tag.SOPClassUID, []string{"1.2.840.10008.5.1.4.1.1.4"}).
+		with(tag.SOPInstanceUID, []string{"1.2.3.4.6.2"}).
+		with(tag.Modality, []string{"MR"}).
+		writeFile(second)
+	assert.NoError(os.Remove(first))
+	parsed, _, _, err = index.update(context.Background(), dir, nil)
+	assert.NoError(err)
+	assert.Equal(1, parsed)
+	assert.Empty(index.find("Modality", "CT"))
+	assert.Equal([]string{second}, index.find("Modality", "MR"))
+	assert.Len(index.Files, 1)
+
+	// files which aren't DICOM are skipped, a broken indexed file is dropped from the index
+	assert.NoError(os.WriteFile(filepath.Join(dir, "notes.txt"), []byte("no DICOM"), 0o644))
+	assert.NoError(os.WriteFile(second, []byte("broken"), 0o644))
+	parsed, skipped, err := index.update(context.Background(), dir, nil)
+	assert.NoError(err)
+	assert.Equal(0, parsed)
+	assert.Len(skipped, 2)
+	assert.Empty(index.Files)
+}
+
+func TestWalkIndexedDicomFiles(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	first, second := filepath.Join(dir, "1.dcm"), filepath.Join(dir, "2.dcm")
+	newInstanceFixture(t, 1).writeFile(first)
+	newInstanceFixture(t, 2).writeFile(second)
+	index, err := loadIndex("")
+	assert.NoError(err)
+	_, _, err = index.update(context.Background(), dir, nil)
+	assert.NoError(err)
+
+	newInstanceFixture(t, 3).with(tag.PatientSex, []string{"M"}).writeFile(second)
+	entries := make([]DatasetEntry, 0)
+	err = walkIndexedDicomFiles(context.Background(), dir, elementSelection{}, index, nil, nil, func(entry DatasetEntry) error {
+		entries = append(entries, entry)
+		return nil
+	})
+	assert.NoError(err)
+	assert.Len(entries, 2)
+	assert.True(entries[0].indexed, "unchanged files are taken from the index")
+	assert.Equal("1.dcm", entries[0].filename)
+	assert.Equal("CT", getDatasetValue(entries[0].dataset, tag.Modality))
+	assert.Equal("", getDatasetValue(entries[0].dataset, tag.Rows), "only key tags are indexed")
+	assert.False(entries[1].indexed, "changed files are parsed")
+	assert.Equal("M", getDatasetValue(entries[1].dataset, tag.PatientSex))
+	assert.Equal("1.2.3.4.5.3", index.Files[second].Tags["SOPInstanceUID"], "parsed files are indexed")
+}
+
+func TestWithoutIndexed(t *testing.T) {
+	assert := assert.New(t)
+
+	indexed := newInstanceFixture(t, 2).entry("2.dcm")
+	indexed.indexed = true
+	entries, count := withoutIndexed([]DatasetEntry{newInstanceFixture(t, 1).entry("1.dcm"), indexed})
+	assert.Len(entries, 1)
+	assert.Equal("1.dcm", entries[0].filename)
+	assert.Equal(1, count)
+	assert.Equal(" - 1 files only indexed left out, expand them to read them", indexedStatus(count))
+	assert.Equal("", indexedStatus(0))
+
+	// indexed files aren't checked for required attributes, only their key tags are known
+	issues := newConformanceIssues([]DatasetEntry{indexed})
+	assert.Empty(issues.files)
+}
@@ -0,0 +1,88 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"slices"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+const keyObjectSelectionDocument = "1.2.840.10008.5.1.4.1.1.88.59"
+
+// keyObjectReference is an instance referenced by a key object selection document and its file if it is loaded
+type keyObjectReference struct {
+	sopInstanceUID string
+	entry          *DatasetEntry // nil if the instance isn't loaded
+}
+
+func (r keyObjectReference) String() string {
+	if r.entry == nil {
+		return r.sopInstanceUID + " - not loaded"
+	}
+	return r.entry.filename
+}
+
+// keyObjectTitle returns the document title like "Of Interest"
+func keyObjectTitle(dataset dicom.Dataset) string {
+	return codeOf(dataset.Elements, tag.ConceptNameCodeSequence, tag.CodeMeaning)
+}
+
+// newKeyObjectReferences returns the instances referenced by the content and the evidence of the key object selection
+// document in order of appearance, resolved to the loaded files
+func newKeyObjectReferences(dataset dicom.Dataset, datasetsWithFilename []DatasetEntry) ([]keyObjectReference, error) {
+	if getDatasetValue(dataset, tag.SOPClassUID) != keyObjectSelectionDocument {
+		return nil, errors.New("no key object selection document")
+	}
+	uids := make([]string, 0)
+	add := func(items [][]*dicom.Element) {
+		for _, item := range items {
+			if uid := itemValue(item, tag.ReferencedSOPInstanceUID); uid != "" && !slices.Contains(uids, uid) {
+				uids = append(uids, uid)
+			}
+		}
+	}
+	for _, item := range contentItems(dataset.Elements) {
+		add(sequenceItems(item, tag.ReferencedSOPSequence))
+	}
+	for _, study := range sequenceItems(dataset.Elements, tag.CurrentRequestedProcedureEvidenceSequence) {
+		for _, series := range sequenceItems(study, tag.ReferencedSeriesSequence) {
+			add(sequenceItems(series, tag.ReferencedSOPSequence))
+		}
+	}
+
+	entries := map[string]*DatasetEntry{}
+	for i := range datasetsWithFilename {
+		entries[getDatasetValue(datasetsWithFilename[i].dataset, tag.SOPInstanceUID)] = &datasetsWithFilename[i]
+	}
+	references := make([]keyObjectReference, 0, len(uids))
+	for _, uid := range uids {
+		references = append(references, keyObjectReference{uid, entries[uid]})
+	}
+	return references, nil
+}
+
+// formatKeyObjectSummary returns the number of referenced instances and of those not loaded for the details
+func formatKeyObjectSummary(references []keyObjectReference) string {
+	missing := 0
+	for _, r := range references {
+		if r.entry == nil {
+			missing++
+		}
+	}
+	return fmt.Sprintf("Key objects: %d referenced, %d not loaded, see :ko", len(references), missing)
+}
+
+// findEntryNode returns the file node of the entry, nil if the tree has none like when sorted by tag
+func findEntryNode(root *tview.TreeNode, entry *DatasetEntry) *tview.TreeNode {
+	var found *tview.TreeNode
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if node.GetReference() == entry {
+			found = node
+		}
+		return found == nil
+	})
+	return found
+}
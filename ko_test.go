@@ -0,0 +1,48 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestKeyObjectReferences(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t)
+	sop := func(uid string) []*dicom.Element {
+		return []*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{uid})}
+	}
+	ko := f.with(tag.SOPClassUID, []string{keyObjectSelectionDocument}).
+		withSequence(tag.ConceptNameCodeSequence, []*dicom.Element{f.element(tag.CodeMeaning, []string{"Of Interest"})}).
+		withSequence(tag.ContentSequence, []*dicom.Element{
+			f.element(tag.ReferencedSOPSequence, [][]*dicom.Element{sop("1.2.3.4.5.2")}),
+		}).
+		withSequence(tag.CurrentRequestedProcedureEvidenceSequence, []*dicom.Element{
+			f.element(tag.ReferencedSeriesSequence, [][]*dicom.Element{{
+				f.element(tag.ReferencedSOPSequence, [][]*dicom.Element{sop("1.2.3.4.5.1"), sop("1.2.3.4.5.2"), sop("1.2.3.9")}),
+			}}),
+		}).
+		entry("ko.dcm")
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("1.dcm"), newInstanceFixture(t, 2).entry("2.dcm"), ko}
+
+	assert.Equal("Of Interest", keyObjectTitle(ko.dataset))
+	references, err := newKeyObjectReferences(ko.dataset, datasets)
+	assert.NoError(err)
+	assert.Equal([]keyObjectReference{{"1.2.3.4.5.2", &datasets[1]}, {"1.2.3.4.5.1", &datasets[0]}, {"1.2.3.9", nil}}, references)
+	assert.Equal("2.dcm", references[0].String())
+	assert.Equal("1.2.3.9 - not loaded", references[2].String())
+	assert.Equal("Key objects: 3 referenced, 1 not loaded, see :ko", formatKeyObjectSummary(references))
+
+	_, err = newKeyObjectReferences(datasets[0].dataset, datasets)
+	assert.Error(err)
+
+	root := tview.NewTreeNode("root")
+	fileNode := tview.NewTreeNode("2.dcm").SetReference(&datasets[1])
+	root.AddChild(tview.NewTreeNode("series").AddChild(fileNode))
+	assert.Same(fileNode, findEntryNode(root, references[0].entry))
+	assert.Nil(findEntryNode(root, references[1].entry))
+}
@@ -0,0 +1,196 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"cmp"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"log/slog"
+	"os"
+	"slices"
+	"sync"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// hugeFileSize is the size from which the pixel data of a file is only read when it is accessed, see parseHugeFile
+var hugeFileSize int64 = 1 << 30
+
+// errMappingFailed is returned by parseHugeFile if the file can't be mapped into memory, e.g. on systems without
+// memory mapped files, so it is read completely instead
+var errMappingFailed = errors.New("mapping the file into memory failed")
+
+// lazyValue is the memory mapped file of an element whose value hasn't been read yet
+type lazyValue struct {
+	path        string
+	data        []byte
+	valueOffset int // of the value after the element header
+}
+
+// lazyValues are the elements of huge files whose values are read on first access
+var lazyValues = struct {
+	sync.Mutex
+	elements map[*dicom.Element]lazyValue
+}{elements: make(map[*dicom.Element]lazyValue)}
+
+// parseHugeFile parses the elements before the pixel data of the memory mapped file. The pixel data element gets its
+// header only, its value is read by loadLazyValue when accessed, so opening multi-gigabyte files like whole slide
+// images is fast and the frames only take memory if they are looked at.
+func parseHugeFile(file *os.File, size int64) (dicom.Dataset, error) {
+	data, err := mapFile(file, size)
+	if err != nil {
+		return dicom.Dataset{}, fmt.Errorf("%w: %w", errMappingFailed, err)
+	}
+	offset, err := findElementOffset(bufio.NewReader(bytes.NewReader(data)), tag.PixelData)
+	if err != nil {
+		offset = size // parsed completely, the parser reports the errors
+	}
+	dataset, err := dicom.Parse(bytes.NewReader(data[:offset]), offset, nil)
+	if err != nil || offset == size {
+		unmapFile(data)
+		return dataset, err
+	}
+
+	scanner := &elementScanner{r: bufio.NewReader(bytes.NewReader(data[offset:])),
+		explicit: getDatasetValue(dataset, tag.TransferSyntaxUID) != implicitVRLittleEndian}
+	t, vr, length, err := scanner.readHeader()
+	if err != nil || t != tag.PixelData {
+		defer unmapFile(data)
+		return dicom.Parse(bytes.NewReader(data), size, nil) // no pixel data but elements after it
+	}
+	value, err := dicom.NewValue(dicom.PixelDataInfo{IsEncapsulated: length == undefinedLength})
+	if err != nil {
+		unmapFile(data)
+		return dataset, err
+	}
+	e := &dicom.Element{Tag: t, ValueRepresentation: tag.VRPixelData, RawValueRepresentation: cmp.Or(vr, "OW"),
+		ValueLength: length, Value: value}
+	dataset.Elements = append(dataset.Elements, e)
+
+	lazyValues.Lock()
+	lazyValues.elements[e] = lazyValue{file.Name(), data, int(offset + scanner.offset)}
+	lazyValues.Unlock()
+	slog.Debug("mapped huge file", "path", file.Name(), "size", size, "pixelDataOffset", offset)
+	return dataset, nil
+}
+
+// isLazyValue returns true if the value of the element hasn't been read yet
+func isLazyValue(e *dicom.Element) bool {
+	lazyValues.Lock()
+	defer lazyValues.Unlock()
+	_, ok := lazyValues.elements[e]
+	return ok
+}
+
+// hasLazyValues returns true if values of the dataset haven't been read yet, e.g. to avoid reading them for overviews
+func hasLazyValues(dataset dicom.Dataset) bool {
+	return slices.ContainsFunc(dataset.Elements, isLazyValue)
+}
+
+// lazyRawValue returns a copy of the encoded value of an element not read yet, nil for other elements. The copy stays
+// valid when the file is unmapped by loadLazyValue or releaseLazyValues.
+func lazyRawValue(e *dicom.Element) []byte {
+	lazyValues.Lock()
+	defer lazyValues.Unlock()
+	if lazy, ok := lazyValues.elements[e]; ok {
+		return bytes.Clone(lazy.data[lazy.valueOffset:])
+	}
+	return nil
+}
+
+// writeLazyRawValue writes the encoded value of an element not read yet to w without copying it first, e.g. to hash
+// the pixel data of huge files. False for other elements.
+func writeLazyRawValue(w io.Writer, e *dicom.Element) bool {
+	lazyValues.Lock()
+	defer lazyValues.Unlock()
+	lazy, ok := lazyValues.elements[e]
+	if ok {
+		w.Write(lazy.data[lazy.valueOffset:])
+	}
+	return ok
+}
+
+// lazyFrame returns a copy of the encoded data of a frame of encapsulated pixel data not read yet, assuming one
+// fragment per frame as usual for tiled images, so a single tile of a huge file can be decoded without reading all of
+// them. False if the pixel data has been read, isn't encapsulated or has fewer fragments.
+func lazyFrame(e *dicom.Element, index int) ([]byte, bool) {
+	lazyValues.Lock()
+	defer lazyValues.Unlock()
+	lazy, ok := lazyValues.elements[e]
+	if !ok || e.ValueLength != undefinedLength {
+		return nil, false
+	}
+	raw := lazy.data[lazy.valueOffset:]
+	fragment := -1 // the basic offset table comes first
+	for offset := 0; offset+8 <= len(raw); fragment++ {
+		t := tag.Tag{Group: binary.LittleEndian.Uint16(raw[offset:]), Element: binary.LittleEndian.Uint16(raw[offset+2:])}
+		length := int(binary.LittleEndian.Uint32(raw[offset+4:]))
+		offset += 8
+		if t != itemTag || offset+length > len(raw) {
+			return nil, false
+		}
+		if fragment == index {
+			return bytes.Clone(raw[offset : offset+length]), true
+		}
+		offset += length
+	}
+	return nil, false
+}
+
+// loadLazyValue reads the value of the element from its mapped file if it hasn't been read yet and unmaps the file
+func loadLazyValue(e *dicom.Element) error {
+	lazyValues.Lock()
+	defer lazyValues.Unlock()
+	lazy, ok := lazyValues.elements[e]
+	if !ok {
+		return nil
+	}
+	dataset, err := dicom.Parse(bytes.NewReader(lazy.data), int64(len(lazy.data)), nil)
+	if err != nil {
+		return fmt.Errorf("reading %s: %w", lazy.path, err)
+	}
+	loaded, err := dataset.FindElementByTag(e.Tag)
+	if err != nil {
+		return fmt.Errorf("reading %s: %w", lazy.path, err)
+	}
+	e.Value = loaded.Value
+	delete(lazyValues.elements, e)
+	if err := unmapFile(lazy.data); err != nil {
+		slog.Warn("unmapping failed", "path", lazy.path, "error", err)
+	}
+	return nil
+}
+
+// loadLazyValues reads the values of all elements of the dataset not read yet, e.g. before writing it
+func loadLazyValues(dataset dicom.Dataset) error {
+	for _, e := range dataset.Elements {
+		if err := loadLazyValue(e); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// releaseLazyValues unmaps the files of the values of the datasets not read yet and forgets the hashes of their values,
+// for datasets which are dropped. The values can't be read anymore afterwards.
+func releaseLazyValues(datasets []DatasetEntry) {
+	forgetHashedValues(datasets)
+	lazyValues.Lock()
+	defer lazyValues.Unlock()
+	for _, entry := range datasets {
+		for _, e := range entry.dataset.Elements {
+			lazy, ok := lazyValues.elements[e]
+			if !ok {
+				continue
+			}
+			delete(lazyValues.elements, e)
+			if err := unmapFile(lazy.data); err != nil {
+				slog.Warn("unmapping failed", "path", lazy.path, "error", err)
+			}
+		}
+	}
+}
@@ -0,0 +1,55 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestParseHugeFile(t *testing.T) {
+	assert := assert.New(t)
+
+	complete, err := elementSelection{}.parseFile("testdata/test.dcm")
+	assert.NoError(err)
+	defer func(size int64) { hugeFileSize = size }(hugeFileSize)
+	hugeFileSize = 0
+	dataset, err := elementSelection{}.parseFile("testdata/test.dcm")
+	assert.NoError(err)
+	assert.Len(dataset.Elements, len(complete.Elements))
+
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	assert.NoError(err)
+	expected, _ := complete.FindElementByTag(tag.PixelData)
+	assert.True(isLazyValue(e))
+	assert.NotEmpty(lazyRawValue(e))
+	assert.Equal(expected.ValueLength, e.ValueLength)
+	assert.Equal(getValueString(expected), getValueString(e))
+	assert.Empty(e.Value.GetValue().(dicom.PixelDataInfo).Frames)
+
+	assert.NoError(loadLazyValue(e))
+	assert.False(isLazyValue(e))
+	assert.Nil(lazyRawValue(e))
+	assert.Equal(len(expected.Value.GetValue().(dicom.PixelDataInfo).Frames), len(e.Value.GetValue().(dicom.PixelDataInfo).Frames))
+	assert.NoError(loadLazyValue(e)) // already read
+}
+
+func TestReleaseLazyValues(t *testing.T) {
+	assert := assert.New(t)
+
+	defer func(size int64) { hugeFileSize = size }(hugeFileSize)
+	hugeFileSize = 0
+	dataset, err := elementSelection{}.parseFile("testdata/test.dcm")
+	assert.NoError(err)
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	assert.NoError(err)
+	raw := lazyRawValue(e)
+	assert.NotEmpty(raw)
+
+	releaseLazyValues([]DatasetEntry{{filename: "test.dcm", dataset: dataset}})
+	assert.False(isLazyValue(e))
+	assert.Nil(lazyRawValue(e))
+	assert.NotEmpty(raw[len(raw)-1:], "copies stay valid after unmapping")
+	releaseLazyValues([]DatasetEntry{{filename: "test.dcm", dataset: dataset}}) // already released
+}
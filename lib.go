@@ -1,11 +1,26 @@
 package main
 
 import (
+	"cmp"
+	"context"
+	"errors"
 	"fmt"
+	"hash/fnv"
+	"io"
+	"io/fs"
+	"log/slog"
 	"os"
+	"path/filepath"
+	"regexp"
+	"slices"
+	"strconv"
 	"strings"
+	"sync"
+	"time"
+	"unicode"
 
 	"github.com/gdamore/tcell/v2"
+	"github.com/mattn/go-runewidth"
 	"github.com/rivo/tview"
 	"github.com/suyashkumar/dicom"
 	"github.com/suyashkumar/dicom/pkg/tag"
@@ -14,19 +29,32 @@ import (
 type DatasetEntry struct {
 	filename string
 	dataset  dicom.Dataset
+	path     string // path of the file as given or found in the input directory
+	size     int64
+	modTime  time.Time // modification time of the file, zero if not read from a file
+	indexed  bool      // only the key tags were taken from the index, the file is parsed when its node is expanded
+	partial  bool      // only some elements were read, by --tags or --read-until or from the index, so it isn't saved
 }
 
 var helpText = `Navigation
 
 Global
 
-- q - quit
-- 1 - sort tree by filenames - under each filename entry the corresponding tags are located
+- q - quit, asks to save or discard unsaved changes first
+- 1 - sort tree by filenames - under each filename entry the corresponding tags are located; images start with a pixel value transforms node listing stored values, rescale or modality LUT, VOI window or LUT, presentation LUT and real world value mappings
 - 2 - sort tree by tags - under each tag the corresponding filenames are located with its values
-- 3 - sort tree by tags and show only the tags which contains different tag values per file
+- 3 - sort tree by tags and show only the tags which contains different tag values per file; switching between 2 and 3 keeps the current tag or value selected, or the next tag shown if it's hidden
+- 4 - sort tree by study and series - study and series show instance count, size, acquisition time span and missing instance numbers
+- 5 - sort tree by file size, largest first
+- 6 - sort tree by file modification time, newest first
+- 7 - sort tree by filenames in natural order, e.g. IM2 before IM10
+- 8 - sort tree by instance number, files without one last
 - / - enter command line with search
+- ? - enter command line with backward search
 - : - enter command line with command
-- ? - help view
+- F1, :help - help view
+- esc, ctrl + c - cancel a running long operation (e.g. loading), shown with its progress in the status line
+- tab, shift + tab - move the focus between the tree, the details and the log pane if shown, which are scrolled with j/k, g/G and the arrow keys; esc returns to the tree
 
 Treeview
 
@@ -40,33 +68,352 @@ Treeview
 - shift + l, shift + → - move to next child - if current node is collapsed it will be expanded
 - 0, ^ - move to first sibling in current hierachy level
 - $ - move to last sibling in current hierachy level
+- expanding a file node again selects the node last selected in it, e.g. to go back and forth between files
 
 - space, enter - toggle collapse state of current node
+- ctrl + space - edit the value of the current tag, checked against its VR and defined terms; dates and times can be set to now, UIDs newly generated, person names edited per component group and component, numbers value by value
 - c - collapse current node and all its siblings
 - e - expand current node and all its siblings
 - shift + c - collapse current node recursively
-- shift + e - expand current node recursively
+- shift + e - expand current node recursively, asks first if that shows more than 20000 nodes
 
 - g, home - go to first node (root)
+- gt, gT - switch to the next/previous tab, each with its own tree, sort mode, query and search
 - shift + g, end - go to last visible node
+- p - jump from a value node of the by-tag tree (2, 3) to the element in its file in the filename tree, and from an element of the other trees to its value node in the by-tag tree
+- m - pick or unpick the current file for the merge view, picked files are marked [picked]
+- ]c, [c - jump to the next or previous element modified in this session, modified elements are marked ±
+- ]w, [w - jump to the next or previous conformance issue, a file lacking required attributes of its SOP class or a required element without value, see tc
 - ctrl + u - half screen up
 - ctrl + d - half screen down
 - ctrl + f, page-down - one screen down
 - ctrl + b, page-up - one screen up
-
-- n - search for next occurence if search text present
-- N - search for prev occurence if search text present
+- zz - scroll so that the current node is centered
+- zt - scroll so that the current node is at the top
+- zb - scroll so that the current node is at the bottom
+- zR - expand the whole tree, asks first like shift + e
+- zM - collapse the whole tree below the root
+- tr - toggle hiding of retired elements
+- tg - toggle hiding of group length elements
+- tp - toggle hiding of private elements
+- tc - toggle marking files with the number of missing and empty required attributes of their SOP class, the details of a file list them by name
+- tf - toggle showing size and modification time of files
+- td - toggle showing date and time values (DA, TM, DT) readable like 2021-03-04 13:22:05.123 next to the raw value
+- tu - toggle showing the units of well-known quantitative values like 1.25 mm for SliceThickness, HU for windows of CT images (on by default)
+- tx - toggle privacy mode, which masks the values of identifying elements like names, IDs and dates with ███ in the tree and details without changing the files, e.g. for screen sharing; --privacy starts in it
+- tl - toggle the log pane with errors of parsing, network and file operations, more with -v or -vv
+- ts - toggle pinning the file, group and sequence of the top rows at the top while scrolling, on by default
+- tn - cycle the line numbers in front of the rows: off, absolute, relative to the current row which shows its absolute number; :number goes to a row
+- io, iO - insert an empty sequence item after, before the current item; on a sequence append, prepend one
+- iy - duplicate the current sequence item
+- id - delete the current sequence item
+- ik, ij - move the current sequence item up, down
+
+- n - search for next occurence in search direction if search text present
+- N - search for prev occurence in search direction if search text present
+- s - search only within the children of the current node, e.g. the per file values of a tag
+
+Search
+
+- search is case insensitive, unless the search text contains upper case letters
+- \c, \C - anywhere in the search text: force case insensitive/sensitive search, toggled with alt + c
+- \<, \> - match start/end of a word, e.g. \<CT\> doesn't match DIRECT, toggled around the text with alt + w
+
+Commandline
+
+- ↑, ↓ - previous/next entry of the search or command history, persisted across sessions
+- ctrl + r - reverse search for the entered text in the history, repeat to search further back
+- alt + b, alt + f - move one word left/right
+- ctrl + a, ctrl + e - move to the beginning/end of the line
+- ctrl + w, ctrl + k, ctrl + u - delete the word before the cursor, up to the end of the line, the whole line
+- ctrl + y - insert the last deleted text
+- pasted text is inserted as is, line breaks replaced by spaces
+- backspace on empty line - leave the command line
+- :number - go to the visible row with the number, 1 is the root; a count before G isn't supported since the digits switch the sort mode
+- :q - quit, asks to save or discard unsaved changes first
+- :q! - quit without asking
+- :w - write the file (single file only)
+- :wa [dir] - write the modified files of all tabs back to their paths, or into the directory if given; files only partly read with --tags, --read-until or from the index are refused
+- :tabnew path - open the DICOM file or directory in a new tab, further inputs given on the command line are opened in tabs as well
+- :export file - write the raw value of the selected element to a file, e.g. to extract an ICC profile or CSA header
+- :import file - replace the value of the selected element by the content of a file
+- :note [text] - attach a review note to the selected element or file, marked with ✎, without text the note is removed
+- :notes [file] - show all notes, or export them to a file
+- :session file - save the tabs with their expanded nodes, current node, sort mode and query, the notes and the hidden elements, restored with 'dcmtagger --session file'
+- :report [file] - write an HTML report comparing the tags of all files, differing values highlighted, default file report.html
+- :screenshot [ansi] [file] - write the rows of the tree view as plain text to screenshot.txt or the file, with ansi including colors and attributes as escape sequences to screenshot.ans, e.g. to share a view in a ticket
+- :dump - show the current node with all its children, also collapsed ones, indented in $PAGER (default less) with the UI suspended until it exits
+- | - in reports and the help, pipe the text to $PAGER instead, e.g. to search it with less
+- :send [AE] - send the current file, or all files outside of a file, with C-STORE to a configured AE or host:port, without AE pick one from the list
+- :query [expr] - show only files matching the query expression, e.g. Modality=="MR" && SliceThickness<1.0, without expression all files
+- :propagate [query] - copy the current element, a sequence with all items, into all other files or the ones matching the query
+- :associations - show the negotiated presentation contexts and PDU sizes of all associations of network operations
+- :conformance - list per series the required attributes of the SOP class missing or empty in its files
+- :slicecheck - check slice positions of all series for gaps, duplicates and non-uniform spacing or orientation
+- :geometry - plot the slices of each series seen edge-on with braille characters, so gaps, overlaps and tilted or flipped slices are visible
+- :timeline - order the series of all files by acquisition time, joining consecutive instances of a series, with pauses of a minute or more highlighted
+- :wsi - list the pyramid levels of the whole slide images by series with their tile grid and downsampling
+- :tile [column row] - preview a tile of the whole slide image of the current file, h j k l move to the neighbouring tiles
+- :seg - list the segments of the segmentation of the current file with their labels and algorithms, and the source images
+- :mask n - show the mask of the first frame of segment n as ● over its source image if loaded
+- :preview [ps] - draw the first frame of the current image with shades, with ps the VOI window, shutter and annotations of the first presentation state referencing it applied; JPEG baseline and RLE images are decoded, JPEG-LS and JPEG 2000 ones if built with -tags charls or openjpeg
+- :phi - list the files probably showing patient data in their pixels, flagged by BurnedInAnnotation YES, secondary capture or text-like edges in ultrasound images, which need visual inspection before sharing
+- :blank [x,y,width,height ...] - zero the pixel regions, e.g. burned-in patient names, in all files of the series of the current file and set BurnedInAnnotation to NO, the regions of the config if none are given, write them with :wa; uncompressed pixel data only
+- :decode - try to read the value of an unknown or private element as ASCII, UTF-16, floats and integer arrays, the plausible readings are shown in the details, e.g. to reverse engineer vendor blobs
+- :ko - list the instances referenced by the current key object selection document, enter jumps to the file, instances not loaded are flagged
+- :diff dir - compare the files with the ones of another directory matched by SOPInstanceUID, listing the differing elements per instance and the instances only on one side
+- :merge [off|clear] - build the by-tag tree (2, 3) of the files picked with m only, off compares all files again, clear also unpicks them
+- :skipped - list the files of the input directories which weren't loaded, like non-DICOM files and DICOMDIR, by reason
+- :codestream - show the basic offset table, the fragment sizes and the JPEG, JPEG-LS or JPEG 2000 codestream parameters of the encapsulated pixel data of the current file without decompressing it
+- :frames - show the per-frame functional groups of the current enhanced multi-frame file as table, one row per frame with the attributes varying between frames
+- :histogram - show the histogram of the pixel values of the current file with the VOI window marked, h/l or the arrow keys move the cursor whose bin is described below; a small histogram is also shown in the details of files with uncompressed pixel data
+- :dose [file] - show CTDIvol and DLP of the irradiation events of all CT radiation dose SR files, or export them as CSV file
+- :checksums [file] - show the SHA-256 checksums of all files, or write them as manifest file in the format of sha256sum, checked with 'dcmtagger checksum --verify'
+- :duplicates [meta] - list clusters of byte-identical files and of files with identical datasets, whose bytes differ e.g. by transfer syntax; the file meta information (group 0002) is only compared with meta
 `
 
-func addAndShowHelpPage(pages *tview.Pages) {
-	viewName := "help"
-	helpView := tview.NewTextView().SetText(string(helpText))
-	helpView.
-		SetTitle("Help").
+// modalView centers its item with a maximal size, but shrinks it to the available space when the terminal is smaller
+type modalView struct {
+	*tview.Box
+	item          tview.Primitive
+	width, height int
+}
+
+func newModalView(item tview.Primitive, width, height int) *modalView {
+	return &modalView{Box: tview.NewBox(), item: item, width: width, height: height}
+}
+
+func (m *modalView) Draw(screen tcell.Screen) {
+	x, y, width, height := m.GetRect()
+	itemWidth, itemHeight := min(m.width, width), min(m.height, height)
+	m.item.SetRect(x+(width-itemWidth)/2, y+(height-itemHeight)/2, itemWidth, itemHeight)
+	m.item.Draw(screen)
+}
+
+func (m *modalView) Focus(delegate func(p tview.Primitive)) {
+	delegate(m.item)
+}
+
+func (m *modalView) HasFocus() bool {
+	return m.item.HasFocus()
+}
+
+// InputHandler passes the keys to the item, the handler of the embedded box would drop them
+func (m *modalView) InputHandler() func(event *tcell.EventKey, setFocus func(p tview.Primitive)) {
+	return m.item.InputHandler()
+}
+
+func (m *modalView) MouseHandler() func(action tview.MouseAction, event *tcell.EventMouse, setFocus func(p tview.Primitive)) (consumed bool, capture tview.Primitive) {
+	return m.item.MouseHandler()
+}
+
+func addAndShowHelpPage(pages *tview.Pages, pager textPager) {
+	addAndShowTextPage(pages, "help", "Help", helpText, pager)
+}
+
+// addAndShowTextPage shows a scrollable text, e.g. a report, in a modal view, which is closed with esc or q. With |
+// the text is piped to the pager instead. Title and text are masked by the pager in privacy mode.
+func addAndShowTextPage(pages *tview.Pages, viewName, title, text string, pager textPager) {
+	text = pager.mask(text)
+	textView := tview.NewTextView().SetText(text)
+	textView.
+		SetTitle(pager.mask(title)).
+		SetTitleAlign(tview.AlignCenter).
+		SetBorder(true).
+		SetBorderPadding(1, 1, 1, 1)
+	textView.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		switch event.Key() {
+		case tcell.KeyEsc:
+			pages.RemovePage(viewName)
+			return nil
+		case tcell.KeyRune:
+			switch event.Rune() {
+			case 'q':
+				pages.RemovePage(viewName)
+				return nil
+			case '|':
+				pages.RemovePage(viewName)
+				pager.show(text)
+				return nil
+			}
+		}
+		return event
+	})
+	pages.AddAndSwitchToPage(viewName, newModalView(textView, 120, 40), true).ShowPage("main")
+}
+
+// addAndShowHistogramPage shows the pixel value histogram with a cursor moved by h/l or the arrow keys, whose bin is
+// described below the chart
+func addAndShowHistogramPage(pages *tview.Pages, title string, histogram *pixelHistogram) {
+	viewName := "histogram"
+	const columns, rows = 100, 12
+	cursor := 0
+	textView := tview.NewTextView()
+	update := func() {
+		textView.SetText(histogram.summary() + "\n\n" + histogram.chart(columns, rows, cursor) + "\n\n" +
+			histogram.readout(columns, cursor))
+	}
+	update()
+	textView.
+		SetTitle(title).
+		SetTitleAlign(tview.AlignCenter).
+		SetBorder(true).
+		SetBorderPadding(1, 1, 1, 1)
+	move := func(delta int) {
+		cursor = min(max(cursor+delta, 0), len(histogram.bins(columns))-1)
+		update()
+	}
+	textView.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		switch event.Key() {
+		case tcell.KeyEsc:
+			pages.RemovePage(viewName)
+			return nil
+		case tcell.KeyLeft:
+			move(-1)
+			return nil
+		case tcell.KeyRight:
+			move(1)
+			return nil
+		case tcell.KeyHome:
+			move(-columns)
+			return nil
+		case tcell.KeyEnd:
+			move(columns)
+			return nil
+		case tcell.KeyRune:
+			switch event.Rune() {
+			case 'q':
+				pages.RemovePage(viewName)
+				return nil
+			case 'h':
+				move(-1)
+				return nil
+			case 'l':
+				move(1)
+				return nil
+			}
+		}
+		return event
+	})
+	pages.AddAndSwitchToPage(viewName, newModalView(textView, columns+6, rows+12), true).ShowPage("main")
+}
+
+// addAndShowTilePage previews a tile of a whole slide image, h, j, k and l move to the neighbouring tiles
+func addAndShowTilePage(pages *tview.Pages, level wsiLevel, column, row int) {
+	viewName := "tile"
+	const columns, rows = 96, 40
+	textView := tview.NewTextView()
+	textView.
 		SetTitleAlign(tview.AlignCenter).
 		SetBorder(true).
 		SetBorderPadding(1, 1, 1, 1)
-	helpView.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+	update := func() {
+		textView.SetTitle(fmt.Sprintf("Tile %d,%d of %dx%d - %s", column, row, level.tilesAcross(), level.tilesDown(), level.entry.filename))
+		index, ok := level.tileFrame(column, row)
+		if !ok {
+			textView.SetText("no frame of this tile, sparse tiles aren't supported")
+			return
+		}
+		img, err := decodeFrame(level.entry.dataset, index)
+		if err != nil {
+			textView.SetText(fmt.Sprintf("frame %d: %s", index+1, err.Error()))
+			return
+		}
+		textView.SetText(renderShades(img, columns, rows) + fmt.Sprintf("\n\nframe %d, %dx%d pixels", index+1,
+			img.Bounds().Dx(), img.Bounds().Dy()))
+	}
+	move := func(dx, dy int) {
+		column = min(max(column+dx, 0), level.tilesAcross()-1)
+		row = min(max(row+dy, 0), level.tilesDown()-1)
+		update()
+	}
+	move(0, 0)
+	textView.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		switch event.Key() {
+		case tcell.KeyEsc:
+			pages.RemovePage(viewName)
+			return nil
+		case tcell.KeyLeft:
+			move(-1, 0)
+			return nil
+		case tcell.KeyRight:
+			move(1, 0)
+			return nil
+		case tcell.KeyUp:
+			move(0, -1)
+			return nil
+		case tcell.KeyDown:
+			move(0, 1)
+			return nil
+		case tcell.KeyRune:
+			switch event.Rune() {
+			case 'q':
+				pages.RemovePage(viewName)
+				return nil
+			case 'h':
+				move(-1, 0)
+				return nil
+			case 'l':
+				move(1, 0)
+				return nil
+			case 'k':
+				move(0, -1)
+				return nil
+			case 'j':
+				move(0, 1)
+				return nil
+			}
+		}
+		return event
+	})
+	pages.AddAndSwitchToPage(viewName, newModalView(textView, columns+6, rows+8), true).ShowPage("main")
+}
+
+// addAndShowAEPicker shows a list of the remote AEs, 'selected' is called with the chosen one
+func addAndShowAEPicker(pages *tview.Pages, aes []remoteAE, selected func(ae remoteAE)) {
+	viewName := "AEPicker"
+	list := tview.NewList().ShowSecondaryText(false)
+	for _, ae := range aes {
+		list.AddItem(ae.String(), "", 0, func() {
+			pages.RemovePage(viewName)
+			selected(ae)
+		})
+	}
+	list.SetTitle("Send to").SetTitleAlign(tview.AlignCenter).SetBorder(true)
+	list.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		switch event.Key() {
+		case tcell.KeyEsc:
+			pages.RemovePage(viewName)
+			return nil
+		case tcell.KeyRune:
+			switch event.Rune() {
+			case 'q':
+				pages.RemovePage(viewName)
+				return nil
+			case 'j':
+				return tcell.NewEventKey(tcell.KeyDown, 0, tcell.ModNone)
+			case 'k':
+				return tcell.NewEventKey(tcell.KeyUp, 0, tcell.ModNone)
+			}
+		}
+		return event
+	})
+	pages.AddAndSwitchToPage(viewName, newModalView(list, 80, len(aes)+2), true).ShowPage("main")
+}
+
+// addAndShowKeyObjectPicker lists the instances referenced by a key object selection document, 'selected' is called
+// with the chosen one
+func addAndShowKeyObjectPicker(pages *tview.Pages, title string, references []keyObjectReference, selected func(reference keyObjectReference)) {
+	viewName := "KeyObjectPicker"
+	list := tview.NewList().ShowSecondaryText(false)
+	for _, reference := range references {
+		list.AddItem(reference.String(), "", 0, func() {
+			pages.RemovePage(viewName)
+			selected(reference)
+		})
+	}
+	list.SetTitle(title).SetTitleAlign(tview.AlignCenter).SetBorder(true)
+	list.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
 		switch event.Key() {
 		case tcell.KeyEsc:
 			pages.RemovePage(viewName)
@@ -76,41 +423,157 @@ func addAndShowHelpPage(pages *tview.Pages) {
 			case 'q':
 				pages.RemovePage(viewName)
 				return nil
+			case 'j':
+				return tcell.NewEventKey(tcell.KeyDown, 0, tcell.ModNone)
+			case 'k':
+				return tcell.NewEventKey(tcell.KeyUp, 0, tcell.ModNone)
 			}
 		}
 		return event
 	})
-	width, height := 120, 40
-	grid := tview.NewGrid().
-		SetColumns(0, width, 0).
-		SetRows(0, height, 0).
-		AddItem(helpView, 1, 1, 1, 1, 0, 0, true)
-	pages.AddAndSwitchToPage(viewName, grid, true).ShowPage("main")
+	pages.AddAndSwitchToPage(viewName, newModalView(list, 100, min(len(references), 30)+2), true).ShowPage("main")
+}
+
+// unsavedChangesText describes the files with pending modifications for the quit confirmation
+func unsavedChangesText(paths []string) string {
+	if len(paths) == 1 {
+		return fmt.Sprintf("%s has unsaved changes.", filepath.Base(paths[0]))
+	}
+	return fmt.Sprintf("%d files have unsaved changes.", len(paths))
+}
+
+// addAndShowQuitConfirmation asks whether to save or discard the unsaved changes of the given files before quitting.
+// Saving is only offered if 'save' is not nil, cancelling with esc or the button just closes the confirmation.
+func addAndShowQuitConfirmation(pages *tview.Pages, paths []string, save func(), discard func()) {
+	viewName := "QuitConfirmation"
+	buttons := []string{"Discard", "Cancel"}
+	if save != nil {
+		buttons = append([]string{"Save"}, buttons...)
+	}
+	modal := tview.NewModal().
+		SetText(unsavedChangesText(paths) + " Quit anyway?").
+		SetButtonBackgroundColor(activeTheme.fieldBackground).
+		AddButtons(buttons).
+		SetDoneFunc(func(_ int, label string) {
+			pages.RemovePage(viewName)
+			switch label {
+			case "Save":
+				save()
+			case "Discard":
+				discard()
+			}
+		})
+	pages.AddAndSwitchToPage(viewName, modal, true).ShowPage("main")
 }
 
-func addAndShowTagEditingPage(pages *tview.Pages, element *dicom.Element) {
+// addAndShowConfirmation asks whether to continue an operation, 'confirmed' is called if so, cancelling with esc or the
+// button just closes the confirmation
+func addAndShowConfirmation(pages *tview.Pages, text string, confirmed func()) {
+	viewName := "Confirmation"
+	modal := tview.NewModal().
+		SetText(text).
+		SetButtonBackgroundColor(activeTheme.fieldBackground).
+		AddButtons([]string{"Continue", "Cancel"}).
+		SetDoneFunc(func(_ int, label string) {
+			pages.RemovePage(viewName)
+			if label == "Continue" {
+				confirmed()
+			}
+		})
+	pages.AddAndSwitchToPage(viewName, modal, true).ShowPage("main")
+}
+
+// addAndShowTagEditingPage shows a form to edit the value of the element, 'saved' is called with the previous value
+// after the new value was set. Values are validated against the VR and defined terms before saving.
+func addAndShowTagEditingPage(pages *tview.Pages, element *dicom.Element, saved func(oldValue string)) {
 	viewName := "TagEditView"
 
-	newValue := ""
-	form := tview.NewForm().
+	vr := element.RawValueRepresentation
+	newValue := strings.Join(getValueStrings(element), "\\")
+	form := tview.NewForm()
+	validate := func(text string) bool {
+		problem := form.GetFormItemByLabel("Problem").(*tview.TextView)
+		if err := validateValue(element.Tag, vr, text); err != nil {
+			problem.SetText(err.Error())
+			return false
+		}
+		problem.SetText("")
+		return true
+	}
+	form.
 		SetItemPadding(0).
-		SetFieldBackgroundColor(tcell.ColorDarkBlue).
-		SetButtonBackgroundColor(tcell.ColorDarkBlue).
+		SetFieldBackgroundColor(activeTheme.fieldBackground).
+		SetButtonBackgroundColor(activeTheme.fieldBackground).
 		AddTextView("Tag", fmt.Sprintf("%04x | %04x", element.Tag.Group, element.Tag.Element), 0, 1, false, false).
 		AddTextView("Name", getTagName(element), 0, 1, false, false).
 		AddTextView("VR", element.RawValueRepresentation, 0, 1, false, false).
 		AddTextView("Length", fmt.Sprint(element.ValueLength), 0, 1, false, false).
-		AddInputField("Value", getValueString(element), 0, nil, func(text string) {
+		AddTextView("Format", formatVRHint(element.Tag, vr), 0, 1, false, false).
+		AddInputField("Value", newValue, 0, nil, func(text string) {
 			newValue = text
+			validate(text)
 		}).
+		AddTextView("Problem", "", 0, 1, false, false).
 		AddButton("Save", func() {
+			if !validate(newValue) {
+				return
+			}
+			oldValue := getValueString(element)
 			stringArray := []string{newValue}
 			element.Value, _ = dicom.NewValue(stringArray)
 			pages.RemovePage(viewName)
-		}).
-		AddButton("Cancel", func() {
-			pages.RemovePage(viewName)
+			saved(oldValue)
+		})
+	input := form.GetFormItemByLabel("Value").(*tview.InputField)
+	if currentValue(vr, time.Now()) != "" {
+		form.AddButton("Now", func() {
+			input.SetText(currentValue(vr, time.Now()))
+		})
+	}
+	if vr == "UI" {
+		form.AddButton("New UID", func() {
+			input.SetText(newUID())
+		})
+	}
+	if isNumericVR(vr) {
+		form.AddButton("Values", func() {
+			addAndShowValuesEditingPage(pages, strings.Split(input.GetText(), "\\"), func(value string) {
+				input.SetText(value)
+				pages.SwitchToPage(viewName).ShowPage("main")
+			}, func() {
+				pages.SwitchToPage(viewName).ShowPage("main")
+			})
+		})
+	}
+	if vr == "PN" {
+		form.AddButton("Components", func() {
+			first, rest, multiple := strings.Cut(input.GetText(), "\\")
+			addAndShowPersonNameEditingPage(pages, first, func(value string) {
+				if multiple {
+					value += "\\" + rest
+				}
+				input.SetText(value)
+				pages.SwitchToPage(viewName).ShowPage("main")
+			}, func() {
+				pages.SwitchToPage(viewName).ShowPage("main")
+			})
+		})
+	}
+	if terms, ok := definedTerms[element.Tag]; ok {
+		input.SetAutocompleteFunc(func(text string) []string {
+			matching := make([]string, 0)
+			for _, term := range terms {
+				if strings.HasPrefix(term, strings.ToUpper(text)) {
+					matching = append(matching, term)
+				}
+			}
+			return matching
 		})
+	}
+	form.AddButton("Cancel", func() {
+		pages.RemovePage(viewName)
+	})
+	form.GetFormItemByLabel("Problem").(*tview.TextView).SetTextColor(activeTheme.problem)
 	form.SetBorder(true).
 		SetTitle("Edit Tag Value").
 		SetTitleAlign(tview.AlignCenter)
@@ -123,51 +586,281 @@ func addAndShowTagEditingPage(pages *tview.Pages, element *dicom.Element) {
 		return event
 	})
 
-	modal := func(p tview.Primitive, width, height int) tview.Primitive {
-		return tview.NewGrid().
-			SetColumns(0, width, 0).
-			SetRows(0, height, 0).
-			AddItem(p, 1, 1, 1, 1, 0, 0, true)
+	pages.AddAndSwitchToPage(viewName, newModalView(form, 80, 13), true).ShowPage("main")
+}
+
+// addAndShowPersonNameEditingPage edits the components of a PN value per component group, 'saved' is called with the
+// joined value and 'cancelled' if the editing is left without saving
+func addAndShowPersonNameEditingPage(pages *tview.Pages, value string, saved func(value string), cancelled func()) {
+	viewName := "PersonNameEditView"
+	name := parsePersonName(value)
+	form := tview.NewForm().
+		SetItemPadding(0).
+		SetFieldBackgroundColor(activeTheme.fieldBackground).
+		SetButtonBackgroundColor(activeTheme.fieldBackground)
+	for i, group := range personNameGroups {
+		for j, part := range personNameParts {
+			form.AddInputField(fmt.Sprintf("%-11s %s", group, part), name[i][j], 0, func(text string, lastChar rune) bool {
+				return !strings.ContainsAny(text, `^=\`) // separators of components, groups and values
+			}, func(text string) {
+				name[i][j] = text
+			})
+		}
+	}
+	form.AddButton("Apply", func() {
+		pages.RemovePage(viewName)
+		saved(name.String())
+	})
+	form.AddButton("Cancel", func() {
+		pages.RemovePage(viewName)
+		cancelled()
+	})
+	form.SetBorder(true).
+		SetTitle("Edit Person Name").
+		SetTitleAlign(tview.AlignCenter)
+	form.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		if event.Key() == tcell.KeyEsc {
+			pages.RemovePage(viewName)
+			cancelled()
+			return nil
+		}
+		return event
+	})
+	pages.AddAndSwitchToPage(viewName, newModalView(form, 80, len(personNameGroups)*len(personNameParts)+4), true).ShowPage("main")
+}
+
+// addAndShowValuesEditingPage edits the values of a multi-valued element one by one, 'saved' is called with the values
+// joined by '\' and 'cancelled' if the editing is left without saving
+func addAndShowValuesEditingPage(pages *tview.Pages, values []string, saved func(value string), cancelled func()) {
+	viewName := "ValuesEditView"
+	values = slices.Clone(values)
+	form := tview.NewForm().
+		SetItemPadding(0).
+		SetFieldBackgroundColor(activeTheme.fieldBackground).
+		SetButtonBackgroundColor(activeTheme.fieldBackground)
+	for i, value := range values {
+		form.AddInputField(fmt.Sprintf("[%d]", i), value, 0, func(text string, lastChar rune) bool {
+			return !strings.Contains(text, `\`)
+		}, func(text string) {
+			values[i] = text
+		})
+	}
+	form.AddButton("Apply", func() {
+		pages.RemovePage(viewName)
+		saved(strings.Join(values, `\`))
+	})
+	form.AddButton("Cancel", func() {
+		pages.RemovePage(viewName)
+		cancelled()
+	})
+	form.SetBorder(true).
+		SetTitle("Edit Values").
+		SetTitleAlign(tview.AlignCenter)
+	form.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		if event.Key() == tcell.KeyEsc {
+			pages.RemovePage(viewName)
+			cancelled()
+			return nil
+		}
+		return event
+	})
+	pages.AddAndSwitchToPage(viewName, newModalView(form, 60, min(len(values), 30)+4), true).ShowPage("main")
+}
+
+// skippedFile is a file of a directory that wasn't loaded with the reason
+type skippedFile struct {
+	path   string
+	reason string
+}
+
+// dicomMagic follows the 128 byte preamble of DICOM files, see PS3.10 7.1
+const dicomMagic = "DICM"
+
+// checkDicomFile returns why the file isn't loaded, empty if it is a DICOM file
+func checkDicomFile(path string) (string, error) {
+	if filepath.Base(path) == "DICOMDIR" {
+		return "DICOMDIR", nil
+	}
+	file, err := os.Open(path)
+	if err != nil {
+		return "", err
+	}
+	defer file.Close()
+	header := make([]byte, 128+len(dicomMagic))
+	if _, err := io.ReadFull(file, header); err != nil || string(header[128:]) != dicomMagic {
+		return "no DICOM file", nil
+	}
+	return "", nil
+}
+
+// formatSkippedFiles lists the skipped files grouped by the reason, like "2 no DICOM file" followed by their paths
+func formatSkippedFiles(skipped []skippedFile) string {
+	if len(skipped) == 0 {
+		return "no files skipped"
+	}
+	reasons := make([]string, 0)
+	pathsByReason := make(map[string][]string)
+	for _, file := range skipped {
+		if _, ok := pathsByReason[file.reason]; !ok {
+			reasons = append(reasons, file.reason)
+		}
+		pathsByReason[file.reason] = append(pathsByReason[file.reason], file.path)
+	}
+	var b strings.Builder
+	for _, reason := range reasons {
+		fmt.Fprintf(&b, "%d %s\n", len(pathsByReason[reason]), reason)
+		for _, path := range pathsByReason[reason] {
+			fmt.Fprintf(&b, "    %s\n", path)
+		}
 	}
-	pages.AddAndSwitchToPage(viewName, modal(form, 64, 11), true).ShowPage("main")
+	return b.String()
 }
 
-func parseDicomFiles(path string) ([]DatasetEntry, error) {
+// parseDicomFiles parses the given file or all files of the given directory and returns the files of the directory
+// which were skipped. On cancellation the datasets parsed so far are returned together with the context error.
+func parseDicomFiles(ctx context.Context, path string, selection elementSelection, progress func(done, total int)) ([]DatasetEntry, []skippedFile, error) {
 	datasetsWithFilename := make([]DatasetEntry, 0)
+	skipped := make([]skippedFile, 0)
+	err := walkDicomFiles(ctx, path, selection, progress, func(file skippedFile) {
+		skipped = append(skipped, file)
+	}, func(entry DatasetEntry) error {
+		datasetsWithFilename = append(datasetsWithFilename, entry)
+		return nil
+	})
+	return datasetsWithFilename, skipped, err
+}
+
+// walkDicomFiles parses the given file or all files of the given directory one after another and calls 'visit' with
+// each, so only one dataset at a time has to be kept in memory. Files of a directory which can't be read, aren't DICOM
+// files or don't match the modalities and SOP classes of the selection are passed to 'skip'. Without 'skip' the walk
+// stops at the first file which can't be read. A given file is always visited.
+func walkDicomFiles(ctx context.Context, path string, selection elementSelection, progress func(done, total int), skip func(file skippedFile), visit func(entry DatasetEntry) error) error {
+	return walkIndexedDicomFiles(ctx, path, selection, nil, progress, skip, visit)
+}
+
+// walkIndexedDicomFiles is walkDicomFiles taking the files of a directory which haven't changed since they were
+// indexed from the index instead of parsing them, with their key tags only. Parsed files are added to the index.
+func walkIndexedDicomFiles(ctx context.Context, path string, selection elementSelection, index *fileIndex, progress func(done, total int), skip func(file skippedFile), visit func(entry DatasetEntry) error) error {
+	abortOnError := skip == nil
+	if skip == nil {
+		skip = func(file skippedFile) {}
+	}
 	pathInfo, err := os.Stat(path)
 	if err != nil {
-		return datasetsWithFilename, err
+		return err
 	}
 
-	if pathInfo.IsDir() {
-		dir := pathInfo.Name()
-		files, err := os.ReadDir(dir)
+	if !pathInfo.IsDir() {
+		dataset, err := selection.parseFile(path)
 		if err != nil {
-			return datasetsWithFilename, err
+			slog.Error("parsing failed", "path", path, "error", err)
+			return err
+		}
+		slog.Debug("parsed", "path", path, "elements", len(dataset.Elements))
+		if index != nil {
+			index.add(path, pathInfo, dataset)
 		}
+		return visit(DatasetEntry{pathInfo.Name(), dataset, path, pathInfo.Size(), pathInfo.ModTime(), false, selection.isPartial()})
+	}
 
-		for _, f := range files {
-			if f.IsDir() {
+	dir := path
+	files, err := os.ReadDir(dir)
+	if err != nil {
+		return err
+	}
+	for i, f := range files {
+		if err := ctx.Err(); err != nil {
+			return err
+		}
+		if progress != nil {
+			progress(i, len(files))
+		}
+		if f.IsDir() {
+			continue
+		}
+		filePath := dir + "/" + f.Name()
+		fileInfo, reason, err := statDirEntry(filePath, f, selection.skipSymlinks)
+		if err == nil && fileInfo != nil && fileInfo.IsDir() {
+			continue // linked directory
+		}
+		if err == nil && reason == "" {
+			if entry, ok := index.entry(filePath, f.Name(), fileInfo); ok {
+				slog.Debug("taken from the index", "path", filePath)
+				if !selection.matches(entry.dataset) {
+					skip(skippedFile{filePath, "modality or SOP class not selected"})
+				} else if err := visit(entry); err != nil {
+					return err
+				}
 				continue
 			}
-			dataset, err := dicom.ParseFile(dir+"/"+f.Name(), nil)
-			if err != nil {
-				return datasetsWithFilename, err
-			}
-			datasetsWithFilename = append(datasetsWithFilename, DatasetEntry{f.Name(), dataset})
+			reason, err = checkDicomFile(filePath)
+		}
+		var dataset dicom.Dataset
+		if err == nil && reason == "" {
+			dataset, err = selection.parseFile(filePath)
+		}
+		if err == nil && reason == "" && index != nil {
+			index.add(filePath, fileInfo, dataset)
 		}
-	} else {
-		dataset, err := dicom.ParseFile(path, nil)
 		if err != nil {
-			return datasetsWithFilename, err
+			slog.Error("reading failed", "path", filePath, "error", err)
+			if abortOnError {
+				return err
+			}
+			reason = entryError(err)
+		}
+		if reason != "" {
+			slog.Info("skipped", "path", filePath, "reason", reason)
+			skip(skippedFile{filePath, reason})
+			continue
+		}
+		slog.Debug("parsed", "path", filePath, "elements", len(dataset.Elements))
+		if !selection.matches(dataset) {
+			slog.Debug("skipped by modality or SOP class", "path", filePath)
+			skip(skippedFile{filePath, "modality or SOP class not selected"})
+			continue
+		}
+		if err := visit(DatasetEntry{f.Name(), dataset, filePath, fileInfo.Size(), fileInfo.ModTime(), false, selection.isPartial()}); err != nil {
+			return err
 		}
-		datasetsWithFilename = append(datasetsWithFilename, DatasetEntry{pathInfo.Name(), dataset})
 	}
+	return nil
+}
 
-	return datasetsWithFilename, err
+// statDirEntry returns the file info of the directory entry, of the target for symbolic links, and why it isn't
+// read, empty if it is a regular file
+func statDirEntry(path string, f os.DirEntry, skipSymlinks bool) (os.FileInfo, string, error) {
+	fileInfo, err := f.Info()
+	if err != nil {
+		return nil, "", err
+	}
+	if fileInfo.Mode()&os.ModeSymlink != 0 {
+		if skipSymlinks {
+			return fileInfo, "symbolic link", nil
+		}
+		if fileInfo, err = os.Stat(path); err != nil {
+			return nil, "", err
+		}
+	}
+	if !fileInfo.Mode().IsRegular() && !fileInfo.IsDir() {
+		return fileInfo, "special file", nil
+	}
+	return fileInfo, "", nil
+}
+
+// entryError returns the error of a file without its path, which is listed next to it anyway
+func entryError(err error) string {
+	var pathErr *fs.PathError
+	if errors.As(err, &pathErr) {
+		return pathErr.Err.Error()
+	}
+	return err.Error()
 }
 
 func writeDatasetToFile(dataset dicom.Dataset, filename string) error {
+	if err := loadLazyValues(dataset); err != nil {
+		return err
+	}
 	file, err := os.Create(filename)
 	if err != nil {
 		return err
@@ -179,8 +872,136 @@ func writeDatasetToFile(dataset dicom.Dataset, filename string) error {
 	return nil
 }
 
+// writeModifiedFiles writes the datasets of the files with the given paths back to them or, if dir isn't empty, into
+// dir with their filenames. Each file is written to a temporary file first, so a failed write keeps the original.
+// Nothing is written if one of the datasets is partial, it would lose the elements not read. saved is called with the
+// path and the written file of each.
+func writeModifiedFiles(datasets []DatasetEntry, paths []string, dir string, saved func(path, target string)) error {
+	if dir != "" {
+		if err := os.MkdirAll(dir, 0o755); err != nil {
+			return err
+		}
+	}
+	entries := make([]DatasetEntry, len(paths))
+	for n, path := range paths { // checked first, so no file is written if one can't be
+		i := slices.IndexFunc(datasets, func(entry DatasetEntry) bool { return entry.path == path })
+		if i < 0 {
+			return fmt.Errorf("%s: not loaded", path)
+		}
+		if datasets[i].partial {
+			return fmt.Errorf("%s: only some elements were read, load it without --tags, --read-until or --index to save it", path)
+		}
+		entries[n] = datasets[i]
+	}
+	for n, path := range paths {
+		target := path
+		if dir != "" {
+			target = filepath.Join(dir, filepath.Base(path))
+		}
+		if err := writeDatasetToFile(entries[n].dataset, target+".tmp"); err != nil {
+			os.Remove(target + ".tmp")
+			return fmt.Errorf("%s: %w", path, err)
+		}
+		if err := os.Rename(target+".tmp", target); err != nil {
+			os.Remove(target + ".tmp")
+			return fmt.Errorf("%s: %w", path, err)
+		}
+		saved(path, target)
+	}
+	return nil
+}
+
 func isTagNode(node *tview.TreeNode) bool {
-	return node.GetReference() != nil
+	_, ok := node.GetReference().(*dicom.Element)
+	return ok
+}
+
+func isFileNode(node *tview.TreeNode) bool {
+	_, ok := node.GetReference().(*DatasetEntry)
+	return ok
+}
+
+// getFileEntry returns the dataset entry of the file the given node belongs to, nil if there is none
+func getFileEntry(tree *tview.TreeView, node *tview.TreeNode) *DatasetEntry {
+	path := getNodePath(tree, node)
+	for i := len(path) - 1; i >= 0; i-- {
+		if entry, ok := path[i].GetReference().(*DatasetEntry); ok {
+			return entry
+		}
+	}
+	return nil
+}
+
+// getNodePath returns the nodes from the root down to the given node, found in a single depth-first search rather
+// than looking up each parent, which walks the whole tree. Just the node if it isn't in the tree.
+func getNodePath(tree *tview.TreeView, node *tview.TreeNode) []*tview.TreeNode {
+	path := make([]*tview.TreeNode, 0)
+	if node == nil {
+		return path
+	}
+	var find func(current *tview.TreeNode) bool
+	find = func(current *tview.TreeNode) bool {
+		path = append(path, current)
+		if current == node {
+			return true
+		}
+		for _, child := range current.GetChildren() {
+			if find(child) {
+				return true
+			}
+		}
+		path = path[:len(path)-1]
+		return false
+	}
+	if root := tree.GetRoot(); root == nil || !find(root) {
+		return []*tview.TreeNode{node}
+	}
+	return path
+}
+
+// getBreadcrumb returns the path to the node like root ▸ file ▸ group ▸ tag, elements by tag and name without value
+func getBreadcrumb(tree *tview.TreeView, node *tview.TreeNode) string {
+	parts := make([]string, 0)
+	var previous any
+	for _, pathNode := range getNodePath(tree, node) {
+		part := strings.TrimSuffix(strings.TrimSpace(pathNode.GetText()), "/")
+		switch ref := pathNode.GetReference().(type) {
+		case *dicom.Element:
+			if ref != previous { // values below a tag in sort by tag refer to the same element
+				part = fmt.Sprintf("(%04x,%04x) %s", ref.Tag.Group, ref.Tag.Element, getTagName(ref))
+			}
+		case *sequenceItem:
+			part = fmt.Sprintf("Item %d", ref.index+1)
+		}
+		previous = pathNode.GetReference()
+		parts = append(parts, strings.ReplaceAll(part, "\t", " "))
+	}
+	return strings.Join(parts, " ▸ ")
+}
+
+// getElementFilePath returns the path of the file containing the element, empty if it isn't a top level element
+func getElementFilePath(datasetsWithFilename []DatasetEntry, e *dicom.Element) string {
+	for _, entry := range datasetsWithFilename {
+		if containsElement(entry.dataset.Elements, e) {
+			return entry.path
+		}
+	}
+	return ""
+}
+
+// containsElement returns true if the element is one of the given ones or nested in their sequence items
+func containsElement(elements []*dicom.Element, e *dicom.Element) bool {
+	for _, element := range elements {
+		if element == e {
+			return true
+		}
+		if isSequence(element) && slices.ContainsFunc(getSequenceItems(element), func(item []*dicom.Element) bool {
+			return containsElement(item, e)
+		}) {
+			return true
+		}
+	}
+	return false
 }
 
 func updateTagValue(node *tview.TreeNode, newValue string) {
@@ -191,28 +1012,88 @@ func updateTagValue(node *tview.TreeNode, newValue string) {
 	}
 }
 
-func findNodeRecursive(tree *tview.TreeView, searchText string) ([]*tview.TreeNode, int) {
+type searchDirection int
+
+const (
+	searchForward searchDirection = iota
+	searchBackward
+)
+
+func (d searchDirection) reversed() searchDirection {
+	if d == searchForward {
+		return searchBackward
+	}
+	return searchForward
+}
+
+// newSearchMatcher compiles the search text with vim like modifiers: smartcase, i.e. the search is only case sensitive if
+// the text contains upper case letters, '\c' or '\C' anywhere force case insensitive or sensitive search and '\<' and
+// '\>' match the start and end of words.
+func newSearchMatcher(searchText string) func(text string) bool {
+	var pattern strings.Builder
+	caseSensitive := false
+	forcedCase := false
+	runes := []rune(searchText)
+	for i := 0; i < len(runes); i++ {
+		if runes[i] == '\\' && i+1 < len(runes) {
+			switch runes[i+1] {
+			case 'c', 'C':
+				caseSensitive, forcedCase = runes[i+1] == 'C', true
+				i++
+				continue
+			case '<', '>':
+				pattern.WriteString(`\b`)
+				i++
+				continue
+			}
+		}
+		if !forcedCase && unicode.IsUpper(runes[i]) {
+			caseSensitive = true
+		}
+		pattern.WriteString(regexp.QuoteMeta(string(runes[i])))
+	}
+
+	expr := pattern.String()
+	if !caseSensitive {
+		expr = "(?i)" + expr
+	}
+	re, err := regexp.Compile(expr)
+	if err != nil {
+		return func(text string) bool { return false }
+	}
+	return re.MatchString
+}
+
+// findNodeRecursive returns all nodes matching the search text, the number of found nodes before the current node and
+// whether the current node was found itself. If a scope node is given only its descendants are searched.
+func findNodeRecursive(tree *tview.TreeView, scope *tview.TreeNode, searchText string) ([]*tview.TreeNode, int, bool) {
+	matches := newSearchMatcher(searchText)
 	findPred := func(node *tview.TreeNode) bool {
-		return strings.Contains(strings.ToLower(node.GetText()), searchText)
+		return matches(node.GetText())
 	}
 
+	searchRoot := tree.GetRoot()
+	if scope != nil {
+		searchRoot = scope
+	}
 	foundNodes := make([]*tview.TreeNode, 0)
-	foundIndex := -1
-	tree.GetRoot().Walk(func(node, parent *tview.TreeNode) bool {
-		if findPred(node) {
-			foundNodes = append(foundNodes, node)
+	foundBefore := 0
+	currentFound := false
+	searchRoot.Walk(func(node, parent *tview.TreeNode) bool {
+		if node == scope {
+			return true
 		}
 		if tree.GetCurrentNode() == node {
-			if len(foundNodes) > 0 {
-				foundIndex = len(foundNodes) - 1
-			} else {
-				foundIndex = 0
-			}
+			foundBefore = len(foundNodes)
+			currentFound = findPred(node)
+		}
+		if findPred(node) {
+			foundNodes = append(foundNodes, node)
 		}
 		return true
 	})
 
-	return foundNodes, foundIndex
+	return foundNodes, foundBefore, currentFound
 }
 
 func collapseAllChildren(node *tview.TreeNode) {
@@ -228,6 +1109,41 @@ func collapseAllRecursive(node *tview.TreeNode) {
 	}
 }
 
+// cyclePane returns the pane delta panes after the focused one, wrapping around like tab and shift + tab, false if
+// none of them has the focus, e.g. the command line or a dialog
+func cyclePane(panes []tview.Primitive, delta int) (tview.Primitive, bool) {
+	index := slices.IndexFunc(panes, func(pane tview.Primitive) bool { return pane.HasFocus() })
+	if index < 0 {
+		return nil, false
+	}
+	return panes[cycleTab(index, len(panes), delta)], true
+}
+
+// expandAllLimit is the number of nodes from which expanding recursively asks for confirmation, since drawing that
+// many rows stalls the UI for seconds
+var expandAllLimit = 20000
+
+// countDescendants returns the number of nodes below the node, the rows shown when it is expanded recursively
+func countDescendants(node *tview.TreeNode) int {
+	count := -1 // not the node itself
+	node.Walk(func(node, parent *tview.TreeNode) bool {
+		count++
+		return true
+	})
+	return count
+}
+
+// expandLevels expands the root and the given number of levels below it, e.g. with 1 the groups of the files are shown
+func expandLevels(root *tview.TreeNode, levels int) {
+	root.Expand()
+	if levels <= 0 {
+		return
+	}
+	for _, child := range root.GetChildren() {
+		expandLevels(child, levels-1)
+	}
+}
+
 func collapseAllLeaves(node *tview.TreeNode) {
 	canCollapse := true
 	for _, child := range node.GetChildren() {
@@ -280,6 +1196,10 @@ func collectSiblings(tree *tview.TreeView, refNode *tview.TreeNode) []*tview.Tre
 	return foundNodes
 }
 
+func isInTree(tree *tview.TreeView, refNode *tview.TreeNode) bool {
+	return refNode == tree.GetRoot() || getParent(tree, refNode) != nil
+}
+
 func getParent(tree *tview.TreeView, refNode *tview.TreeNode) *tview.TreeNode {
 	var foundNode *tview.TreeNode
 	tree.GetRoot().Walk(func(node, parent *tview.TreeNode) bool {
@@ -292,6 +1212,19 @@ func getParent(tree *tview.TreeView, refNode *tview.TreeNode) *tview.TreeNode {
 	return foundNode
 }
 
+// findElementNode returns the node referencing the element, the deepest one if several do, so in the by-tag tree the
+// value node of the file rather than the tag node
+func findElementNode(root *tview.TreeNode, e *dicom.Element) *tview.TreeNode {
+	var found *tview.TreeNode
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if node.GetReference() == e {
+			found = node
+		}
+		return true
+	})
+	return found
+}
+
 func expandPathToNode(tree *tview.TreeView, node *tview.TreeNode) {
 	if node == tree.GetRoot() {
 		node.Expand()
@@ -332,6 +1265,36 @@ func expandOrMoveToFirstChild(tree *tview.TreeView) {
 	}
 }
 
+// branchPositions are the nodes last selected below the file nodes, so re-expanding a file selects the element looked
+// at before, e.g. when going back and forth comparing files
+type branchPositions map[*tview.TreeNode]*tview.TreeNode
+
+// remember stores the node as position of the file it belongs to
+func (p branchPositions) remember(tree *tview.TreeView, node *tview.TreeNode) {
+	path := getNodePath(tree, node)
+	for _, ancestor := range path[:max(len(path)-1, 0)] {
+		if isFileNode(ancestor) {
+			p[ancestor] = node
+			return
+		}
+	}
+}
+
+// restore selects the node last selected below the expanded file node, false if there is none
+func (p branchPositions) restore(tree *tview.TreeView, fileNode *tview.TreeNode) bool {
+	node, ok := p[fileNode]
+	if !ok || !fileNode.IsExpanded() {
+		return false
+	}
+	if !slices.Contains(getNodePath(tree, node), fileNode) {
+		delete(p, fileNode) // removed from the file, e.g. a deleted sequence item
+		return false
+	}
+	expandPathToNode(tree, node)
+	tree.SetCurrentNode(node)
+	return true
+}
+
 func collapseOrMoveToParent(tree *tview.TreeView) {
 	currentNode := tree.GetCurrentNode()
 	if len(currentNode.GetChildren()) > 0 && currentNode.IsExpanded() {
@@ -405,20 +1368,39 @@ func jumpToLastVisibleNode(tree *tview.TreeView) {
 	tree.SetCurrentNode(nodes[len(nodes)-1])
 }
 
-func jumpToNextFoundNode(searchText string, tree *tview.TreeView) {
-	jumpToNthFoundNode(searchText, 1, tree)
+// jumpToRow selects the visible node in the given row counted from 1 for the root, rows beyond the last one select
+// the last node like in vim
+func jumpToRow(tree *tview.TreeView, row int) {
+	nodes := collectAllVisible(tree)
+	tree.SetCurrentNode(nodes[min(max(row, 1), len(nodes))-1])
+}
+
+func jumpToNextFoundNode(searchText string, direction searchDirection, tree *tview.TreeView, scope *tview.TreeNode) {
+	jumpToFoundNode(searchText, direction, false, tree, scope)
 }
 
-func jumpToPrevFoundNode(searchText string, tree *tview.TreeView) {
-	jumpToNthFoundNode(searchText, -1, tree)
+func jumpToPrevFoundNode(searchText string, direction searchDirection, tree *tview.TreeView, scope *tview.TreeNode) {
+	jumpToFoundNode(searchText, direction.reversed(), false, tree, scope)
 }
 
-func jumpToNthFoundNode(searchText string, offset int, tree *tview.TreeView) {
+// jumpToFoundNode jumps to the next node containing the search text in the given direction, wrapping around at the
+// start or end of the tree. With 'includeCurrent' the current node is kept if it matches, e.g. for incremental search.
+// A scope node restricts the search to its descendants, it is ignored if it isn't part of the tree anymore.
+func jumpToFoundNode(searchText string, direction searchDirection, includeCurrent bool, tree *tview.TreeView, scope *tview.TreeNode) {
 	if len(searchText) > 1 {
-		foundNodes, currentIdx := findNodeRecursive(tree, searchText)
+		if scope != nil && !isInTree(tree, scope) {
+			scope = nil
+		}
+		foundNodes, foundBefore, currentFound := findNodeRecursive(tree, scope, searchText)
 		len := len(foundNodes)
 		if len > 0 {
-			newNode := foundNodes[(currentIdx+len+offset)%len]
+			newIdx := foundBefore
+			if direction == searchForward && currentFound && !includeCurrent {
+				newIdx = foundBefore + 1
+			} else if direction == searchBackward && !(currentFound && includeCurrent) {
+				newIdx = foundBefore - 1
+			}
+			newNode := foundNodes[(newIdx+len)%len]
 			if newNode != tree.GetCurrentNode() {
 				tree.SetCurrentNode(newNode)
 				expandPathToNode(tree, newNode)
@@ -427,6 +1409,29 @@ func jumpToNthFoundNode(searchText string, offset int, tree *tview.TreeView) {
 	}
 }
 
+// jumpToMatchingNode selects the next node after the current one matching the predicate in tree order, collapsed
+// nodes included, or the previous one if backward, wrapping around. Returns false if no node matches.
+func jumpToMatchingNode(tree *tview.TreeView, backward bool, match func(node *tview.TreeNode) bool) bool {
+	nodes := make([]*tview.TreeNode, 0)
+	tree.GetRoot().Walk(func(node, parent *tview.TreeNode) bool {
+		nodes = append(nodes, node)
+		return true
+	})
+	current := max(slices.Index(nodes, tree.GetCurrentNode()), 0)
+	for i := 1; i <= len(nodes); i++ {
+		offset := i
+		if backward {
+			offset = len(nodes) - i
+		}
+		if node := nodes[(current+offset)%len(nodes)]; match(node) {
+			expandPathToNode(tree, node)
+			tree.SetCurrentNode(node)
+			return true
+		}
+	}
+	return false
+}
+
 func sortTreeByFilename(rootDir string, tree *tview.TreeView, datasetsWithFilename []DatasetEntry) (*tview.TreeView, *tview.TreeNode) {
 	if tree.GetRoot() != nil {
 		tree.GetRoot().ClearChildren()
@@ -434,33 +1439,150 @@ func sortTreeByFilename(rootDir string, tree *tview.TreeView, datasetsWithFilena
 	root := tview.NewTreeNode(rootDir).SetSelectable(true)
 	tree.SetRoot(root).SetCurrentNode(root)
 
-	for _, entry := range datasetsWithFilename {
-		fileNode := tview.NewTreeNode(entry.filename).SetSelectable(true)
+	for i, entry := range datasetsWithFilename {
+		fileNode := tview.NewTreeNode(entry.filename).SetSelectable(true).SetReference(&datasetsWithFilename[i])
 		if len(datasetsWithFilename) == 1 {
 			tree.SetRoot(fileNode) // only one file, so this name is root then
 		} else {
 			root.AddChild(fileNode)
 		}
 
-		var currentGroupNode *tview.TreeNode
-		var currentGroup uint16
-		for _, e := range entry.dataset.Elements {
-			if currentGroup != e.Tag.Group {
-				currentGroup = e.Tag.Group
-				groupTagText := fmt.Sprintf("%04x", e.Tag.Group)
-				currentGroupNode = tview.NewTreeNode(groupTagText).SetSelectable(true)
-				fileNode.AddChild(currentGroupNode)
+		addElementNodes(fileNode, entry.dataset)
+	}
+
+	return tree, root
+}
+
+// compareBySize orders entries by file size, largest first
+func compareBySize(a, b DatasetEntry) int {
+	return cmp.Compare(b.size, a.size)
+}
+
+// compareByModTime orders entries by modification time, newest first
+func compareByModTime(a, b DatasetEntry) int {
+	return b.modTime.Compare(a.modTime)
+}
+
+// compareNatural compares file names with embedded numbers by their numeric value, so IM2 comes before IM10
+func compareNatural(a, b string) int {
+	for a != "" && b != "" {
+		aDigits, bDigits := isDigit(a[0]), isDigit(b[0])
+		if aDigits != bDigits {
+			return strings.Compare(a, b)
+		}
+		aPart, bPart := leadingRun(a, aDigits), leadingRun(b, bDigits)
+		a, b = a[len(aPart):], b[len(bPart):]
+		if aDigits {
+			aNumber, bNumber := strings.TrimLeft(aPart, "0"), strings.TrimLeft(bPart, "0")
+			if c := cmp.Or(cmp.Compare(len(aNumber), len(bNumber)), strings.Compare(aNumber, bNumber)); c != 0 {
+				return c
 			}
+		} else if c := strings.Compare(aPart, bPart); c != 0 {
+			return c
+		}
+	}
+	return cmp.Compare(len(a), len(b))
+}
+
+func isDigit(c byte) bool {
+	return c >= '0' && c <= '9'
+}
 
-			tagName := getTagName(e)
-			value := getValueString(e)
-			elementText := fmt.Sprintf("\t%04x %s (%s, %d): %s", e.Tag.Element, tagName, e.RawValueRepresentation, e.ValueLength, value)
-			elementNode := tview.NewTreeNode(elementText).SetSelectable(true).SetReference(e)
-			currentGroupNode.AddChild(elementNode)
+// leadingRun returns the leading ASCII digits or non-digits of the text
+func leadingRun(text string, digits bool) string {
+	end := 0
+	for end < len(text) && isDigit(text[end]) == digits {
+		end++
+	}
+	return text[:end]
+}
+
+// compareByFilename orders entries by file name in natural order
+func compareByFilename(a, b DatasetEntry) int {
+	return compareNatural(a.filename, b.filename)
+}
+
+// compareByInstanceNumber orders entries by instance number, files without one last, in natural file name order
+func compareByInstanceNumber(a, b DatasetEntry) int {
+	aNumber, aErr := strconv.Atoi(getDatasetValue(a.dataset, tag.InstanceNumber))
+	bNumber, bErr := strconv.Atoi(getDatasetValue(b.dataset, tag.InstanceNumber))
+	switch {
+	case aErr != nil && bErr != nil:
+		return compareByFilename(a, b)
+	case aErr != nil:
+		return 1
+	case bErr != nil:
+		return -1
+	}
+	return cmp.Or(cmp.Compare(aNumber, bNumber), compareByFilename(a, b))
+}
+
+// sortEntries returns a copy of the entries in the given order, entries comparing equal keep their order
+func sortEntries(entries []DatasetEntry, compare func(a, b DatasetEntry) int) []DatasetEntry {
+	sorted := slices.Clone(entries)
+	slices.SortStableFunc(sorted, compare)
+	return sorted
+}
+
+// markFileInfo appends size and modification time to all file nodes below the given node
+// formatDateTimes returns the values of a DA, TM or DT element in readable form joined by '\', false for other
+// elements or if a value is invalid
+func formatDateTimes(e *dicom.Element) (string, bool) {
+	values := getValueStrings(e)
+	formatted := make([]string, 0, len(values))
+	for _, value := range values {
+		text, ok := formatDateTime(e.RawValueRepresentation, value)
+		if !ok {
+			return "", false
 		}
+		formatted = append(formatted, text)
 	}
+	return strings.Join(formatted, `\`), len(formatted) > 0
+}
 
-	return tree, root
+// markDateTimes appends the readable form of date and time values to the value nodes
+func markDateTimes(root *tview.TreeNode) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if e, ok := node.GetReference().(*dicom.Element); ok && len(node.GetChildren()) == 0 {
+			if text, ok := formatDateTimes(e); ok {
+				node.SetText(fmt.Sprintf("%s (%s)", node.GetText(), text))
+			}
+		}
+		return true
+	})
+}
+
+func markFileInfo(root *tview.TreeNode) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if entry, ok := node.GetReference().(*DatasetEntry); ok && entry.path != "" {
+			node.SetText(fmt.Sprintf("%s (%s, %s)", node.GetText(), formatSize(entry.size), entry.modTime.Format("2006-01-02 15:04:05")))
+		}
+		return true
+	})
+}
+
+// addElementNodes adds the elements of the dataset grouped by their tag group to the given file node
+func addElementNodes(fileNode *tview.TreeNode, dataset dicom.Dataset) {
+	var currentGroupNode *tview.TreeNode
+	var currentGroup uint16
+	for _, e := range dataset.Elements {
+		if currentGroupNode == nil || currentGroup != e.Tag.Group {
+			currentGroup = e.Tag.Group
+			currentGroupNode = tview.NewTreeNode(getGroupText(e.Tag.Group)).SetSelectable(true)
+			fileNode.AddChild(currentGroupNode)
+		}
+
+		elementNode := tview.NewTreeNode(getElementText(e)).SetSelectable(true).SetReference(e)
+		currentGroupNode.AddChild(elementNode)
+		if isSequence(e) {
+			addSequenceItemNodes(elementNode, e)
+		}
+	}
+}
+
+// getElementText returns the text of an element node in the by file tree
+func getElementText(e *dicom.Element) string {
+	return fmt.Sprintf("\t%04x %s (%s, %d): %s", e.Tag.Element, getTagName(e), e.RawValueRepresentation, e.ValueLength, getValueString(e))
 }
 
 func sortTreeByTags(rootDir string, tree *tview.TreeView, datasetsWithFilename []DatasetEntry, minDiffValuesPerTag int) (*tview.TreeView, *tview.TreeNode) {
@@ -484,7 +1606,7 @@ func sortTreeByTags(rootDir string, tree *tview.TreeView, datasetsWithFilename [
 			if !ok {
 				valuesByTag[e.Tag] = make(map[string]bool)
 			}
-			valuesByTag[e.Tag][e.Value.String()] = true
+			valuesByTag[e.Tag][getValueKey(e)] = true
 
 			_, ok = valueLengthsByTag[e.Tag]
 			if !ok {
@@ -500,8 +1622,7 @@ func sortTreeByTags(rootDir string, tree *tview.TreeView, datasetsWithFilename [
 		for _, e := range entry.dataset.Elements {
 			currentGroupNode, ok := groupNodesByGroupTag[e.Tag.Group]
 			if !ok {
-				groupTagText := fmt.Sprintf("%04x/", e.Tag.Group)
-				currentGroupNode = tview.NewTreeNode(groupTagText).SetSelectable(true)
+				currentGroupNode = tview.NewTreeNode(getGroupText(e.Tag.Group) + "/").SetSelectable(true)
 				root.AddChild(currentGroupNode)
 				groupNodesByGroupTag[e.Tag.Group] = currentGroupNode
 			}
@@ -534,13 +1655,81 @@ func sortTreeByTags(rootDir string, tree *tview.TreeView, datasetsWithFilename [
 
 func getTagName(e *dicom.Element) string {
 	var tagName string
-	if tagInfo, err := tag.Find(e.Tag); err == nil {
+	if tagInfo, err := findTag(e.Tag); err == nil {
 		tagName = tagInfo.Name
 	}
 	return tagName
 }
 
+// binary values above this length are never formatted, only their length is shown
+const maxFormattedBinaryLength = 64
+
+const undefinedLength = 0xffffffff
+
+// isLargeBinaryValue returns true for pixel data and binary values (OB, OW, UN, ...) that are too big for formatting
+func isLargeBinaryValue(e *dicom.Element) bool {
+	switch e.Value.ValueType() {
+	case dicom.PixelData:
+		return true
+	case dicom.Bytes:
+		return e.ValueLength > maxFormattedBinaryLength
+	}
+	return false
+}
+
+// hashedValue is the hash of a large value by getValueKey, with the value it was computed for
+type hashedValue struct {
+	value dicom.Value
+	hash  string
+}
+
+// hashedValues keeps the hashes of large values, so they are only read once and not on every build of the tree
+var hashedValues = struct {
+	sync.Mutex
+	elements map[*dicom.Element]hashedValue
+}{elements: make(map[*dicom.Element]hashedValue)}
+
+// getValueKey returns a string for comparing the values of elements with same tag. Large binary values and pixel
+// data are compared by a hash of their raw value, so no string of the whole buffer is built. The hash is computed again
+// only if the value of the element was replaced, e.g. by editing it or reading a lazy value.
+func getValueKey(e *dicom.Element) string {
+	if !isLargeBinaryValue(e) {
+		return e.Value.String()
+	}
+	hashedValues.Lock()
+	hashed, ok := hashedValues.elements[e]
+	hashedValues.Unlock()
+	if ok && hashed.value == e.Value {
+		return hashed.hash
+	}
+	hash := fnv.New128a()
+	hashElements(hash, []*dicom.Element{e}, true)
+	hashed = hashedValue{e.Value, string(hash.Sum(nil))}
+	hashedValues.Lock()
+	hashedValues.elements[e] = hashed
+	hashedValues.Unlock()
+	return hashed.hash
+}
+
+// forgetHashedValues drops the hashes of the values of datasets which are dropped
+func forgetHashedValues(datasets []DatasetEntry) {
+	hashedValues.Lock()
+	defer hashedValues.Unlock()
+	for _, entry := range datasets {
+		for _, e := range entry.dataset.Elements {
+			delete(hashedValues.elements, e)
+		}
+	}
+}
+
 func getValueString(e *dicom.Element) string {
+	if isLargeBinaryValue(e) {
+		if e.ValueLength == undefinedLength {
+			return "<binary data, undefined length>"
+		}
+		return fmt.Sprintf("<binary data, %d bytes>", e.ValueLength)
+	}
+
 	value := e.Value.String()
 	if e.Value.ValueType() == dicom.Strings {
 		valueList := e.Value.GetValue().([]string)
@@ -548,10 +1737,7 @@ func getValueString(e *dicom.Element) string {
 			value = valueList[0]
 		}
 	}
-	const maxLength = 50
-	if len(value) > maxLength {
-		value = value[:maxLength-4] + "...]"
-	}
-
-	return value
+	// truncated by display width at grapheme boundaries, so multibyte and wide characters like CJK names stay intact
+	const maxWidth = 50
+	return runewidth.Truncate(value, maxWidth, "...]")
 }
@@ -0,0 +1,576 @@
+package main
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestGetValueString(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t)
+	assert.Equal("Doe^John", getValueString(f.element(tag.PatientName, []string{"Doe^John"})))
+	assert.Equal("[0.5 0.5]", getValueString(f.element(tag.PixelSpacing, []string{"0.5", "0.5"})))
+	assert.Equal("<binary data, 1000 bytes>", getValueString(newBinaryElement(t, 0x0029, 0x1010, 1000)))
+
+	// truncated by display width, not bytes
+	name := "ヤマダ^タロウ=山田^太郎=やまだ^たろう"
+	assert.Equal(name, getValueString(f.element(tag.PatientName, []string{name})))
+	assert.Equal(strings.Repeat("山田", 11)+"山...]", getValueString(f.element(tag.PatientName, []string{strings.Repeat("山田", 30)})))
+}
+
+func TestGetValueKey(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t)
+	pixelData := func(data ...int) *dicom.Element {
+		pixels := make([][]int, len(data))
+		for i, value := range data {
+			pixels[i] = []int{value}
+		}
+		return f.element(tag.PixelData, dicom.PixelDataInfo{Frames: []frame.Frame{{
+			NativeData: frame.NativeFrame{Data: pixels, Rows: 1, Cols: len(data), BitsPerSample: 16},
+		}}})
+	}
+	// same size, different pixels
+	assert.Equal(getValueKey(pixelData(1, 2)), getValueKey(pixelData(1, 2)))
+	assert.NotEqual(getValueKey(pixelData(1, 2)), getValueKey(pixelData(2, 1)))
+	// hashed once per value, again after the value was replaced
+	e := pixelData(1, 2)
+	key := getValueKey(e)
+	assert.Equal(key, hashedValues.elements[e].hash)
+	e.Value = pixelData(2, 1).Value
+	assert.NotEqual(key, getValueKey(e))
+	forgetHashedValues([]DatasetEntry{{dataset: dicom.Dataset{Elements: []*dicom.Element{e}}}})
+	assert.NotContains(hashedValues.elements, e)
+
+	binary := newBinaryElement(t, 0x0029, 0x1010, 1000)
+	assert.Len(getValueKey(binary), 16)
+	assert.Equal("[Doe^John]", getValueKey(f.element(tag.PatientName, []string{"Doe^John"})))
+}
+
+func TestSortTreeByFilename(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newInstanceFixture(t, 1).withPrivate(0x0029, 0x1010, "LO", []string{"private"}).entry("file1.dcm"),
+		newInstanceFixture(t, 2).entry("file2.dcm"),
+	}
+	_, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+
+	assert.Len(root.GetChildren(), 2)
+	fileNode := root.GetChildren()[0]
+	assert.Equal("file1.dcm", fileNode.GetText())
+	groupTexts := make([]string, 0)
+	for _, groupNode := range fileNode.GetChildren() {
+		groupTexts = append(groupTexts, groupNode.GetText())
+	}
+	assert.Equal([]string{"0008 – Identifying", "0010 – Patient", "0018 – Acquisition", "0020 – Relationship",
+		"0028 – Image Presentation", "0029 – Private"}, groupTexts)
+}
+
+func TestSortTreeByFileInfo(t *testing.T) {
+	assert := assert.New(t)
+
+	now := time.Date(2024, 3, 1, 12, 0, 0, 0, time.UTC)
+	datasets := []DatasetEntry{
+		newInstanceFixture(t, 1).entry("file1.dcm"),
+		newInstanceFixture(t, 2).entry("file2.dcm"),
+		newInstanceFixture(t, 3).entry("file3.dcm"),
+	}
+	datasets[0].size, datasets[0].modTime = 2048, now
+	datasets[1].size, datasets[1].modTime = 4096, now.Add(-time.Hour)
+	datasets[2].size, datasets[2].modTime = 2048, now.Add(time.Hour)
+
+	fileNames := func(entries []DatasetEntry) []string {
+		_, root := sortTreeByFilename("root", tview.NewTreeView(), entries)
+		names := make([]string, 0)
+		for _, fileNode := range root.GetChildren() {
+			names = append(names, fileNode.GetText())
+		}
+		return names
+	}
+	assert.Equal([]string{"file2.dcm", "file1.dcm", "file3.dcm"}, fileNames(sortEntries(datasets, compareBySize)))
+	assert.Equal([]string{"file3.dcm", "file1.dcm", "file2.dcm"}, fileNames(sortEntries(datasets, compareByModTime)))
+	assert.Equal("file1.dcm", datasets[0].filename)
+
+	_, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	markFileInfo(root)
+	assert.Equal("file1.dcm (2.0 KiB, 2024-03-01 12:00:00)", root.GetChildren()[0].GetText())
+}
+
+func TestCompareNatural(t *testing.T) {
+	assert := assert.New(t)
+
+	names := []string{"IM10", "IM2", "IM1", "IM002a", "IM", "CT9.dcm", "CT10.dcm", "IM02"}
+	slices.SortStableFunc(names, compareNatural)
+	assert.Equal([]string{"CT9.dcm", "CT10.dcm", "IM", "IM1", "IM2", "IM02", "IM002a", "IM10"}, names)
+}
+
+func TestCompareByInstanceNumber(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newInstanceFixture(t, 10).entry("a.dcm"),
+		newFixture(t).with(tag.Modality, []string{"CT"}).entry("b.dcm"),
+		newInstanceFixture(t, 2).entry("c.dcm"),
+		newInstanceFixture(t, 2).entry("b10.dcm"),
+		newInstanceFixture(t, 2).entry("b9.dcm"),
+	}
+	filenames := make([]string, 0)
+	for _, entry := range sortEntries(datasets, compareByInstanceNumber) {
+		filenames = append(filenames, entry.filename)
+	}
+	assert.Equal([]string{"b9.dcm", "b10.dcm", "c.dcm", "a.dcm", "b.dcm"}, filenames)
+}
+
+func TestGetBreadcrumb(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newInstanceFixture(t, 1)
+	f.withSequence(tag.ReferencedImageSequence, []*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.3"})})
+	datasets := []DatasetEntry{f.entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+
+	var found *tview.TreeNode
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if e, ok := node.GetReference().(*dicom.Element); ok && e.Tag == tag.ReferencedSOPInstanceUID {
+			found = node
+		}
+		return found == nil
+	})
+	assert.Equal("root ▸ file1.dcm ▸ 0008 – Identifying ▸ (0008,1140) ReferencedImageSequence ▸ Item 1 ▸ "+
+		"(0008,1155) ReferencedSOPInstanceUID", getBreadcrumb(tree, found))
+	assert.Equal("root", getBreadcrumb(tree, root))
+	assert.Equal("file1.dcm", getFileEntry(tree, found).filename)
+	assert.Nil(getFileEntry(tree, root))
+	detached := tview.NewTreeNode("detached")
+	assert.Equal([]*tview.TreeNode{detached}, getNodePath(tree, detached))
+	assert.Empty(getNodePath(tree, nil))
+}
+
+func TestFindElementNode(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	e, err := datasets[0].dataset.FindElementByTag(tag.InstanceNumber)
+	assert.NoError(err)
+
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	node := findElementNode(root, e)
+	assert.Equal("root ▸ file1.dcm ▸ 0020 – Relationship ▸ (0020,0013) InstanceNumber", getBreadcrumb(tree, node))
+
+	tree, root = sortTreeByTags("root", tview.NewTreeView(), datasets, 1)
+	node = findElementNode(root, e)
+	assert.Empty(node.GetChildren())
+	assert.Contains(node.GetText(), "file1.dcm")
+	assert.Same(e, node.GetReference())
+
+	other := newInstanceFixture(t, 3).element(tag.InstanceNumber, []string{"3"})
+	assert.Nil(findElementNode(root, other))
+}
+
+func TestMarkDateTimes(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newFixture(t).
+		with(tag.StudyDate, []string{"20210304"}).
+		with(tag.Modality, []string{"CT"}).
+		entry("file1.dcm")}
+	tree, _ := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	markDateTimes(tree.GetRoot())
+	group := tree.GetRoot().GetChildren()[0]
+	assert.True(strings.HasSuffix(group.GetChildren()[0].GetText(), ": 20210304 (2021-03-04)"))
+	assert.True(strings.HasSuffix(group.GetChildren()[1].GetText(), ": CT"))
+}
+
+func TestSequenceFixture(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t)
+	f.withSequence(tag.ReferencedImageSequence,
+		[]*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.3"})},
+		[]*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.4"})})
+	dataset := f.dataset()
+	e, err := dataset.FindElementByTag(tag.ReferencedImageSequence)
+
+	assert.NoError(err)
+	assert.Equal(dicom.Sequences, e.Value.ValueType())
+	assert.Len(e.Value.GetValue(), 2)
+}
+
+func TestParseDicomFilesBrokenFile(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	writeBrokenFile(t, dir, "broken.dcm")
+	newInstanceFixture(t, 1).writeFile(filepath.Join(dir, "1.dcm"))
+	entries, skipped, err := parseDicomFiles(context.Background(), dir, elementSelection{}, nil)
+
+	assert.NoError(err)
+	assert.Len(entries, 1)
+	assert.Len(skipped, 1)
+	assert.Equal(dir+"/broken.dcm", skipped[0].path)
+
+	// without collecting the skipped files the first broken file is an error
+	err = walkDicomFiles(context.Background(), dir, elementSelection{}, nil, nil, func(entry DatasetEntry) error { return nil })
+	assert.Error(err)
+}
+
+func TestParseDicomFilesSymlinksAndUnreadableFiles(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	target := filepath.Join(t.TempDir(), "target.dcm")
+	newInstanceFixture(t, 1).writeFile(target)
+	assert.NoError(os.Symlink(target, filepath.Join(dir, "link.dcm")))
+	assert.NoError(os.Symlink(filepath.Join(dir, "missing.dcm"), filepath.Join(dir, "dangling.dcm")))
+	assert.NoError(os.Symlink(t.TempDir(), filepath.Join(dir, "subdir")))
+
+	entries, skipped, err := parseDicomFiles(context.Background(), dir, elementSelection{}, nil)
+	assert.NoError(err)
+	assert.Len(entries, 1)
+	assert.Equal("link.dcm", entries[0].filename)
+	assert.Greater(entries[0].size, int64(0))
+	assert.Equal([]skippedFile{{dir + "/dangling.dcm", "no such file or directory"}}, skipped)
+
+	entries, skipped, err = parseDicomFiles(context.Background(), dir, elementSelection{skipSymlinks: true}, nil)
+	assert.NoError(err)
+	assert.Empty(entries)
+	assert.Len(skipped, 3)
+	assert.Equal("symbolic link", skipped[0].reason)
+}
+
+func TestJumpToFoundNode(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newFixture(t).with(tag.Modality, []string{"CT"}).entry("file1.dcm"),
+		newFixture(t).with(tag.Modality, []string{"CT"}).entry("file2.dcm"),
+		newFixture(t).with(tag.Modality, []string{"CT"}).entry("file3.dcm"),
+	}
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	files := root.GetChildren()
+
+	jumpToFoundNode("file", searchBackward, true, tree, nil)
+	assert.Equal(files[2], tree.GetCurrentNode())
+	jumpToFoundNode("file", searchForward, true, tree, nil)
+	assert.Equal(files[2], tree.GetCurrentNode())
+	jumpToNextFoundNode("file", searchForward, tree, nil)
+	assert.Equal(files[0], tree.GetCurrentNode())
+	jumpToNextFoundNode("file", searchBackward, tree, nil)
+	assert.Equal(files[2], tree.GetCurrentNode())
+	jumpToPrevFoundNode("file", searchBackward, tree, nil)
+	assert.Equal(files[0], tree.GetCurrentNode())
+
+	jumpToFoundNode("CT", searchForward, false, tree, files[1])
+	assert.Equal(tag.Modality, tree.GetCurrentNode().GetReference().(*dicom.Element).Tag)
+	assert.Equal("file2.dcm", getFileEntry(tree, tree.GetCurrentNode()).filename)
+}
+
+func TestNewSearchMatcher(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.True(newSearchMatcher("direct")("DIRECT"))
+	assert.False(newSearchMatcher("Direct")("DIRECT"))
+	assert.True(newSearchMatcher(`Direct\c`)("DIRECT"))
+	assert.False(newSearchMatcher(`direct\C`)("DIRECT"))
+	assert.True(newSearchMatcher("CT")("DIRECT"))
+	assert.False(newSearchMatcher(`\<CT\>`)("DIRECT"))
+	assert.True(newSearchMatcher(`\<CT\>`)("0060 Modality (CS, 2): CT"))
+	assert.True(newSearchMatcher("1.2.3")("1.2.3.4"))
+	assert.False(newSearchMatcher("1.2.3")("1x2x3"))
+}
+
+func TestUnsavedChangesText(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal("IM1.dcm has unsaved changes.", unsavedChangesText([]string{"study/IM1.dcm"}))
+	assert.Equal("2 files have unsaved changes.", unsavedChangesText([]string{"study/IM1.dcm", "study/IM2.dcm"}))
+}
+
+func TestParseDicomFilesSkipsNonDicomFiles(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	newInstanceFixture(t, 1).writeFile(filepath.Join(dir, "1.dcm"))
+	newInstanceFixture(t, 2).writeFile(filepath.Join(dir, "DICOMDIR"))
+	assert.NoError(os.WriteFile(filepath.Join(dir, "report.pdf"), []byte("%PDF-1.4"), 0o644))
+	assert.NoError(os.WriteFile(filepath.Join(dir, "photo.jpg"), make([]byte, 200), 0o644))
+
+	entries, skipped, err := parseDicomFiles(context.Background(), dir, elementSelection{}, nil)
+	assert.NoError(err)
+	assert.Len(entries, 1)
+	assert.Equal([]skippedFile{
+		{dir + "/DICOMDIR", "DICOMDIR"},
+		{dir + "/photo.jpg", "no DICOM file"},
+		{dir + "/report.pdf", "no DICOM file"},
+	}, skipped)
+	assert.Contains(formatSkippedFiles(skipped), "2 no DICOM file\n    "+dir+"/photo.jpg\n    "+dir+"/report.pdf\n")
+}
+
+func TestExpandLevels(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	_, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	collapseAllRecursive(root)
+	expandLevels(root, 1)
+	fileNode := root.GetChildren()[0]
+	assert.True(root.IsExpanded())
+	assert.True(fileNode.IsExpanded())
+	assert.False(fileNode.GetChildren()[0].IsExpanded())
+
+	collapseAllRecursive(root)
+	expandLevels(root, 0)
+	assert.True(root.IsExpanded())
+	assert.False(fileNode.IsExpanded())
+}
+
+func TestCountDescendants(t *testing.T) {
+	assert := assert.New(t)
+
+	root := tview.NewTreeNode("root")
+	file := tview.NewTreeNode("file").AddChild(tview.NewTreeNode("group").AddChild(tview.NewTreeNode("element")))
+	root.AddChild(file).AddChild(tview.NewTreeNode("other"))
+	assert.Equal(4, countDescendants(root))
+	assert.Equal(2, countDescendants(file))
+	assert.Equal(0, countDescendants(file.GetChildren()[0].GetChildren()[0]))
+}
+
+// newMotionTree returns a tree with the root, a collapsed branch with two leaves and a leaf, the root selected
+func newMotionTree() (*tview.TreeView, *tview.TreeNode, *tview.TreeNode) {
+	branch := tview.NewTreeNode("branch").AddChild(tview.NewTreeNode("leaf 1")).AddChild(tview.NewTreeNode("leaf 2"))
+	leaf := tview.NewTreeNode("leaf")
+	root := tview.NewTreeNode("root").AddChild(branch.Collapse()).AddChild(leaf)
+	return tview.NewTreeView().SetRoot(root).SetCurrentNode(root), branch, leaf
+}
+
+func TestExpandOrMoveToFirstChild(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, branch, leaf := newMotionTree()
+	tree.SetCurrentNode(branch)
+	expandOrMoveToFirstChild(tree)
+	assert.True(branch.IsExpanded())
+	assert.Same(branch, tree.GetCurrentNode())
+	expandOrMoveToFirstChild(tree)
+	assert.Same(branch.GetChildren()[0], tree.GetCurrentNode())
+
+	tree.SetCurrentNode(leaf)
+	expandOrMoveToFirstChild(tree)
+	assert.Same(leaf, tree.GetCurrentNode())
+}
+
+func TestCollapseOrMoveToParent(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, branch, _ := newMotionTree()
+	branch.Expand()
+	tree.SetCurrentNode(branch.GetChildren()[1])
+	collapseOrMoveToParent(tree)
+	assert.Same(branch, tree.GetCurrentNode())
+	collapseOrMoveToParent(tree)
+	assert.False(branch.IsExpanded())
+	assert.Same(branch, tree.GetCurrentNode())
+	collapseOrMoveToParent(tree)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+	assert.False(branch.IsExpanded())
+	collapseOrMoveToParent(tree)
+	assert.False(tree.GetRoot().IsExpanded())
+	collapseOrMoveToParent(tree)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+}
+
+func TestMoveToParentAndFirstChild(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, branch, leaf := newMotionTree()
+	tree.SetCurrentNode(branch)
+	moveToFirstChild(tree)
+	assert.True(branch.IsExpanded())
+	assert.Same(branch.GetChildren()[0], tree.GetCurrentNode())
+	moveToFirstChild(tree)
+	assert.Same(branch.GetChildren()[0], tree.GetCurrentNode())
+
+	moveToParent(tree)
+	assert.Same(branch, tree.GetCurrentNode())
+	assert.True(branch.IsExpanded())
+	moveToParent(tree)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+	moveToParent(tree)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+
+	tree.SetCurrentNode(leaf)
+	moveToParent(tree)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+}
+
+func TestMoveToFirstAndLastSibling(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, branch, leaf := newMotionTree()
+	tree.SetCurrentNode(branch)
+	moveToLastSibling(tree)
+	assert.Same(leaf, tree.GetCurrentNode())
+	moveToLastSibling(tree)
+	assert.Same(leaf, tree.GetCurrentNode())
+	moveToFirstSibling(tree)
+	assert.Same(branch, tree.GetCurrentNode())
+
+	branch.Expand()
+	tree.SetCurrentNode(branch.GetChildren()[0])
+	moveToLastSibling(tree)
+	assert.Same(branch.GetChildren()[1], tree.GetCurrentNode()) // within the level of the current node only
+
+	tree.SetCurrentNode(tree.GetRoot())
+	moveToFirstSibling(tree)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+	moveToLastSibling(tree)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+}
+
+func TestExpandAndCollapseSiblings(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, branch, leaf := newMotionTree()
+	other := tview.NewTreeNode("other branch").AddChild(tview.NewTreeNode("leaf 3")).Collapse()
+	tree.GetRoot().AddChild(other)
+	tree.SetCurrentNode(leaf)
+
+	expandCurrentAndAllSiblings(tree)
+	assert.True(branch.IsExpanded())
+	assert.True(other.IsExpanded())
+	assert.Same(leaf, tree.GetCurrentNode())
+
+	tree.SetCurrentNode(branch)
+	collapseCurrentAndAllSiblings(tree)
+	assert.False(branch.IsExpanded())
+	assert.False(other.IsExpanded())
+	assert.True(tree.GetRoot().IsExpanded())
+	assert.Same(branch, tree.GetCurrentNode())
+}
+
+func TestJumpToRow(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, branch, leaf := newMotionTree()
+	jumpToRow(tree, 2)
+	assert.Same(branch, tree.GetCurrentNode())
+	jumpToRow(tree, 3)
+	assert.Same(leaf, tree.GetCurrentNode()) // the children of the collapsed branch aren't counted
+	branch.Expand()
+	jumpToRow(tree, 3)
+	assert.Same(branch.GetChildren()[0], tree.GetCurrentNode())
+	jumpToRow(tree, 99)
+	assert.Same(leaf, tree.GetCurrentNode())
+	jumpToRow(tree, 0)
+	assert.Same(tree.GetRoot(), tree.GetCurrentNode())
+}
+
+func TestBranchPositions(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	e, err := datasets[0].dataset.FindElementByTag(tag.InstanceNumber)
+	assert.NoError(err)
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	file := root.GetChildren()[0]
+	node := findElementNode(root, e)
+
+	positions := branchPositions{}
+	positions.remember(tree, file)
+	assert.Empty(positions)
+	positions.remember(tree, node)
+	assert.Same(node, positions[file])
+
+	file.CollapseAll()
+	assert.False(positions.restore(tree, file))
+	file.Expand()
+	assert.True(positions.restore(tree, file))
+	assert.Same(node, tree.GetCurrentNode())
+	assert.True(getParent(tree, node).IsExpanded())
+	assert.False(positions.restore(tree, root.GetChildren()[1]))
+
+	getParent(tree, node).RemoveChild(node)
+	assert.False(positions.restore(tree, file))
+	assert.Empty(positions)
+}
+
+func TestWriteModifiedFiles(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	first, second := filepath.Join(dir, "1.dcm"), filepath.Join(dir, "2.dcm")
+	newInstanceFixture(t, 1).writeFile(first)
+	newInstanceFixture(t, 2).writeFile(second)
+	datasets, _, err := parseDicomFiles(context.Background(), dir, elementSelection{}, nil)
+	assert.NoError(err)
+	for _, entry := range datasets {
+		e, err := entry.dataset.FindElementByTag(tag.PatientName)
+		assert.NoError(err)
+		e.Value, err = dicom.NewValue([]string{"Changed"})
+		assert.NoError(err)
+	}
+
+	saved := make([]string, 0)
+	assert.NoError(writeModifiedFiles(datasets, []string{first}, "", func(path, target string) {
+		saved = append(saved, target)
+	}))
+	assert.Equal([]string{first}, saved)
+	output := filepath.Join(t.TempDir(), "blanked")
+	assert.NoError(writeModifiedFiles(datasets, []string{second}, output, func(path, target string) {
+		saved = append(saved, target)
+	}))
+	assert.Equal([]string{first, filepath.Join(output, "2.dcm")}, saved)
+	for path, name := range map[string]string{first: "Changed", second: "Doe^John", saved[1]: "Changed"} {
+		dataset, err := elementSelection{}.parseFile(path)
+		assert.NoError(err)
+		assert.Equal(name, getDatasetValue(dataset, tag.PatientName), path)
+	}
+	entries, err := os.ReadDir(dir)
+	assert.NoError(err)
+	assert.Len(entries, 2, "no temporary files are left")
+
+	assert.Error(writeModifiedFiles(datasets, []string{filepath.Join(dir, "3.dcm")}, "", func(path, target string) {}))
+
+	// partial datasets would lose the elements not read, nothing is written then
+	pixelData := tag.PixelData
+	partial, _, err := parseDicomFiles(context.Background(), dir, elementSelection{readUntil: &pixelData}, nil)
+	assert.NoError(err)
+	assert.True(partial[0].partial)
+	written := false
+	err = writeModifiedFiles(append(datasets[:1:1], partial[1]), []string{first, second}, "", func(path, target string) {
+		written = true
+	})
+	assert.ErrorContains(err, "only some elements were read")
+	assert.False(written)
+}
+
+func TestModalViewPassesKeys(t *testing.T) {
+	assert := assert.New(t)
+
+	var received []rune
+	textView := tview.NewTextView()
+	textView.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		received = append(received, event.Rune())
+		return nil
+	})
+	pages := tview.NewPages().AddPage("help", newModalView(textView, 40, 10), true, true)
+	textView.Focus(func(p tview.Primitive) {})
+
+	pages.InputHandler()(tcell.NewEventKey(tcell.KeyRune, 'q', tcell.ModNone), func(p tview.Primitive) {})
+	assert.Equal([]rune{'q'}, received)
+}
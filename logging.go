@@ -0,0 +1,88 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"log/slog"
+	"os"
+	"strings"
+	"sync"
+)
+
+// maxLogLines is the number of log records kept for the log pane
+const maxLogLines = 1000
+
+// logBuffer keeps the latest log records for the log pane, each write is one record
+type logBuffer struct {
+	mutex   sync.Mutex
+	lines   []string
+	changed func() // called after a record was added, e.g. to update the log pane
+}
+
+func (b *logBuffer) Write(p []byte) (int, error) {
+	b.mutex.Lock()
+	b.lines = append(b.lines, strings.TrimSuffix(string(p), "\n"))
+	if len(b.lines) > maxLogLines {
+		b.lines = b.lines[len(b.lines)-maxLogLines:]
+	}
+	changed := b.changed
+	b.mutex.Unlock()
+	if changed != nil {
+		changed()
+	}
+	return len(p), nil
+}
+
+func (b *logBuffer) String() string {
+	b.mutex.Lock()
+	defer b.mutex.Unlock()
+	return strings.Join(b.lines, "\n")
+}
+
+// setChanged sets the function called after each record
+func (b *logBuffer) setChanged(changed func()) {
+	b.mutex.Lock()
+	defer b.mutex.Unlock()
+	b.changed = changed
+}
+
+// logLevel returns the level of the given verbosity: warnings and errors only by default, -v adds informational
+// messages, -vv debug messages
+func logLevel(verbosity int) slog.Level {
+	switch {
+	case verbosity >= 2:
+		return slog.LevelDebug
+	case verbosity == 1:
+		return slog.LevelInfo
+	}
+	return slog.LevelWarn
+}
+
+// setupLogging makes a logger writing into the returned buffer and the log file, if given, the default one. The file
+// has to be closed by the caller, it is nil without log file.
+func setupLogging(verbosity int, logFile string) (*logBuffer, *os.File, error) {
+	buffer := &logBuffer{}
+	var w io.Writer = buffer
+	var file *os.File
+	if logFile != "" {
+		var err error
+		if file, err = os.OpenFile(logFile, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0o644); err != nil {
+			return nil, nil, err
+		}
+		w = io.MultiWriter(buffer, file)
+	}
+	slog.SetDefault(slog.New(slog.NewTextHandler(w, &slog.HandlerOptions{Level: logLevel(verbosity)})))
+	return buffer, file, nil
+}
+
+// expandVerbosityFlags rewrites -v and -vv to --verbose with a count, as flags can't be counted otherwise
+func expandVerbosityFlags(args []string) []string {
+	expanded := make([]string, 0, len(args))
+	for _, arg := range args {
+		if len(arg) >= 2 && arg[0] == '-' && strings.Trim(arg[1:], "v") == "" {
+			arg = fmt.Sprintf("--verbose=%d", len(arg)-1)
+		}
+		expanded = append(expanded, arg)
+	}
+	return expanded
+}
@@ -0,0 +1,56 @@
+package main
+
+import (
+	"fmt"
+	"log/slog"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestExpandVerbosityFlags(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal([]string{"--verbose=1", "dir"}, expandVerbosityFlags([]string{"-v", "dir"}))
+	assert.Equal([]string{"--verbose=2", "--theme", "no-color"}, expandVerbosityFlags([]string{"-vv", "--theme", "no-color"}))
+	assert.Equal([]string{"-", "--version", "-vx"}, expandVerbosityFlags([]string{"-", "--version", "-vx"}))
+	assert.Equal(slog.LevelWarn, logLevel(0))
+	assert.Equal(slog.LevelInfo, logLevel(1))
+	assert.Equal(slog.LevelDebug, logLevel(3))
+}
+
+func TestLogBuffer(t *testing.T) {
+	assert := assert.New(t)
+
+	changes := 0
+	buffer := &logBuffer{}
+	buffer.setChanged(func() { changes++ })
+	for i := 0; i < maxLogLines+5; i++ {
+		fmt.Fprintf(buffer, "record %d\n", i)
+	}
+	lines := strings.Split(buffer.String(), "\n")
+	assert.Len(lines, maxLogLines)
+	assert.Equal("record 5", lines[0])
+	assert.Equal(maxLogLines+5, changes)
+}
+
+func TestSetupLogging(t *testing.T) {
+	assert := assert.New(t)
+	defer slog.SetDefault(slog.Default())
+
+	path := filepath.Join(t.TempDir(), "dcmtagger.log")
+	buffer, file, err := setupLogging(1, path)
+	assert.NoError(err)
+	slog.Info("association accepted", "peer", "127.0.0.1:4242")
+	slog.Debug("parsed", "path", "1.dcm")
+	assert.NoError(file.Close())
+
+	assert.Contains(buffer.String(), `msg="association accepted" peer=127.0.0.1:4242`)
+	assert.NotContains(buffer.String(), "parsed")
+	data, err := os.ReadFile(path)
+	assert.NoError(err)
+	assert.Equal(buffer.String()+"\n", string(data))
+}
@@ -1,23 +1,25 @@
 package main
 
 import (
+	"cmp"
+	"context"
+	"crypto/tls"
+	"errors"
 	"fmt"
+	"log/slog"
+	"net"
+	"os"
+	"slices"
 	"strings"
 
-	"github.com/alexflint/go-arg"
 	"github.com/gdamore/tcell/v2"
 	"github.com/rivo/tview"
 	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
 )
 
 var version = "unknown"
 
-type args struct {
-	Input string `arg:"positional" help:"The DICOM input file or directory"`
-}
-
-func (args) Version() string { return "Version " + version }
-
 type EditMode int
 
 const (
@@ -26,90 +28,1389 @@ const (
 )
 
 func main() {
+	defer recoverCrash()
 	var args args
-	p := arg.MustParse(&args)
-	if args.Input == "" {
-		p.Fail("Missing DICOM input file or directory")
+	os.Args = append(os.Args[:1], withDefaultCommand(expandVerbosityFlags(os.Args[1:]))...)
+	p := parseArgs(&args, os.Args[1:])
+	switch {
+	case args.Completions != nil:
+		script, err := completionScript(args.Completions.Shell, commandSpecs(args), flagSpecs(args))
+		if err != nil {
+			failUsage(p, err.Error())
+		}
+		fmt.Print(script)
+		return
+	case args.Man != nil:
+		fmt.Print(manPage(commandSpecs(args), flagSpecs(args), version))
+		return
 	}
-
-	datasetsWithFilename, err := parseDicomFiles(args.Input)
+	logBuffer, logFile, err := setupLogging(args.Verbose, args.LogFile)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "Error opening log file: '%s'\n", err.Error())
+		os.Exit(exitError)
+	}
+	if logFile != nil {
+		defer logFile.Close()
+	}
+	if args.Config == "" {
+		args.Config = configPath()
+	}
+	cfg, err := loadConfig(args.Config)
 	if err != nil {
-		fmt.Printf("Error reading input: '%s'\n", err.Error())
+		fmt.Fprintf(os.Stderr, "Error reading config: '%s'\n", err.Error())
+		os.Exit(exitError)
+	}
+	if dictionary, err := loadDictionary(dictionaryPath()); err != nil {
+		// not fatal, so update-dict can replace a broken file
+		fmt.Fprintf(os.Stderr, "Error reading dictionary, using the built-in one: '%s'\n", err.Error())
+	} else {
+		userDictionary = dictionary
+	}
+	if args.AuditLog == "" {
+		args.AuditLog = auditLogPath()
+	}
+	audit := newAuditLog(args.AuditLog)
+	if args.Browse == nil {
+		findings, err := runBatchCommand(p, args, cfg, audit)
+		if err != nil {
+			fmt.Fprintf(os.Stderr, "Error %s\n", err.Error())
+			os.Exit(exitError)
+		}
+		if findings {
+			os.Exit(exitFindings)
+		}
 		return
 	}
 
+	browse := args.Browse
+	var restored *session // restored once all its tabs are loaded
+	if browse.Session != "" {
+		if browse.Input != "" {
+			failUsage(p, "--session can't be combined with inputs, they are taken from the session")
+		}
+		s, err := loadSession(browse.Session)
+		if err != nil {
+			failUsage(p, err.Error())
+		}
+		restored = &s
+		browse.Input = s.Tabs[0].Input
+		for _, tab := range s.Tabs[1:] {
+			browse.Tabs = append(browse.Tabs, tab.Input)
+		}
+		browse.Hide = s.Hide
+	}
+	if browse.Input == "" && browse.MWL == "" && browse.Listen == 0 {
+		failUsage(p, "Missing DICOM input file or directory")
+	}
+	if !networking && (browse.MWL != "" || browse.Listen != 0) {
+		failUsage(p, "--mwl and --listen need DICOM networking, which isn't compiled in")
+	}
+	filter, err := newDisplayFilter(browse.Hide)
+	if err != nil {
+		failUsage(p, err.Error())
+	}
+	selectedTheme, err := findTheme(browse.Theme, browse.NoColor || os.Getenv("NO_COLOR") != "")
+	if err != nil {
+		failUsage(p, err.Error())
+	}
+	selectedTheme.apply()
+	var worklistAE remoteAE
+	var worklistTLS *tls.Config
+	if browse.MWL != "" {
+		if worklistAE, err = cfg.resolveAE(browse.MWL, browse.AET, browse.CalledAET); err != nil {
+			failUsage(p, err.Error())
+		}
+		if worklistTLS, err = worklistAE.TLS.clientConfig(); err != nil {
+			fmt.Fprintf(os.Stderr, "Error reading TLS certificates: '%s'\n", err.Error())
+			os.Exit(1)
+		}
+	}
+	selection, activeQuery, err := browse.parse() // files not matching the query are left out of the tree
+	if err != nil {
+		failUsage(p, err.Error())
+	}
+	startSortMode, startExpand, err := cfg.startup(browse.Sort, browse.Expand)
+	if err != nil {
+		failUsage(p, err.Error())
+	}
+	redact, err := newRedaction(cfg.Privacy)
+	if err != nil {
+		failUsage(p, err.Error())
+	}
+	grouping, err := newFileGrouping(browse.GroupBy)
+	if err != nil {
+		failUsage(p, err.Error())
+	}
+
+	datasetsWithFilename := make([]DatasetEntry, 0)
+
 	// global state
 	searchText := ""
+	searchDir := searchForward
+	var searchScope *tview.TreeNode // node to search in for scoped search
+	cmdlineHistory := loadHistory(historyPath())
+	cmdlineKillRing := &killRing{}
+	var pendingKey rune // first key of a two key command like 'zz'
+	var nodeBeforeJump *tview.TreeNode // current node before g jumped to the root, restored by gt and gT
+	sortMode := startSortMode // key of the current sort mode
+	reviewNotes := notes{}
+	showConformance := false       // mark file nodes with the result of the SOP class checklist
+	showFileInfo := false          // show size and modification time on file nodes
+	showDateTimes := false         // show date and time values in readable form next to the raw value
+	showUnits := true              // show the units of well-known quantitative values
+	picked := pickedFiles{}        // files picked with 'm' for the merge view
+	mergePicked := false           // build the by-tag trees of the picked files only
+	positions := branchPositions{} // last selected node per file node, restored when it's expanded again
+	privacy := browse.Privacy      // mask identifying values in the UI, e.g. for screen sharing
+	netLog := newProtocolLog()
+	var lastCurrentNode *tview.TreeNode // node the details are shown for
 
 	// create tree nodes with dicom tags
 	app := tview.NewApplication()
 
-	rootDir := args.Input
+	rootDir := browse.Input
+	if browse.MWL != "" {
+		rootDir = "Worklist " + browse.MWL
+	} else if browse.Input == "" {
+		rootDir = fmt.Sprintf("Storage SCP :%d", browse.Listen)
+	}
 
 	pages := tview.NewPages()
 
 	statusLine := tview.NewTextView()
+	notifications := newNotifier(func() { app.Draw() })
+	app.SetAfterDrawFunc(notifications.draw)
+	// showError shows the error in the status line and as notification and logs it
+	showError := func(message string, err error) {
+		slog.Error(message, "error", err)
+		statusLine.SetText(message + ": " + err.Error())
+		notifications.error(message + ": " + err.Error())
+	}
+	// pageText shows the text in $PAGER with the UI suspended, text pages and the pager mask it in privacy mode
+	pageText := textPager{
+		mask: func(text string) string {
+			if privacy {
+				return redact.mask(text)
+			}
+			return text
+		},
+		show: func(text string) {
+			if err := showInPager(app, text); err != nil {
+				showError("Error showing pager", err)
+			}
+		},
+	}
+	modified := map[string]bool{} // paths of files with unsaved modifications of all tabs
+	edited := editedElements{}    // elements with unsaved modifications, marked in the tree
+	// readEntries returns the datasets of the active tab whose files have been read, files with only the key tags of
+	// the index are left out and counted in the status line
+	readEntries := func() []DatasetEntry {
+		entries, indexed := withoutIndexed(datasetsWithFilename)
+		if indexed > 0 {
+			statusLine.SetText(strings.TrimPrefix(indexedStatus(indexed), " - "))
+		}
+		return entries
+	}
+	// tabs are the workspaces of the inputs, the state of the active one is kept in the variables above
+	tabs := []*workspace{newWorkspace(rootDir, nil)}
+	activeTab := 0
+	// saveDataset writes the only loaded dataset, as :w does, false if writing failed
+	saveDataset := func() bool {
+		if err := writeDatasetToFile(datasetsWithFilename[0].dataset, "write_test_copy.dcm"); err != nil {
+			showError("Error saving write_test_copy.dcm", err)
+			return false
+		}
+		clear(modified)
+		notifications.info("saved to write_test_copy.dcm")
+		if err := audit.record("save", "write_test_copy.dcm", "from "+datasetsWithFilename[0].path); err != nil {
+			showError("Error writing audit log", err)
+		}
+		return true
+	}
+	// saveModified writes all modified files back to their paths or into dir, as :wa does, false if writing failed.
+	// Files written before the failure aren't modified anymore.
+	saveModified := func(dir string) bool {
+		paths := make([]string, 0, len(modified))
+		for path := range modified {
+			paths = append(paths, path)
+		}
+		slices.Sort(paths)
+		datasets := slices.Clone(datasetsWithFilename) // the datasets of the active tab aren't stored in it yet
+		for i, tab := range tabs {
+			if i != activeTab {
+				datasets = append(datasets, tab.datasets...)
+			}
+		}
+		err := writeModifiedFiles(datasets, paths, dir, func(path, target string) {
+			delete(modified, path)
+			if err := audit.record("save", target, "from "+path); err != nil {
+				showError("Error writing audit log", err)
+			}
+		})
+		if err != nil {
+			showError("Error saving", err)
+			return false
+		}
+		notifications.info(fmt.Sprintf("saved %d files", len(paths)))
+		return true
+	}
+	// quit stops the application, after confirmation if there are unsaved modifications
+	quit := func() {
+		if len(modified) == 0 {
+			app.Stop()
+			return
+		}
+		save := func() {
+			if saveModified("") { // otherwise the error is shown and the modifications are kept
+				app.Stop()
+			}
+		}
+		paths := make([]string, 0, len(modified))
+		for path := range modified {
+			paths = append(paths, path)
+		}
+		slices.Sort(paths)
+		addAndShowQuitConfirmation(pages, paths, save, app.Stop)
+	}
 
 	tree := tview.NewTreeView()
+	treeView := newScrollView(tree, browse.ScrollOff)
+	treeView.style = cfg.Tree
+	cfg.Tree.registerJoints()
 	tree, root := sortTreeByFilename(rootDir, tree, datasetsWithFilename[:])
 	collapseAllRecursive(root)
-	statusLine.SetText("Sort by filename")
-	cmdline := tview.NewInputField().SetFieldBackgroundColor(tcell.ColorBlack)
+	cfg.Tree.apply(tree, root)
+	cmdline := tview.NewInputField().SetFieldBackgroundColor(tview.Styles.PrimitiveBackgroundColor)
+	detailView := tview.NewTextView().SetWrap(true)
+	breadcrumb := tview.NewTextView().SetWrap(false)
+	logView := tview.NewTextView().SetWrap(false)
+	logView.SetBorder(true).SetTitle("Log")
+	treeColumn := tview.NewFlex().SetDirection(tview.FlexRow).AddItem(treeView, 0, 1, true) // log pane added below
+	logBuffer.setChanged(func() {
+		go app.QueueUpdateDraw(func() { // records are also logged on the UI goroutine, which mustn't block
+			if treeColumn.GetItemCount() > 1 {
+				logView.SetText(logBuffer.String()).ScrollToEnd()
+			}
+		})
+	})
 	mainGrid := tview.NewGrid().
-		SetRows(-1, 1, 1).
-		SetColumns(-1).
+		SetRows(-1, 1, 1, 1).
+		SetColumns(-2, -1).
 		SetBorders(true).
-		AddItem(tree, 0, 0, 1, 1, 0, 0, true).
-		AddItem(statusLine, 1, 0, 1, 1, 0, 0, false).
-		AddItem(cmdline, 2, 0, 1, 1, 0, 0, false)
+		AddItem(treeColumn, 0, 0, 1, 1, 0, 0, true).
+		AddItem(detailView, 0, 1, 1, 1, 0, 0, false).
+		AddItem(breadcrumb, 1, 0, 1, 2, 0, 0, false).
+		AddItem(statusLine, 2, 0, 1, 2, 0, 0, false).
+		AddItem(cmdline, 3, 0, 1, 2, 0, 0, false)
+
+	// sortTree rebuilds the tree in the current sort mode and applies the display filter, returns the number of hidden
+	// elements
+	sortTree := func() int {
+		status := ""
+		clear(positions) // of the nodes of the previous tree
+		shown, indexed := datasetsWithFilename, 0
+		if activeQuery != nil || sortMode == '2' || sortMode == '3' {
+			shown, indexed = withoutIndexed(shown) // the query and the tags of the files need all elements
+		}
+		shown = activeQuery.filter(shown)
+		merged := mergePicked && (sortMode == '2' || sortMode == '3')
+		if merged {
+			shown = picked.filter(shown)
+		}
+		switch sortMode {
+		case '1':
+			tree, root = sortTreeByFilename(rootDir, tree, shown)
+			collapseAllRecursive(root)
+			status = "Sort by filename"
+		case '2':
+			tree, root = sortTreeByTags(rootDir, tree, shown, 0)
+			collapseAllLeaves(root)
+			status = "Sort by tag"
+		case '3':
+			tree, root = sortTreeByTags(rootDir, tree, shown, 1)
+			collapseAllLeaves(root)
+			status = "Sort by tag, show only different tag values"
+		case '4':
+			tree, root = sortTreeByHierarchy(rootDir, tree, shown)
+			collapseAllFiles(root)
+			status = "Sort by study and series"
+		case '5':
+			tree, root = sortTreeByFilename(rootDir, tree, sortEntries(shown, compareBySize))
+			collapseAllRecursive(root)
+			status = "Sort by file size"
+		case '6':
+			tree, root = sortTreeByFilename(rootDir, tree, sortEntries(shown, compareByModTime))
+			collapseAllRecursive(root)
+			status = "Sort by modification time"
+		case '7':
+			tree, root = sortTreeByFilename(rootDir, tree, sortEntries(shown, compareByFilename))
+			collapseAllRecursive(root)
+			status = "Sort by filename in natural order"
+		case '8':
+			tree, root = sortTreeByFilename(rootDir, tree, sortEntries(shown, compareByInstanceNumber))
+			collapseAllRecursive(root)
+			status = "Sort by instance number"
+		}
+		if len(grouping) > 0 && sortMode != '2' && sortMode != '3' && sortMode != '4' {
+			grouping.apply(root)
+			status += " - grouped by " + grouping.String()
+		}
+		cfg.Tree.apply(tree, root)
+		hidden := hideFilteredElements(root, filter)
+		reviewNotes.markNodes(root)
+		edited.markNodes(tree.GetRoot())
+		addValueTransformNodes(tree.GetRoot())
+		if showConformance {
+			markConformance(root)
+			status += " - conformance marked"
+		}
+		if showFileInfo {
+			markFileInfo(root)
+		}
+		if showDateTimes {
+			markDateTimes(root)
+		}
+		if showUnits {
+			markUnits(tree.GetRoot(), shown)
+		}
+		markPickedFiles(tree.GetRoot(), picked)
+		if privacy {
+			redact.collect(datasetsWithFilename)
+			redact.maskNodes(tree.GetRoot())
+			status += " - privacy mode"
+		}
+		if merged {
+			status += fmt.Sprintf(" - merge of %d picked files", len(shown))
+		}
+		status += indexedStatus(indexed)
+		if hidden > 0 {
+			status += fmt.Sprintf(" - %s, %d hidden", filter, hidden)
+		}
+		if activeQuery != nil {
+			status += fmt.Sprintf(" - query %s, %d of %d files", activeQuery, len(shown), len(datasetsWithFilename))
+		}
+		statusLine.SetText(status)
+		return hidden
+	}
+
+	// expandAll expands the node recursively, asking first if that shows so many nodes that the UI stalls
+	expandAll := func(node *tview.TreeNode) {
+		if count := countDescendants(node); count > expandAllLimit {
+			text := fmt.Sprintf("Expanding shows %d nodes, which may stall the UI for seconds. Expand anyway?", count)
+			addAndShowConfirmation(pages, text, func() { node.ExpandAll() })
+			return
+		}
+		node.ExpandAll()
+	}
+
+	// jumpToProvenance selects the element of the current value node of the by-tag tree in the filename tree, or the
+	// value node of the current element of another tree in the by-tag tree last used
+	tagSortMode := '2'
+	if sortMode == '3' {
+		tagSortMode = '3'
+	}
+	jumpToProvenance := func() {
+		currentNode := tree.GetCurrentNode()
+		e, ok := currentNode.GetReference().(*dicom.Element)
+		if !ok {
+			statusLine.SetText("No element selected")
+			return
+		}
+		if sortMode == '2' || sortMode == '3' {
+			if len(currentNode.GetChildren()) > 0 {
+				statusLine.SetText("No value node selected")
+				return
+			}
+			tagSortMode = sortMode
+			sortMode = '1'
+		} else {
+			sortMode = tagSortMode
+		}
+		sortTree()
+		node := findElementNode(tree.GetRoot(), e)
+		if node == nil && sortMode == '3' { // the value is the same in all files
+			sortMode = '2'
+			sortTree()
+			node = findElementNode(tree.GetRoot(), e)
+		}
+		if node == nil {
+			statusLine.SetText(statusLine.GetText(false) + " - element not shown in this tree")
+			return
+		}
+		expandPathToNode(tree, node)
+		tree.SetCurrentNode(node)
+		statusLine.SetText(statusLine.GetText(false) + " - " + getElementFilePath(datasetsWithFilename, e))
+	}
+
+	tasks := newTaskRunner(app, statusLine)
+	var loadErr error
+	var loadedDatasets []DatasetEntry
+	var indexErr error
+	var skippedFiles []skippedFile // files of the inputs which weren't loaded, listed by :skipped
+	skippedStatus := func(skipped []skippedFile) string {
+		if len(skipped) == 0 {
+			return ""
+		}
+		return fmt.Sprintf(" - %d files skipped, see :skipped", len(skipped))
+	}
+
+	// switchTab stores the state of the active tab and restores the one of the given tab, whose tree is built when it's
+	// shown the first time
+	storeTab := func() {
+		tab := tabs[activeTab]
+		tab.datasets, tab.root, tab.current = datasetsWithFilename, tree.GetRoot(), tree.GetCurrentNode()
+		tab.sortMode, tab.tagSortMode, tab.query = sortMode, tagSortMode, activeQuery
+		tab.picked, tab.mergePicked = picked, mergePicked
+		tab.searchText, tab.searchDir, tab.searchScope = searchText, searchDir, searchScope
+	}
+	switchTab := func(index int) {
+		storeTab()
+		activeTab = index
+		tab := tabs[activeTab]
+		rootDir, datasetsWithFilename, root = tab.rootDir, tab.datasets, tab.root
+		sortMode, tagSortMode, activeQuery = tab.sortMode, tab.tagSortMode, tab.query
+		picked, mergePicked = tab.picked, tab.mergePicked
+		searchText, searchDir, searchScope = tab.searchText, tab.searchDir, tab.searchScope
+		if root == nil {
+			tree.SetRoot(tview.NewTreeNode(rootDir)) // sortTree clears the children of the previous root
+			sortTree()
+			expandLevels(tree.GetRoot(), startExpand)
+		} else {
+			tree.SetRoot(root).SetCurrentNode(tab.current)
+			statusLine.SetText(fmt.Sprintf("Tab %d of %d - %s", activeTab+1, len(tabs), rootDir))
+		}
+	}
+	// openTabs adds a tab for each path once its files are loaded in the background, one after another, and calls done
+	// afterwards if set. With show the last tab is shown, otherwise the active tab stays.
+	var openTabs func(paths []string, show bool, done func())
+	openTabs = func(paths []string, show bool, done func()) {
+		if len(paths) == 0 {
+			if done != nil {
+				done()
+			}
+			return
+		}
+		path := paths[0]
+		var entries []DatasetEntry
+		var skipped []skippedFile
+		started := tasks.start("Loading "+path, func(ctx context.Context, progress func(done, total int)) error {
+			var err error
+			entries, skipped, err = parseDicomFiles(ctx, path, selection, progress)
+			return err
+		}, func(err error) {
+			if err != nil && !errors.Is(err, context.Canceled) {
+				releaseLazyValues(entries)
+				showError("Error reading "+path, err)
+			} else {
+				tab := newWorkspace(path, entries)
+				tab.sortMode = startSortMode
+				tabs = append(tabs, tab)
+				skippedFiles = append(skippedFiles, skipped...)
+				if show && len(paths) == 1 {
+					switchTab(len(tabs) - 1)
+					statusLine.SetText(statusLine.GetText(false) + skippedStatus(skipped))
+				} else {
+					statusLine.SetText(fmt.Sprintf("Opened %s in tab %d%s", path, len(tabs), skippedStatus(skipped)))
+				}
+			}
+			openTabs(paths[1:], show, done)
+		})
+		if !started {
+			statusLine.SetText("Another operation is running, wait for it or cancel it with esc")
+		}
+	}
+	// applySession restores the notes, filters and trees of the tabs of the session given with --session
+	applySession := func() {
+		showConformance, showFileInfo = restored.Conformance, restored.FileInfo
+		for i, tab := range tabs {
+			index := slices.IndexFunc(restored.Tabs, func(t sessionTab) bool { return t.Input == tab.rootDir })
+			if index < 0 {
+				continue // not loaded
+			}
+			switchTab(i)
+			restored.restoreNotes(reviewNotes, datasetsWithFilename)
+			sortMode, activeQuery = rune(restored.Tabs[index].SortMode[0]), nil
+			if text := restored.Tabs[index].Query; text != "" {
+				q, err := parseQuery(text)
+				if err != nil {
+					showError("Invalid query in session", err)
+				}
+				activeQuery = q
+			}
+			sortTree()
+			restored.Tabs[index].treeState().restore(tree)
+		}
+		switchTab(min(restored.ActiveTab, len(tabs)-1))
+		statusLine.SetText("Restored session " + browse.Session + " - " + statusLine.GetText(false))
+	}
+
+	if browse.Input != "" || browse.MWL != "" {
+		tasks.start("Loading "+rootDir, func(ctx context.Context, progress func(done, total int)) error {
+			var err error
+			if browse.MWL != "" {
+				query := worklistQuery{modality: browse.MWLModality, stationAET: browse.MWLStation, date: browse.MWLDate}
+				loadedDatasets, err = queryWorklist(ctx, worklistAE, query, worklistTLS, netLog)
+				return err
+			}
+			if !browse.Index {
+				loadedDatasets, skippedFiles, err = parseDicomFiles(ctx, browse.Input, selection, progress)
+				return err
+			}
+			// unchanged files are taken from the index and only parsed when expanded
+			var index *fileIndex
+			if index, indexErr = loadIndex(indexPath(browse.Input)); indexErr != nil {
+				index = nil
+			}
+			err = walkIndexedDicomFiles(ctx, browse.Input, selection, index, progress, func(file skippedFile) {
+				skippedFiles = append(skippedFiles, file)
+			}, func(entry DatasetEntry) error {
+				loadedDatasets = append(loadedDatasets, entry)
+				return nil
+			})
+			if index != nil && err == nil {
+				indexErr = index.save()
+			}
+			return err
+		}, func(err error) {
+			if err != nil && !errors.Is(err, context.Canceled) {
+				loadErr = err
+				app.Stop()
+				return
+			}
+			datasetsWithFilename = append(loadedDatasets, datasetsWithFilename...) // keep objects received meanwhile
+			sortTree()
+			expandLevels(tree.GetRoot(), startExpand)
+			statusLine.SetText(statusLine.GetText(false) + skippedStatus(skippedFiles))
+			if err != nil {
+				statusLine.SetText(fmt.Sprintf("Loading cancelled, %d files loaded - %s", len(datasetsWithFilename), statusLine.GetText(false)))
+			}
+			if indexErr != nil {
+				showError("Error updating index", indexErr)
+			}
+			if restored != nil {
+				openTabs(browse.Tabs, false, applySession)
+			} else {
+				openTabs(browse.Tabs, false, nil)
+			}
+		})
+	}
+
+	if browse.Listen != 0 {
+		spoolDir := browse.Spool
+		if spoolDir == "" {
+			spoolDir, err = os.MkdirTemp("", "dcmtagger-spool-")
+		} else {
+			err = os.MkdirAll(spoolDir, 0o755)
+		}
+		var listener net.Listener
+		if err == nil {
+			listener, err = net.Listen("tcp", fmt.Sprintf(":%d", browse.Listen))
+		}
+		var serverTLS *tls.Config
+		if err == nil {
+			serverTLS, err = cfg.TLS.serverConfig()
+		}
+		if serverTLS != nil {
+			listener = tls.NewListener(listener, serverTLS)
+		}
+		if err != nil {
+			fmt.Fprintf(os.Stderr, "Error starting storage SCP: '%s'\n", err.Error())
+			os.Exit(1)
+		}
+		defer listener.Close()
+		scp := newStorageSCP(spoolDir, func(path string) {
+			received, _, err := parseDicomFiles(context.Background(), path, selection, nil)
+			app.QueueUpdateDraw(func() {
+				if err != nil {
+					showError("Error reading received file", err)
+					return
+				}
+				datasetsWithFilename = append(datasetsWithFilename, received...)
+				state := saveTreeState(tree)
+				sortTree()
+				state.restore(tree)
+				statusLine.SetText(fmt.Sprintf("Received %s, %d files in %s", received[0].filename, len(datasetsWithFilename), spoolDir))
+			})
+		}, netLog)
+		go func() {
+			defer recoverCrash()
+			scp.serve(listener)
+		}()
+		if browse.Input == "" && browse.MWL == "" {
+			statusLine.SetText(fmt.Sprintf("Storage SCP listening on port %d, receiving to %s", browse.Listen, spoolDir))
+		}
+	}
+
+	// sendToAE sends the file of the current node, or all files if the current node belongs to none
+	sendToAE := func(ae remoteAE) {
+		paths := make([]string, 0)
+		if entry := getFileEntry(tree, tree.GetCurrentNode()); entry != nil && entry.path != "" {
+			paths = append(paths, entry.path)
+		} else {
+			for _, entry := range datasetsWithFilename {
+				if entry.path != "" {
+					paths = append(paths, entry.path)
+				}
+			}
+		}
+		if len(paths) == 0 {
+			statusLine.SetText("No files to send")
+			return
+		}
+		tlsConfig, err := ae.TLS.clientConfig()
+		if err != nil {
+			showError("Error reading TLS certificates", err)
+			return
+		}
+		started := tasks.start(fmt.Sprintf("Sending %d files to %s", len(paths), ae.Name), func(ctx context.Context, progress func(done, total int)) error {
+			return sendFiles(ctx, ae, paths, tlsConfig, netLog, progress)
+		}, func(err error) {
+			if errors.Is(err, context.Canceled) {
+				statusLine.SetText("Sending cancelled")
+				return
+			}
+			if err != nil {
+				showError("Error sending to "+ae.Name, err)
+				return
+			}
+			for _, path := range paths {
+				if err := audit.record("send", path, "to "+ae.String()); err != nil {
+					showError("Error writing audit log", err)
+					return
+				}
+			}
+			sent := fmt.Sprintf("Sent %d files to %s", len(paths), ae.Name)
+			statusLine.SetText(sent) // replaces the progress
+			notifications.info(sent)
+		})
+		if !started {
+			statusLine.SetText("Another task is still running")
+		}
+	}
 
 	app.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
 		switch event.Key() {
+		case tcell.KeyEsc, tcell.KeyCtrlC:
+			if tasks.cancelRunning() {
+				return nil
+			}
+			if event.Key() == tcell.KeyCtrlC { // instead of tview stopping the application right away
+				quit()
+				return nil
+			}
 		case tcell.KeyRune:
 			switch event.Rune() {
 			case '/':
 				app.SetFocus(cmdline)
-				cmdline.SetText("/")
+				setCmdlineText(cmdline, "/")
+				cmdlineHistory.reset()
 				return nil
 			case ':':
 				app.SetFocus(cmdline)
-				cmdline.SetText(":")
+				setCmdlineText(cmdline, ":")
+				cmdlineHistory.reset()
 				return nil
 			case '?':
-				addAndShowHelpPage(pages)
+				app.SetFocus(cmdline)
+				setCmdlineText(cmdline, "?")
+				cmdlineHistory.reset()
+				return nil
+			}
+		case tcell.KeyF1:
+			addAndShowHelpPage(pages, pageText)
+			return nil
+		case tcell.KeyTab, tcell.KeyBacktab:
+			panes := []tview.Primitive{tree, detailView}
+			if treeColumn.GetItemCount() > 1 {
+				panes = append(panes, logView)
+			}
+			delta := 1
+			if event.Key() == tcell.KeyBacktab {
+				delta = -1
+			}
+			if pane, ok := cyclePane(panes, delta); ok {
+				app.SetFocus(pane)
 				return nil
 			}
 		}
 		return event
 	})
+	// the details and the log are scrolled with the keys of text views once focused with tab, esc returns to the tree
+	for _, view := range []*tview.TextView{detailView, logView} {
+		view.SetDoneFunc(func(key tcell.Key) {
+			if key == tcell.KeyEscape {
+				app.SetFocus(tree)
+			}
+		})
+	}
 
 	cmdline.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
+		historyPrefix := cmdline.GetLabel()
+		if event.Key() != tcell.KeyCtrlR {
+			cmdlineHistory.search = ""
+		}
+
 		switch event.Key() {
 		case tcell.KeyEsc:
-			cmdline.SetText("")
+			setCmdlineText(cmdline, "")
 			app.SetFocus(tree)
 			return nil
+		case tcell.KeyBackspace, tcell.KeyBackspace2:
+			if cmdline.GetText() == "" {
+				setCmdlineText(cmdline, "")
+				app.SetFocus(tree)
+				return nil
+			}
+		case tcell.KeyCtrlW, tcell.KeyCtrlK, tcell.KeyCtrlU:
+			cmdlineKillRing.startKill(cmdline.GetText())
+		case tcell.KeyCtrlY:
+			cmdlineKillRing.yank(cmdline)
+			return nil
+		case tcell.KeyRune:
+			if event.Modifiers()&tcell.ModAlt != 0 && historyPrefix != ":" {
+				switch event.Rune() {
+				case 'c':
+					cmdline.SetText(toggleSearchCaseSensitivity(cmdline.GetText()))
+					return nil
+				case 'w':
+					cmdline.SetText(toggleSearchWholeWord(cmdline.GetText()))
+					return nil
+				}
+			}
+		case tcell.KeyUp:
+			if entry, ok := cmdlineHistory.previous(historyPrefix); ok {
+				setCmdlineText(cmdline, entry)
+			}
+			return nil
+		case tcell.KeyDown:
+			setCmdlineText(cmdline, cmdlineHistory.next(historyPrefix))
+			return nil
+		case tcell.KeyCtrlR:
+			if entry, ok := cmdlineHistory.reverseSearch(cmdline.GetText()); ok {
+				setCmdlineText(cmdline, entry)
+			}
+			return nil
 		case tcell.KeyEnter:
-			cmdlineText := cmdline.GetText()
+			cmdlineText := getCmdlineText(cmdline)
+			if len(cmdlineText) > 1 {
+				if err := cmdlineHistory.add(cmdlineText); err != nil {
+					showError("Error writing command history", err)
+				}
+			}
 			if strings.HasPrefix(cmdlineText, ":") {
 				if cmdlineText == ":q" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					quit()
+					return nil
+				} else if cmdlineText == ":q!" {
 					app.Stop()
 					return nil
 				} else if cmdlineText == ":w" {
 					if len(datasetsWithFilename) == 1 {
-						writeDatasetToFile(datasetsWithFilename[0].dataset, "write_test_copy.dcm")
-						statusLine.SetText("saved to write_test_copy.dcm")
+						saveDataset()
 					}
-					cmdline.SetText("")
+					setCmdlineText(cmdline, "")
 					app.SetFocus(tree)
+				} else if cmdlineText == ":wa" || strings.HasPrefix(cmdlineText, ":wa ") {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					if len(modified) == 0 {
+						statusLine.SetText("No modified files")
+						return nil
+					}
+					saveModified(strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":wa")))
+					return nil
+				} else if cmdlineText == ":help" {
+					addAndShowHelpPage(pages, pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if strings.HasPrefix(cmdlineText, ":export ") || strings.HasPrefix(cmdlineText, ":import ") {
+					command, filename, _ := strings.Cut(cmdlineText, " ")
+					filename = strings.TrimSpace(filename)
+					currentNode := tree.GetCurrentNode()
+					if !isTagNode(currentNode) {
+						statusLine.SetText("No element selected")
+					} else if e := currentNode.GetReference().(*dicom.Element); command == ":export" {
+						if err := exportElementValue(e, filename); err != nil {
+							showError("Error exporting value", err)
+						} else {
+							notifications.info("Value exported to " + filename)
+						}
+					} else if err := importElementValue(e, filename); err != nil {
+						showError("Error importing value", err)
+					} else {
+						if getFileEntry(tree, currentNode) != nil { // element node of the by file tree
+							currentNode.SetText(getElementText(e))
+						}
+						modified[getElementFilePath(datasetsWithFilename, e)] = true
+						edited[e] = true
+						edited.markNodes(tree.GetRoot())
+						notifications.info("Value imported from " + filename)
+						if err := audit.record("import", getElementFilePath(datasetsWithFilename, e), fmt.Sprintf("(%04x,%04x) %s from %s", e.Tag.Group, e.Tag.Element, getTagName(e), filename)); err != nil {
+							showError("Error writing audit log", err)
+						}
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					return nil
+				} else if cmdlineText == ":note" || strings.HasPrefix(cmdlineText, ":note ") {
+					currentNode := tree.GetCurrentNode()
+					if isTagNode(currentNode) || isFileNode(currentNode) {
+						reviewNotes.set(currentNode, strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":note")))
+						lastCurrentNode = nil // update details
+					} else {
+						statusLine.SetText("Notes can only be attached to elements or files")
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					return nil
+				} else if cmdlineText == ":skipped" {
+					addAndShowTextPage(pages, "report", "Skipped Files", formatSkippedFiles(skippedFiles), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if cmdlineText == ":notes" {
+					addAndShowTextPage(pages, "report", "Notes", reviewNotes.list(datasetsWithFilename), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if strings.HasPrefix(cmdlineText, ":notes ") {
+					filename := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":notes"))
+					if err := reviewNotes.export(filename, datasetsWithFilename); err != nil {
+						showError("Error exporting notes", err)
+					} else {
+						notifications.info("Notes exported to " + filename)
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					return nil
+				} else if strings.HasPrefix(cmdlineText, ":session ") {
+					filename := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":session"))
+					redact.unmaskNodes() // the session keeps the paths of the unmasked texts
+					storeTab()
+					s := newSession(tabs, activeTab, reviewNotes)
+					s.Hide, s.Conformance, s.FileInfo = filter.names(), showConformance, showFileInfo
+					if privacy {
+						redact.maskNodes(tree.GetRoot())
+					}
+					if err := s.save(filename); err != nil {
+						showError("Error saving session", err)
+					} else {
+						notifications.info("Session saved to " + filename)
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					return nil
+				} else if cmdlineText == ":screenshot" || strings.HasPrefix(cmdlineText, ":screenshot ") {
+					filename := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":screenshot"))
+					ansi := filename == "ansi" || strings.HasPrefix(filename, "ansi ")
+					if ansi {
+						filename = cmp.Or(strings.TrimSpace(strings.TrimPrefix(filename, "ansi")), "screenshot.ans")
+					}
+					filename = cmp.Or(filename, "screenshot.txt")
+					treeView.capture = func(screen tcell.Screen) {
+						x, y, width, height := treeView.GetRect()
+						text := formatScreen(screen, x, y, width, height, ansi)
+						if err := os.WriteFile(filename, []byte(text), 0o644); err != nil {
+							showError("Error writing screenshot", err)
+						} else {
+							notifications.info("Screenshot written to " + filename)
+						}
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					return nil
+				} else if cmdlineText == ":report" || strings.HasPrefix(cmdlineText, ":report ") {
+					filename := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":report"))
+					if filename == "" {
+						filename = "report.html"
+					}
+					entries, indexed := withoutIndexed(datasetsWithFilename)
+					title := rootDir
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					started := tasks.start("Writing report", func(ctx context.Context, progress func(done, total int)) error {
+						return writeHTMLReportToFile(ctx, filename, title, entries, progress)
+					}, func(err error) {
+						if errors.Is(err, context.Canceled) {
+							statusLine.SetText("Report cancelled")
+						} else if err != nil {
+							showError("Error writing report", err)
+						} else {
+							statusLine.SetText("Report written to " + filename + indexedStatus(indexed)) // replaces the progress
+							notifications.info("Report written to " + filename)
+						}
+					})
+					if !started {
+						statusLine.SetText("Another operation is running, wait for it or cancel it with esc")
+					}
+					return nil
+				} else if cmdlineText == ":send" || strings.HasPrefix(cmdlineText, ":send ") {
+					name := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":send"))
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					if name == "" {
+						if len(cfg.AEs) == 0 {
+							statusLine.SetText("No AEs configured in " + args.Config)
+							return nil
+						}
+						addAndShowAEPicker(pages, cfg.AEs, func(ae remoteAE) {
+							ae, _ = cfg.resolveAE(ae.Name, browse.AET, browse.CalledAET)
+							sendToAE(ae)
+						})
+						return nil
+					}
+					ae, err := cfg.resolveAE(name, browse.AET, browse.CalledAET)
+					if err != nil {
+						statusLine.SetText(err.Error())
+						return nil
+					}
+					sendToAE(ae)
+					return nil
+				} else if cmdlineText == ":query" || strings.HasPrefix(cmdlineText, ":query ") {
+					text := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":query"))
+					if text == "" {
+						activeQuery = nil
+					} else if q, err := parseQuery(text); err != nil {
+						showError("Invalid query", err)
+						return nil
+					} else {
+						activeQuery = q
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					sortTree()
+					return nil
+				} else if cmdlineText == ":propagate" || strings.HasPrefix(cmdlineText, ":propagate ") {
+					var q *query
+					if text := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":propagate")); text != "" {
+						var err error
+						if q, err = parseQuery(text); err != nil {
+							showError("Invalid query", err)
+							return nil
+						}
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					e, ok := tree.GetCurrentNode().GetReference().(*dicom.Element)
+					if !ok {
+						statusLine.SetText("No element selected")
+						return nil
+					}
+					changed, err := propagateElement(readEntries(), e, q)
+					if err != nil {
+						statusLine.SetText(err.Error())
+						return nil
+					}
+					source := getElementFilePath(datasetsWithFilename, e)
+					details := fmt.Sprintf("(%04x,%04x) %s propagated from %s", e.Tag.Group, e.Tag.Element, getTagName(e), source)
+					for _, path := range changed {
+						modified[path] = true
+						if err := audit.record("edit", path, details); err != nil {
+							showError("Error writing audit log", err)
+							return nil
+						}
+					}
+					edited.addByTag(datasetsWithFilename, changed, e.Tag)
+					state := saveTreeState(tree)
+					sortTree()
+					state.restore(tree)
+					notifications.info(fmt.Sprintf("%s propagated to %d files", getTagName(e), len(changed)))
+					return nil
+				} else if cmdlineText == ":associations" {
+					addAndShowTextPage(pages, "report", "Association negotiations", netLog.String(), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if cmdlineText == ":conformance" {
+					entries, indexed := withoutIndexed(datasetsWithFilename)
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					var report string
+					started := tasks.start("Checking required attributes", func(ctx context.Context, progress func(done, total int)) error {
+						var err error
+						report, err = checkSeriesConformance(ctx, entries, progress)
+						return err
+					}, func(err error) {
+						if errors.Is(err, context.Canceled) {
+							statusLine.SetText("Conformance check cancelled")
+						} else if err != nil {
+							showError("Error checking required attributes", err)
+						} else {
+							statusLine.SetText(strings.TrimPrefix(indexedStatus(indexed), " - "))
+							addAndShowTextPage(pages, "report", "Required attributes per series", report, pageText)
+						}
+					})
+					if !started {
+						statusLine.SetText("Another operation is running, wait for it or cancel it with esc")
+					}
+					return nil
+				} else if cmdlineText == ":slicecheck" {
+					addAndShowTextPage(pages, "report", "Slice geometry check", checkSliceGeometry(readEntries()), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if cmdlineText == ":geometry" {
+					addAndShowTextPage(pages, "report", "Slice geometry", plotSliceGeometry(readEntries(), 100, 12), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if strings.HasPrefix(cmdlineText, ":tabnew ") {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					openTabs([]string{strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":tabnew"))}, true, nil)
+					return nil
+				} else if cmdlineText == ":timeline" {
+					addAndShowTextPage(pages, "report", "Acquisition timeline", formatTimeline(readEntries()), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if cmdlineText == ":checksums" || strings.HasPrefix(cmdlineText, ":checksums ") {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entries := slices.Clone(datasetsWithFilename)
+					filename := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":checksums"))
+					var manifest []checksumEntry
+					started := tasks.start(fmt.Sprintf("Computing checksums of %d files", len(entries)), func(ctx context.Context, progress func(done, total int)) error {
+						var err error
+						manifest, err = manifestOfEntries(ctx, entries, progress)
+						return err
+					}, func(err error) {
+						if errors.Is(err, context.Canceled) {
+							statusLine.SetText("Computing checksums cancelled")
+							return
+						}
+						if err != nil {
+							showError("Error computing checksums", err)
+							return
+						}
+						statusLine.SetText(fmt.Sprintf("Computed checksums of %d files", len(manifest))) // replaces the progress
+						if filename == "" {
+							addAndShowTextPage(pages, "report", "SHA-256 checksums", formatManifest(manifest), pageText)
+						} else if err := writeManifest(filename, manifest); err != nil {
+							showError("Error writing manifest", err)
+						} else {
+							notifications.info("Manifest written to " + filename)
+						}
+					})
+					if !started {
+						statusLine.SetText("Another operation is running, wait for it or cancel it with esc")
+					}
+					return nil
+				} else if cmdlineText == ":duplicates" || cmdlineText == ":duplicates meta" {
+					entries, indexed := withoutIndexed(datasetsWithFilename)
+					includeMeta := cmdlineText == ":duplicates meta"
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					var report string
+					started := tasks.start("Finding duplicates", func(ctx context.Context, progress func(done, total int)) error {
+						var err error
+						report, err = formatDuplicates(ctx, entries, includeMeta, progress)
+						return err
+					}, func(err error) {
+						if errors.Is(err, context.Canceled) {
+							statusLine.SetText("Finding duplicates cancelled")
+						} else if err != nil {
+							showError("Error finding duplicates", err)
+						} else {
+							statusLine.SetText(strings.TrimPrefix(indexedStatus(indexed), " - "))
+							addAndShowTextPage(pages, "report", "Duplicates", report, pageText)
+						}
+					})
+					if !started {
+						statusLine.SetText("Another operation is running, wait for it or cancel it with esc")
+					}
+					return nil
+				} else if cmdlineText == ":dose" {
+					addAndShowTextPage(pages, "report", "CT radiation dose", formatDoseReports(newDoseReports(readEntries())), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if strings.HasPrefix(cmdlineText, ":dose ") {
+					filename := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":dose"))
+					if err := exportDoseReports(filename, newDoseReports(datasetsWithFilename)); err != nil {
+						showError("Error exporting dose reports", err)
+					} else {
+						notifications.info("Dose reports exported to " + filename)
+					}
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					return nil
+				} else if cmdlineText == ":histogram" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					histogram, err := newPixelHistogram(entry.dataset)
+					if err != nil {
+						statusLine.SetText(fmt.Sprintf("%s: %s", entry.filename, err.Error()))
+						return nil
+					}
+					addAndShowHistogramPage(pages, "Pixel values of "+entry.filename, histogram)
+					return nil
+				} else if cmdlineText == ":wsi" {
+					addAndShowTextPage(pages, "report", "Whole Slide Images", formatWSIPyramids(datasetsWithFilename), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if cmdlineText == ":tile" || strings.HasPrefix(cmdlineText, ":tile ") {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					level, err := newWSILevel(entry)
+					if err != nil {
+						statusLine.SetText(fmt.Sprintf("%s: %s", entry.filename, err.Error()))
+						return nil
+					}
+					column, row := 0, 0
+					if arguments := strings.Fields(strings.TrimPrefix(cmdlineText, ":tile")); len(arguments) > 0 {
+						if _, err := fmt.Sscanf(strings.Join(arguments, " "), "%d %d", &column, &row); err != nil {
+							statusLine.SetText("Usage: :tile [column row]")
+							return nil
+						}
+					}
+					addAndShowTilePage(pages, level, column, row)
+					return nil
+				} else if cmdlineText == ":seg" || strings.HasPrefix(cmdlineText, ":mask ") {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					seg, err := newSegmentation(entry.dataset)
+					if err != nil {
+						statusLine.SetText(fmt.Sprintf("%s: %s", entry.filename, err.Error()))
+						return nil
+					}
+					if cmdlineText == ":seg" {
+						addAndShowTextPage(pages, "report", "Segments of "+entry.filename, formatSegmentation(seg, datasetsWithFilename), pageText)
+						return nil
+					}
+					var number int
+					if _, err := fmt.Sscanf(strings.TrimPrefix(cmdlineText, ":mask"), "%d", &number); err != nil {
+						statusLine.SetText("Usage: :mask segment-number")
+						return nil
+					}
+					title, text, err := seg.renderSegment(entry.dataset, number, datasetsWithFilename)
+					if err != nil {
+						statusLine.SetText(fmt.Sprintf("%s: %s", entry.filename, err.Error()))
+						return nil
+					}
+					addAndShowTextPage(pages, "report", title, text, pageText)
+					return nil
+				} else if cmdlineText == ":preview" || cmdlineText == ":preview ps" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					var ps *presentationState
+					title := "Preview of " + entry.filename
+					if cmdlineText == ":preview ps" {
+						states := findPresentationStates(entry.dataset, datasetsWithFilename)
+						if len(states) == 0 {
+							statusLine.SetText("No presentation state references " + entry.filename)
+							return nil
+						}
+						ps = &states[0]
+						title += " with " + ps.entry.filename
+					}
+					text, err := renderPreview(entry.dataset, ps, 96, 40)
+					if err != nil {
+						statusLine.SetText(fmt.Sprintf("%s: %s", entry.filename, err.Error()))
+						return nil
+					}
+					if ps != nil {
+						text += "\n\nApplied: " + cmp.Or(strings.Join(ps.transformations(), ", "), "nothing")
+					}
+					addAndShowTextPage(pages, "report", title, text, pageText)
+					return nil
+				} else if cmdlineText == ":ko" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					references, err := newKeyObjectReferences(entry.dataset, datasetsWithFilename)
+					if err != nil {
+						statusLine.SetText(fmt.Sprintf("%s: %s", entry.filename, err.Error()))
+						return nil
+					}
+					if len(references) == 0 {
+						statusLine.SetText(entry.filename + " references no instances")
+						return nil
+					}
+					title := cmp.Or(keyObjectTitle(entry.dataset), "Key objects") + " - " + entry.filename
+					addAndShowKeyObjectPicker(pages, title, references, func(reference keyObjectReference) {
+						if reference.entry == nil {
+							statusLine.SetText(reference.sopInstanceUID + " is not loaded")
+							return
+						}
+						node := findEntryNode(tree.GetRoot(), reference.entry)
+						if node == nil {
+							statusLine.SetText(reference.entry.filename + " has no file node in this view")
+							return
+						}
+						expandPathToNode(tree, node)
+						tree.SetCurrentNode(node)
+					})
+					return nil
+				} else if strings.HasPrefix(cmdlineText, ":diff ") {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					dir := strings.TrimSpace(strings.TrimPrefix(cmdlineText, ":diff"))
+					current, currentDir := datasetsWithFilename, rootDir
+					var other []DatasetEntry
+					started := tasks.start("Loading "+dir, func(ctx context.Context, progress func(done, total int)) error {
+						var err error
+						other, _, err = parseDicomFiles(ctx, dir, selection, progress)
+						return err
+					}, func(err error) {
+						defer releaseLazyValues(other) // only needed for the report
+						if err != nil {
+							if !errors.Is(err, context.Canceled) {
+								showError("Error reading "+dir, err)
+							}
+							return
+						}
+						addAndShowTextPage(pages, "report", "Comparison with "+dir, newDirectoryDiff(currentDir, dir, current, other).String(), pageText)
+					})
+					if !started {
+						statusLine.SetText("Another operation is running, wait for it or cancel it with esc")
+					}
+					return nil
+				} else if cmdlineText == ":merge" || cmdlineText == ":merge off" || cmdlineText == ":merge clear" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					switch {
+					case cmdlineText == ":merge clear":
+						clear(picked)
+						mergePicked = false
+					case cmdlineText == ":merge off":
+						mergePicked = false
+					case len(picked) == 0:
+						statusLine.SetText("No files picked, pick them with m")
+						return nil
+					default:
+						mergePicked, sortMode = true, tagSortMode
+					}
+					sortTree()
+					return nil
+				} else if row, ok := parseRowCommand(cmdlineText); ok {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					jumpToRow(tree, row)
+					return nil
+				} else if cmdlineText == ":decode" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					e, ok := tree.GetCurrentNode().GetReference().(*dicom.Element)
+					if !ok || !canTryDecode(e) {
+						statusLine.SetText("No unknown or private element selected")
+						return nil
+					}
+					detailView.SetText(pageText.mask(getElementDetails(e) + "\n\n" + formatDecodings(e)))
+					return nil
+				} else if cmdlineText == ":blank" || strings.HasPrefix(cmdlineText, ":blank ") {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					regions, err := parseBlankRegions(strings.TrimPrefix(cmdlineText, ":blank"))
+					if err != nil {
+						statusLine.SetText(err.Error())
+						return nil
+					}
+					if len(regions) == 0 {
+						regions = cfg.Blank
+					}
+					if len(regions) == 0 {
+						statusLine.SetText("No regions given and none configured")
+						return nil
+					}
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					blanked, err := blankSeries(datasetsWithFilename, entry, regions)
+					if err != nil {
+						showError("Error blanking pixels", err)
+					}
+					details := fmt.Sprintf("pixel regions %v zeroed, BurnedInAnnotation set to NO", regions)
+					for _, b := range blanked {
+						modified[b.path] = true
+						if e, err := b.dataset.FindElementByTag(tag.PixelData); err == nil {
+							edited[e] = true
+						}
+						if err := audit.record("blank", b.path, details); err != nil {
+							showError("Error writing audit log", err)
+							return nil
+						}
+					}
+					state := saveTreeState(tree)
+					sortTree()
+					state.restore(tree)
+					if len(blanked) > 0 {
+						notifications.info(fmt.Sprintf("Blanked %d regions in %d files of the series, write them with :wa", len(regions), len(blanked)))
+					}
+					return nil
+				} else if cmdlineText == ":phi" {
+					addAndShowTextPage(pages, "report", "Burned-in patient data", formatBurnedInPHI(datasetsWithFilename), pageText)
+					setCmdlineText(cmdline, "")
+					return nil
+				} else if cmdlineText == ":codestream" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					raw, err := rawPixelData(entry)
+					if err != nil {
+						showError("Error reading pixel data", err)
+						return nil
+					}
+					pixelData, err := parseEncapsulatedPixelData(raw)
+					if err != nil {
+						showError("Error reading pixel data", err)
+						return nil
+					}
+					addAndShowTextPage(pages, "report", "Codestream of "+entry.filename, pixelData.String(), pageText)
+					return nil
+				} else if cmdlineText == ":dump" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					pageText.page(formatNodeDump(tree.GetCurrentNode()))
+					return nil
+				} else if cmdlineText == ":frames" {
+					setCmdlineText(cmdline, "")
+					app.SetFocus(tree)
+					entry := getFileEntry(tree, tree.GetCurrentNode())
+					if entry == nil {
+						statusLine.SetText("No file selected")
+						return nil
+					}
+					table, err := newFrameTable(entry.dataset)
+					if err != nil {
+						statusLine.SetText(fmt.Sprintf("%s: %s", entry.filename, err.Error()))
+						return nil
+					}
+					addAndShowTextPage(pages, "report", "Per-frame functional groups of "+entry.filename, table.String(), pageText)
+					return nil
 				}
 				if cmdlineText == ":" {
-					cmdline.SetText("")
+					setCmdlineText(cmdline, "")
 					app.SetFocus(tree)
 					return nil
 				}
 			}
-			if strings.HasPrefix(cmdlineText, "/") {
+			if strings.HasPrefix(cmdlineText, "/") || strings.HasPrefix(cmdlineText, "?") || strings.HasPrefix(cmdlineText, "s/") {
 				app.SetFocus(tree)
 				return nil
 			}
@@ -119,25 +1420,140 @@ func main() {
 	})
 
 	cmdline.SetChangedFunc(func(text string) {
-		cmdlineText := text
-		if strings.HasPrefix(cmdlineText, "/") && len(cmdlineText) > 1 {
-			searchText = strings.ToLower(cmdlineText[1:])
-			jumpToNthFoundNode(searchText, 0, tree)
+		cmdlineKillRing.finishKill(text)
+		prompt := cmdline.GetLabel()
+		if (prompt == "/" || prompt == "?" || prompt == "s/") && len(text) > 0 {
+			searchText = text
+			searchDir = searchForward
+			if prompt == "?" {
+				searchDir = searchBackward
+			}
+			if prompt != "s/" {
+				searchScope = nil
+			}
+			jumpToFoundNode(searchText, searchDir, true, tree, searchScope)
 		}
 	})
 
+	// update everything depending on the current node once it changed
+	app.SetBeforeDrawFunc(func(screen tcell.Screen) bool {
+		currentNode := tree.GetCurrentNode()
+		if currentNode == lastCurrentNode {
+			return false
+		}
+		lastCurrentNode = currentNode
+		positions.remember(tree, currentNode)
+
+		title := "dcmtagger - " + rootDir
+		if entry := getFileEntry(tree, currentNode); entry != nil {
+			title = "dcmtagger - " + entry.path
+		}
+		crumbs := formatTabs(tabs, activeTab) + getBreadcrumb(tree, currentNode)
+		if privacy {
+			title, crumbs = redact.mask(title), redact.mask(crumbs)
+		}
+		setTerminalTitle(title)
+		breadcrumb.SetText(crumbs)
+
+		switch reference := currentNode.GetReference().(type) {
+		case *DatasetEntry:
+			text := getFileSummary(reference)
+			if states := findPresentationStates(reference.dataset, datasetsWithFilename); len(states) > 0 {
+				text += "\n\n" + formatPresentationStates(states)
+			}
+			if references, err := newKeyObjectReferences(reference.dataset, datasetsWithFilename); err == nil {
+				text += "\n\n" + formatKeyObjectSummary(references)
+			}
+			if result, ok := checkConformance(reference.dataset); ok && showConformance && !reference.indexed {
+				text += "\n\n" + result.details()
+			}
+			if reasons := checkBurnedInPHI(reference.dataset, false); len(reasons) > 0 {
+				text += "\n\nBurned-in patient data likely, inspect before sharing: " + strings.Join(reasons, ", ")
+			}
+			if reference.indexed {
+				text += "\n\nKey tags taken from the index, expand the file to read all elements"
+			} else if hasLazyValues(reference.dataset) {
+				text += "\n\nPixel data read on access, see :histogram"
+			} else if histogram, err := newPixelHistogram(reference.dataset); err == nil {
+				text += "\n\n" + histogram.summary() + "\n\n" + histogram.chart(32, 4, -1)
+			}
+			detailView.SetText(text)
+		case *dicom.Element:
+			detailView.SetText(getElementDetails(reference))
+		case *hierarchyStats:
+			detailView.SetText(reference.details())
+		case *valueTransforms:
+			detailView.SetText(reference.details())
+		default:
+			detailView.SetText("")
+		}
+		if note, ok := reviewNotes.get(currentNode.GetReference()); ok {
+			detailView.SetText("Note: " + note + "\n\n" + detailView.GetText(false))
+		}
+		if privacy {
+			detailView.SetText(redact.mask(detailView.GetText(false)))
+		}
+		detailView.ScrollToBeginning()
+		return false
+	})
+
+	// readIndexedFile parses the file of the node if only its key tags were taken from the index, the tree is rebuilt
+	// with all its elements then. True if the file was read.
+	readIndexedFile := func(node *tview.TreeNode) bool {
+		entry, ok := node.GetReference().(*DatasetEntry)
+		if !ok || !entry.indexed {
+			return false
+		}
+		dataset, err := selection.parseFile(entry.path)
+		if err != nil {
+			showError("Error reading "+entry.path, err)
+			return false
+		}
+		for i := range datasetsWithFilename {
+			if datasetsWithFilename[i].path == entry.path {
+				datasetsWithFilename[i].dataset, datasetsWithFilename[i].indexed = dataset, false
+				datasetsWithFilename[i].partial = selection.isPartial()
+			}
+		}
+		state := saveTreeState(tree)
+		sortTree()
+		state.restore(tree)
+		return true
+	}
 	tree.SetSelectedFunc(func(node *tview.TreeNode) {
 		node.SetExpanded(!node.IsExpanded())
+		if !readIndexedFile(node) {
+			positions.restore(tree, node)
+		}
 	})
+	// expandWith runs the motion on the current node, a file expanded again selects the node last selected in it
+	expandWith := func(motion func(tree *tview.TreeView)) {
+		node := tree.GetCurrentNode()
+		wasCollapsed := !node.IsExpanded()
+		motion(tree)
+		if wasCollapsed && !readIndexedFile(node) {
+			positions.restore(tree, node)
+		}
+	}
 
 	// key handlings
 	tree.SetInputCapture(func(event *tcell.EventKey) *tcell.EventKey {
 		currentNode := tree.GetCurrentNode()
+		prevKey := pendingKey
+		pendingKey = 0
 
 		switch key := event.Key(); key {
 		case tcell.KeyCtrlSpace:
 			if isTagNode(currentNode) {
-				addAndShowTagEditingPage(pages, currentNode.GetReference().(*dicom.Element))
+				e := currentNode.GetReference().(*dicom.Element)
+				addAndShowTagEditingPage(pages, e, func(oldValue string) {
+					modified[getElementFilePath(datasetsWithFilename, e)] = true
+					edited[e] = true
+					edited.markNodes(tree.GetRoot())
+					if err := audit.recordEdit("edit", getElementFilePath(datasetsWithFilename, e), e, oldValue); err != nil {
+						showError("Error writing audit log", err)
+					}
+				})
 			} else {
 				return event
 			}
@@ -155,9 +1571,9 @@ func main() {
 			}
 		case tcell.KeyRight:
 			if event.Modifiers() == tcell.ModShift {
-				moveToFirstChild(tree)
+				expandWith(moveToFirstChild)
 			} else {
-				expandOrMoveToFirstChild(tree)
+				expandWith(expandOrMoveToFirstChild)
 			}
 		case tcell.KeyUp:
 			if event.Modifiers() == tcell.ModShift {
@@ -176,21 +1592,148 @@ func main() {
 		case tcell.KeyEnd:
 			jumpToLastVisibleNode(tree)
 		case tcell.KeyRune:
+			if prevKey == 'g' && (event.Rune() == 't' || event.Rune() == 'T') {
+				tree.SetCurrentNode(nodeBeforeJump) // the tab is kept as it was before g
+				if len(tabs) == 1 {
+					statusLine.SetText("No other tab, open one with :tabnew")
+					return nil
+				}
+				delta := 1
+				if event.Rune() == 'T' {
+					delta = -1
+				}
+				switchTab(cycleTab(activeTab, len(tabs), delta))
+				return nil
+			}
+			if prevKey == 't' {
+				switch event.Rune() {
+				case 'r':
+					filter.hideRetired = !filter.hideRetired
+				case 'g':
+					filter.hideGroupLength = !filter.hideGroupLength
+				case 'p':
+					filter.hidePrivate = !filter.hidePrivate
+				case 'c':
+					showConformance = !showConformance
+					sortTree()
+					return nil
+				case 'f':
+					showFileInfo = !showFileInfo
+					sortTree()
+					return nil
+				case 'd':
+					showDateTimes = !showDateTimes
+					state := saveTreeState(tree)
+					sortTree()
+					state.restore(tree)
+					return nil
+				case 'u':
+					showUnits = !showUnits
+					state := saveTreeState(tree)
+					sortTree()
+					state.restore(tree)
+					return nil
+				case 'x':
+					privacy = !privacy
+					lastCurrentNode = nil // update the details on the next draw
+					if privacy {
+						redact.collect(datasetsWithFilename)
+						redact.maskNodes(tree.GetRoot())
+						statusLine.SetText("Privacy mode, identifying values masked")
+						return nil
+					}
+					redact.unmaskNodes() // the state is saved with the unmasked texts of the rebuilt tree
+					state := saveTreeState(tree)
+					sortTree()
+					state.restore(tree)
+					return nil
+				case 's':
+					treeView.sticky = !treeView.sticky
+					return nil
+				case 'n':
+					treeView.numbers = treeView.numbers.next()
+					statusLine.SetText("Line numbers " + treeView.numbers.String())
+					return nil
+				case 'l':
+					if treeColumn.GetItemCount() > 1 {
+						treeColumn.RemoveItem(logView)
+					} else {
+						logView.SetText(logBuffer.String()).ScrollToEnd()
+						treeColumn.AddItem(logView, 10, 0, false)
+					}
+					return nil
+				default:
+					return nil
+				}
+				if sortTree() == 0 {
+					statusLine.SetText(statusLine.GetText(false) + " - " + filter.String())
+				}
+				return nil
+			}
+			if prevKey == 'i' {
+				description, e, err := editSequenceItemsInTree(tree, event.Rune())
+				if err != nil {
+					statusLine.SetText(err.Error())
+					return nil
+				}
+				details := fmt.Sprintf("(%04x,%04x) %s: %s", e.Tag.Group, e.Tag.Element, getTagName(e), description)
+				modified[getElementFilePath(datasetsWithFilename, e)] = true
+				edited[e] = true
+				edited.markNodes(tree.GetRoot())
+				if err := audit.record("edit", getElementFilePath(datasetsWithFilename, e), details); err != nil {
+					showError("Error writing audit log", err)
+					return nil
+				}
+				statusLine.SetText(details)
+				return nil
+			}
+			if prevKey == 'z' {
+				switch event.Rune() {
+				case 'z':
+					treeView.alignSelection(alignCenter)
+				case 't':
+					treeView.alignSelection(alignTop)
+				case 'b':
+					treeView.alignSelection(alignBottom)
+				case 'R':
+					expandAll(tree.GetRoot())
+				case 'M':
+					collapseAllRecursive(tree.GetRoot())
+					if path := getNodePath(tree, currentNode); len(path) > 1 {
+						tree.SetCurrentNode(path[1])
+					}
+				}
+				return nil
+			}
+			if prevKey == ']' || prevKey == '[' {
+				switch event.Rune() {
+				case 'c':
+					if !jumpToMatchingNode(tree, prevKey == '[', edited.isEdited) {
+						statusLine.SetText("No modified elements")
+					}
+				case 'w':
+					if !jumpToMatchingNode(tree, prevKey == '[', newConformanceIssues(datasetsWithFilename).isIssue) {
+						statusLine.SetText("No conformance issues")
+					}
+				}
+				return nil
+			}
 			switch event.Rune() {
-			case '1':
-				tree, root = sortTreeByFilename(rootDir, tree, datasetsWithFilename[:])
-				collapseAllRecursive(root)
-				statusLine.SetText("Sort by filename")
-			case '2':
-				tree, root = sortTreeByTags(rootDir, tree, datasetsWithFilename[:], 0)
-				collapseAllLeaves(root)
-				statusLine.SetText("Sort by tag")
-			case '3':
-				tree, root = sortTreeByTags(rootDir, tree, datasetsWithFilename[:], 1)
-				collapseAllLeaves(root)
-				statusLine.SetText("Sort by tag, show only different tag values")
+			case '1', '2', '3', '4', '5', '6', '7', '8':
+				previous := sortMode
+				sortMode = event.Rune()
+				if previous != sortMode && strings.ContainsRune("23", previous) && strings.ContainsRune("23", sortMode) {
+					position, state := saveTagPosition(tree), saveTreeState(tree)
+					sortTree()
+					state.restore(tree)
+					if position.restore(tree) {
+						treeView.alignSelection(alignCenter)
+					}
+				} else {
+					sortTree()
+				}
 			case 'q':
-				app.Stop()
+				quit()
 			case 'J':
 				moveDownSameLevel(tree)
 			case 'K':
@@ -198,11 +1741,11 @@ func main() {
 			case 'h':
 				collapseOrMoveToParent(tree)
 			case 'l':
-				expandOrMoveToFirstChild(tree)
+				expandWith(expandOrMoveToFirstChild)
 			case 'H':
 				moveToParent(tree)
 			case 'L':
-				moveToFirstChild(tree)
+				expandWith(moveToFirstChild)
 			case '0', '^':
 				moveToFirstSibling(tree)
 			case '$':
@@ -212,17 +1755,47 @@ func main() {
 			case 'c':
 				collapseCurrentAndAllSiblings(tree)
 			case 'E':
-				currentNode.ExpandAll()
+				expandAll(currentNode)
 			case 'C':
 				currentNode.CollapseAll()
 			case 'g':
+				nodeBeforeJump = currentNode
 				jumpToRoot(tree)
+				pendingKey = 'g' // gt, gT
 			case 'G':
 				jumpToLastVisibleNode(tree)
 			case 'n':
-				jumpToNextFoundNode(searchText, tree)
+				jumpToNextFoundNode(searchText, searchDir, tree, searchScope)
 			case 'N':
-				jumpToPrevFoundNode(searchText, tree)
+				jumpToPrevFoundNode(searchText, searchDir, tree, searchScope)
+			case 'p':
+				jumpToProvenance()
+			case 'm':
+				entry := getFileEntry(tree, currentNode)
+				if entry == nil {
+					statusLine.SetText("No file selected")
+					return nil
+				}
+				isPicked := picked.toggle(entry)
+				state := saveTreeState(tree)
+				sortTree()
+				state.restore(tree)
+				if isPicked {
+					statusLine.SetText(fmt.Sprintf("Picked %s, %d files picked, :merge compares them", entry.filename, len(picked)))
+				} else {
+					statusLine.SetText(fmt.Sprintf("Unpicked %s, %d files picked", entry.filename, len(picked)))
+				}
+			case 'z', 't', 'i', ']', '[':
+				pendingKey = event.Rune()
+			case 's':
+				searchScope = currentNode
+				if parent := getParent(tree, currentNode); len(currentNode.GetChildren()) == 0 && parent != nil {
+					searchScope = parent
+				}
+				statusLine.SetText("Search within " + strings.TrimSpace(searchScope.GetText()))
+				app.SetFocus(cmdline)
+				setCmdlineText(cmdline, "s/")
+				cmdlineHistory.reset()
 
 			default:
 				return event // not handled, pass on
@@ -236,7 +1809,27 @@ func main() {
 
 	pages.AddPage("main", mainGrid, true, true)
 
-	if err := app.SetRoot(pages, true).Run(); err != nil {
+	screen, err := newPasteScreen(func(text string) {
+		app.QueueUpdateDraw(func() {
+			if field, ok := app.GetFocus().(*tview.InputField); ok {
+				insertText(field, strings.TrimSpace(text))
+			}
+		})
+	})
+	if err != nil {
 		panic(err)
 	}
+	app.SetScreen(screen)
+	setCrashScreen(screen)
+
+	pushTerminalTitle()
+	err = app.SetRoot(pages, true).Run()
+	setCrashScreen(nil)
+	popTerminalTitle()
+	if err != nil {
+		panic(err)
+	}
+	if loadErr != nil {
+		fmt.Printf("Error reading input: '%s'\n", loadErr.Error())
+	}
 }
@@ -0,0 +1,47 @@
+package main
+
+import (
+	"cmp"
+
+	"github.com/rivo/tview"
+)
+
+// pickedFiles are the paths of the files picked with 'm', the merge view builds the by-tag trees of these files only.
+// Paths are used rather than the entries since the trees reference copies of them if a query is active.
+type pickedFiles map[string]bool
+
+func pickKey(entry *DatasetEntry) string {
+	return cmp.Or(entry.path, entry.filename)
+}
+
+// toggle picks the file or removes it, returns true if it's picked afterwards
+func (p pickedFiles) toggle(entry *DatasetEntry) bool {
+	key := pickKey(entry)
+	if p[key] {
+		delete(p, key)
+		return false
+	}
+	p[key] = true
+	return true
+}
+
+// filter returns the picked entries in their order
+func (p pickedFiles) filter(entries []DatasetEntry) []DatasetEntry {
+	picked := make([]DatasetEntry, 0, len(p))
+	for i := range entries {
+		if p[pickKey(&entries[i])] {
+			picked = append(picked, entries[i])
+		}
+	}
+	return picked
+}
+
+// markPickedFiles appends a mark to the nodes of the picked files
+func markPickedFiles(root *tview.TreeNode, picked pickedFiles) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if entry, ok := node.GetReference().(*DatasetEntry); ok && picked[pickKey(entry)] {
+			node.SetText(node.GetText() + " [picked]")
+		}
+		return true
+	})
+}
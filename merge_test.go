@@ -0,0 +1,48 @@
+package main
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestPickedFiles(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newInstanceFixture(t, 1).entry("1.dcm"),
+		newInstanceFixture(t, 2).with(tag.SeriesDescription, []string{"B"}).entry("2.dcm"),
+		newInstanceFixture(t, 3).with(tag.SeriesDescription, []string{"C"}).entry("3.dcm"),
+	}
+	picked := pickedFiles{}
+	assert.True(picked.toggle(&datasets[2]))
+	assert.True(picked.toggle(&datasets[0]))
+	assert.True(picked.toggle(&datasets[1]))
+	assert.False(picked.toggle(&datasets[1]))
+
+	copies := append([]DatasetEntry{}, datasets...) // like filtered by a query
+	merged := picked.filter(copies)
+	assert.Len(merged, 2)
+	assert.Equal("1.dcm", merged[0].filename)
+	assert.Equal("3.dcm", merged[1].filename)
+
+	_, root := sortTreeByTags("root", tview.NewTreeView(), merged, 0)
+	valueNodes := map[string]int{}
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if _, filename, ok := strings.Cut(node.GetText(), "\t - "); ok {
+			valueNodes[filename]++
+		}
+		return true
+	})
+	assert.NotZero(valueNodes["1.dcm"])
+	assert.NotZero(valueNodes["3.dcm"])
+	assert.Zero(valueNodes["2.dcm"])
+
+	_, root = sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	markPickedFiles(root, picked)
+	assert.Equal("1.dcm [picked]", root.GetChildren()[0].GetText())
+	assert.Equal("2.dcm", root.GetChildren()[1].GetText())
+}
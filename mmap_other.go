@@ -0,0 +1,17 @@
+//go:build !unix
+
+package main
+
+import (
+	"errors"
+	"os"
+)
+
+// mapFile isn't supported, huge files are read completely instead
+func mapFile(file *os.File, size int64) ([]byte, error) {
+	return nil, errors.New("not supported")
+}
+
+func unmapFile(data []byte) error {
+	return nil
+}
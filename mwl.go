@@ -0,0 +1,93 @@
+//go:build !nonet
+
+package main
+
+import (
+	"context"
+	"crypto/tls"
+	"fmt"
+	"time"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+const modalityWorklistFind = "1.2.840.10008.5.1.4.31"
+
+// worklistQuery are the matching keys of a modality worklist query, empty ones match everything
+type worklistQuery struct {
+	modality   string
+	stationAET string
+	date       string // DA or date range like 20240301-20240305
+}
+
+// identifier returns the query identifier with the matching keys and the usual return keys of a worklist item
+func (q worklistQuery) identifier() ([]*dicom.Element, error) {
+	stepKeys := []struct {
+		tag   tag.Tag
+		value string
+	}{
+		{tag.Modality, q.modality},
+		{tag.ScheduledStationAETitle, q.stationAET},
+		{tag.ScheduledProcedureStepStartDate, q.date},
+		{tag.ScheduledProcedureStepStartTime, ""},
+		{tag.ScheduledPerformingPhysicianName, ""},
+		{tag.ScheduledProcedureStepDescription, ""},
+		{tag.ScheduledProcedureStepID, ""},
+	}
+	step := make([]*dicom.Element, 0)
+	for _, key := range stepKeys {
+		e, err := newQueryElement(key.tag, key.value)
+		if err != nil {
+			return nil, err
+		}
+		step = append(step, e)
+	}
+	stepSequence, err := dicom.NewElement(tag.ScheduledProcedureStepSequence, [][]*dicom.Element{step})
+	if err != nil {
+		return nil, err
+	}
+
+	identifier := []*dicom.Element{stepSequence}
+	for _, t := range []tag.Tag{
+		tag.AccessionNumber, tag.ReferringPhysicianName, tag.PatientName, tag.PatientID, tag.PatientBirthDate,
+		tag.PatientSex, tag.StudyInstanceUID, tag.RequestedProcedureDescription, tag.RequestedProcedureID,
+	} {
+		e, err := newQueryElement(t, "")
+		if err != nil {
+			return nil, err
+		}
+		identifier = append(identifier, e)
+	}
+	return identifier, nil
+}
+
+// queryWorklist queries the worklist SCP and returns one entry per scheduled procedure step, which can be browsed
+// like files
+func queryWorklist(ctx context.Context, ae remoteAE, query worklistQuery, tlsConfig *tls.Config, netLog *protocolLog) ([]DatasetEntry, error) {
+	identifier, err := query.identifier()
+	if err != nil {
+		return nil, err
+	}
+	contexts := []presentationContext{{id: 1, abstractSyntax: modalityWorklistFind, transferSyntaxes: []string{implicitVRLittleEndian}}}
+	a, err := dialAssociation(ctx, ae.address(), ae.CallingAET, ae.CalledAET, contexts, tlsConfig, netLog)
+	if err != nil {
+		return nil, err
+	}
+	matches, err := a.find(ctx, modalityWorklistFind, identifier)
+	if err != nil {
+		a.conn.Close()
+		return nil, err
+	}
+	if err := a.release(); err != nil {
+		return nil, err
+	}
+
+	entries := make([]DatasetEntry, 0)
+	for i, match := range matches {
+		dataset := dicom.Dataset{Elements: match}
+		name := fmt.Sprintf("%03d %s %s", i+1, getDatasetValue(dataset, tag.PatientName), getDatasetValue(dataset, tag.AccessionNumber))
+		entries = append(entries, DatasetEntry{name, dataset, "", 0, time.Time{}, false, false})
+	}
+	return entries, nil
+}
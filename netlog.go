@@ -0,0 +1,82 @@
+//go:build !nonet
+
+package main
+
+import (
+	"fmt"
+	"strings"
+	"sync"
+	"time"
+)
+
+// reasons of presentation context results in an association acceptance
+var presentationContextResults = map[byte]string{
+	0: "accepted",
+	1: "user rejection",
+	2: "no reason",
+	3: "abstract syntax not supported",
+	4: "transfer syntaxes not supported",
+}
+
+// protocolLog records the association negotiations of all network operations, to inspect them for interoperability
+// problems. It is safe for concurrent use, a nil log discards everything.
+type protocolLog struct {
+	mutex   sync.Mutex
+	entries []string
+	now     func() time.Time
+}
+
+func newProtocolLog() *protocolLog {
+	return &protocolLog{now: time.Now}
+}
+
+func (l *protocolLog) add(text string) {
+	if l == nil {
+		return
+	}
+	l.mutex.Lock()
+	defer l.mutex.Unlock()
+	l.entries = append(l.entries, l.now().Format(time.DateTime)+" "+text)
+}
+
+func (l *protocolLog) String() string {
+	if l == nil {
+		return ""
+	}
+	l.mutex.Lock()
+	defer l.mutex.Unlock()
+	if len(l.entries) == 0 {
+		return "no associations"
+	}
+	return strings.Join(l.entries, "\n\n")
+}
+
+// addNegotiation records the proposed and accepted presentation contexts, the response is nil if the association was
+// rejected or failed with the given error
+func (l *protocolLog) addNegotiation(peer string, request associateParams, response *associateParams, err error) {
+	if l == nil {
+		return
+	}
+	lines := []string{fmt.Sprintf("A-ASSOCIATE %s -> %s (%s)", request.callingAET, request.calledAET, peer)}
+	lines = append(lines, fmt.Sprintf("  proposed, max PDU length %d:", request.maxPDULength))
+	for _, pc := range request.contexts {
+		lines = append(lines, fmt.Sprintf("    [%d] %s: %s", pc.id, pc.abstractSyntax, strings.Join(pc.transferSyntaxes, ", ")))
+	}
+	if response == nil {
+		lines = append(lines, fmt.Sprintf("  failed: %v", err))
+		l.add(strings.Join(lines, "\n"))
+		return
+	}
+	lines = append(lines, fmt.Sprintf("  accepted, max PDU length %d:", response.maxPDULength))
+	for _, pc := range response.contexts {
+		result, ok := presentationContextResults[pc.result]
+		if !ok {
+			result = fmt.Sprintf("result %d", pc.result)
+		}
+		if pc.result == 0 && len(pc.transferSyntaxes) > 0 {
+			result += " with " + pc.transferSyntaxes[0]
+		}
+		lines = append(lines, fmt.Sprintf("    [%d] %s", pc.id, result))
+	}
+	l.add(strings.Join(lines, "\n"))
+}
@@ -0,0 +1,45 @@
+//go:build nonet
+
+package main
+
+import (
+	"context"
+	"crypto/tls"
+	"errors"
+	"net"
+)
+
+// Stubs of the network features for builds with -tags nonet, for a tag browser without the DICOM network code.
+
+// networking tells if the DICOM network features are compiled in
+const networking = false
+
+var errNoNetworking = errors.New("DICOM networking isn't compiled in, build without -tags nonet")
+
+type protocolLog struct{}
+
+func newProtocolLog() *protocolLog { return &protocolLog{} }
+
+func (l *protocolLog) String() string { return errNoNetworking.Error() }
+
+type worklistQuery struct {
+	modality   string
+	stationAET string
+	date       string
+}
+
+func queryWorklist(ctx context.Context, ae remoteAE, query worklistQuery, tlsConfig *tls.Config, netLog *protocolLog) ([]DatasetEntry, error) {
+	return nil, errNoNetworking
+}
+
+func sendFiles(ctx context.Context, ae remoteAE, paths []string, tlsConfig *tls.Config, netLog *protocolLog, progress func(done, total int)) error {
+	return errNoNetworking
+}
+
+type storageSCP struct{}
+
+func newStorageSCP(spoolDir string, received func(path string), netLog *protocolLog) *storageSCP {
+	return &storageSCP{}
+}
+
+func (s *storageSCP) serve(listener net.Listener) error { return errNoNetworking }
@@ -0,0 +1,84 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"strings"
+
+	"github.com/rivo/tview"
+)
+
+// marker appended to the text of nodes with a note
+const noteMarker = " ✎"
+
+// notes holds the free-text review notes attached to elements or files, keyed by noteKey of the node reference, i.e.
+// the *dicom.Element or the path of the *DatasetEntry, so they survive rebuilding the tree in another sort mode.
+type notes map[any]string
+
+// fileNote is the key of the note of a file
+type fileNote string
+
+// noteKey returns the key of the note of the node reference. Files are keyed by path like picked ones, since the
+// trees reference copies of the entries if they are sorted by size, time or instance number, a query is active or
+// picked files are merged.
+func noteKey(reference any) any {
+	if entry, ok := reference.(*DatasetEntry); ok {
+		return fileNote(pickKey(entry))
+	}
+	return reference
+}
+
+// get returns the note of the node reference
+func (n notes) get(reference any) (string, bool) {
+	if reference == nil {
+		return "", false
+	}
+	note, ok := n[noteKey(reference)]
+	return note, ok
+}
+
+// set attaches the note to the reference of the node and updates the node marker, an empty note removes it
+func (n notes) set(node *tview.TreeNode, note string) {
+	text := strings.TrimSuffix(node.GetText(), noteMarker)
+	if note == "" {
+		delete(n, noteKey(node.GetReference()))
+		node.SetText(text)
+		return
+	}
+	n[noteKey(node.GetReference())] = note
+	node.SetText(text + noteMarker)
+}
+
+// markNodes appends the note marker to all nodes with a note below the given node
+func (n notes) markNodes(root *tview.TreeNode) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if _, ok := n.get(node.GetReference()); ok {
+			node.SetText(node.GetText() + noteMarker)
+		}
+		return true
+	})
+}
+
+// list returns the notes of the given datasets, per file the file note followed by the notes of its elements
+func (n notes) list(datasetsWithFilename []DatasetEntry) string {
+	lines := make([]string, 0)
+	for i := range datasetsWithFilename {
+		entry := &datasetsWithFilename[i]
+		if note, ok := n.get(entry); ok {
+			lines = append(lines, fmt.Sprintf("%s: %s", entry.path, note))
+		}
+		for _, e := range entry.dataset.Elements {
+			if note, ok := n[e]; ok {
+				lines = append(lines, fmt.Sprintf("%s (%04x,%04x) %s: %s", entry.path, e.Tag.Group, e.Tag.Element, getTagName(e), note))
+			}
+		}
+	}
+	if len(lines) == 0 {
+		return "no notes"
+	}
+	return strings.Join(lines, "\n")
+}
+
+func (n notes) export(filename string, datasetsWithFilename []DatasetEntry) error {
+	return os.WriteFile(filename, []byte(n.list(datasetsWithFilename)+"\n"), 0o644)
+}
@@ -0,0 +1,40 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestNotes(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newFixture(t).with(tag.Modality, []string{"CT"}).entry("file1.dcm")}
+	tree, _ := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	fileNode := tree.GetRoot() // the only file
+	elementNode := fileNode.GetChildren()[0].GetChildren()[0]
+
+	reviewNotes := notes{}
+	reviewNotes.set(fileNode, "check orientation")
+	reviewNotes.set(elementNode, "should be MR")
+	assert.Equal("file1.dcm"+noteMarker, fileNode.GetText())
+	assert.Equal("file1.dcm: check orientation\nfile1.dcm (0008,0060) Modality: should be MR", reviewNotes.list(datasets))
+
+	_, root := sortTreeByTags("root", tview.NewTreeView(), append(datasets, datasets[0]), 0)
+	reviewNotes.markNodes(root)
+	valueNode := root.GetChildren()[0].GetChildren()[0].GetChildren()[0]
+	assert.Contains(valueNode.GetText(), noteMarker)
+
+	reviewNotes.set(fileNode, "")
+	assert.Equal("file1.dcm", fileNode.GetText())
+
+	// trees of sorted copies of the entries
+	tree, _ = sortTreeByFilename("root", tview.NewTreeView(), sortEntries(datasets, compareBySize))
+	reviewNotes.set(tree.GetRoot(), "copy")
+	assert.Contains(reviewNotes.list(datasets), "file1.dcm: copy")
+	tree, _ = sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	reviewNotes.markNodes(tree.GetRoot())
+	assert.Equal("file1.dcm"+noteMarker, tree.GetRoot().GetText())
+}
@@ -0,0 +1,84 @@
+package main
+
+import (
+	"log/slog"
+	"sync"
+	"time"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/mattn/go-runewidth"
+	"github.com/rivo/tview"
+)
+
+// notificationDuration is how long a notification is shown
+const notificationDuration = 4 * time.Second
+
+// maxNotificationWidth limits the box, longer messages are truncated
+const maxNotificationWidth = 60
+
+// notification is a transient message drawn in a box at the top right corner over the UI
+type notification struct {
+	text    string
+	isError bool
+}
+
+// notifier shows notifications on top of everything after each draw, so they neither take the focus nor need a page.
+// Each notification replaces the previous one and disappears after notificationDuration.
+type notifier struct {
+	mutex   sync.Mutex
+	current *notification
+	timer   *time.Timer
+	redraw  func() // requests a redraw after the notification disappeared
+}
+
+func newNotifier(redraw func()) *notifier {
+	return &notifier{redraw: redraw}
+}
+
+// info shows a confirmation like a written file
+func (n *notifier) info(text string) {
+	slog.Info(text)
+	n.show(&notification{text: text})
+}
+
+// error shows a failure, which is also logged by the caller
+func (n *notifier) error(text string) {
+	n.show(&notification{text: text, isError: true})
+}
+
+func (n *notifier) show(shown *notification) {
+	n.mutex.Lock()
+	defer n.mutex.Unlock()
+	n.current = shown
+	if n.timer != nil {
+		n.timer.Stop()
+	}
+	n.timer = time.AfterFunc(notificationDuration, func() {
+		n.mutex.Lock()
+		if n.current == shown {
+			n.current = nil
+		}
+		n.mutex.Unlock()
+		n.redraw()
+	})
+}
+
+// draw draws the current notification, if any, with a border in the top right corner of the screen
+func (n *notifier) draw(screen tcell.Screen) {
+	n.mutex.Lock()
+	current := n.current
+	n.mutex.Unlock()
+	if current == nil {
+		return
+	}
+	screenWidth, _ := screen.Size()
+	text := runewidth.Truncate(current.text, min(maxNotificationWidth, screenWidth-4), "…")
+	width := runewidth.StringWidth(text) + 4
+	box := tview.NewTextView().SetText(text)
+	box.SetBorder(true).SetBorderPadding(0, 0, 1, 1)
+	if current.isError {
+		box.SetBorderColor(activeTheme.problem).SetTitle("Error")
+	}
+	box.SetRect(max(screenWidth-width-1, 0), 1, width, 3)
+	box.Draw(screen)
+}
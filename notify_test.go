@@ -0,0 +1,40 @@
+package main
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestNotifier(t *testing.T) {
+	assert := assert.New(t)
+
+	screen := tcell.NewSimulationScreen("UTF-8")
+	assert.NoError(screen.Init())
+	defer screen.Fini()
+	screen.SetSize(40, 5)
+
+	rowText := func(y int) string {
+		var row strings.Builder
+		for x := 0; x < 40; x++ {
+			mainc, _, _, _ := screen.GetContent(x, y)
+			row.WriteRune(mainc)
+		}
+		return row.String()
+	}
+
+	n := newNotifier(func() {})
+	n.draw(screen)
+	assert.Equal(strings.Repeat(" ", 40), rowText(2))
+
+	n.info("Report written to report.html")
+	n.draw(screen)
+	assert.Equal("      │ Report written to report.html │ ", rowText(2))
+
+	n.error(strings.Repeat("x", 50))
+	n.draw(screen)
+	assert.Contains(rowText(2), "│ "+strings.Repeat("x", 35)+"… │")
+	assert.True(n.current.isError)
+}
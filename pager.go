@@ -0,0 +1,62 @@
+package main
+
+import (
+	"fmt"
+	"os"
+	"os/exec"
+	"strings"
+
+	"github.com/rivo/tview"
+)
+
+// defaultPager is used for long texts if $PAGER isn't set
+const defaultPager = "less"
+
+// pagerCommand returns the pager command with its arguments from the value of $PAGER, e.g. "less -S"
+func pagerCommand(env string) []string {
+	if fields := strings.Fields(env); len(fields) > 0 {
+		return fields
+	}
+	return []string{defaultPager}
+}
+
+// textPager masks the texts shown by text pages in privacy mode and pipes them to the pager
+type textPager struct {
+	mask func(text string) string // returns the text unchanged outside of privacy mode
+	show func(text string)        // shows the masked text in the pager
+}
+
+// page masks the text and shows it in the pager
+func (p textPager) page(text string) {
+	p.show(p.mask(text))
+}
+
+// showInPager suspends the UI and pipes the text to the user's pager, the UI is resumed when the pager exits
+func showInPager(app *tview.Application, text string) error {
+	command := pagerCommand(os.Getenv("PAGER"))
+	var err error
+	app.Suspend(func() {
+		cmd := exec.Command(command[0], command[1:]...)
+		cmd.Stdin = strings.NewReader(text)
+		cmd.Stdout, cmd.Stderr = os.Stdout, os.Stderr
+		err = cmd.Run()
+	})
+	if err != nil {
+		return fmt.Errorf("running pager %s: %w", command[0], err)
+	}
+	return nil
+}
+
+// formatNodeDump returns the texts of the node and of all its descendants, also the collapsed ones, indented by depth
+func formatNodeDump(node *tview.TreeNode) string {
+	var b strings.Builder
+	var write func(node *tview.TreeNode, depth int)
+	write = func(node *tview.TreeNode, depth int) {
+		b.WriteString(strings.Repeat("  ", depth) + strings.TrimLeft(node.GetText(), "\t") + "\n")
+		for _, child := range node.GetChildren() {
+			write(child, depth+1)
+		}
+	}
+	write(node, 0)
+	return b.String()
+}
@@ -0,0 +1,41 @@
+package main
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestPagerCommand(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal([]string{"less"}, pagerCommand(""))
+	assert.Equal([]string{"less"}, pagerCommand("  "))
+	assert.Equal([]string{"less", "-S"}, pagerCommand("less -S"))
+	assert.Equal([]string{"more"}, pagerCommand("more"))
+}
+
+func TestFormatNodeDump(t *testing.T) {
+	assert := assert.New(t)
+
+	root := tview.NewTreeNode("image.dcm")
+	sequence := tview.NewTreeNode("\t1111 ReferencedStudySequence").SetExpanded(false)
+	root.AddChild(tview.NewTreeNode("\t0010 PatientName (PN, 8): Doe^John")).AddChild(sequence)
+	sequence.AddChild(tview.NewTreeNode("Item 1"))
+
+	assert.Equal("image.dcm\n  0010 PatientName (PN, 8): Doe^John\n  1111 ReferencedStudySequence\n    Item 1\n", formatNodeDump(root))
+}
+
+func TestTextPager(t *testing.T) {
+	assert := assert.New(t)
+
+	var shown string
+	pager := textPager{
+		mask: func(text string) string { return strings.ReplaceAll(text, "Doe^John", redactionMask) },
+		show: func(text string) { shown = text },
+	}
+	pager.page("PatientName Doe^John")
+	assert.Equal("PatientName "+redactionMask, shown)
+}
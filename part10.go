@@ -0,0 +1,69 @@
+package main
+
+import (
+	"encoding/binary"
+	"errors"
+	"os"
+	"slices"
+)
+
+// Writing of part 10 files (PS3.10), e.g. of datasets received by the storage SCP.
+
+const (
+	implementationClassUID = "2.25.196213418374632125591036406539862458193"
+	implementationVersion  = "DCMTAGGER"
+)
+
+// appendExplicitElement appends an element in explicit VR little endian encoding, values are padded to even length
+func appendExplicitElement(buf []byte, element uint16, vr string, value []byte) []byte {
+	if len(value)%2 == 1 {
+		padding := byte(' ')
+		if vr == "UI" || vr == "OB" {
+			padding = 0
+		}
+		value = append(slices.Clone(value), padding)
+	}
+	buf = binary.LittleEndian.AppendUint16(buf, 0x0002)
+	buf = binary.LittleEndian.AppendUint16(buf, element)
+	buf = append(buf, vr...)
+	if vr == "OB" {
+		buf = append(buf, 0, 0)
+		buf = binary.LittleEndian.AppendUint32(buf, uint32(len(value)))
+	} else {
+		buf = binary.LittleEndian.AppendUint16(buf, uint16(len(value)))
+	}
+	return append(buf, value...)
+}
+
+// encodeFileMeta returns the preamble, the DICM prefix and the file meta information group of a part 10 file
+func encodeFileMeta(sopClass, sopInstance, transferSyntax, sourceAET string) []byte {
+	meta := appendExplicitElement(nil, 0x0001, "OB", []byte{0, 1})
+	meta = appendExplicitElement(meta, 0x0002, "UI", []byte(sopClass))
+	meta = appendExplicitElement(meta, 0x0003, "UI", []byte(sopInstance))
+	meta = appendExplicitElement(meta, 0x0010, "UI", []byte(transferSyntax))
+	meta = appendExplicitElement(meta, 0x0012, "UI", []byte(implementationClassUID))
+	meta = appendExplicitElement(meta, 0x0013, "SH", []byte(implementationVersion))
+	meta = appendExplicitElement(meta, 0x0016, "AE", []byte(sourceAET))
+
+	header := append(make([]byte, 128), "DICM"...)
+	header = appendExplicitElement(header, 0x0000, "UL", binary.LittleEndian.AppendUint32(nil, uint32(len(meta))))
+	return append(header, meta...)
+}
+
+// writeReceivedFile writes the dataset received in the given transfer syntax as part 10 file
+func writeReceivedFile(path, sopClass, sopInstance, transferSyntax, sourceAET string, dataset []byte) error {
+	if sopInstance == "" || transferSyntax == "" {
+		return errors.New("missing SOP instance UID or transfer syntax")
+	}
+	file, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	for _, data := range [][]byte{encodeFileMeta(sopClass, sopInstance, transferSyntax, sourceAET), dataset} {
+		if _, err := file.Write(data); err != nil {
+			file.Close()
+			return err
+		}
+	}
+	return file.Close()
+}
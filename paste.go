@@ -0,0 +1,68 @@
+package main
+
+import (
+	"strings"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/rivo/tview"
+)
+
+// pasteScreen enables bracketed paste on the terminal and collects the key events between the paste start and end
+// markers, so pasted text isn't interpreted as key presses. The collected text is handed to the paste function, line
+// breaks replaced by spaces as all input fields are single line.
+type pasteScreen struct {
+	tcell.Screen
+	paste   func(text string)
+	pasting bool
+	text    strings.Builder
+}
+
+func newPasteScreen(paste func(text string)) (*pasteScreen, error) {
+	screen, err := tcell.NewScreen()
+	if err != nil {
+		return nil, err
+	}
+	return &pasteScreen{Screen: screen, paste: paste}, nil
+}
+
+func (s *pasteScreen) Init() error {
+	if err := s.Screen.Init(); err != nil {
+		return err
+	}
+	s.EnablePaste()
+	return nil
+}
+
+func (s *pasteScreen) PollEvent() tcell.Event {
+	for {
+		event := s.Screen.PollEvent()
+		switch event := event.(type) {
+		case *tcell.EventPaste:
+			s.pasting = event.Start()
+			if event.End() {
+				s.paste(s.text.String())
+				s.text.Reset()
+			}
+			continue
+		case *tcell.EventKey:
+			if s.pasting {
+				switch event.Key() {
+				case tcell.KeyRune:
+					s.text.WriteRune(event.Rune())
+				case tcell.KeyEnter, tcell.KeyLF, tcell.KeyTab:
+					s.text.WriteRune(' ')
+				}
+				continue
+			}
+		}
+		return event
+	}
+}
+
+// insertText inserts the text at the cursor position of the input field by feeding it as key events
+func insertText(field *tview.InputField, text string) {
+	handler := field.InputHandler()
+	for _, r := range text {
+		handler(tcell.NewEventKey(tcell.KeyRune, r, tcell.ModNone), func(p tview.Primitive) {})
+	}
+}
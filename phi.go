@@ -0,0 +1,106 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// textBlockSize is the edge length in pixels of the blocks checked for text
+const textBlockSize = 16
+
+// textEdgeDensity is the share of strong horizontal edges above which a block probably contains text, glyph strokes
+// produce many of them while anatomy and speckle rarely do
+const textEdgeDensity = 0.15
+
+// secondaryCaptureClass is the SOP class of secondary capture images and the prefix of the multi-frame ones, often
+// screenshots with text
+const secondaryCaptureClass = "1.2.840.10008.5.1.4.1.1.7"
+
+// countTextBlocks returns the number of blocks of the frame with a high density of strong horizontal edges, i.e. with
+// a difference of at least a quarter of the value range of the frame to their right neighbor. The first sample is
+// used of color pixels.
+func countTextBlocks(native frame.NativeFrame) int {
+	if native.Cols < 2 || len(native.Data) < native.Rows*native.Cols {
+		return 0
+	}
+	value := func(x, y int) int {
+		if pixel := native.Data[y*native.Cols+x]; len(pixel) > 0 {
+			return pixel[0]
+		}
+		return 0
+	}
+	low, high := value(0, 0), value(0, 0)
+	for y := range native.Rows {
+		for x := range native.Cols {
+			low, high = min(low, value(x, y)), max(high, value(x, y))
+		}
+	}
+	threshold := (high - low) / 4
+	if threshold == 0 {
+		return 0
+	}
+	blocks := 0
+	for top := 0; top+textBlockSize <= native.Rows; top += textBlockSize {
+		for left := 0; left+textBlockSize <= native.Cols; left += textBlockSize {
+			edges := 0
+			for y := top; y < top+textBlockSize; y++ {
+				for x := left; x < left+textBlockSize-1; x++ {
+					if difference := value(x+1, y) - value(x, y); difference >= threshold || -difference >= threshold {
+						edges++
+					}
+				}
+			}
+			if float64(edges)/float64(textBlockSize*(textBlockSize-1)) > textEdgeDensity {
+				blocks++
+			}
+		}
+	}
+	return blocks
+}
+
+// checkBurnedInPHI returns the reasons why the image probably shows patient data in its pixels and needs visual
+// inspection before sharing, none if it looks safe. The text detection only covers native ultrasound pixel data, pixel
+// data not read yet is only read if readPixels is set.
+func checkBurnedInPHI(dataset dicom.Dataset, readPixels bool) []string {
+	reasons := make([]string, 0)
+	if getDatasetValue(dataset, tag.BurnedInAnnotation) == "YES" {
+		reasons = append(reasons, "BurnedInAnnotation is YES")
+	}
+	if sopClass := getDatasetValue(dataset, tag.SOPClassUID); sopClass == secondaryCaptureClass ||
+		strings.HasPrefix(sopClass, secondaryCaptureClass+".") {
+		reasons = append(reasons, "secondary capture")
+	}
+	if getDatasetValue(dataset, tag.Modality) != "US" || (!readPixels && hasLazyValues(dataset)) {
+		return reasons
+	}
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	if err != nil || loadLazyValue(e) != nil {
+		return reasons
+	}
+	if info, ok := e.Value.GetValue().(dicom.PixelDataInfo); ok && !info.IsEncapsulated && len(info.Frames) > 0 {
+		if blocks := countTextBlocks(info.Frames[0].NativeData); blocks > 0 {
+			reasons = append(reasons, fmt.Sprintf("ultrasound with text-like edges in %d blocks", blocks))
+		}
+	}
+	return reasons
+}
+
+// formatBurnedInPHI lists the files needing visual inspection for burned-in patient data with the reasons
+func formatBurnedInPHI(datasetsWithFilename []DatasetEntry) string {
+	lines := make([]string, 0)
+	for _, entry := range datasetsWithFilename {
+		if reasons := checkBurnedInPHI(entry.dataset, true); len(reasons) > 0 {
+			lines = append(lines, fmt.Sprintf("%s: %s", entry.path, strings.Join(reasons, ", ")))
+		}
+	}
+	summary := fmt.Sprintf("%d of %d files need visual inspection for burned-in patient data before sharing",
+		len(lines), len(datasetsWithFilename))
+	if len(lines) == 0 {
+		return summary
+	}
+	return summary + "\n\n" + strings.Join(lines, "\n")
+}
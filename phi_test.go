@@ -0,0 +1,58 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// newUltrasoundFrame returns a 32x32 frame of smooth gray, with white vertical strokes like glyphs in the top left
+// block if withText is set
+func newUltrasoundFrame(withText bool) frame.NativeFrame {
+	data := make([][]int, 0, 32*32)
+	for y := range 32 {
+		for x := range 32 {
+			value := 64 + x + y
+			if withText && y < 16 && x < 16 && x%3 == 0 {
+				value = 255
+			}
+			data = append(data, []int{value})
+		}
+	}
+	return frame.NativeFrame{Data: data, Rows: 32, Cols: 32, BitsPerSample: 8}
+}
+
+func TestCountTextBlocks(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal(1, countTextBlocks(newUltrasoundFrame(true)))
+	assert.Zero(countTextBlocks(newUltrasoundFrame(false)))
+	assert.Zero(countTextBlocks(frame.NativeFrame{}))
+}
+
+func TestCheckBurnedInPHI(t *testing.T) {
+	assert := assert.New(t)
+
+	ultrasound := func(withText bool) DatasetEntry {
+		return newFixture(t).
+			with(tag.Modality, []string{"US"}).
+			with(tag.PixelData, dicom.PixelDataInfo{Frames: []frame.Frame{{NativeData: newUltrasoundFrame(withText)}}}).
+			entry("us.dcm")
+	}
+	assert.Equal([]string{"ultrasound with text-like edges in 1 blocks"}, checkBurnedInPHI(ultrasound(true).dataset, true))
+	assert.Empty(checkBurnedInPHI(ultrasound(false).dataset, true))
+
+	annotated := newInstanceFixture(t, 1).with(tag.BurnedInAnnotation, []string{"YES"}).dataset()
+	assert.Equal([]string{"BurnedInAnnotation is YES"}, checkBurnedInPHI(annotated, true))
+	screenshot := newFixture(t).with(tag.SOPClassUID, []string{"1.2.840.10008.5.1.4.1.1.7.4"}).dataset()
+	assert.Equal([]string{"secondary capture"}, checkBurnedInPHI(screenshot, true))
+	photo := newFixture(t).with(tag.SOPClassUID, []string{"1.2.840.10008.5.1.4.1.1.77.1.4"}).dataset()
+	assert.Empty(checkBurnedInPHI(photo, true))
+
+	datasets := []DatasetEntry{ultrasound(true), newInstanceFixture(t, 1).entry("ct.dcm")}
+	assert.Equal("1 of 2 files need visual inspection for burned-in patient data before sharing\n\n"+
+		"us.dcm: ultrasound with text-like edges in 1 blocks", formatBurnedInPHI(datasets))
+}
@@ -0,0 +1,52 @@
+package main
+
+import (
+	"fmt"
+	"strings"
+)
+
+// personNameGroups and personNameParts are the names of the component groups and their components of PN values
+var (
+	personNameGroups = []string{"Alphabetic", "Ideographic", "Phonetic"}
+	personNameParts  = []string{"Family", "Given", "Middle", "Prefix", "Suffix"}
+)
+
+// personName are the components of a PN value by component group, see PS3.5 6.2.1
+type personName [3][5]string
+
+// parsePersonName splits the value into component groups separated by '=' and their components separated by '^'
+func parsePersonName(value string) personName {
+	var name personName
+	for i, group := range strings.SplitN(strings.TrimRight(value, " "), "=", len(name)) {
+		for j, part := range strings.SplitN(group, "^", len(name[i])) {
+			name[i][j] = part
+		}
+	}
+	return name
+}
+
+// String joins the components, trailing empty components and groups are left out
+func (n personName) String() string {
+	groups := make([]string, len(n))
+	for i, group := range n {
+		groups[i] = strings.TrimRight(strings.Join(group[:], "^"), "^")
+	}
+	return strings.TrimRight(strings.Join(groups, "="), "=")
+}
+
+// details lists the non-empty components per component group, e.g. "Alphabetic: Family Yamada, Given Tarou"
+func (n personName) details() []string {
+	lines := make([]string, 0)
+	for i, group := range n {
+		parts := make([]string, 0)
+		for j, part := range group {
+			if part != "" {
+				parts = append(parts, fmt.Sprintf("%s %s", personNameParts[j], part))
+			}
+		}
+		if len(parts) > 0 {
+			lines = append(lines, fmt.Sprintf("%s: %s", personNameGroups[i], strings.Join(parts, ", ")))
+		}
+	}
+	return lines
+}
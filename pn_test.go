@@ -0,0 +1,39 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestPersonName(t *testing.T) {
+	assert := assert.New(t)
+
+	name := parsePersonName("Yamada^Tarou=山田^太郎=やまだ^たろう")
+	assert.Equal("Yamada", name[0][0])
+	assert.Equal("太郎", name[1][1])
+	assert.Equal("やまだ", name[2][0])
+	assert.Equal([]string{
+		"Alphabetic: Family Yamada, Given Tarou",
+		"Ideographic: Family 山田, Given 太郎",
+		"Phonetic: Family やまだ, Given たろう",
+	}, name.details())
+	assert.Equal("Yamada^Tarou=山田^太郎=やまだ^たろう", name.String())
+
+	name = parsePersonName("Doe^John^^Dr ")
+	assert.Equal([]string{"Alphabetic: Family Doe, Given John, Prefix Dr"}, name.details())
+	name[0][4] = "Jr"
+	assert.Equal("Doe^John^^Dr^Jr", name.String())
+	name[1][0] = "ドウ"
+	assert.Equal("Doe^John^^Dr^Jr=ドウ", name.String())
+	assert.Equal("", parsePersonName("").String())
+	assert.Equal("=^Given", personName{{}, {"", "Given"}}.String())
+}
+
+func TestPersonNameDetails(t *testing.T) {
+	assert := assert.New(t)
+
+	e := newFixture(t).element(tag.PatientName, []string{"Doe^John"})
+	assert.Contains(getElementDetails(e), "Components:  Alphabetic: Family Doe, Given John")
+}
@@ -0,0 +1,114 @@
+package main
+
+import (
+	"cmp"
+	"fmt"
+	"slices"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// redactionMask replaces identifying values in privacy mode
+const redactionMask = "███"
+
+// defaultRedactedTags are masked in privacy mode if the config names none
+var defaultRedactedTags = []string{
+	"PatientName", "PatientID", "OtherPatientIDsSequence", "OtherPatientNames", "PatientBirthDate", "PatientAddress",
+	"IssuerOfPatientID", "AccessionNumber", "StudyID", "StudyDate", "SeriesDate", "AcquisitionDate", "ContentDate",
+	"AcquisitionDateTime", "ReferringPhysicianName", "PerformingPhysicianName", "InstitutionName",
+}
+
+// redaction masks the values of identifying elements in the texts shown by the UI, e.g. for screen sharing. The
+// datasets aren't changed.
+type redaction struct {
+	tags      map[tag.Tag]bool
+	values    []string                   // values of the tags in the loaded files, longest first
+	originals map[*tview.TreeNode]string // unmasked texts of the masked nodes
+}
+
+// newRedaction creates the redaction of the elements with the given keywords, of the default ones if none are given
+func newRedaction(keywords []string) (*redaction, error) {
+	r := &redaction{tags: map[tag.Tag]bool{}, originals: map[*tview.TreeNode]string{}}
+	if len(keywords) == 0 {
+		keywords = defaultRedactedTags
+	}
+	for _, keyword := range keywords {
+		info, err := findTagByName(keyword)
+		if err != nil {
+			return nil, fmt.Errorf("unknown keyword '%s' in privacy", keyword)
+		}
+		r.tags[info.Tag] = true
+	}
+	return r, nil
+}
+
+// collect gathers the values to mask from the elements of the datasets, also in sequences. Single characters, like
+// the patient's sex, would mask too much.
+func (r *redaction) collect(datasetsWithFilename []DatasetEntry) {
+	found := map[string]bool{}
+	var add func(elements []*dicom.Element)
+	add = func(elements []*dicom.Element) {
+		for _, e := range elements {
+			if isSequence(e) {
+				for _, item := range getSequenceItems(e) {
+					add(item)
+				}
+				continue
+			}
+			if !r.tags[e.Tag] {
+				continue
+			}
+			values := append([]string{getValueString(e)}, getValueStrings(e)...)
+			if text, ok := formatDateTimes(e); ok {
+				values = append(values, text)
+			}
+			for _, value := range values {
+				if value = strings.TrimSpace(value); len(value) > 1 {
+					found[value] = true
+				}
+			}
+		}
+	}
+	for _, entry := range datasetsWithFilename {
+		add(entry.dataset.Elements)
+	}
+	r.values = r.values[:0]
+	for value := range found {
+		r.values = append(r.values, value)
+	}
+	// longest first, so values containing others are masked whole
+	slices.SortFunc(r.values, func(a, b string) int {
+		return cmp.Or(cmp.Compare(len(b), len(a)), strings.Compare(a, b))
+	})
+}
+
+// mask replaces all identifying values in the text
+func (r *redaction) mask(text string) string {
+	for _, value := range r.values {
+		text = strings.ReplaceAll(text, value, redactionMask)
+	}
+	return text
+}
+
+// maskNodes masks the identifying values in the texts of all nodes below the root
+func (r *redaction) maskNodes(root *tview.TreeNode) {
+	clear(r.originals)
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if masked := r.mask(node.GetText()); masked != node.GetText() {
+			r.originals[node] = node.GetText()
+			node.SetText(masked)
+		}
+		return true
+	})
+}
+
+// unmaskNodes restores the texts of the nodes masked last, so the tree state can be saved with them
+func (r *redaction) unmaskNodes() {
+	for node, text := range r.originals {
+		node.SetText(text)
+	}
+	clear(r.originals)
+}
@@ -0,0 +1,44 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestRedaction(t *testing.T) {
+	assert := assert.New(t)
+
+	_, err := newRedaction([]string{"PatientName", "NoSuchKeyword"})
+	assert.Error(err)
+	r, err := newRedaction(nil)
+	assert.NoError(err)
+
+	f := newInstanceFixture(t, 1).with(tag.PatientSex, []string{"M"})
+	f.withSequence(tag.ReferencedStudySequence, []*dicom.Element{f.element(tag.AccessionNumber, []string{"ACC42"})})
+	entry := f.entry("image.dcm")
+	r.collect([]DatasetEntry{entry})
+	assert.Equal("███ (███), ███, CT, M", r.mask("Doe^John (12345), 20210304, CT, M"))
+	assert.Equal("Study ███ (███)", r.mask("Study 20210304 (2021-03-04)"))
+
+	root := tview.NewTreeNode("root")
+	node := tview.NewTreeNode(getElementText(f.element(tag.PatientName, []string{"Doe^John"})))
+	modality := tview.NewTreeNode(getElementText(f.element(tag.Modality, []string{"CT"})))
+	root.AddChild(node).AddChild(modality)
+	text := node.GetText()
+	r.maskNodes(root)
+	assert.NotContains(node.GetText(), "Doe^John")
+	assert.Contains(node.GetText(), "███")
+	assert.Contains(modality.GetText(), ": CT")
+	r.unmaskNodes()
+	assert.Equal(text, node.GetText())
+
+	// values of other elements stay
+	r, err = newRedaction([]string{"AccessionNumber"})
+	assert.NoError(err)
+	r.collect([]DatasetEntry{entry})
+	assert.Equal("███ Doe^John", r.mask("ACC42 Doe^John"))
+}
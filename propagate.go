@@ -0,0 +1,47 @@
+package main
+
+import (
+	"errors"
+	"slices"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// setElement replaces the element with the same tag in the dataset or inserts it in tag order
+func setElement(dataset *dicom.Dataset, e *dicom.Element) {
+	index, found := slices.BinarySearchFunc(dataset.Elements, e, func(a, b *dicom.Element) int {
+		return compareTags(a.Tag, b.Tag)
+	})
+	if found {
+		dataset.Elements[index] = e
+	} else {
+		dataset.Elements = slices.Insert(dataset.Elements, index, e)
+	}
+}
+
+func newQueryElement(t tag.Tag, value string) (*dicom.Element, error) {
+	return dicom.NewElement(t, []string{value})
+}
+
+// propagateElement copies the top level element, including all items of a sequence, from the file it belongs to into
+// all other files matching the query, all other files for a nil query. Returns the paths of the changed files.
+func propagateElement(datasetsWithFilename []DatasetEntry, e *dicom.Element, q *query) ([]string, error) {
+	source := slices.IndexFunc(datasetsWithFilename, func(entry DatasetEntry) bool {
+		return slices.Contains(entry.dataset.Elements, e)
+	})
+	if source < 0 {
+		return nil, errors.New("only top level elements can be propagated")
+	}
+
+	changed := make([]string, 0)
+	for i := range datasetsWithFilename {
+		target := &datasetsWithFilename[i]
+		if i == source || (q != nil && !q.expr.match(target.dataset)) {
+			continue
+		}
+		setElement(&target.dataset, copyElement(e))
+		changed = append(changed, target.path)
+	}
+	return changed, nil
+}
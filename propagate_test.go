@@ -0,0 +1,53 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestPropagateElement(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newInstanceFixture(t, 1)
+	sequence := newReferenceSequence(f, "1.2.3")
+	f.withElement(sequence)
+	datasets := []DatasetEntry{
+		f.entry("1.dcm"),
+		newFixture(t).with(tag.Modality, []string{"CT"}).with(tag.PatientID, []string{"other"}).entry("2.dcm"),
+		newFixture(t).with(tag.Modality, []string{"MR"}).entry("3.dcm"),
+	}
+
+	changed, err := propagateElement(datasets, sequence, nil)
+	assert.NoError(err)
+	assert.Equal([]string{"2.dcm", "3.dcm"}, changed)
+	for _, entry := range datasets[1:] {
+		copied, err := entry.dataset.FindElementByTag(tag.ReferencedImageSequence)
+		assert.NoError(err)
+		assert.NotSame(sequence, copied)
+		assert.Equal([]string{"1.2.3"}, itemUIDs(copied))
+	}
+	assert.Equal([]tag.Tag{tag.Modality, tag.ReferencedImageSequence}, elementTags(datasets[2].dataset), "inserted in tag order")
+
+	patientID, _ := datasets[0].dataset.FindElementByTag(tag.PatientID)
+	q, err := parseQuery(`Modality=="CT"`)
+	assert.NoError(err)
+	changed, err = propagateElement(datasets, patientID, q)
+	assert.NoError(err)
+	assert.Equal([]string{"2.dcm"}, changed)
+	assert.Equal("12345", getDatasetValue(datasets[1].dataset, tag.PatientID), "existing element replaced")
+	assert.Equal("", getDatasetValue(datasets[2].dataset, tag.PatientID))
+
+	_, err = propagateElement(datasets, getSequenceItems(sequence)[0][0], nil)
+	assert.Error(err)
+}
+
+func elementTags(dataset dicom.Dataset) []tag.Tag {
+	tags := make([]tag.Tag, 0)
+	for _, e := range dataset.Elements {
+		tags = append(tags, e.Tag)
+	}
+	return tags
+}
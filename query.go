@@ -0,0 +1,316 @@
+package main
+
+import (
+	"fmt"
+	"regexp"
+	"slices"
+	"strconv"
+	"strings"
+	"unicode"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// query selects files by an expression over their top level elements like
+//
+//	Modality=="MR" && (SliceThickness<1.0 || SeriesDescription=~"(?i)thin")
+//
+// Elements are given by keyword or as (gggg,eeee). A comparison is true if any of the values of a multi valued element
+// matches, values are compared as numbers if both sides are numbers and as text otherwise. A single element without
+// comparison checks its presence.
+type query struct {
+	text string
+	expr queryExpr
+}
+
+type queryExpr interface {
+	match(dataset dicom.Dataset) bool
+}
+
+type queryAnd struct{ left, right queryExpr }
+type queryOr struct{ left, right queryExpr }
+type queryNot struct{ expr queryExpr }
+type queryExists struct{ tag tag.Tag }
+
+type queryComparison struct {
+	tag     tag.Tag
+	op      string
+	literal string
+	number  float64
+	numeric bool           // literal is a number
+	regexp  *regexp.Regexp // for =~
+}
+
+func (q queryAnd) match(dataset dicom.Dataset) bool { return q.left.match(dataset) && q.right.match(dataset) }
+func (q queryOr) match(dataset dicom.Dataset) bool  { return q.left.match(dataset) || q.right.match(dataset) }
+func (q queryNot) match(dataset dicom.Dataset) bool { return !q.expr.match(dataset) }
+
+func (q queryExists) match(dataset dicom.Dataset) bool {
+	_, err := dataset.FindElementByTag(q.tag)
+	return err == nil
+}
+
+func (q queryComparison) match(dataset dicom.Dataset) bool {
+	e, err := dataset.FindElementByTag(q.tag)
+	if err != nil {
+		return q.op == "!="
+	}
+	op := q.op
+	if op == "!=" {
+		op = "=="
+	}
+	matched := slices.ContainsFunc(getValueStrings(e), func(value string) bool {
+		return q.matchValue(op, strings.TrimSpace(value))
+	})
+	return matched != (q.op == "!=")
+}
+
+func (q queryComparison) matchValue(op, value string) bool {
+	if op == "=~" {
+		return q.regexp.MatchString(value)
+	}
+	cmp := strings.Compare(value, q.literal)
+	if number, err := strconv.ParseFloat(value, 64); err == nil && q.numeric {
+		switch {
+		case number < q.number:
+			cmp = -1
+		case number > q.number:
+			cmp = 1
+		default:
+			cmp = 0
+		}
+	}
+	switch op {
+	case "==":
+		return cmp == 0
+	case "<":
+		return cmp < 0
+	case "<=":
+		return cmp <= 0
+	case ">":
+		return cmp > 0
+	case ">=":
+		return cmp >= 0
+	}
+	return false
+}
+
+// parseQuery parses the query expression, errors give the position of the problem
+func parseQuery(text string) (*query, error) {
+	tokens, err := tokenizeQuery(text)
+	if err != nil {
+		return nil, err
+	}
+	p := &queryParser{tokens: tokens}
+	expr, err := p.parseOr()
+	if err != nil {
+		return nil, err
+	}
+	if p.pos < len(p.tokens) {
+		return nil, fmt.Errorf("unexpected '%s' at %d", p.tokens[p.pos].text, p.tokens[p.pos].offset+1)
+	}
+	return &query{text: text, expr: expr}, nil
+}
+
+func (q *query) String() string {
+	return q.text
+}
+
+// filter returns the entries matching the query, all entries for a nil query
+func (q *query) filter(entries []DatasetEntry) []DatasetEntry {
+	if q == nil {
+		return entries
+	}
+	matching := make([]DatasetEntry, 0)
+	for _, entry := range entries {
+		if q.expr.match(entry.dataset) {
+			matching = append(matching, entry)
+		}
+	}
+	return matching
+}
+
+type queryTokenKind int
+
+const (
+	tokenIdent queryTokenKind = iota
+	tokenString
+	tokenOperator
+)
+
+type queryToken struct {
+	kind   queryTokenKind
+	text   string
+	offset int
+}
+
+var queryOperators = []string{"&&", "||", "==", "!=", "<=", ">=", "=~", "<", ">", "!", "(", ")"}
+
+func tokenizeQuery(text string) ([]queryToken, error) {
+	tokens := make([]queryToken, 0)
+	runes := []rune(text)
+	for pos := 0; pos < len(runes); {
+		r := runes[pos]
+		switch {
+		case unicode.IsSpace(r):
+			pos++
+		case r == '"':
+			end := pos + 1
+			var value strings.Builder
+			for ; end < len(runes) && runes[end] != '"'; end++ {
+				if runes[end] == '\\' && end+1 < len(runes) {
+					end++
+				}
+				value.WriteRune(runes[end])
+			}
+			if end == len(runes) {
+				return nil, fmt.Errorf("unterminated string at %d", pos+1)
+			}
+			tokens = append(tokens, queryToken{tokenString, value.String(), pos})
+			pos = end + 1
+		case r == '(' && pos+11 <= len(runes) && isTagLiteral(string(runes[pos:pos+11])):
+			tokens = append(tokens, queryToken{tokenIdent, string(runes[pos : pos+11]), pos})
+			pos += 11
+		case unicode.IsLetter(r) || unicode.IsDigit(r) || r == '.' || r == '-' || r == '_':
+			end := pos
+			for end < len(runes) && (unicode.IsLetter(runes[end]) || unicode.IsDigit(runes[end]) || strings.ContainsRune(".-_^", runes[end])) {
+				end++
+			}
+			tokens = append(tokens, queryToken{tokenIdent, string(runes[pos:end]), pos})
+			pos = end
+		default:
+			found := false
+			for _, op := range queryOperators {
+				if strings.HasPrefix(string(runes[pos:]), op) {
+					tokens = append(tokens, queryToken{tokenOperator, op, pos})
+					pos += len([]rune(op))
+					found = true
+					break
+				}
+			}
+			if !found {
+				return nil, fmt.Errorf("unexpected '%c' at %d", r, pos+1)
+			}
+		}
+	}
+	return tokens, nil
+}
+
+var tagLiteral = regexp.MustCompile(`^\([0-9A-Fa-f]{4},[0-9A-Fa-f]{4}\)$`)
+
+func isTagLiteral(text string) bool {
+	return tagLiteral.MatchString(text)
+}
+
+// queryParser is a recursive descent parser, && binds stronger than ||
+type queryParser struct {
+	tokens []queryToken
+	pos    int
+}
+
+func (p *queryParser) peekOperator(op string) bool {
+	return p.pos < len(p.tokens) && p.tokens[p.pos].kind == tokenOperator && p.tokens[p.pos].text == op
+}
+
+func (p *queryParser) next() (queryToken, error) {
+	if p.pos == len(p.tokens) {
+		return queryToken{}, fmt.Errorf("unexpected end of query")
+	}
+	p.pos++
+	return p.tokens[p.pos-1], nil
+}
+
+func (p *queryParser) parseOr() (queryExpr, error) {
+	left, err := p.parseAnd()
+	for err == nil && p.peekOperator("||") {
+		p.pos++
+		var right queryExpr
+		if right, err = p.parseAnd(); err == nil {
+			left = queryOr{left, right}
+		}
+	}
+	return left, err
+}
+
+func (p *queryParser) parseAnd() (queryExpr, error) {
+	left, err := p.parseUnary()
+	for err == nil && p.peekOperator("&&") {
+		p.pos++
+		var right queryExpr
+		if right, err = p.parseUnary(); err == nil {
+			left = queryAnd{left, right}
+		}
+	}
+	return left, err
+}
+
+func (p *queryParser) parseUnary() (queryExpr, error) {
+	if p.peekOperator("!") {
+		p.pos++
+		expr, err := p.parseUnary()
+		return queryNot{expr}, err
+	}
+	if p.peekOperator("(") {
+		p.pos++
+		expr, err := p.parseOr()
+		if err != nil {
+			return nil, err
+		}
+		if !p.peekOperator(")") {
+			return nil, fmt.Errorf("missing ')'")
+		}
+		p.pos++
+		return expr, nil
+	}
+	return p.parseComparison()
+}
+
+func (p *queryParser) parseComparison() (queryExpr, error) {
+	token, err := p.next()
+	if err != nil {
+		return nil, err
+	}
+	if token.kind != tokenIdent {
+		return nil, fmt.Errorf("expected element at %d", token.offset+1)
+	}
+	t, err := parseQueryTag(token.text)
+	if err != nil {
+		return nil, fmt.Errorf("%w at %d", err, token.offset+1)
+	}
+
+	if p.pos == len(p.tokens) || p.tokens[p.pos].kind != tokenOperator || !strings.ContainsAny(p.tokens[p.pos].text, "=<>") {
+		return queryExists{t}, nil
+	}
+	op, _ := p.next()
+	literal, err := p.next()
+	if err != nil {
+		return nil, err
+	}
+	if literal.kind == tokenOperator {
+		return nil, fmt.Errorf("expected value at %d", literal.offset+1)
+	}
+	comparison := queryComparison{tag: t, op: op.text, literal: literal.text}
+	if op.text == "=~" {
+		if comparison.regexp, err = regexp.Compile(literal.text); err != nil {
+			return nil, fmt.Errorf("invalid regular expression at %d: %w", literal.offset+1, err)
+		}
+	} else if number, err := strconv.ParseFloat(literal.text, 64); err == nil {
+		comparison.number, comparison.numeric = number, true
+	}
+	return comparison, nil
+}
+
+// parseQueryTag returns the tag of a keyword like PatientName or of (gggg,eeee)
+func parseQueryTag(text string) (tag.Tag, error) {
+	if isTagLiteral(text) {
+		group, _ := strconv.ParseUint(text[1:5], 16, 16)
+		element, _ := strconv.ParseUint(text[6:10], 16, 16)
+		return tag.Tag{Group: uint16(group), Element: uint16(element)}, nil
+	}
+	info, err := findTagByName(text)
+	if err != nil {
+		return tag.Tag{}, fmt.Errorf("unknown element '%s'", text)
+	}
+	return info.Tag, nil
+}
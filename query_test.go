@@ -0,0 +1,53 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestQuery(t *testing.T) {
+	assert := assert.New(t)
+
+	entries := []DatasetEntry{
+		newInstanceFixture(t, 1).entry("ct.dcm"),
+		newFixture(t).
+			with(tag.Modality, []string{"MR"}).
+			with(tag.SliceThickness, []string{"0.8"}).
+			with(tag.SeriesDescription, []string{"T1 Thin"}).
+			with(tag.ImageType, []string{"ORIGINAL", "PRIMARY"}).
+			entry("mr1.dcm"),
+		newFixture(t).
+			with(tag.Modality, []string{"MR"}).
+			with(tag.SliceThickness, []string{"3"}).
+			entry("mr2.dcm"),
+	}
+	filenames := func(text string) []string {
+		q, err := parseQuery(text)
+		if !assert.NoError(err, text) {
+			return nil
+		}
+		names := make([]string, 0)
+		for _, entry := range q.filter(entries) {
+			names = append(names, entry.filename)
+		}
+		return names
+	}
+
+	assert.Equal([]string{"mr1.dcm"}, filenames(`Modality=="MR" && SliceThickness<1.0`))
+	assert.Equal([]string{"mr2.dcm"}, filenames(`Modality==MR && SliceThickness>=3`))
+	assert.Equal([]string{"ct.dcm", "mr2.dcm"}, filenames(`SliceThickness>1`), "numeric, not text comparison")
+	assert.Equal([]string{"ct.dcm", "mr2.dcm"}, filenames(`!(SeriesDescription=~"(?i)thin")`))
+	assert.Equal([]string{"ct.dcm", "mr1.dcm"}, filenames(`PatientName || ImageType=="PRIMARY"`))
+	assert.Equal([]string{"mr1.dcm", "mr2.dcm"}, filenames(`(0008,0060)!="CT"`))
+	assert.Equal([]string{"ct.dcm"}, filenames(`Modality=="CT" || Modality=="MR" && SliceThickness>5`), "&& binds stronger")
+
+	for _, invalid := range []string{`Modality==`, `Unknown=="x"`, `Modality=="MR`, `(Modality`, `Modality=~"("`, `"MR"==Modality`, `Modality MR`, `Modality # 1`} {
+		_, err := parseQuery(invalid)
+		assert.Error(err, invalid)
+	}
+
+	var q *query
+	assert.Len(q.filter(entries), 3, "a nil query matches everything")
+}
@@ -0,0 +1,120 @@
+package main
+
+import (
+	"encoding/binary"
+	"fmt"
+	"math"
+	"os"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+)
+
+// getRawValue returns the value bytes of an element as they are encoded in an explicit VR little endian file, without
+// padding. Multiple string values are separated by '\'.
+func getRawValue(e *dicom.Element) ([]byte, error) {
+	switch e.Value.ValueType() {
+	case dicom.Bytes:
+		return e.Value.GetValue().([]byte), nil
+	case dicom.Strings:
+		return []byte(strings.Join(e.Value.GetValue().([]string), `\`)), nil
+	case dicom.Ints:
+		data := make([]byte, 0)
+		for _, v := range e.Value.GetValue().([]int) {
+			switch e.RawValueRepresentation {
+			case "US", "SS":
+				data = binary.LittleEndian.AppendUint16(data, uint16(v))
+			case "UL", "SL", "AT":
+				data = binary.LittleEndian.AppendUint32(data, uint32(v))
+			default:
+				return nil, fmt.Errorf("unsupported VR %s for integer values", e.RawValueRepresentation)
+			}
+		}
+		return data, nil
+	case dicom.Floats:
+		data := make([]byte, 0)
+		for _, v := range e.Value.GetValue().([]float64) {
+			switch e.RawValueRepresentation {
+			case "FL", "OF":
+				data = binary.LittleEndian.AppendUint32(data, math.Float32bits(float32(v)))
+			case "FD", "OD":
+				data = binary.LittleEndian.AppendUint64(data, math.Float64bits(v))
+			default:
+				return nil, fmt.Errorf("unsupported VR %s for float values", e.RawValueRepresentation)
+			}
+		}
+		return data, nil
+	}
+	return nil, fmt.Errorf("values of VR %s can't be exported", e.RawValueRepresentation)
+}
+
+// setRawValue replaces the value of an element by the given bytes, interpreted like written by getRawValue
+func setRawValue(e *dicom.Element, data []byte) error {
+	var value dicom.Value
+	var err error
+	switch e.Value.ValueType() {
+	case dicom.Bytes:
+		value, err = dicom.NewValue(data)
+	case dicom.Strings:
+		value, err = dicom.NewValue(strings.Split(string(data), `\`))
+	case dicom.Ints:
+		size := map[string]int{"US": 2, "SS": 2, "UL": 4, "AT": 4, "SL": 4}[e.RawValueRepresentation]
+		if size == 0 || len(data)%size != 0 {
+			return fmt.Errorf("%d bytes are no valid %s value", len(data), e.RawValueRepresentation)
+		}
+		ints := make([]int, 0)
+		for i := 0; i < len(data); i += size {
+			switch e.RawValueRepresentation {
+			case "US":
+				ints = append(ints, int(binary.LittleEndian.Uint16(data[i:])))
+			case "SS":
+				ints = append(ints, int(int16(binary.LittleEndian.Uint16(data[i:]))))
+			case "UL", "AT":
+				ints = append(ints, int(binary.LittleEndian.Uint32(data[i:])))
+			case "SL":
+				ints = append(ints, int(int32(binary.LittleEndian.Uint32(data[i:]))))
+			}
+		}
+		value, err = dicom.NewValue(ints)
+	case dicom.Floats:
+		size := map[string]int{"FL": 4, "OF": 4, "FD": 8, "OD": 8}[e.RawValueRepresentation]
+		if size == 0 || len(data)%size != 0 {
+			return fmt.Errorf("%d bytes are no valid %s value", len(data), e.RawValueRepresentation)
+		}
+		floats := make([]float64, 0)
+		for i := 0; i < len(data); i += size {
+			if size == 4 {
+				floats = append(floats, float64(math.Float32frombits(binary.LittleEndian.Uint32(data[i:]))))
+			} else {
+				floats = append(floats, math.Float64frombits(binary.LittleEndian.Uint64(data[i:])))
+			}
+		}
+		value, err = dicom.NewValue(floats)
+	default:
+		return fmt.Errorf("values of VR %s can't be imported", e.RawValueRepresentation)
+	}
+	if err != nil {
+		return err
+	}
+	e.Value = value
+	e.ValueLength = uint32(len(data) + len(data)%2) // values are padded to even length
+	return nil
+}
+
+// exportElementValue writes the raw value of the element to the given file
+func exportElementValue(e *dicom.Element, filename string) error {
+	data, err := getRawValue(e)
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(filename, data, 0o644)
+}
+
+// importElementValue replaces the value of the element by the content of the given file
+func importElementValue(e *dicom.Element, filename string) error {
+	data, err := os.ReadFile(filename)
+	if err != nil {
+		return err
+	}
+	return setRawValue(e, data)
+}
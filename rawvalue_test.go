@@ -0,0 +1,39 @@
+package main
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestRawValueRoundTrip(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newFixture(t)
+	elements := []struct {
+		e    *dicom.Element
+		want []byte
+	}{
+		{f.element(tag.PixelSpacing, []string{"0.5", "0.5"}), []byte(`0.5\0.5`)},
+		{f.element(tag.Rows, []int{512}), []byte{0x00, 0x02}},
+		{newBinaryElement(t, 0x0029, 0x1010, 3), []byte{0, 0, 0}},
+	}
+	for _, test := range elements {
+		data, err := getRawValue(test.e)
+		assert.NoError(err)
+		assert.Equal(test.want, data)
+	}
+
+	filename := filepath.Join(t.TempDir(), "value.bin")
+	rows := f.element(tag.Rows, []int{512})
+	assert.NoError(exportElementValue(rows, filename))
+	columns := f.element(tag.Columns, []int{256})
+	assert.NoError(importElementValue(columns, filename))
+	assert.Equal([]int{512}, columns.Value.GetValue())
+	assert.Equal(uint32(2), columns.ValueLength)
+
+	assert.Error(setRawValue(columns, []byte{1, 2, 3}))
+}
@@ -0,0 +1,134 @@
+package main
+
+import (
+	"bytes"
+	"cmp"
+	"context"
+	"fmt"
+	"html/template"
+	"io"
+	"os"
+	"slices"
+	"strings"
+
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// comparisonRow is one tag of the comparison report with its value per file
+type comparisonRow struct {
+	Tag     string
+	Name    string
+	VR      string
+	Values  []string // per file in the order of the report files, multiple values joined by '\'
+	Present []bool   // per file whether it contains the tag
+	Differs bool
+}
+
+type comparisonReport struct {
+	Title string
+	Files []string
+	Rows  []comparisonRow
+}
+
+// newComparisonReport collects the top level elements of all datasets by tag, sorted by tag, with the values per file
+func newComparisonReport(title string, datasetsWithFilename []DatasetEntry) comparisonReport {
+	report, _ := compareDatasets(context.Background(), title, datasetsWithFilename, nil)
+	return report
+}
+
+// compareDatasets is newComparisonReport checking for cancellation and reporting its progress per file
+func compareDatasets(ctx context.Context, title string, datasetsWithFilename []DatasetEntry, progress func(done, total int)) (comparisonReport, error) {
+	report := comparisonReport{Title: title}
+	rowsByTag := make(map[tag.Tag]*comparisonRow)
+	tags := make([]tag.Tag, 0)
+	for i, entry := range datasetsWithFilename {
+		if err := ctx.Err(); err != nil {
+			return comparisonReport{}, err
+		}
+		if progress != nil {
+			progress(i, len(datasetsWithFilename))
+		}
+		report.Files = append(report.Files, entry.filename)
+		for _, e := range entry.dataset.Elements {
+			row, ok := rowsByTag[e.Tag]
+			if !ok {
+				row = &comparisonRow{
+					Tag:     fmt.Sprintf("(%04x,%04x)", e.Tag.Group, e.Tag.Element),
+					Name:    getTagName(e),
+					VR:      e.RawValueRepresentation,
+					Values:  make([]string, len(datasetsWithFilename)),
+					Present: make([]bool, len(datasetsWithFilename)),
+				}
+				rowsByTag[e.Tag] = row
+				tags = append(tags, e.Tag)
+			}
+			row.Values[i] = strings.Join(getValueStrings(e), `\`)
+			row.Present[i] = true
+		}
+	}
+
+	slices.SortFunc(tags, func(a, b tag.Tag) int {
+		return cmp.Or(cmp.Compare(a.Group, b.Group), cmp.Compare(a.Element, b.Element))
+	})
+	for _, t := range tags {
+		row := rowsByTag[t]
+		for i := range row.Values {
+			if row.Values[i] != row.Values[0] || row.Present[i] != row.Present[0] {
+				row.Differs = true
+			}
+		}
+		report.Rows = append(report.Rows, *row)
+	}
+	return report, nil
+}
+
+// differs returns true if any tag differs between the files
+func (r comparisonReport) differs() bool {
+	return slices.ContainsFunc(r.Rows, func(row comparisonRow) bool { return row.Differs })
+}
+
+var htmlReportTemplate = template.Must(template.New("report").Parse(`<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{{.Title}}</title>
+<style>
+body { font-family: sans-serif; font-size: 13px; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 2px 6px; text-align: left; vertical-align: top; }
+th { background: #eee; position: sticky; top: 0; }
+td.tag { font-family: monospace; white-space: nowrap; }
+tr.differs td { background: #fff3c4; }
+td.missing { color: #999; font-style: italic; }
+</style>
+</head>
+<body>
+<h1>{{.Title}}</h1>
+<p>{{len .Files}} files, {{len .Rows}} tags, differing values are highlighted.</p>
+<table>
+<tr><th>Tag</th><th>Name</th><th>VR</th>{{range .Files}}<th>{{.}}</th>{{end}}</tr>
+{{range .Rows}}{{$row := .}}<tr{{if .Differs}} class="differs"{{end}}><td class="tag">{{.Tag}}</td><td>{{.Name}}</td><td>{{.VR}}</td>
+{{- range $i, $value := .Values}}{{if index $row.Present $i}}<td>{{$value}}</td>{{else}}<td class="missing">missing</td>{{end}}{{end}}</tr>
+{{end}}</table>
+</body>
+</html>
+`))
+
+// writeHTMLReport writes the comparison of all datasets as standalone HTML page
+func writeHTMLReport(w io.Writer, title string, datasetsWithFilename []DatasetEntry) error {
+	return htmlReportTemplate.Execute(w, newComparisonReport(title, datasetsWithFilename))
+}
+
+// writeHTMLReportToFile writes the report only after all datasets were compared, so a cancelled report doesn't leave
+// a partial file behind
+func writeHTMLReportToFile(ctx context.Context, filename string, title string, datasetsWithFilename []DatasetEntry, progress func(done, total int)) error {
+	report, err := compareDatasets(ctx, title, datasetsWithFilename, progress)
+	if err != nil {
+		return err
+	}
+	var html bytes.Buffer
+	if err := htmlReportTemplate.Execute(&html, report); err != nil {
+		return err
+	}
+	return os.WriteFile(filename, html.Bytes(), 0o644)
+}
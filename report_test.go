@@ -0,0 +1,49 @@
+package main
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestNewComparisonReport(t *testing.T) {
+	assert := assert.New(t)
+
+	entries := []DatasetEntry{
+		newFixture(t).with(tag.PatientName, []string{"Doe^John"}).with(tag.Modality, []string{"CT"}).entry("1.dcm"),
+		newFixture(t).with(tag.Modality, []string{"MR"}).with(tag.PatientName, []string{"Doe^John"}).entry("2.dcm"),
+		newFixture(t).with(tag.PatientName, []string{"Doe^John"}).entry("3.dcm"),
+	}
+	report := newComparisonReport("test", entries)
+
+	assert.Equal([]string{"1.dcm", "2.dcm", "3.dcm"}, report.Files)
+	assert.Len(report.Rows, 2)
+	assert.Equal("(0008,0060)", report.Rows[0].Tag)
+	assert.Equal([]string{"CT", "MR", ""}, report.Rows[0].Values)
+	assert.Equal([]bool{true, true, false}, report.Rows[0].Present)
+	assert.True(report.Rows[0].Differs)
+	assert.Equal("PatientName", report.Rows[1].Name)
+	assert.False(report.Rows[1].Differs)
+	assert.True(report.differs())
+	assert.False(newComparisonReport("test", entries[:1]).differs())
+
+	var html strings.Builder
+	assert.NoError(writeHTMLReport(&html, "<test>", entries))
+	assert.Contains(html.String(), "&lt;test&gt;")
+	assert.Contains(html.String(), `<tr class="differs"><td class="tag">(0008,0060)</td>`)
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	filename := filepath.Join(t.TempDir(), "report.html")
+	assert.ErrorIs(writeHTMLReportToFile(ctx, filename, "test", entries, nil), context.Canceled)
+	assert.NoFileExists(filename)
+	assert.NoError(writeHTMLReportToFile(context.Background(), filename, "test", entries, nil))
+	written, err := os.ReadFile(filename)
+	assert.NoError(err)
+	assert.Contains(string(written), `<tr class="differs"><td class="tag">(0008,0060)</td>`)
+}
@@ -0,0 +1,68 @@
+package main
+
+import (
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/gdamore/tcell/v2"
+)
+
+// sgrAttributes are the SGR parameters of the text attributes
+var sgrAttributes = []struct {
+	attribute tcell.AttrMask
+	parameter string
+}{
+	{tcell.AttrBold, "1"}, {tcell.AttrDim, "2"}, {tcell.AttrItalic, "3"}, {tcell.AttrUnderline, "4"},
+	{tcell.AttrBlink, "5"}, {tcell.AttrReverse, "7"}, {tcell.AttrStrikeThrough, "9"},
+}
+
+// sgrColor returns the SGR parameters of a foreground (base 30) or background (base 40) color as 24 bit color
+func sgrColor(c tcell.Color, base int) string {
+	if !c.Valid() {
+		return strconv.Itoa(base + 9) // default color
+	}
+	r, g, b := c.RGB()
+	return fmt.Sprintf("%d;2;%d;%d;%d", base+8, r, g, b)
+}
+
+// sgrSequence returns the escape sequence switching to the style, resetting the previous one first
+func sgrSequence(style tcell.Style) string {
+	fg, bg, attributes := style.Decompose()
+	parameters := []string{"0", sgrColor(fg, 30), sgrColor(bg, 40)}
+	for _, a := range sgrAttributes {
+		if attributes&a.attribute != 0 {
+			parameters = append(parameters, a.parameter)
+		}
+	}
+	return "\x1b[" + strings.Join(parameters, ";") + "m"
+}
+
+// formatScreen returns the text of the rows of the screen region, plain without trailing spaces or with the colors
+// and attributes as ANSI escape sequences, e.g. to share a view with `cat` in a terminal. Wide characters are written
+// once.
+func formatScreen(screen tcell.Screen, x, y, width, height int, ansi bool) string {
+	rows := make([]string, 0, height)
+	for row := y; row < y+height; row++ {
+		var b strings.Builder
+		var previous tcell.Style
+		for col := x; col < x+width; {
+			mainc, combc, style, cellWidth := screen.GetContent(col, row)
+			if ansi && (col == x || style != previous) {
+				b.WriteString(sgrSequence(style))
+				previous = style
+			}
+			b.WriteRune(mainc)
+			for _, c := range combc {
+				b.WriteRune(c)
+			}
+			col += max(cellWidth, 1)
+		}
+		if ansi {
+			rows = append(rows, b.String()+"\x1b[0m")
+		} else {
+			rows = append(rows, strings.TrimRight(b.String(), " "))
+		}
+	}
+	return strings.Join(rows, "\n") + "\n"
+}
@@ -0,0 +1,28 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestFormatScreen(t *testing.T) {
+	assert := assert.New(t)
+
+	screen := tcell.NewSimulationScreen("UTF-8")
+	if err := screen.Init(); err != nil {
+		t.Fatal(err)
+	}
+	defer screen.Fini()
+	screen.SetSize(8, 3)
+	bold := tcell.StyleDefault.Foreground(tcell.NewRGBColor(255, 0, 0)).Bold(true)
+	for i, r := range "ab" {
+		screen.SetContent(1+i, 1, r, nil, bold)
+	}
+	screen.SetContent(3, 1, '界', nil, tcell.StyleDefault)
+
+	assert.Equal("ab界\n", formatScreen(screen, 1, 1, 6, 1, false))
+	assert.Equal("\n ab界\n\n", formatScreen(screen, 0, 0, 8, 3, false))
+	assert.Equal("\x1b[0;38;2;255;0;0;49;1mab\x1b[0;39;49m界  \x1b[0m\n", formatScreen(screen, 1, 1, 6, 1, true))
+}
@@ -0,0 +1,209 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"image"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+const segmentationStorage = "1.2.840.10008.5.1.4.1.1.66.4"
+
+// segment is a segment of a segmentation with the indices of its frames
+type segment struct {
+	number        int
+	label         string
+	algorithmType string // AUTOMATIC, SEMIAUTOMATIC or MANUAL
+	algorithmName string
+	category      string // meaning of the segmented property category like Tissue
+	propertyType  string // meaning of the segmented property type like Liver
+	frames        []int
+}
+
+// segmentation are the segments of a SEG object and the instances it was derived from
+type segmentation struct {
+	segments     []segment
+	sources      []string       // referenced SOP instance UIDs in order of appearance
+	frameSources map[int]string // SOP instance UID of the source image by frame index
+}
+
+// itemValue returns the value of the attribute in the item, multiple values joined by '\'
+func itemValue(item []*dicom.Element, t tag.Tag) string {
+	if e := findItemElement(item, t); e != nil {
+		return strings.TrimSpace(strings.Join(getValueStrings(e), `\`))
+	}
+	return ""
+}
+
+// sequenceItems returns the items of the sequence in the item, none if it isn't there
+func sequenceItems(item []*dicom.Element, t tag.Tag) [][]*dicom.Element {
+	if e := findItemElement(item, t); e != nil && isSequence(e) {
+		return getSequenceItems(e)
+	}
+	return nil
+}
+
+func newSegmentation(dataset dicom.Dataset) (segmentation, error) {
+	s := segmentation{frameSources: map[int]string{}}
+	if getDatasetValue(dataset, tag.SOPClassUID) != segmentationStorage {
+		return s, errors.New("no segmentation")
+	}
+	addSource := func(uid string) {
+		if uid != "" && !slices.Contains(s.sources, uid) {
+			s.sources = append(s.sources, uid)
+		}
+	}
+	for _, item := range sequenceItems(dataset.Elements, tag.SegmentSequence) {
+		number, _ := strconv.Atoi(itemValue(item, tag.SegmentNumber))
+		s.segments = append(s.segments, segment{
+			number:        number,
+			label:         itemValue(item, tag.SegmentLabel),
+			algorithmType: itemValue(item, tag.SegmentAlgorithmType),
+			algorithmName: itemValue(item, tag.SegmentAlgorithmName),
+			category:      codeOf(item, tag.SegmentedPropertyCategoryCodeSequence, tag.CodeMeaning),
+			propertyType:  codeOf(item, tag.SegmentedPropertyTypeCodeSequence, tag.CodeMeaning),
+		})
+	}
+	for i, item := range sequenceItems(dataset.Elements, tag.PerFrameFunctionalGroupsSequence) {
+		for _, identification := range sequenceItems(item, tag.SegmentIdentificationSequence) {
+			number, _ := strconv.Atoi(itemValue(identification, tag.ReferencedSegmentNumber))
+			for j := range s.segments {
+				if s.segments[j].number == number {
+					s.segments[j].frames = append(s.segments[j].frames, i)
+				}
+			}
+		}
+		for _, derivation := range sequenceItems(item, tag.DerivationImageSequence) {
+			for _, source := range sequenceItems(derivation, tag.SourceImageSequence) {
+				uid := itemValue(source, tag.ReferencedSOPInstanceUID)
+				s.frameSources[i] = uid
+				addSource(uid)
+			}
+		}
+	}
+	for _, series := range sequenceItems(dataset.Elements, tag.ReferencedSeriesSequence) {
+		for _, instance := range sequenceItems(series, tag.ReferencedInstanceSequence) {
+			addSource(itemValue(instance, tag.ReferencedSOPInstanceUID))
+		}
+	}
+	return s, nil
+}
+
+// segment returns the segment with the number, false if there is none
+func (s segmentation) segment(number int) (segment, bool) {
+	for _, seg := range s.segments {
+		if seg.number == number {
+			return seg, true
+		}
+	}
+	return segment{}, false
+}
+
+// formatSegmentation lists the segments and the referenced source images, with their file names if they are loaded
+func formatSegmentation(s segmentation, datasetsWithFilename []DatasetEntry) string {
+	rows := [][]string{{"Segment", "Label", "Category", "Type", "Algorithm", "Frames"}}
+	for _, seg := range s.segments {
+		algorithm := seg.algorithmType
+		if seg.algorithmName != "" {
+			algorithm += " " + seg.algorithmName
+		}
+		rows = append(rows, []string{fmt.Sprint(seg.number), seg.label, seg.category, seg.propertyType, algorithm,
+			fmt.Sprint(len(seg.frames))})
+	}
+	var b strings.Builder
+	fmt.Fprintf(&b, "%d segments\n\n%s\n\n%d referenced source images\n", len(s.segments), formatTable(rows), len(s.sources))
+	filenames := map[string]string{}
+	for _, entry := range datasetsWithFilename {
+		filenames[getDatasetValue(entry.dataset, tag.SOPInstanceUID)] = entry.filename
+	}
+	for _, uid := range s.sources {
+		filename, ok := filenames[uid]
+		if !ok {
+			filename = "not loaded"
+		}
+		fmt.Fprintf(&b, "%s  %s\n", uid, filename)
+	}
+	return b.String()
+}
+
+// nativeFrame returns a native frame of the pixel data, e.g. of a segmentation mask where pixels are set if not zero
+func nativeFrame(dataset dicom.Dataset, index int) (frame.NativeFrame, error) {
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	if err != nil {
+		return frame.NativeFrame{}, errors.New("no pixel data")
+	}
+	if err := loadLazyValue(e); err != nil {
+		return frame.NativeFrame{}, err
+	}
+	info, ok := e.Value.GetValue().(dicom.PixelDataInfo)
+	if !ok || info.IsEncapsulated || index >= len(info.Frames) {
+		return frame.NativeFrame{}, fmt.Errorf("no native frame %d", index+1)
+	}
+	return info.Frames[index].NativeData, nil
+}
+
+// renderMaskOverlay draws the source image like renderShades with the cells containing pixels of the mask as '●',
+// the source may be nil
+func renderMaskOverlay(mask frame.NativeFrame, source image.Image, columns, rows int) string {
+	var base []string
+	if source != nil {
+		base = strings.Split(renderShades(source, columns, rows), "\n")
+	}
+	lines := make([]string, 0, rows)
+	for row := 0; row < rows; row++ {
+		line := []rune(strings.Repeat(" ", columns))
+		if base != nil {
+			line = []rune(base[row])
+		}
+		for column := 0; column < columns; column++ {
+			x0, x1 := column*mask.Cols/columns, max((column+1)*mask.Cols/columns, column*mask.Cols/columns+1)
+			y0, y1 := row*mask.Rows/rows, max((row+1)*mask.Rows/rows, row*mask.Rows/rows+1)
+		cell:
+			for y := y0; y < min(y1, mask.Rows); y++ {
+				for x := x0; x < min(x1, mask.Cols); x++ {
+					if pixel := mask.Data[y*mask.Cols+x]; len(pixel) > 0 && pixel[0] != 0 {
+						line[column] = '●'
+						break cell
+					}
+				}
+			}
+		}
+		lines = append(lines, string(line))
+	}
+	return strings.Join(lines, "\n")
+}
+
+// renderSegment draws the mask of the first frame of the segment over its source image if it is loaded, returns the
+// title and the drawing
+func (s segmentation) renderSegment(dataset dicom.Dataset, number int, datasetsWithFilename []DatasetEntry) (string, string, error) {
+	seg, ok := s.segment(number)
+	if !ok || len(seg.frames) == 0 {
+		return "", "", fmt.Errorf("no frames of segment %d", number)
+	}
+	mask, err := nativeFrame(dataset, seg.frames[0])
+	if err != nil {
+		return "", "", err
+	}
+	var source image.Image
+	sourceName := "source image not loaded"
+	if uid := s.frameSources[seg.frames[0]]; uid != "" {
+		for _, entry := range datasetsWithFilename {
+			if getDatasetValue(entry.dataset, tag.SOPInstanceUID) != uid {
+				continue
+			}
+			if source, err = decodeFrame(entry.dataset, 0); err == nil {
+				sourceName = "on " + entry.filename
+			} else {
+				sourceName = "source image " + err.Error()
+			}
+		}
+	}
+	title := fmt.Sprintf("Segment %d %s, frame %d %s", number, seg.label, seg.frames[0]+1, sourceName)
+	return title, renderMaskOverlay(mask, source, 96, 40), nil
+}
@@ -0,0 +1,64 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/frame"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func newSegmentationFixture(t *testing.T) *fixture {
+	f := newFixture(t)
+	segmentItem := func(number int, label, meaning string) []*dicom.Element {
+		return []*dicom.Element{
+			f.element(tag.SegmentNumber, []int{number}),
+			f.element(tag.SegmentLabel, []string{label}),
+			f.element(tag.SegmentAlgorithmType, []string{"MANUAL"}),
+			f.element(tag.SegmentedPropertyTypeCodeSequence, [][]*dicom.Element{{f.element(tag.CodeMeaning, []string{meaning})}}),
+		}
+	}
+	frameItem := func(number int, source string) []*dicom.Element {
+		return []*dicom.Element{
+			f.element(tag.DerivationImageSequence, [][]*dicom.Element{{
+				f.element(tag.SourceImageSequence, [][]*dicom.Element{{f.element(tag.ReferencedSOPInstanceUID, []string{source})}}),
+			}}),
+			f.element(tag.SegmentIdentificationSequence, [][]*dicom.Element{{f.element(tag.ReferencedSegmentNumber, []int{number})}}),
+		}
+	}
+	return f.with(tag.SOPClassUID, []string{segmentationStorage}).
+		withSequence(tag.SegmentSequence, segmentItem(1, "Liver", "Liver"), segmentItem(2, "Tumor", "Neoplasm")).
+		withSequence(tag.PerFrameFunctionalGroupsSequence, frameItem(1, "1.2.3.4.5.1"), frameItem(2, "1.2.3.4.5.1"), frameItem(1, "1.2.3.4.5.2")).
+		with(tag.PixelData, dicom.PixelDataInfo{Frames: []frame.Frame{
+			{NativeData: frame.NativeFrame{Data: [][]int{{1}, {0}, {0}, {0}}, Rows: 2, Cols: 2, BitsPerSample: 1}},
+		}})
+}
+
+func TestSegmentation(t *testing.T) {
+	assert := assert.New(t)
+
+	dataset := newSegmentationFixture(t).dataset()
+	seg, err := newSegmentation(dataset)
+	assert.NoError(err)
+	assert.Len(seg.segments, 2)
+	assert.Equal(segment{1, "Liver", "MANUAL", "", "", "Liver", []int{0, 2}}, seg.segments[0])
+	assert.Equal([]int{1}, seg.segments[1].frames)
+	assert.Equal([]string{"1.2.3.4.5.1", "1.2.3.4.5.2"}, seg.sources)
+
+	report := formatSegmentation(seg, []DatasetEntry{newInstanceFixture(t, 1).entry("ct1.dcm")})
+	assert.Contains(report, "Segment  Label  Category  Type      Algorithm  Frames\n1        Liver            Liver     MANUAL     2\n")
+	assert.Contains(report, "1.2.3.4.5.1  ct1.dcm\n1.2.3.4.5.2  not loaded\n")
+
+	title, text, err := seg.renderSegment(dataset, 1, nil)
+	assert.NoError(err)
+	assert.Equal("Segment 1 Liver, frame 1 source image not loaded", title)
+	assert.Equal(96*40+39, len([]rune(text)))
+	assert.Equal('●', []rune(text)[0])
+	assert.Equal(' ', []rune(text)[95])
+	_, _, err = seg.renderSegment(dataset, 3, nil)
+	assert.Error(err)
+
+	_, err = newSegmentation(newInstanceFixture(t, 1).dataset())
+	assert.Error(err)
+}
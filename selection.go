@@ -0,0 +1,311 @@
+package main
+
+import (
+	"bufio"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"regexp"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// elementSelection limits which elements of a file are read and which files of a directory are kept, the zero value
+// reads everything
+type elementSelection struct {
+	tags       []tag.Tag // only these top level elements are kept, all if empty
+	readUntil  *tag.Tag  // parsing stops before this tag, e.g. to skip pixel data
+	modalities []string  // only files of these modalities are kept, all if empty
+	sopClasses []string  // only files of these SOP class UIDs are kept, all if empty
+
+	skipSymlinks bool // symbolic links in a directory are skipped instead of reading their targets
+}
+
+var hexTag = regexp.MustCompile(`^[0-9A-Fa-f]{8}$`)
+
+// tagListItem matches one item of a tag list, (gggg,eeee) contains the separator itself
+var tagListItem = regexp.MustCompile(`\s*\([0-9A-Fa-f]{4},[0-9A-Fa-f]{4}\)|[^,]+`)
+
+// parseTagList parses a comma separated list of keywords or tags like PatientName,0020000E,(0008,0060)
+func parseTagList(text string) ([]tag.Tag, error) {
+	tags := make([]tag.Tag, 0)
+	for _, name := range tagListItem.FindAllString(text, -1) {
+		if name = strings.TrimSpace(name); name == "" {
+			continue
+		}
+		t, err := parseTagName(name)
+		if err != nil {
+			return nil, err
+		}
+		tags = append(tags, t)
+	}
+	if len(tags) == 0 {
+		return nil, errors.New("no tags given")
+	}
+	return tags, nil
+}
+
+// parseTagName returns the tag of a keyword, of (gggg,eeee) or of ggggeeee
+func parseTagName(name string) (tag.Tag, error) {
+	if hexTag.MatchString(name) {
+		value, _ := strconv.ParseUint(name, 16, 32)
+		return tag.Tag{Group: uint16(value >> 16), Element: uint16(value)}, nil
+	}
+	return parseQueryTag(name)
+}
+
+func compareTags(a, b tag.Tag) int {
+	if a.Group != b.Group {
+		return int(a.Group) - int(b.Group)
+	}
+	return int(a.Element) - int(b.Element)
+}
+
+// keep adds the tag to the selected tags if only some are selected, so the files can be filtered by its value
+func (s *elementSelection) keep(t tag.Tag) {
+	if len(s.tags) > 0 && !slices.Contains(s.tags, t) {
+		s.tags = append(s.tags, t)
+	}
+}
+
+// isPartial returns true if only some elements of the files are read, datasets read so mustn't be written back
+func (s elementSelection) isPartial() bool {
+	return len(s.tags) > 0 || s.readUntil != nil
+}
+
+// matches reports whether the dataset is of one of the selected modalities and SOP classes
+func (s elementSelection) matches(dataset dicom.Dataset) bool {
+	if len(s.modalities) > 0 && !slices.Contains(s.modalities, getDatasetValue(dataset, tag.Modality)) {
+		return false
+	}
+	return len(s.sopClasses) == 0 || slices.Contains(s.sopClasses, getDatasetValue(dataset, tag.SOPClassUID))
+}
+
+// parseFile parses the selected elements of the file. With selected tags parsing stops after the last of them, so
+// only the beginning of most files has to be read. The pixel data of huge files is read when accessed.
+func (s elementSelection) parseFile(path string) (dicom.Dataset, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return dicom.Dataset{}, err
+	}
+	defer file.Close()
+	info, err := file.Stat()
+	if err != nil {
+		return dicom.Dataset{}, err
+	}
+	size := info.Size()
+	if size >= hugeFileSize && s.readUntil == nil && len(s.tags) == 0 {
+		if dataset, err := parseHugeFile(file, size); !errors.Is(err, errMappingFailed) {
+			return dataset, err
+		}
+	}
+	if s.readUntil != nil {
+		// files the offset can't be determined for are parsed completely, the parser reports their errors
+		if offset, err := findElementOffset(bufio.NewReader(file), *s.readUntil); err == nil {
+			size = offset
+		}
+		if _, err := file.Seek(0, io.SeekStart); err != nil {
+			return dicom.Dataset{}, err
+		}
+	}
+	reader := io.LimitReader(file, size)
+	if len(s.tags) == 0 {
+		return dicom.Parse(reader, size, nil)
+	}
+
+	parser, err := dicom.NewParser(reader, size, nil)
+	if err != nil {
+		return dicom.Dataset{}, fmt.Errorf("%s: %w", path, err)
+	}
+	last := slices.MaxFunc(s.tags, compareTags)
+	dataset := dicom.Dataset{Elements: make([]*dicom.Element, 0, len(s.tags))}
+	metadata := parser.GetMetadata()
+	for _, e := range metadata.Elements {
+		if slices.Contains(s.tags, e.Tag) {
+			dataset.Elements = append(dataset.Elements, e)
+		}
+	}
+	for {
+		e, err := parser.Next()
+		if errors.Is(err, dicom.ErrorEndOfDICOM) {
+			break
+		}
+		if err != nil {
+			return dicom.Dataset{}, fmt.Errorf("%s: %w", path, err)
+		}
+		if e.Tag.Group == 0x0002 {
+			continue // file meta information already taken from the metadata
+		}
+		if slices.Contains(s.tags, e.Tag) {
+			dataset.Elements = append(dataset.Elements, e)
+		}
+		if compareTags(e.Tag, last) >= 0 {
+			break
+		}
+	}
+	return dataset, nil
+}
+
+// VRs with a 4 byte length in explicit VR encoding
+var longLengthVRs = []string{"OB", "OD", "OF", "OL", "OV", "OW", "SQ", "SV", "UC", "UN", "UR", "UT", "UV"}
+
+// elementScanner reads element headers of a little endian dataset and skips their values
+type elementScanner struct {
+	r        *bufio.Reader
+	offset   int64
+	explicit bool
+}
+
+func (s *elementScanner) read(n int) ([]byte, error) {
+	data := make([]byte, n)
+	_, err := io.ReadFull(s.r, data)
+	s.offset += int64(n)
+	return data, err
+}
+
+func (s *elementScanner) skip(n int64) error {
+	skipped, err := s.r.Discard(int(n))
+	s.offset += int64(skipped)
+	return err
+}
+
+// readHeader reads the tag, VR and length of the next element, the VR is empty for implicit VR and items
+func (s *elementScanner) readHeader() (tag.Tag, string, uint32, error) {
+	data, err := s.read(8)
+	if err != nil {
+		return tag.Tag{}, "", 0, err
+	}
+	t := tag.Tag{Group: binary.LittleEndian.Uint16(data), Element: binary.LittleEndian.Uint16(data[2:])}
+	if !s.explicit || t.Group == 0xfffe {
+		return t, "", binary.LittleEndian.Uint32(data[4:]), nil
+	}
+	vr := string(data[4:6])
+	if !slices.Contains(longLengthVRs, vr) {
+		return t, vr, uint32(binary.LittleEndian.Uint16(data[6:])), nil
+	}
+	data, err = s.read(4)
+	if err != nil {
+		return tag.Tag{}, "", 0, err
+	}
+	return t, vr, binary.LittleEndian.Uint32(data), nil
+}
+
+// skipValue skips the value of an element, values of undefined length are items up to a sequence delimitation
+func (s *elementScanner) skipValue(length uint32) error {
+	if length != undefinedLength {
+		return s.skip(int64(length))
+	}
+	for {
+		t, _, itemLength, err := s.readHeader()
+		if err != nil {
+			return err
+		}
+		switch {
+		case t == sequenceDelimiterTag:
+			return nil
+		case t == itemTag && itemLength == undefinedLength:
+			if err := s.skipItemElements(); err != nil {
+				return err
+			}
+		case t == itemTag:
+			if err := s.skip(int64(itemLength)); err != nil {
+				return err
+			}
+		default:
+			return fmt.Errorf("unexpected element (%04x,%04x) in sequence", t.Group, t.Element)
+		}
+	}
+}
+
+// skipItemElements skips the elements of an item of undefined length up to the item delimitation
+func (s *elementScanner) skipItemElements() error {
+	for {
+		t, _, length, err := s.readHeader()
+		if err != nil {
+			return err
+		}
+		if t == itemDelimiterTag {
+			return nil
+		}
+		if t.Group == 0xfffe {
+			return fmt.Errorf("unexpected element (%04x,%04x) in item", t.Group, t.Element)
+		}
+		if err := s.skipValue(length); err != nil {
+			return err
+		}
+	}
+}
+
+func trimUID(data []byte) string {
+	return strings.TrimRight(string(data), "\x00 ")
+}
+
+// findElementOffset returns the offset of the first top level element with the given or a higher tag in a part 10
+// file, which is the file size if there is none. Only uncompressed little endian transfer syntaxes are supported.
+func findElementOffset(r *bufio.Reader, until tag.Tag) (int64, error) {
+	s := &elementScanner{r: r, explicit: true}
+	preamble, err := s.read(132)
+	if err != nil {
+		return 0, err
+	}
+	if string(preamble[128:]) != "DICM" {
+		return 0, errors.New("no DICOM part 10 file")
+	}
+	transferSyntax := ""
+	for {
+		data, err := r.Peek(2)
+		if err != nil {
+			return s.offset, nil // only file meta information
+		}
+		if binary.LittleEndian.Uint16(data) != 0x0002 {
+			break
+		}
+		t, _, length, err := s.readHeader()
+		if err != nil {
+			return 0, err
+		}
+		if t.Element != 0x0010 {
+			if err := s.skip(int64(length)); err != nil {
+				return 0, err
+			}
+			continue
+		}
+		value, err := s.read(int(length))
+		if err != nil {
+			return 0, err
+		}
+		transferSyntax = trimUID(value)
+	}
+
+	switch transferSyntax {
+	case implicitVRLittleEndian:
+		s.explicit = false
+	case explicitVRLittleEndian:
+	default:
+		if !strings.HasPrefix(transferSyntax, "1.2.840.10008.1.2.4.") && !strings.HasPrefix(transferSyntax, "1.2.840.10008.1.2.5") {
+			return 0, fmt.Errorf("unsupported transfer syntax %s", transferSyntax) // big endian and deflated datasets
+		}
+	}
+	for {
+		start := s.offset
+		t, _, length, err := s.readHeader()
+		if errors.Is(err, io.EOF) {
+			return start, nil
+		}
+		if err != nil {
+			return 0, err
+		}
+		if compareTags(t, until) >= 0 {
+			return start, nil
+		}
+		if err := s.skipValue(length); err != nil {
+			return 0, err
+		}
+	}
+}
@@ -0,0 +1,148 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"encoding/binary"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestParseTagList(t *testing.T) {
+	assert := assert.New(t)
+
+	tags, err := parseTagList("PatientName,0020000E, (0008,0060),SOPInstanceUID")
+	assert.NoError(err)
+	assert.Equal([]tag.Tag{tag.PatientName, tag.SeriesInstanceUID, tag.Modality, tag.SOPInstanceUID}, tags)
+
+	for _, invalid := range []string{"", ",", "NoSuchKeyword", "0020000", "(0008,006)"} {
+		_, err := parseTagList(invalid)
+		assert.Error(err, invalid)
+	}
+}
+
+func TestParseSelectedTags(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	newInstanceFixture(t, 1).writeFile(filepath.Join(dir, "1.dcm"))
+	newInstanceFixture(t, 2).writeFile(filepath.Join(dir, "2.dcm"))
+
+	selection := elementSelection{tags: []tag.Tag{tag.PatientName, tag.Modality, {Group: 0x0002, Element: 0x0010}}}
+	entries, _, err := parseDicomFiles(context.Background(), dir, selection, nil)
+	assert.NoError(err)
+	assert.Len(entries, 2)
+	for _, entry := range entries {
+		assert.Equal([]tag.Tag{{Group: 0x0002, Element: 0x0010}, tag.Modality, tag.PatientName}, elementTags(entry.dataset))
+		assert.Equal("Doe^John", getDatasetValue(entry.dataset, tag.PatientName))
+	}
+
+	entries, _, err = parseDicomFiles(context.Background(), dir, elementSelection{}, nil)
+	assert.NoError(err)
+	assert.Greater(len(entries[0].dataset.Elements), 10)
+}
+
+func TestParseFilteredFiles(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	newInstanceFixture(t, 1).writeFile(filepath.Join(dir, "1.dcm"))
+	newFixture(t).
+		with(tag.SOPClassUID, []string{"1.2.840.10008.5.1.4.1.1.11.1"}).
+		with(tag.SOPInstanceUID, []string{"1.2.3.4.6"}).
+		with(tag.Modality, []string{"PR"}).
+		writeFile(filepath.Join(dir, "2.dcm"))
+
+	entries, _, err := parseDicomFiles(context.Background(), dir, elementSelection{modalities: []string{"CT", "MR"}}, nil)
+	assert.NoError(err)
+	assert.Len(entries, 1)
+	assert.Equal("1.dcm", entries[0].filename)
+
+	entries, _, err = parseDicomFiles(context.Background(), dir, elementSelection{sopClasses: []string{"1.2.840.10008.5.1.4.1.1.11.1"}}, nil)
+	assert.NoError(err)
+	assert.Len(entries, 1)
+	assert.Equal("2.dcm", entries[0].filename)
+
+	// a given file is always read
+	entries, _, err = parseDicomFiles(context.Background(), filepath.Join(dir, "2.dcm"), elementSelection{modalities: []string{"CT"}}, nil)
+	assert.NoError(err)
+	assert.Len(entries, 1)
+}
+
+func TestParseUntilTag(t *testing.T) {
+	assert := assert.New(t)
+
+	path := filepath.Join(t.TempDir(), "1.dcm")
+	f := newInstanceFixture(t, 1)
+	f.withSequence(tag.ReferencedImageSequence, []*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.3"})}).
+		withPrivate(0x0029, 0x1010, "OB", make([]byte, 16)).
+		withElement(newBinaryElement(t, 0x7fe0, 0x0010, 64)).
+		writeFile(path)
+
+	dataset, err := elementSelection{readUntil: &tag.Tag{Group: 0x0029, Element: 0x0000}}.parseFile(path)
+	assert.NoError(err)
+	for _, e := range dataset.Elements {
+		assert.Less(e.Tag.Group, uint16(0x0029))
+	}
+	assert.Equal("Doe^John", getDatasetValue(dataset, tag.PatientName))
+	_, err = dataset.FindElementByTag(tag.ReferencedImageSequence)
+	assert.NoError(err)
+
+	dataset, err = elementSelection{readUntil: &tag.PatientName}.parseFile(path)
+	assert.NoError(err)
+	_, err = dataset.FindElementByTag(tag.PatientName)
+	assert.Error(err)
+	assert.Equal("CT", getDatasetValue(dataset, tag.Modality))
+}
+
+func TestFindElementOffset(t *testing.T) {
+	assert := assert.New(t)
+
+	header := func(buf []byte, group, element uint16, vr string, length uint32) []byte {
+		buf = binary.LittleEndian.AppendUint16(buf, group)
+		buf = binary.LittleEndian.AppendUint16(buf, element)
+		if group == 0xfffe {
+			return binary.LittleEndian.AppendUint32(buf, length)
+		}
+		buf = append(buf, vr...)
+		if vr == "SQ" || vr == "OB" {
+			buf = append(buf, 0, 0)
+			return binary.LittleEndian.AppendUint32(buf, length)
+		}
+		return binary.LittleEndian.AppendUint16(buf, uint16(length))
+	}
+	data := append(make([]byte, 128), "DICM"...)
+	data = append(header(data, 0x0002, 0x0010, "UI", 20), "1.2.840.10008.1.2.1\x00"...)
+	data = append(header(data, 0x0008, 0x0060, "CS", 2), "CT"...)
+	data = header(data, 0x0008, 0x1140, "SQ", undefinedLength)
+	data = header(data, 0xfffe, 0xe000, "", undefinedLength)
+	data = append(header(data, 0x0008, 0x1150, "UI", 2), "1\x00"...)
+	data = header(data, 0xfffe, 0xe00d, "", 0)
+	data = header(data, 0xfffe, 0xe0dd, "", 0)
+	patientName := int64(len(data))
+	data = append(header(data, 0x0010, 0x0010, "PN", 4), "Doe^"...)
+	pixelData := int64(len(data))
+	data = append(header(data, 0x7fe0, 0x0010, "OB", 4), 1, 2, 3, 4)
+
+	for _, c := range []struct {
+		until  tag.Tag
+		offset int64
+	}{
+		{tag.PatientName, patientName},
+		{tag.PatientID, pixelData},
+		{tag.PixelData, pixelData},
+		{tag.Tag{Group: 0xfffa, Element: 0xfffa}, int64(len(data))},
+	} {
+		offset, err := findElementOffset(bufio.NewReader(bytes.NewReader(data)), c.until)
+		assert.NoError(err)
+		assert.Equal(c.offset, offset, c.until.String())
+	}
+
+	_, err := findElementOffset(bufio.NewReader(bytes.NewReader(make([]byte, 200))), tag.PixelData)
+	assert.Error(err)
+}
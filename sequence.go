@@ -0,0 +1,180 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"slices"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+)
+
+// sequenceItem is the reference of an item node in the tree
+type sequenceItem struct {
+	sequence *dicom.Element
+	index    int
+}
+
+func isSequence(e *dicom.Element) bool {
+	return e.Value.ValueType() == dicom.Sequences
+}
+
+// getSequenceItems returns the elements of all items of the sequence
+func getSequenceItems(e *dicom.Element) [][]*dicom.Element {
+	items := make([][]*dicom.Element, 0)
+	for _, item := range e.Value.GetValue().([]*dicom.SequenceItemValue) {
+		items = append(items, item.GetValue().([]*dicom.Element))
+	}
+	return items
+}
+
+// setSequenceItems replaces the items of the sequence, it is written with undefined length afterwards
+func setSequenceItems(e *dicom.Element, items [][]*dicom.Element) error {
+	value, err := dicom.NewValue(items)
+	if err != nil {
+		return err
+	}
+	e.Value = value
+	e.ValueLength = undefinedLength
+	return nil
+}
+
+// copyElement returns a deep copy of the element, so the copy of a sequence item can be edited independently
+func copyElement(e *dicom.Element) *dicom.Element {
+	copied := *e
+	if isSequence(e) {
+		items := make([][]*dicom.Element, 0)
+		for _, item := range getSequenceItems(e) {
+			items = append(items, copyElements(item))
+		}
+		copied.Value, _ = dicom.NewValue(items)
+	}
+	return &copied
+}
+
+func copyElements(elements []*dicom.Element) []*dicom.Element {
+	copied := make([]*dicom.Element, 0, len(elements))
+	for _, e := range elements {
+		copied = append(copied, copyElement(e))
+	}
+	return copied
+}
+
+// editSequenceItems applies a structural edit to the items of a sequence and returns the index of the item to select,
+// -1 if there is none left. Operations are:
+//
+//	o - insert an empty item after the given index, O - before it
+//	y - duplicate the item
+//	d - delete the item
+//	k, j - move the item up or down
+func editSequenceItems(e *dicom.Element, index int, operation rune) (int, error) {
+	items := getSequenceItems(e)
+	if operation != 'o' && operation != 'O' && (index < 0 || index >= len(items)) {
+		return index, errors.New("no sequence item selected")
+	}
+	switch operation {
+	case 'o':
+		index++
+		items = slices.Insert(items, index, []*dicom.Element{})
+	case 'O':
+		index = max(index, 0)
+		items = slices.Insert(items, index, []*dicom.Element{})
+	case 'y':
+		items = slices.Insert(items, index+1, copyElements(items[index]))
+		index++
+	case 'd':
+		items = slices.Delete(items, index, index+1)
+		index = min(index, len(items)-1)
+	case 'k', 'j':
+		other := index - 1
+		if operation == 'j' {
+			other = index + 1
+		}
+		if other < 0 || other >= len(items) {
+			return index, errors.New("item can't be moved further")
+		}
+		items[index], items[other] = items[other], items[index]
+		index = other
+	default:
+		return index, fmt.Errorf("unknown sequence item operation '%c'", operation)
+	}
+	return index, setSequenceItems(e, items)
+}
+
+// addSequenceItemNodes adds a node per item with its elements to the node of the sequence element, replacing existing
+// ones
+func addSequenceItemNodes(elementNode *tview.TreeNode, e *dicom.Element) {
+	elementNode.ClearChildren()
+	for i, item := range getSequenceItems(e) {
+		itemNode := tview.NewTreeNode(fmt.Sprintf("\tItem %d (%d elements)", i+1, len(item))).
+			SetSelectable(true).
+			SetReference(&sequenceItem{e, i})
+		elementNode.AddChild(itemNode)
+		for _, itemElement := range item {
+			node := tview.NewTreeNode(getItemElementText(itemElement)).SetSelectable(true).SetReference(itemElement)
+			itemNode.AddChild(node)
+			if isSequence(itemElement) {
+				addSequenceItemNodes(node, itemElement)
+			}
+		}
+	}
+}
+
+// getItemElementText returns the text of the node of an element in a sequence item, which isn't below a group node
+func getItemElementText(e *dicom.Element) string {
+	return fmt.Sprintf("\t(%04x,%04x) %s (%s, %d): %s", e.Tag.Group, e.Tag.Element, getTagName(e), e.RawValueRepresentation, e.ValueLength, getValueString(e))
+}
+
+// editSequenceItemsInTree applies the operation to the sequence of the current item node, or of the current sequence
+// element node for inserting, and updates its nodes. Returns a description of the edit and the edited sequence.
+func editSequenceItemsInTree(tree *tview.TreeView, operation rune) (string, *dicom.Element, error) {
+	node := tree.GetCurrentNode()
+	elementNode, index := node, -1
+	var e *dicom.Element
+	switch ref := node.GetReference().(type) {
+	case *sequenceItem:
+		elementNode, e, index = getParent(tree, node), ref.sequence, ref.index
+	case *dicom.Element:
+		if !isSequence(ref) {
+			return "", nil, errors.New("no sequence or sequence item selected")
+		}
+		e = ref
+		if operation == 'o' {
+			index = len(getSequenceItems(e)) - 1 // append
+		}
+	default:
+		return "", nil, errors.New("no sequence or sequence item selected")
+	}
+	if elementNode == nil {
+		return "", nil, errors.New("sequence not found in tree")
+	}
+
+	newIndex, err := editSequenceItems(e, index, operation)
+	if err != nil {
+		return "", nil, err
+	}
+	addSequenceItemNodes(elementNode, e)
+	if parent := getParent(tree, elementNode); parent != nil {
+		if _, nested := parent.GetReference().(*sequenceItem); nested {
+			elementNode.SetText(getItemElementText(e))
+		} else {
+			elementNode.SetText(getElementText(e))
+		}
+	}
+	elementNode.SetExpanded(true)
+	if newIndex >= 0 && newIndex < len(elementNode.GetChildren()) {
+		tree.SetCurrentNode(elementNode.GetChildren()[newIndex])
+	} else {
+		tree.SetCurrentNode(elementNode)
+	}
+
+	switch operation {
+	case 'o', 'O':
+		return fmt.Sprintf("insert item %d", newIndex+1), e, nil
+	case 'y':
+		return fmt.Sprintf("duplicate item %d", index+1), e, nil
+	case 'd':
+		return fmt.Sprintf("delete item %d", index+1), e, nil
+	}
+	return fmt.Sprintf("move item %d to %d", index+1, newIndex+1), e, nil
+}
@@ -0,0 +1,121 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// itemUIDs returns the referenced SOP instance UID of every item, empty for items without
+func itemUIDs(e *dicom.Element) []string {
+	uids := make([]string, 0)
+	for _, item := range getSequenceItems(e) {
+		uids = append(uids, getDatasetValue(dicom.Dataset{Elements: item}, tag.ReferencedSOPInstanceUID))
+	}
+	return uids
+}
+
+func newReferenceSequence(f *fixture, uids ...string) *dicom.Element {
+	items := make([][]*dicom.Element, 0)
+	for _, uid := range uids {
+		items = append(items, []*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{uid})})
+	}
+	return f.element(tag.ReferencedImageSequence, items)
+}
+
+func TestEditSequenceItems(t *testing.T) {
+	assert := assert.New(t)
+
+	e := newReferenceSequence(newFixture(t), "1", "2")
+
+	index, err := editSequenceItems(e, 0, 'o')
+	assert.NoError(err)
+	assert.Equal(1, index)
+	assert.Equal([]string{"1", "", "2"}, itemUIDs(e))
+
+	index, err = editSequenceItems(e, 0, 'O')
+	assert.NoError(err)
+	assert.Equal(0, index)
+	assert.Equal([]string{"", "1", "", "2"}, itemUIDs(e))
+
+	index, err = editSequenceItems(e, 0, 'd')
+	assert.NoError(err)
+	assert.Equal(0, index)
+	index, err = editSequenceItems(e, 1, 'd')
+	assert.NoError(err)
+	assert.Equal(1, index)
+	assert.Equal([]string{"1", "2"}, itemUIDs(e))
+
+	index, err = editSequenceItems(e, 1, 'y')
+	assert.NoError(err)
+	assert.Equal(2, index)
+	assert.Equal([]string{"1", "2", "2"}, itemUIDs(e))
+	items := getSequenceItems(e)
+	assert.NotSame(items[1][0], items[2][0], "duplicated items are independent")
+
+	index, err = editSequenceItems(e, 0, 'j')
+	assert.NoError(err)
+	assert.Equal(1, index)
+	assert.Equal([]string{"2", "1", "2"}, itemUIDs(e))
+	index, err = editSequenceItems(e, 1, 'k')
+	assert.NoError(err)
+	assert.Equal(0, index)
+	assert.Equal([]string{"1", "2", "2"}, itemUIDs(e))
+	_, err = editSequenceItems(e, 0, 'k')
+	assert.Error(err)
+
+	_, err = editSequenceItems(e, 3, 'd')
+	assert.Error(err)
+	assert.Equal(uint32(undefinedLength), e.ValueLength)
+}
+
+func TestEditSequenceItemsInTree(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newInstanceFixture(t, 1)
+	sequence := newReferenceSequence(f, "1", "2")
+	f.withElement(sequence)
+	datasets := []DatasetEntry{f.entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+
+	var sequenceNode *tview.TreeNode
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if node.GetReference() == sequence {
+			sequenceNode = node
+		}
+		return true
+	})
+	if !assert.NotNil(sequenceNode) {
+		return
+	}
+	assert.Len(sequenceNode.GetChildren(), 2)
+	assert.Equal("\tItem 1 (1 elements)", sequenceNode.GetChildren()[0].GetText())
+	assert.Contains(sequenceNode.GetChildren()[1].GetChildren()[0].GetText(), "(0008,1155) ReferencedSOPInstanceUID")
+
+	tree.SetCurrentNode(sequenceNode)
+	description, e, err := editSequenceItemsInTree(tree, 'o')
+	assert.NoError(err)
+	assert.Same(sequence, e)
+	assert.Equal("insert item 3", description)
+	assert.Len(sequenceNode.GetChildren(), 3)
+	assert.Equal(sequenceNode.GetChildren()[2], tree.GetCurrentNode())
+
+	description, _, err = editSequenceItemsInTree(tree, 'k')
+	assert.NoError(err)
+	assert.Equal("move item 3 to 2", description)
+	assert.Equal([]string{"1", "", "2"}, itemUIDs(sequence))
+	assert.Equal(sequenceNode.GetChildren()[1], tree.GetCurrentNode())
+
+	_, _, err = editSequenceItemsInTree(tree, 'd')
+	assert.NoError(err)
+	assert.Equal([]string{"1", "2"}, itemUIDs(sequence))
+	assert.Equal("file1.dcm", getFileEntry(tree, tree.GetCurrentNode()).filename)
+	assert.Equal("file1.dcm", getElementFilePath(datasets, getSequenceItems(sequence)[0][0]))
+
+	tree.SetCurrentNode(root)
+	_, _, err = editSequenceItemsInTree(tree, 'o')
+	assert.Error(err)
+}
@@ -0,0 +1,140 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"slices"
+	"strings"
+)
+
+// session is the review state written with :session and restored with --session, so a colleague sees the same tabs,
+// trees, notes and filters
+type session struct {
+	Tabs        []sessionTab  `json:"tabs"`
+	ActiveTab   int           `json:"activeTab"`
+	Hide        []string      `json:"hide,omitempty"` // element kinds like --hide
+	Conformance bool          `json:"conformance,omitempty"`
+	FileInfo    bool          `json:"fileInfo,omitempty"`
+	Notes       []sessionNote `json:"notes,omitempty"`
+}
+
+// sessionTab is the input of a tab with the state of its tree, nodes identified by the path of their texts
+type sessionTab struct {
+	Input    string   `json:"input"`
+	SortMode string   `json:"sortMode"`
+	Query    string   `json:"query,omitempty"`
+	Expanded []string `json:"expanded,omitempty"`
+	Current  string   `json:"current,omitempty"`
+}
+
+// sessionNote is a review note of a file or one of its top level elements
+type sessionNote struct {
+	Path string `json:"path"`
+	Tag  string `json:"tag,omitempty"` // like (0010,0010), empty for the note of the file
+	Note string `json:"note"`
+}
+
+func newSessionTab(tab *workspace, state treeState) sessionTab {
+	sessionTab := sessionTab{Input: tab.rootDir, SortMode: string(tab.sortMode), Current: state.current}
+	if tab.query != nil {
+		sessionTab.Query = tab.query.String()
+	}
+	for path := range state.expanded {
+		sessionTab.Expanded = append(sessionTab.Expanded, path)
+	}
+	slices.Sort(sessionTab.Expanded)
+	return sessionTab
+}
+
+// newSession collects the state of the tabs, whose state has to be stored before if they are shown, and the notes of
+// their files
+func newSession(tabs []*workspace, active int, n notes) session {
+	s := session{ActiveTab: active}
+	for _, tab := range tabs {
+		state := treeState{}
+		if tab.root != nil {
+			state = saveNodeState(tab.root, tab.current)
+		}
+		s.Tabs = append(s.Tabs, newSessionTab(tab, state))
+		s.Notes = append(s.Notes, newSessionNotes(n, tab.datasets)...)
+	}
+	return s
+}
+
+// treeState returns the state of the tree to restore
+func (t sessionTab) treeState() treeState {
+	state := treeState{expanded: make(map[string]bool), current: t.Current}
+	for _, path := range t.Expanded {
+		state.expanded[path] = true
+	}
+	return state
+}
+
+// newSessionNotes returns the notes of the files and their top level elements, as listed by :notes
+func newSessionNotes(n notes, datasetsWithFilename []DatasetEntry) []sessionNote {
+	sessionNotes := make([]sessionNote, 0)
+	for i := range datasetsWithFilename {
+		entry := &datasetsWithFilename[i]
+		if note, ok := n.get(entry); ok {
+			sessionNotes = append(sessionNotes, sessionNote{Path: entry.path, Note: note})
+		}
+		for _, e := range entry.dataset.Elements {
+			if note, ok := n[e]; ok {
+				sessionNotes = append(sessionNotes, sessionNote{entry.path, fmt.Sprintf("(%04x,%04x)", e.Tag.Group, e.Tag.Element), note})
+			}
+		}
+	}
+	return sessionNotes
+}
+
+// restoreNotes attaches the notes of the session to the files and elements of the datasets they belong to
+func (s session) restoreNotes(n notes, datasetsWithFilename []DatasetEntry) {
+	for _, note := range s.Notes {
+		for i := range datasetsWithFilename {
+			entry := &datasetsWithFilename[i]
+			if entry.path != note.Path {
+				continue
+			}
+			if note.Tag == "" {
+				n[noteKey(entry)] = note.Note
+				continue
+			}
+			for _, e := range entry.dataset.Elements {
+				if fmt.Sprintf("(%04x,%04x)", e.Tag.Group, e.Tag.Element) == note.Tag {
+					n[e] = note.Note
+				}
+			}
+		}
+	}
+}
+
+func (s session) save(filename string) error {
+	data, err := json.MarshalIndent(s, "", "  ")
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(filename, append(data, '\n'), 0o644)
+}
+
+func loadSession(filename string) (session, error) {
+	var s session
+	data, err := os.ReadFile(filename)
+	if err != nil {
+		return s, err
+	}
+	if err := json.Unmarshal(data, &s); err != nil {
+		return s, fmt.Errorf("invalid session %s: %w", filename, err)
+	}
+	if len(s.Tabs) == 0 {
+		return s, errors.New("no tabs in session " + filename)
+	}
+	for _, tab := range s.Tabs {
+		if len(tab.SortMode) != 1 || !strings.Contains("12345678", tab.SortMode) {
+			return s, fmt.Errorf("invalid sort mode '%s' in session %s", tab.SortMode, filename)
+		}
+	}
+	s.ActiveTab = min(max(s.ActiveTab, 0), len(s.Tabs)-1)
+	return s, nil
+}
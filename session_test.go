@@ -0,0 +1,74 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestSession(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	tree, root := sortTreeByFilename("study", tview.NewTreeView(), datasets)
+	collapseAllRecursive(root)
+	root.Expand()
+	fileNode := root.GetChildren()[1]
+	fileNode.Expand()
+	tree.SetCurrentNode(fileNode)
+	modality, err := datasets[1].dataset.FindElementByTag(tag.Modality)
+	assert.NoError(err)
+	reviewNotes := notes{&datasets[0]: "check", modality: "should be MR"}
+
+	tab := newWorkspace("study", datasets)
+	tab.root, tab.current, tab.sortMode = tree.GetRoot(), tree.GetCurrentNode(), '2'
+	tab.query, err = parseQuery(`Modality=="CT"`)
+	assert.NoError(err)
+	s := newSession([]*workspace{tab}, 0, reviewNotes)
+	s.Hide = []string{"private"}
+	assert.Equal([]sessionTab{{
+		Input:    "study",
+		SortMode: "2",
+		Query:    `Modality=="CT"`,
+		Expanded: []string{"/study", "/study/file2.dcm"},
+		Current:  "/study/file2.dcm",
+	}}, s.Tabs)
+	assert.Equal([]sessionNote{{"file1.dcm", "", "check"}, {"file2.dcm", "(0008,0060)", "should be MR"}}, s.Notes)
+
+	filename := filepath.Join(t.TempDir(), "session.json")
+	assert.NoError(s.save(filename))
+	loaded, err := loadSession(filename)
+	assert.NoError(err)
+	assert.Equal(s, loaded)
+
+	reloaded := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	restoredNotes := notes{}
+	loaded.restoreNotes(restoredNotes, reloaded)
+	assert.Equal("file1.dcm: check\nfile2.dcm (0008,0060) Modality: should be MR", restoredNotes.list(reloaded))
+
+	tree, root = sortTreeByFilename("study", tview.NewTreeView(), reloaded)
+	collapseAllRecursive(root)
+	loaded.Tabs[0].treeState().restore(tree)
+	assert.True(root.IsExpanded())
+	assert.Equal("file2.dcm", tree.GetCurrentNode().GetText())
+	assert.True(tree.GetCurrentNode().IsExpanded())
+	assert.False(root.GetChildren()[0].IsExpanded())
+}
+
+func TestLoadSessionErrors(t *testing.T) {
+	assert := assert.New(t)
+
+	filename := filepath.Join(t.TempDir(), "session.json")
+	assert.NoError(os.WriteFile(filename, []byte(`{"tabs": []}`), 0o644))
+	_, err := loadSession(filename)
+	assert.ErrorContains(err, "no tabs")
+	assert.NoError(os.WriteFile(filename, []byte(`{"tabs": [{"input": "a", "sortMode": "9"}]}`), 0o644))
+	_, err = loadSession(filename)
+	assert.ErrorContains(err, "invalid sort mode")
+	_, err = loadSession(filepath.Join(t.TempDir(), "missing.json"))
+	assert.Error(err)
+}
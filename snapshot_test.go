@@ -0,0 +1,96 @@
+package main
+
+import (
+	"errors"
+	"flag"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+var updateSnapshots = flag.Bool("update", false, "rewrite the golden snapshots in testdata/snapshots")
+
+// renderSnapshot draws the primitive on a simulated screen of the given size and returns the text of its rows without
+// trailing spaces, wide characters only once.
+func renderSnapshot(t *testing.T, p tview.Primitive, width, height int) string {
+	screen := tcell.NewSimulationScreen("UTF-8")
+	if err := screen.Init(); err != nil {
+		t.Fatal(err)
+	}
+	defer screen.Fini()
+	screen.SetSize(width, height)
+	p.SetRect(0, 0, width, height)
+	p.Draw(screen)
+	return formatScreen(screen, 0, 0, width, height, false)
+}
+
+// assertSnapshot compares the rendered text with the golden file of the given name. With -update the golden files are
+// written instead, so changed renderings can be reviewed in the diff.
+func assertSnapshot(t *testing.T, name, rendered string) {
+	path := filepath.Join("testdata", "snapshots", name+".txt")
+	if *updateSnapshots {
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(path, []byte(rendered), 0o644); err != nil {
+			t.Fatal(err)
+		}
+		t.Logf("wrote snapshot %s", path)
+		return
+	}
+	golden, err := os.ReadFile(path)
+	if errors.Is(err, fs.ErrNotExist) {
+		t.Fatalf("snapshot %s is missing, create it with -update and commit it", path)
+	}
+	if err != nil {
+		t.Fatal(err)
+	}
+	assert.Equal(t, string(golden), rendered, "snapshot %s differs, rerun with -update if intended", name)
+}
+
+func newSnapshotDatasets(t *testing.T) []DatasetEntry {
+	f := newInstanceFixture(t, 1)
+	f.withSequence(tag.ReferencedImageSequence, []*dicom.Element{f.element(tag.ReferencedSOPInstanceUID, []string{"1.2.3"})})
+	return []DatasetEntry{f.entry("IM1"), newInstanceFixture(t, 2).entry("IM2"), newInstanceFixture(t, 10).entry("IM10")}
+}
+
+func TestSnapshotSortByFilename(t *testing.T) {
+	datasets := newSnapshotDatasets(t)
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	collapseAllRecursive(root)
+	root.Expand()
+	root.GetChildren()[0].ExpandAll()
+	assertSnapshot(t, "sort_by_filename", renderSnapshot(t, newScrollView(tree, 0), 100, 30))
+
+	tree, _ = sortTreeByFilename("root", tview.NewTreeView(), sortEntries(datasets, compareByFilename))
+	collapseAllChildren(tree.GetRoot())
+	assertSnapshot(t, "sort_by_filename_natural", renderSnapshot(t, newScrollView(tree, 0), 100, 5))
+}
+
+func TestSnapshotSortByTags(t *testing.T) {
+	tree, root := sortTreeByTags("root", tview.NewTreeView(), newSnapshotDatasets(t), 1)
+	collapseAllLeaves(root)
+	assertSnapshot(t, "sort_by_tags_different", renderSnapshot(t, newScrollView(tree, 0), 100, 20))
+}
+
+func TestSnapshotSortByHierarchy(t *testing.T) {
+	tree, root := sortTreeByHierarchy("root", tview.NewTreeView(), newSnapshotDatasets(t))
+	collapseAllFiles(root)
+	assertSnapshot(t, "sort_by_hierarchy", renderSnapshot(t, newScrollView(tree, 0), 100, 10))
+}
+
+func TestSnapshotMarkerAndStickyHeaders(t *testing.T) {
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), newSnapshotDatasets(t))
+	view := newScrollView(tree, 0)
+	view.marker = "► "
+	elements := root.GetChildren()[2].GetChildren()[3].GetChildren()
+	tree.SetCurrentNode(elements[len(elements)-1])
+	assertSnapshot(t, "marker_sticky_headers", renderSnapshot(t, view, 80, 8))
+}
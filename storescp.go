@@ -0,0 +1,159 @@
+//go:build !nonet
+
+package main
+
+import (
+	"errors"
+	"log/slog"
+	"net"
+	"path/filepath"
+	"regexp"
+	"slices"
+)
+
+const verificationSOPClass = "1.2.840.10008.1.1"
+
+// status of a C-STORE response if the file couldn't be written
+const statusOutOfResources = 0xa700
+
+// preferred transfer syntaxes of received objects, other proposed ones are accepted as well as the data is only stored
+var preferredTransferSyntaxes = []string{explicitVRLittleEndian, implicitVRLittleEndian}
+
+// storageSCP accepts C-STORE requests from any AE, writes the received objects as files to the spool directory and
+// reports their paths. C-ECHO requests are answered, too.
+type storageSCP struct {
+	spoolDir string
+	received func(path string)
+	netLog   *protocolLog
+}
+
+func newStorageSCP(spoolDir string, received func(path string), netLog *protocolLog) *storageSCP {
+	return &storageSCP{spoolDir: spoolDir, received: received, netLog: netLog}
+}
+
+// serve accepts associations until the listener is closed
+func (s *storageSCP) serve(listener net.Listener) error {
+	for {
+		conn, err := listener.Accept()
+		if err != nil {
+			if errors.Is(err, net.ErrClosed) {
+				return nil
+			}
+			return err
+		}
+		go s.handle(conn)
+	}
+}
+
+// acceptContexts accepts all proposed presentation contexts, choosing a preferred transfer syntax if proposed
+func acceptContexts(proposed []presentationContext) []presentationContext {
+	accepted := make([]presentationContext, 0)
+	for _, pc := range proposed {
+		if len(pc.transferSyntaxes) == 0 {
+			continue
+		}
+		transferSyntax := pc.transferSyntaxes[0]
+		for _, preferred := range preferredTransferSyntaxes {
+			if slices.Contains(pc.transferSyntaxes, preferred) {
+				transferSyntax = preferred
+				break
+			}
+		}
+		pc.transferSyntaxes = []string{transferSyntax}
+		accepted = append(accepted, pc)
+	}
+	return accepted
+}
+
+func (s *storageSCP) handle(conn net.Conn) {
+	defer recoverCrash()
+	defer conn.Close()
+	peer := conn.RemoteAddr().String()
+	pduType, data, err := readPDU(conn)
+	if err != nil || pduType != pduAssociateRQ {
+		slog.Warn("no association requested", "peer", peer, "error", err)
+		return
+	}
+	request, err := decodeAssociate(data)
+	if err != nil {
+		slog.Warn("invalid association request", "peer", peer, "error", err)
+		writePDU(conn, pduAbort, make([]byte, 4))
+		return
+	}
+	slog.Info("association accepted", "peer", peer, "callingAET", request.callingAET, "calledAET", request.calledAET)
+	response := associateParams{
+		calledAET:    request.calledAET,
+		callingAET:   request.callingAET,
+		contexts:     acceptContexts(request.contexts),
+		maxPDULength: defaultMaxPDULength,
+	}
+	s.netLog.addNegotiation(peer, request, &response, nil)
+	if err := writePDU(conn, pduAssociateAC, response.encode(true)); err != nil {
+		return
+	}
+
+	a := &association{conn: conn, maxPDULength: request.maxPDULength, contexts: response.contexts}
+	for {
+		message, err := a.receive()
+		if err != nil {
+			slog.Debug("association ended", "peer", peer, "reason", err)
+			return // released, aborted or broken
+		}
+		switch message.commandInt(tagCommandField) {
+		case commandCEchoRQ:
+			err = a.send(message.pcID, encodeCommand(
+				commandValue{tagAffectedSOPClassUID, verificationSOPClass},
+				commandValue{tagCommandField, uint16(commandCEchoRSP)},
+				commandValue{tagMessageIDBeingRespondedTo, uint16(message.commandInt(tagMessageID))},
+				commandValue{tagCommandDataSetType, uint16(noDataset)},
+				commandValue{tagStatus, uint16(statusSuccess)},
+			), nil)
+		case commandCStoreRQ:
+			err = s.store(a, message, request.callingAET)
+		default:
+			slog.Warn("unsupported command, association aborted", "peer", peer, "command", message.commandInt(tagCommandField))
+			writePDU(conn, pduAbort, make([]byte, 4))
+			return
+		}
+		if err != nil {
+			slog.Warn("sending response failed", "peer", peer, "error", err)
+			return
+		}
+	}
+}
+
+// unsafeFilenameChars are replaced in SOP instance UIDs used as filename
+var unsafeFilenameChars = regexp.MustCompile(`[^0-9A-Za-z.]`)
+
+// store writes the received object and sends the response
+func (s *storageSCP) store(a *association, message dimseMessage, callingAET string) error {
+	sopClass := message.commandString(tagAffectedSOPClassUID)
+	sopInstance := message.commandString(tagAffectedSOPInstanceUID)
+	status := statusSuccess
+	transferSyntax := ""
+	for _, pc := range a.contexts {
+		if pc.id == message.pcID {
+			transferSyntax = pc.transferSyntaxes[0]
+		}
+	}
+	path := filepath.Join(s.spoolDir, unsafeFilenameChars.ReplaceAllString(sopInstance, "_")+".dcm")
+	if err := writeReceivedFile(path, sopClass, sopInstance, transferSyntax, callingAET, message.dataset); err != nil {
+		slog.Error("writing received object failed", "path", path, "error", err)
+		status = statusOutOfResources
+	} else {
+		slog.Debug("received object", "path", path, "sopClass", sopClass, "callingAET", callingAET)
+	}
+
+	err := a.send(message.pcID, encodeCommand(
+		commandValue{tagAffectedSOPClassUID, sopClass},
+		commandValue{tagCommandField, uint16(commandCStoreRSP)},
+		commandValue{tagMessageIDBeingRespondedTo, uint16(message.commandInt(tagMessageID))},
+		commandValue{tagCommandDataSetType, uint16(noDataset)},
+		commandValue{tagStatus, uint16(status)},
+		commandValue{tagAffectedSOPInstanceUID, sopInstance},
+	), nil)
+	if status == statusSuccess {
+		s.received(path)
+	}
+	return err
+}
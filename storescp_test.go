@@ -0,0 +1,127 @@
+//go:build !nonet
+
+package main
+
+import (
+	"context"
+	"crypto/ecdsa"
+	"crypto/elliptic"
+	"crypto/rand"
+	"crypto/tls"
+	"crypto/x509"
+	"crypto/x509/pkix"
+	"encoding/pem"
+	"math/big"
+	"net"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestStorageSCP(t *testing.T) {
+	assert := assert.New(t)
+
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer listener.Close()
+	received := make(chan string, 1)
+	netLog := newProtocolLog()
+	go newStorageSCP(t.TempDir(), func(path string) { received <- path }, netLog).serve(listener)
+
+	const ctImageStorage = "1.2.840.10008.5.1.4.1.1.2"
+	contexts := []presentationContext{{id: 1, abstractSyntax: ctImageStorage, transferSyntaxes: []string{implicitVRLittleEndian}}}
+	a, err := dialAssociation(context.Background(), listener.Addr().String(), "SCU", "STORE", contexts, nil, nil)
+	if err != nil {
+		t.Fatal(err)
+	}
+	dataset, err := encodeImplicit(newInstanceFixture(t, 1).dataset().Elements)
+	assert.NoError(err)
+	command := encodeCommand(
+		commandValue{tagAffectedSOPClassUID, ctImageStorage},
+		commandValue{tagCommandField, uint16(commandCStoreRQ)},
+		commandValue{tagMessageID, uint16(1)},
+		commandValue{tagPriority, uint16(0)},
+		commandValue{tagCommandDataSetType, uint16(0)},
+		commandValue{tagAffectedSOPInstanceUID, "1.2.3.4.5.1"},
+	)
+	assert.NoError(a.send(1, command, dataset))
+	response, err := a.receive()
+	assert.NoError(err)
+	assert.Equal(statusSuccess, response.commandInt(tagStatus))
+	assert.NoError(a.release())
+
+	parsed, err := dicom.ParseFile(<-received, nil)
+	assert.NoError(err)
+	assert.Equal("1.2.3.4.5.1", getDatasetValue(parsed, tag.SOPInstanceUID))
+	assert.Equal("SCU", getDatasetValue(parsed, tag.SourceApplicationEntityTitle))
+	assert.Contains(netLog.String(), "A-ASSOCIATE SCU -> STORE")
+	assert.Contains(netLog.String(), "[1] accepted with "+implicitVRLittleEndian)
+}
+
+// writeSelfSignedCert writes a certificate for 127.0.0.1 and its key as PEM files
+func writeSelfSignedCert(t *testing.T, dir string) (string, string) {
+	key, err := ecdsa.GenerateKey(elliptic.P256(), rand.Reader)
+	if err != nil {
+		t.Fatal(err)
+	}
+	template := &x509.Certificate{
+		SerialNumber: big.NewInt(1),
+		Subject:      pkix.Name{CommonName: "dcmtagger test"},
+		IPAddresses:  []net.IP{net.ParseIP("127.0.0.1")},
+		NotBefore:    time.Now().Add(-time.Hour),
+		NotAfter:     time.Now().Add(time.Hour),
+		KeyUsage:     x509.KeyUsageDigitalSignature | x509.KeyUsageCertSign,
+		ExtKeyUsage:  []x509.ExtKeyUsage{x509.ExtKeyUsageServerAuth, x509.ExtKeyUsageClientAuth},
+		IsCA:         true,
+
+		BasicConstraintsValid: true,
+	}
+	der, err := x509.CreateCertificate(rand.Reader, template, template, &key.PublicKey, key)
+	if err != nil {
+		t.Fatal(err)
+	}
+	keyDer, err := x509.MarshalECPrivateKey(key)
+	if err != nil {
+		t.Fatal(err)
+	}
+	certPath, keyPath := filepath.Join(dir, "cert.pem"), filepath.Join(dir, "key.pem")
+	os.WriteFile(certPath, pem.EncodeToMemory(&pem.Block{Type: "CERTIFICATE", Bytes: der}), 0o600)
+	os.WriteFile(keyPath, pem.EncodeToMemory(&pem.Block{Type: "EC PRIVATE KEY", Bytes: keyDer}), 0o600)
+	return certPath, keyPath
+}
+
+func TestTLSAssociation(t *testing.T) {
+	assert := assert.New(t)
+
+	certPath, keyPath := writeSelfSignedCert(t, t.TempDir())
+	settings := &tlsSettings{CA: certPath, Cert: certPath, Key: keyPath} // mutual authentication with the same cert
+	serverTLS, err := settings.serverConfig()
+	assert.NoError(err)
+	clientTLS, err := settings.clientConfig()
+	assert.NoError(err)
+
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer listener.Close()
+	go newStorageSCP(t.TempDir(), func(path string) {}, nil).serve(tls.NewListener(listener, serverTLS))
+
+	contexts := []presentationContext{{id: 1, abstractSyntax: verificationSOPClass, transferSyntaxes: []string{implicitVRLittleEndian}}}
+	a, err := dialAssociation(context.Background(), listener.Addr().String(), "SCU", "SCP", contexts, clientTLS, nil)
+	if err != nil {
+		t.Fatal(err)
+	}
+	assert.Len(a.contexts, 1)
+	assert.NoError(a.release())
+
+	_, err = dialAssociation(context.Background(), listener.Addr().String(), "SCU", "SCP", contexts, nil, nil)
+	assert.Error(err) // plain connection refused by TLS listener
+}
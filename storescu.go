@@ -0,0 +1,197 @@
+//go:build !nonet
+
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"crypto/tls"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+)
+
+// fileMeta are the file meta information needed to send a part 10 file
+type fileMeta struct {
+	sopClass       string
+	sopInstance    string
+	transferSyntax string
+}
+
+// readFileMeta reads the preamble and the file meta information of a part 10 file, r is at the dataset afterwards
+func readFileMeta(r *bufio.Reader, path string) (fileMeta, error) {
+	var meta fileMeta
+	preamble := make([]byte, 132)
+	if _, err := io.ReadFull(r, preamble); err != nil || !bytes.Equal(preamble[128:], []byte("DICM")) {
+		return meta, fmt.Errorf("%s is no DICOM part 10 file", path)
+	}
+	for {
+		header, err := r.Peek(12)
+		if len(header) < 8 || binary.LittleEndian.Uint16(header) != 0x0002 {
+			break
+		}
+		element := binary.LittleEndian.Uint16(header[2:])
+		var length, headerLength int
+		switch string(header[4:6]) {
+		case "OB", "OW", "UN", "SQ", "UT", "UC", "UR":
+			if err != nil {
+				return meta, errors.New("truncated file meta information")
+			}
+			length, headerLength = int(binary.LittleEndian.Uint32(header[8:])), 12
+		default:
+			length, headerLength = int(binary.LittleEndian.Uint16(header[6:])), 8
+		}
+		if length < 0 || length > maxFileMetaValueLength {
+			return meta, errors.New("invalid file meta information")
+		}
+		r.Discard(headerLength)
+		data := make([]byte, length)
+		if _, err := io.ReadFull(r, data); err != nil {
+			return meta, errors.New("truncated file meta information")
+		}
+		value := trimUID(data)
+		switch element {
+		case 0x0002:
+			meta.sopClass = value
+		case 0x0003:
+			meta.sopInstance = value
+		case 0x0010:
+			meta.transferSyntax = value
+		}
+	}
+	if meta.sopClass == "" || meta.sopInstance == "" || meta.transferSyntax == "" {
+		return meta, fmt.Errorf("incomplete file meta information in %s", path)
+	}
+	return meta, nil
+}
+
+// maxFileMetaValueLength limits the values of the file meta information, which are UIDs and short strings
+const maxFileMetaValueLength = 1 << 16
+
+// readPart10Meta reads only the file meta information of a part 10 file, not its dataset
+func readPart10Meta(path string) (fileMeta, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return fileMeta{}, err
+	}
+	defer file.Close()
+	return readFileMeta(bufio.NewReader(file), path)
+}
+
+// readPart10File reads the file meta information of a part 10 file and returns it with the encoded dataset
+func readPart10File(path string) (fileMeta, []byte, error) {
+	file, err := os.Open(path)
+	if err != nil {
+		return fileMeta{}, nil, err
+	}
+	defer file.Close()
+	r := bufio.NewReader(file)
+	meta, err := readFileMeta(r, path)
+	if err != nil {
+		return meta, nil, err
+	}
+	dataset, err := io.ReadAll(r)
+	return meta, dataset, err
+}
+
+// sendFiles sends the part 10 files with C-STORE to the remote AE in one association, proposing one presentation
+// context per SOP class and transfer syntax of the files. Only the file meta information is read for that, each file
+// is read completely once when it's sent. Files whose context the SCP rejected are skipped and reported in the error.
+func sendFiles(ctx context.Context, ae remoteAE, paths []string, tlsConfig *tls.Config, netLog *protocolLog, progress func(done, total int)) error {
+	contexts := make([]presentationContext, 0)
+	for _, path := range paths {
+		meta, err := readPart10Meta(path)
+		if err != nil {
+			return err
+		}
+		if _, err := findContext(contexts, meta); err != nil {
+			if len(contexts) == 128 {
+				return errors.New("too many different SOP classes and transfer syntaxes")
+			}
+			id := byte(2*len(contexts) + 1)
+			contexts = append(contexts, presentationContext{id: id, abstractSyntax: meta.sopClass, transferSyntaxes: []string{meta.transferSyntax}})
+		}
+	}
+
+	a, err := dialAssociation(ctx, ae.address(), ae.CallingAET, ae.CalledAET, contexts, tlsConfig, netLog)
+	if err != nil {
+		return err
+	}
+	skipped := make([]error, 0)
+	for i, path := range paths {
+		if err := ctx.Err(); err != nil {
+			a.conn.Close()
+			return err
+		}
+		if progress != nil {
+			progress(i, len(paths))
+		}
+		err := a.store(path)
+		var rejected rejectedContextError
+		if errors.As(err, &rejected) {
+			skipped = append(skipped, err)
+		} else if err != nil {
+			a.conn.Close()
+			return err
+		}
+	}
+	if err := a.release(); err != nil {
+		return err
+	}
+	return errors.Join(skipped...)
+}
+
+// rejectedContextError is returned for a file whose presentation context the SCP didn't accept
+type rejectedContextError struct {
+	path string
+	meta fileMeta
+}
+
+func (e rejectedContextError) Error() string {
+	return fmt.Sprintf("%s skipped, no presentation context accepted for %s in %s", e.path, e.meta.sopClass, e.meta.transferSyntax)
+}
+
+func findContext(contexts []presentationContext, meta fileMeta) (presentationContext, error) {
+	for _, pc := range contexts {
+		if pc.abstractSyntax == meta.sopClass && pc.transferSyntaxes[0] == meta.transferSyntax {
+			return pc, nil
+		}
+	}
+	return presentationContext{}, fmt.Errorf("no presentation context accepted for %s in %s", meta.sopClass, meta.transferSyntax)
+}
+
+// store sends one file with C-STORE and checks the response status, a rejectedContextError if the SCP didn't accept
+// the SOP class and transfer syntax of the file
+func (a *association) store(path string) error {
+	meta, dataset, err := readPart10File(path)
+	if err != nil {
+		return err
+	}
+	pc, err := findContext(a.contexts, meta)
+	if err != nil {
+		return rejectedContextError{path, meta}
+	}
+	command := encodeCommand(
+		commandValue{tagAffectedSOPClassUID, meta.sopClass},
+		commandValue{tagCommandField, uint16(commandCStoreRQ)},
+		commandValue{tagMessageID, a.nextMessageID()},
+		commandValue{tagPriority, uint16(0)},
+		commandValue{tagCommandDataSetType, uint16(0)},
+		commandValue{tagAffectedSOPInstanceUID, meta.sopInstance},
+	)
+	if err := a.send(pc.id, command, dataset); err != nil {
+		return err
+	}
+	response, err := a.receive()
+	if err != nil {
+		return err
+	}
+	// warnings (0x0001, 0xBxxx) are accepted as success
+	if status := response.commandInt(tagStatus); status != statusSuccess && status != 0x0001 && status&0xf000 != 0xb000 {
+		return fmt.Errorf("C-STORE of %s failed with status 0x%04x", path, status)
+	}
+	return nil
+}
@@ -0,0 +1,65 @@
+//go:build !nonet
+
+package main
+
+import (
+	"context"
+	"fmt"
+	"net"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestSendFiles(t *testing.T) {
+	assert := assert.New(t)
+
+	dir := t.TempDir()
+	paths := make([]string, 0)
+	for i := 1; i <= 2; i++ {
+		path := filepath.Join(dir, fmt.Sprintf("source%d.dcm", i))
+		newInstanceFixture(t, i).writeFile(path)
+		paths = append(paths, path)
+	}
+
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer listener.Close()
+	received := make(chan string, len(paths))
+	go newStorageSCP(t.TempDir(), func(path string) { received <- path }, nil).serve(listener)
+
+	ae := remoteAE{Name: "TEST", Host: "127.0.0.1", Port: listener.Addr().(*net.TCPAddr).Port, CallingAET: "SCU", CalledAET: "STORE"}
+	progressCalls := 0
+	netLog := newProtocolLog()
+	assert.NoError(sendFiles(context.Background(), ae, paths, nil, netLog, func(done, total int) { progressCalls++ }))
+	assert.Equal(2, progressCalls)
+	assert.Equal(1, strings.Count(netLog.String(), "A-ASSOCIATE SCU -> STORE"))
+
+	for range paths {
+		parsed, err := dicom.ParseFile(<-received, nil)
+		assert.NoError(err)
+		assert.Equal("SCU", getDatasetValue(parsed, tag.SourceApplicationEntityTitle))
+	}
+
+	_, _, err = readPart10File(filepath.Join(dir, "missing.dcm"))
+	assert.Error(err)
+
+	// the meta information alone is the same as the one read with the dataset
+	meta, dataset, err := readPart10File(paths[0])
+	assert.NoError(err)
+	assert.NotEmpty(dataset)
+	metaOnly, err := readPart10Meta(paths[0])
+	assert.NoError(err)
+	assert.Equal(meta, metaOnly)
+
+	// files of rejected contexts are skipped
+	var rejected rejectedContextError
+	assert.ErrorAs((&association{}).store(paths[0]), &rejected)
+	assert.Equal(paths[0], rejected.path)
+}
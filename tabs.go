@@ -0,0 +1,53 @@
+package main
+
+import (
+	"fmt"
+	"path/filepath"
+	"strings"
+
+	"github.com/rivo/tview"
+)
+
+// workspace is the state of a tab. The state of the active tab is kept in the variables of main while it is shown
+// and only stored here when switching to another tab.
+type workspace struct {
+	rootDir     string
+	datasets    []DatasetEntry
+	root        *tview.TreeNode // nil until the tree is built when the tab is shown the first time
+	current     *tview.TreeNode
+	sortMode    rune
+	tagSortMode rune
+	query       *query
+	searchText  string
+	searchDir   searchDirection
+	searchScope *tview.TreeNode
+	picked      pickedFiles
+	mergePicked bool
+}
+
+func newWorkspace(rootDir string, datasets []DatasetEntry) *workspace {
+	return &workspace{rootDir: rootDir, datasets: datasets, sortMode: '1', tagSortMode: '2', searchDir: searchForward,
+		picked: pickedFiles{}}
+}
+
+// cycleTab returns the index of the tab delta tabs after the active one, wrapping around at both ends like gt and gT
+func cycleTab(active, count, delta int) int {
+	return ((active+delta)%count + count) % count
+}
+
+// formatTabs returns the numbered tabs named by the base name of their input with the active one in brackets,
+// followed by a separator to the breadcrumb. Empty with a single tab.
+func formatTabs(tabs []*workspace, active int) string {
+	if len(tabs) < 2 {
+		return ""
+	}
+	names := make([]string, 0, len(tabs))
+	for i, tab := range tabs {
+		name := fmt.Sprintf("%d %s", i+1, filepath.Base(tab.rootDir))
+		if i == active {
+			name = "[" + name + "]"
+		}
+		names = append(names, name)
+	}
+	return strings.Join(names, " ") + " │ "
+}
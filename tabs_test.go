@@ -0,0 +1,44 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestCycleTab(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal(1, cycleTab(0, 3, 1))
+	assert.Equal(0, cycleTab(2, 3, 1))
+	assert.Equal(2, cycleTab(0, 3, -1))
+	assert.Equal(0, cycleTab(0, 1, 1))
+}
+
+func TestFormatTabs(t *testing.T) {
+	assert := assert.New(t)
+
+	tabs := []*workspace{newWorkspace("/data/study1", nil)}
+	assert.Equal("", formatTabs(tabs, 0))
+	tabs = append(tabs, newWorkspace("study2", nil))
+	assert.Equal("1 study1 [2 study2] │ ", formatTabs(tabs, 1))
+}
+
+func TestCyclePane(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, details, log := tview.NewBox(), tview.NewBox(), tview.NewBox()
+	panes := []tview.Primitive{tree, details, log}
+	_, ok := cyclePane(panes, 1)
+	assert.False(ok) // e.g. the command line has the focus
+
+	details.Focus(nil)
+	pane, ok := cyclePane(panes, 1)
+	assert.True(ok)
+	assert.Same(log, pane)
+	pane, _ = cyclePane(panes, -1)
+	assert.Same(tree, pane)
+	pane, _ = cyclePane(panes[:2], 1)
+	assert.Same(tree, pane)
+}
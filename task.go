@@ -0,0 +1,74 @@
+package main
+
+import (
+	"context"
+	"fmt"
+	"sync"
+
+	"github.com/rivo/tview"
+)
+
+// taskRunner runs long operations in the background, reports their progress in the status line and allows to cancel
+// them without quitting the app. Only one task runs at a time.
+type taskRunner struct {
+	app        *tview.Application
+	statusLine *tview.TextView
+
+	mutex  sync.Mutex
+	cancel context.CancelFunc // set while a task is running
+}
+
+func newTaskRunner(app *tview.Application, statusLine *tview.TextView) *taskRunner {
+	return &taskRunner{app: app, statusLine: statusLine}
+}
+
+// start runs 'run' in the background, which has to check the context for cancellation and should report its progress.
+// Afterwards 'done' is called on the UI goroutine with the error returned by 'run', which is context.Canceled if the
+// task was cancelled. Returns false if another task is still running.
+func (r *taskRunner) start(name string, run func(ctx context.Context, progress func(done, total int)) error, done func(err error)) bool {
+	r.mutex.Lock()
+	if r.cancel != nil {
+		r.mutex.Unlock()
+		return false
+	}
+	ctx, cancel := context.WithCancel(context.Background())
+	r.cancel = cancel
+	r.mutex.Unlock()
+
+	r.statusLine.SetText(name + "...")
+	go func() {
+		defer recoverCrash()
+		lastPercent := -1
+		progress := func(done, total int) {
+			percent := 100 * done / max(total, 1)
+			if percent == lastPercent {
+				return // avoid flooding the UI with redraws
+			}
+			lastPercent = percent
+			r.app.QueueUpdateDraw(func() {
+				r.statusLine.SetText(fmt.Sprintf("%s... %d/%d (%d%%) - esc to cancel", name, done, total, percent))
+			})
+		}
+
+		err := run(ctx, progress)
+		r.app.QueueUpdateDraw(func() {
+			r.mutex.Lock()
+			r.cancel = nil
+			r.mutex.Unlock()
+			cancel()
+			done(err)
+		})
+	}()
+	return true
+}
+
+// cancelRunning cancels the running task, returns false if there is none.
+func (r *taskRunner) cancelRunning() bool {
+	r.mutex.Lock()
+	defer r.mutex.Unlock()
+	if r.cancel == nil {
+		return false
+	}
+	r.cancel()
+	return true
+}
@@ -0,0 +1,49 @@
+package main
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"unicode"
+)
+
+// terminalOutput returns stdout if it is the terminal the UI is drawn on, nil if it's redirected, e.g. to a file, so
+// escape sequences don't end up in it
+func terminalOutput() io.Writer {
+	if info, err := os.Stdout.Stat(); err == nil && info.Mode()&os.ModeCharDevice != 0 {
+		return os.Stdout
+	}
+	return nil
+}
+
+// pushTerminalTitle saves the current terminal title on the xterm title stack, so it can be restored on exit
+func pushTerminalTitle() {
+	if w := terminalOutput(); w != nil {
+		fmt.Fprint(w, "\x1b[22;0t")
+	}
+}
+
+func popTerminalTitle() {
+	if w := terminalOutput(); w != nil {
+		fmt.Fprint(w, "\x1b[23;0t")
+	}
+}
+
+// setTerminalTitle sets the window title via the OSC 0 escape sequence, terminals not supporting it ignore it
+func setTerminalTitle(title string) {
+	if w := terminalOutput(); w != nil {
+		fmt.Fprintf(w, "\x1b]0;%s\x07", sanitizeTerminalTitle(title))
+	}
+}
+
+// sanitizeTerminalTitle removes control characters like ESC and BEL, so e.g. a filename can't end the title sequence
+// and inject escape sequences of its own
+func sanitizeTerminalTitle(title string) string {
+	return strings.Map(func(r rune) rune {
+		if unicode.IsControl(r) {
+			return -1
+		}
+		return r
+	}, title)
+}
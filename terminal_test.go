@@ -0,0 +1,15 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestSanitizeTerminalTitle(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal("dcmtagger - images/IM1", sanitizeTerminalTitle("dcmtagger - images/IM1"))
+	assert.Equal("dcmtagger - IM]0;pwned\\", sanitizeTerminalTitle("dcmtagger - IM\x07\x1b]0;pwned\x1b\\"))
+	assert.Equal("dcmtagger - Bild ä", sanitizeTerminalTitle("dcmtagger - Bild ä\u009b"))
+}
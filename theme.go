@@ -0,0 +1,77 @@
+package main
+
+import (
+	"fmt"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/rivo/tview"
+)
+
+// theme are the colors of the UI and how the selection is indicated
+type theme struct {
+	name            string
+	styles          tview.Theme
+	fieldBackground tcell.Color // background of input fields and buttons
+	problem         tcell.Color // text color of validation problems
+	marker          string      // drawn in front of the selected row, so it doesn't depend on colors only
+	reverse         bool        // selected row shown in reverse video, for terminals without colors
+}
+
+var noColors = tview.Theme{
+	PrimitiveBackgroundColor:    tcell.ColorDefault,
+	ContrastBackgroundColor:     tcell.ColorDefault,
+	MoreContrastBackgroundColor: tcell.ColorDefault,
+	BorderColor:                 tcell.ColorDefault,
+	TitleColor:                  tcell.ColorDefault,
+	GraphicsColor:               tcell.ColorDefault,
+	PrimaryTextColor:            tcell.ColorDefault,
+	SecondaryTextColor:          tcell.ColorDefault,
+	TertiaryTextColor:           tcell.ColorDefault,
+	InverseTextColor:            tcell.ColorDefault,
+	ContrastSecondaryTextColor:  tcell.ColorDefault,
+}
+
+var themes = []theme{
+	{name: "default", styles: tview.Styles, fieldBackground: tcell.ColorDarkBlue, problem: tcell.ColorRed},
+	{name: "high-contrast", styles: tview.Theme{
+		PrimitiveBackgroundColor:    tcell.ColorBlack,
+		ContrastBackgroundColor:     tcell.ColorNavy,
+		MoreContrastBackgroundColor: tcell.ColorWhite,
+		BorderColor:                 tcell.ColorWhite,
+		TitleColor:                  tcell.ColorWhite,
+		GraphicsColor:               tcell.ColorWhite,
+		PrimaryTextColor:            tcell.ColorWhite,
+		SecondaryTextColor:          tcell.ColorYellow,
+		TertiaryTextColor:           tcell.ColorAqua,
+		InverseTextColor:            tcell.ColorBlack,
+		ContrastSecondaryTextColor:  tcell.ColorYellow,
+	}, fieldBackground: tcell.ColorNavy, problem: tcell.ColorYellow, marker: "► "},
+	{name: "no-color", styles: noColors, fieldBackground: tcell.ColorDefault, problem: tcell.ColorDefault, marker: "► ",
+		reverse: true},
+}
+
+// activeTheme is the theme the UI is built with, set once at startup
+var activeTheme = themes[0]
+
+// findTheme returns the theme with the given name. NO_COLOR or --no-color select the no-color theme unless another
+// one is given explicitly.
+func findTheme(name string, noColor bool) (theme, error) {
+	if name == "" {
+		name = "default"
+		if noColor {
+			name = "no-color"
+		}
+	}
+	for _, t := range themes {
+		if t.name == name {
+			return t, nil
+		}
+	}
+	return theme{}, fmt.Errorf("unknown theme '%s', supported: default, high-contrast, no-color", name)
+}
+
+// apply makes the theme the active one, it has to be called before any primitive is created
+func (t theme) apply() {
+	tview.Styles = t.styles
+	activeTheme = t
+}
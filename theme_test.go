@@ -0,0 +1,29 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestFindTheme(t *testing.T) {
+	assert := assert.New(t)
+
+	selected, err := findTheme("", false)
+	assert.NoError(err)
+	assert.Equal("default", selected.name)
+	assert.Empty(selected.marker)
+
+	selected, err = findTheme("", true)
+	assert.NoError(err)
+	assert.Equal("no-color", selected.name)
+	assert.True(selected.reverse)
+
+	selected, err = findTheme("high-contrast", true)
+	assert.NoError(err)
+	assert.Equal("high-contrast", selected.name)
+	assert.NotEmpty(selected.marker)
+
+	_, err = findTheme("solarized", false)
+	assert.Error(err)
+}
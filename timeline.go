@@ -0,0 +1,102 @@
+package main
+
+import (
+	"cmp"
+	"fmt"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// timelineGap is the minimal pause between acquisitions highlighted in the timeline
+const timelineGap = time.Minute
+
+// parseDateTime parses a DT value, or a DA and TM value concatenated, with optional trailing parts. The time zone
+// offset is ignored.
+func parseDateTime(value string) (time.Time, error) {
+	if i := strings.IndexAny(value, "+-"); i >= 0 {
+		value = value[:i]
+	}
+	value, fraction, _ := strings.Cut(strings.TrimSpace(value), ".")
+	const layout = "20060102150405"
+	if len(value) < 4 || len(value) > len(layout) || len(value)%2 != 0 {
+		return time.Time{}, fmt.Errorf("invalid date time '%s'", value)
+	}
+	t, err := time.Parse(layout[:len(value)], value)
+	if err != nil {
+		return time.Time{}, err
+	}
+	if fraction != "" && len(value) == len(layout) {
+		nanoseconds, err := time.ParseDuration("0." + fraction + "s")
+		if err != nil {
+			return time.Time{}, err
+		}
+		t = t.Add(nanoseconds)
+	}
+	return t, nil
+}
+
+// timelineRun are consecutive acquisitions of the same series without a gap
+type timelineRun struct {
+	series      string
+	first, last time.Time
+	count       int
+}
+
+// newTimeline orders the instances by acquisition time and joins consecutive ones of the same series into runs. It
+// also returns the number of instances without acquisition time.
+func newTimeline(datasetsWithFilename []DatasetEntry) ([]timelineRun, int) {
+	type acquisition struct {
+		series string
+		time   time.Time
+	}
+	acquisitions := make([]acquisition, 0)
+	untimed := 0
+	for _, entry := range datasetsWithFilename {
+		t, err := parseDateTime(getAcquisitionDateTime(entry.dataset))
+		if err != nil {
+			untimed++
+			continue
+		}
+		series := strings.TrimSpace(fmt.Sprintf("Series %s %s", getDatasetValue(entry.dataset, tag.SeriesNumber),
+			getDatasetValue(entry.dataset, tag.SeriesDescription)))
+		acquisitions = append(acquisitions, acquisition{series, t})
+	}
+	slices.SortStableFunc(acquisitions, func(a, b acquisition) int { return a.time.Compare(b.time) })
+
+	runs := make([]timelineRun, 0)
+	for _, a := range acquisitions {
+		if len(runs) > 0 {
+			last := &runs[len(runs)-1]
+			if last.series == a.series && a.time.Sub(last.last) < timelineGap {
+				last.last = a.time
+				last.count++
+				continue
+			}
+		}
+		runs = append(runs, timelineRun{a.series, a.time, a.time, 1})
+	}
+	return runs, untimed
+}
+
+// formatTimeline lists the runs of acquisitions with their time span, the date whenever it changes and pauses of at
+// least timelineGap in between
+func formatTimeline(datasetsWithFilename []DatasetEntry) string {
+	runs, untimed := newTimeline(datasetsWithFilename)
+	lines := make([]string, 0)
+	for i, run := range runs {
+		if i == 0 || run.first.Format(time.DateOnly) != runs[i-1].last.Format(time.DateOnly) {
+			lines = append(lines, run.first.Format(time.DateOnly))
+		} else if pause := run.first.Sub(runs[i-1].last); pause >= timelineGap {
+			lines = append(lines, fmt.Sprintf("    ⋯ %s pause ⋯", pause.Round(time.Second)))
+		}
+		lines = append(lines, fmt.Sprintf("%s - %s  %s, %d instances", run.first.Format(time.TimeOnly),
+			run.last.Format(time.TimeOnly), run.series, run.count))
+	}
+	if untimed > 0 {
+		lines = append(lines, fmt.Sprintf("%d instances without acquisition time", untimed))
+	}
+	return cmp.Or(strings.Join(lines, "\n"), "no instances")
+}
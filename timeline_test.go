@@ -0,0 +1,53 @@
+package main
+
+import (
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestParseDateTime(t *testing.T) {
+	assert := assert.New(t)
+
+	parsed, err := parseDateTime("20210304101502.25+0100")
+	assert.NoError(err)
+	assert.Equal(time.Date(2021, 3, 4, 10, 15, 2, 250000000, time.UTC), parsed)
+	parsed, err = parseDateTime("20210304")
+	assert.NoError(err)
+	assert.Equal(time.Date(2021, 3, 4, 0, 0, 0, 0, time.UTC), parsed)
+	_, err = parseDateTime("")
+	assert.Error(err)
+	_, err = parseDateTime("202103041")
+	assert.Error(err)
+}
+
+func TestFormatTimeline(t *testing.T) {
+	assert := assert.New(t)
+
+	acquired := func(instance int, series, dateTime string) DatasetEntry {
+		return newInstanceFixture(t, instance).
+			with(tag.SeriesNumber, []string{series}).
+			with(tag.SeriesDescription, []string{"axial"}).
+			with(tag.AcquisitionDateTime, []string{dateTime}).
+			entry("")
+	}
+	entries := []DatasetEntry{
+		acquired(2, "1", "20210304101510"),
+		acquired(1, "1", "20210304101500"),
+		acquired(3, "2", "20210304102000"),
+		acquired(4, "1", "20210304102010"),
+		acquired(5, "1", "20210305080000"),
+		newInstanceFixture(t, 6).entry(""),
+	}
+	assert.Equal(`2021-03-04
+10:15:00 - 10:15:10  Series 1 axial, 2 instances
+    ⋯ 4m50s pause ⋯
+10:20:00 - 10:20:00  Series 2 axial, 1 instances
+10:20:10 - 10:20:10  Series 1 axial, 1 instances
+2021-03-05
+08:00:00 - 08:00:00  Series 1 axial, 1 instances
+1 instances without acquisition time`, formatTimeline(entries))
+	assert.Equal("no instances", formatTimeline(nil))
+}
@@ -0,0 +1,117 @@
+package main
+
+import (
+	"fmt"
+	"unicode/utf8"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+)
+
+// treeStyle are the line graphics, indentation and icons of the tree, configured in the config file
+type treeStyle struct {
+	Vertical     string            `json:"vertical"`     // line down to further siblings, │ if empty
+	Branch       string            `json:"branch"`       // connector of a node with further siblings, ├ if empty
+	Last         string            `json:"last"`         // connector of the last sibling, └ if empty
+	Horizontal   string            `json:"horizontal"`   // line from the connector to the text, ─ if empty
+	NoGraphics   bool              `json:"noGraphics"`   // indent only, without lines
+	Indent       int               `json:"indent"`       // columns per level, 2 if not set
+	CompactDepth int               `json:"compactDepth"` // levels deeper than this are indented by one column only
+	Icons        map[string]string `json:"icons"`        // prefix of the node text by node kind, see nodeKind
+}
+
+// glyphs returns the runes tview draws the tree lines with: vertical, last sibling and first sibling connector, where
+// the branch connector results from joining the last sibling connector with the vertical line or the next sibling
+func (s treeStyle) glyphs() (vertical, bottomLeft, topLeft, horizontal rune) {
+	glyph := func(text string, fallback rune) rune {
+		if r, _ := utf8.DecodeRuneInString(text); r != utf8.RuneError {
+			return r
+		}
+		return fallback
+	}
+	vertical = glyph(s.Vertical, tview.BoxDrawingsLightVertical)
+	bottomLeft = glyph(s.Last, tview.BoxDrawingsLightUpAndRight)
+	topLeft = tview.BoxDrawingsLightDownAndRight
+	if s.Branch != "" {
+		topLeft = glyph(s.Branch, topLeft)
+	}
+	horizontal = glyph(s.Horizontal, tview.BoxDrawingsLightHorizontal)
+	return vertical, bottomLeft, topLeft, horizontal
+}
+
+// isDefault returns true if the tree is drawn with tview's own line graphics
+func (s treeStyle) isDefault() bool {
+	return s.Vertical == "" && s.Branch == "" && s.Last == "" && s.Horizontal == ""
+}
+
+// registerJoints teaches tview how custom glyphs join, as it only knows box drawing characters: a last sibling
+// connector overdrawn by a vertical line or a following sibling becomes a branch connector
+func (s treeStyle) registerJoints() {
+	vertical, bottomLeft, topLeft, _ := s.glyphs()
+	branch := topLeft
+	for _, other := range []rune{vertical, topLeft} {
+		key := string([]rune{min(bottomLeft, other), max(bottomLeft, other)})
+		if _, known := tview.SemigraphicJoints[key]; !known && bottomLeft != other {
+			tview.SemigraphicJoints[key] = branch
+		}
+	}
+}
+
+// withGlyphs replaces the border runes tview draws the tree with for the duration of the call, so the borders of other
+// primitives keep theirs
+func (s treeStyle) withGlyphs(draw func()) {
+	if s.isDefault() {
+		draw()
+		return
+	}
+	borders := tview.Borders
+	tview.Borders.Vertical, tview.Borders.BottomLeft, tview.Borders.TopLeft, tview.Borders.Horizontal = s.glyphs()
+	defer func() { tview.Borders = borders }()
+	draw()
+}
+
+// nodeKind returns the kind of node the icons are configured by: root, folder (study, series), file, group, element,
+// sequence or item
+func nodeKind(node, parent *tview.TreeNode) string {
+	switch ref := node.GetReference().(type) {
+	case *DatasetEntry:
+		return "file"
+	case *hierarchyStats:
+		return "folder"
+	case *sequenceItem:
+		return "item"
+	case *dicom.Element:
+		if isSequence(ref) {
+			return "sequence"
+		}
+		return "element"
+	}
+	if parent == nil {
+		return "root"
+	}
+	return "group"
+}
+
+// apply sets indentation and icons of all nodes below and including the given one
+func (s treeStyle) apply(tree *tview.TreeView, root *tview.TreeNode) {
+	tree.SetGraphics(!s.NoGraphics)
+	indent := s.Indent
+	if indent <= 0 {
+		indent = 2
+	}
+	depths := map[*tview.TreeNode]int{}
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		if parent != nil {
+			depths[node] = depths[parent] + 1
+		}
+		if s.CompactDepth > 0 && depths[node] > s.CompactDepth {
+			node.SetIndent(1)
+		} else {
+			node.SetIndent(indent)
+		}
+		if icon := s.Icons[nodeKind(node, parent)]; icon != "" {
+			node.SetText(fmt.Sprintf("%s %s", icon, node.GetText()))
+		}
+		return true
+	})
+}
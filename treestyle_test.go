@@ -0,0 +1,42 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestTreeStyleIcons(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	style := treeStyle{Icons: map[string]string{"root": "R", "file": "F", "group": "G"}, CompactDepth: 2}
+	style.apply(tree, root)
+
+	assert.Equal("R root", root.GetText())
+	fileNode := root.GetChildren()[0]
+	assert.Equal("F file1.dcm", fileNode.GetText())
+	groupNode := fileNode.GetChildren()[0]
+	assert.Equal("G 0008 – Identifying", groupNode.GetText())
+}
+
+func TestTreeStyleGlyphs(t *testing.T) {
+	assert := assert.New(t)
+
+	vertical, bottomLeft, topLeft, horizontal := treeStyle{}.glyphs()
+	assert.Equal([]rune{'│', '└', '┌', '─'}, []rune{vertical, bottomLeft, topLeft, horizontal})
+	assert.True(treeStyle{}.isDefault())
+
+	style := treeStyle{Vertical: "|", Branch: "+", Last: "`", Horizontal: "-"}
+	style.registerJoints()
+	assert.Equal('+', tview.SemigraphicJoints[string([]rune{'+', '`'})])
+	assert.Equal('+', tview.SemigraphicJoints[string([]rune{'`', '|'})])
+
+	style.withGlyphs(func() {
+		assert.Equal('|', tview.Borders.Vertical)
+		assert.Equal('`', tview.Borders.BottomLeft)
+	})
+	assert.Equal('│', tview.Borders.Vertical)
+}
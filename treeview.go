@@ -0,0 +1,332 @@
+package main
+
+import (
+	"cmp"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/mattn/go-runewidth"
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+type scrollAlign int
+
+const (
+	alignNone scrollAlign = iota
+	alignCenter
+	alignTop
+	alignBottom
+)
+
+// lineNumbers are the numbers drawn in the gutter in front of the rows, toggled with tn
+type lineNumbers int
+
+const (
+	noLineNumbers lineNumbers = iota
+	absoluteLineNumbers
+	relativeLineNumbers // distance to the selection, the selected row shows its absolute number like in vim
+)
+
+func (n lineNumbers) next() lineNumbers {
+	return (n + 1) % 3
+}
+
+func (n lineNumbers) String() string {
+	return [...]string{"off", "absolute", "relative"}[n]
+}
+
+// width returns the columns of the numbers for the given number of rows, with a space to the tree
+func (n lineNumbers) width(rows int) int {
+	if n == noLineNumbers {
+		return 0
+	}
+	return len(strconv.Itoa(max(rows, 1))) + 1
+}
+
+// scrollView wraps the tree view to add vim like scrolling behaviour on top of tview's "keep selection visible"
+// logic. tview doesn't allow setting the scroll offset directly, so the offset is steered by temporarily selecting
+// the node that has to become visible and drawing again.
+type scrollView struct {
+	*tview.TreeView
+	scrollOff int         // number of rows kept visible above and below the selection
+	align     scrollAlign // pending repositioning of the selection, applied on next draw
+	marker    string      // drawn in a gutter in front of the selected row, none if empty
+	reverse   bool        // show the selected row in reverse video
+	style     treeStyle   // line graphics of the tree
+	sticky    bool        // pin the ancestors of the top row scrolled out of view at the top
+	numbers   lineNumbers // row numbers drawn in the gutter in front of the marker
+
+	capture func(screen tcell.Screen) // called once after the next draw, e.g. to save a screenshot of the view
+}
+
+func newScrollView(tree *tview.TreeView, scrollOff int) *scrollView {
+	return &scrollView{TreeView: tree, scrollOff: scrollOff, marker: activeTheme.marker, reverse: activeTheme.reverse,
+		sticky: true}
+}
+
+// alignSelection scrolls the view on next draw, so that the selection is centered, at the top or at the bottom.
+func (v *scrollView) alignSelection(align scrollAlign) {
+	v.align = align
+}
+
+func (v *scrollView) Draw(screen tcell.Screen) {
+	v.style.withGlyphs(func() { v.draw(screen) })
+	if capture := v.capture; capture != nil {
+		v.capture = nil
+		capture(screen)
+	}
+}
+
+func (v *scrollView) draw(screen tcell.Screen) {
+	numberWidth := v.numbers.width(len(collectAllVisible(v.TreeView)))
+	if gutter := numberWidth + runewidth.StringWidth(v.marker); gutter > 0 {
+		// the tree is drawn right of the gutter, the full rect is restored for mouse handling and the file links
+		x, y, width, height := v.GetRect()
+		v.TreeView.SetRect(x+gutter, y, width-gutter, height)
+		defer func() {
+			v.TreeView.SetRect(x, y, width, height)
+			v.drawGutter(screen, numberWidth, gutter)
+		}()
+	}
+	v.TreeView.Draw(screen)
+	align := v.align
+	v.align = alignNone
+
+	nodes := collectAllVisible(v.TreeView)
+	currentIdx := slices.Index(nodes, v.GetCurrentNode())
+	_, _, _, height := v.GetInnerRect()
+	if currentIdx >= 0 && height > 0 && len(nodes) > height {
+		margin := min(v.scrollOff, (height-1)/2)
+		offset := v.GetScrollOffset()
+		newOffset := offset
+		switch align {
+		case alignCenter:
+			newOffset = currentIdx - height/2
+		case alignTop:
+			newOffset = currentIdx - margin
+		case alignBottom:
+			newOffset = currentIdx + margin - height + 1
+		}
+		if currentIdx-newOffset < margin {
+			newOffset = currentIdx - margin
+		} else if newOffset+height-1-currentIdx < margin {
+			newOffset = currentIdx + margin - height + 1
+		}
+		newOffset = max(0, min(newOffset, len(nodes)-height))
+		if newOffset != offset {
+			v.scrollTo(screen, nodes, newOffset, height)
+		}
+	}
+	if v.sticky {
+		v.drawStickyHeaders(screen, collectAllVisible(v.TreeView))
+	}
+}
+
+// stickyHeaders returns the ancestors of the top rows which are scrolled out of view, outermost first, each pinned
+// header covering the next row. The root isn't pinned and the selection is never covered.
+func (v *scrollView) stickyHeaders(nodes []*tview.TreeNode, height int) []*tview.TreeNode {
+	parents := map[*tview.TreeNode]*tview.TreeNode{}
+	v.GetRoot().Walk(func(node, parent *tview.TreeNode) bool {
+		parents[node] = parent
+		return node.IsExpanded()
+	})
+	offset := v.GetScrollOffset()
+	maxHeaders := min(slices.Index(nodes, v.GetCurrentNode())-offset, height/2)
+	headers := make([]*tview.TreeNode, 0)
+	for len(headers) < maxHeaders && offset+len(headers) < len(nodes) {
+		row := offset + len(headers)
+		var next *tview.TreeNode
+		for ancestor := parents[nodes[row]]; ancestor != nil && parents[ancestor] != nil; ancestor = parents[ancestor] {
+			if slices.Index(nodes, ancestor) < row && !slices.Contains(headers, ancestor) {
+				next = ancestor // continue to the outermost one
+			}
+		}
+		if next == nil {
+			break
+		}
+		headers = append(headers, next)
+	}
+	return headers
+}
+
+// drawStickyHeaders draws the pinned ancestors underlined over the top rows, indented by their depth
+func (v *scrollView) drawStickyHeaders(screen tcell.Screen, nodes []*tview.TreeNode) {
+	x, y, width, height := v.GetInnerRect()
+	style := tcell.StyleDefault.Background(tview.Styles.PrimitiveBackgroundColor).
+		Foreground(tview.Styles.SecondaryTextColor).Underline(true)
+	for row, header := range v.stickyHeaders(nodes, height) {
+		for col := x; col < x+width; col++ {
+			screen.SetContent(col, y+row, ' ', nil, style)
+		}
+		col := x + 2*row
+		for _, r := range strings.ReplaceAll(header.GetText(), "\t", " ") {
+			if col >= x+width {
+				break
+			}
+			screen.SetContent(col, y+row, r, nil, style)
+			col += max(runewidth.RuneWidth(r), 1)
+		}
+	}
+}
+
+// drawGutter draws the line numbers and the marker of the selected row in the gutter, optionally showing the row in
+// reverse, so the selection is visible without colors
+func (v *scrollView) drawGutter(screen tcell.Screen, numberWidth, gutter int) {
+	x, y, width, height := v.GetInnerRect()
+	background := tcell.StyleDefault.Background(tview.Styles.PrimitiveBackgroundColor)
+	for row := 0; row < height; row++ {
+		for col := x; col < x+gutter; col++ {
+			screen.SetContent(col, y+row, ' ', nil, background)
+		}
+	}
+	nodes := collectAllVisible(v.TreeView)
+	offset := v.GetScrollOffset()
+	currentIdx := slices.Index(nodes, v.GetCurrentNode())
+	for row := 0; numberWidth > 0 && row < height && offset+row < len(nodes); row++ {
+		number, color := offset+row+1, tview.Styles.SecondaryTextColor
+		if offset+row == currentIdx {
+			color = tview.Styles.PrimaryTextColor
+		} else if v.numbers == relativeLineNumbers {
+			number = max(offset+row-currentIdx, currentIdx-offset-row)
+		}
+		tview.Print(screen, strconv.Itoa(number), x, y+row, numberWidth-1, tview.AlignRight, color)
+	}
+	row := currentIdx - offset
+	if v.marker == "" || row < 0 || row >= height {
+		return
+	}
+	tview.Print(screen, v.marker, x+numberWidth, y+row, gutter-numberWidth, tview.AlignLeft, tview.Styles.PrimaryTextColor)
+	if !v.reverse {
+		return
+	}
+	for col := x + gutter; col < x+width; {
+		mainc, combc, style, cellWidth := screen.GetContent(col, y+row)
+		screen.SetContent(col, y+row, mainc, combc, style.Reverse(true))
+		col += max(cellWidth, 1)
+	}
+}
+
+// scrollTo sets the scroll offset to the given row: selecting the node at the new top row scrolls up if needed, selecting
+// the node at the new bottom row scrolls down if needed. Afterwards the real selection is restored, which is then
+// visible without further scrolling.
+func (v *scrollView) scrollTo(screen tcell.Screen, nodes []*tview.TreeNode, offset int, height int) {
+	currentNode := v.GetCurrentNode()
+	v.SetCurrentNode(nodes[offset])
+	v.TreeView.Draw(screen)
+	v.SetCurrentNode(nodes[min(offset+height-1, len(nodes)-1)])
+	v.TreeView.Draw(screen)
+	v.SetCurrentNode(currentNode)
+	v.TreeView.Draw(screen)
+}
+
+// treeState is the expansion state and selection of a tree, with nodes identified by the texts on their path, so it
+// can be restored after rebuilding the tree
+type treeState struct {
+	expanded map[string]bool
+	current  string
+}
+
+func saveTreeState(tree *tview.TreeView) treeState {
+	return saveNodeState(tree.GetRoot(), tree.GetCurrentNode())
+}
+
+// saveNodeState saves the state of the tree below root, e.g. of a tab not shown
+func saveNodeState(root, current *tview.TreeNode) treeState {
+	state := treeState{expanded: make(map[string]bool)}
+	walkWithPath(root, "", func(node *tview.TreeNode, path string) {
+		if node.IsExpanded() {
+			state.expanded[path] = true
+		}
+		if node == current {
+			state.current = path
+		}
+	})
+	return state
+}
+
+// restore expands the previously expanded nodes, collapses all others and selects the previous current node
+func (s treeState) restore(tree *tview.TreeView) {
+	walkWithPath(tree.GetRoot(), "", func(node *tview.TreeNode, path string) {
+		if len(node.GetChildren()) > 0 {
+			node.SetExpanded(s.expanded[path])
+		}
+		if path == s.current {
+			tree.SetCurrentNode(node)
+		}
+	})
+}
+
+// tagPosition is the tag of the current node of a by-tag tree and its element if a value node is selected, so the
+// position is kept when switching between the trees of all tags and of differing tags only (2, 3)
+type tagPosition struct {
+	tag   tag.Tag
+	value *dicom.Element // nil if the tag node is selected
+	ok    bool
+}
+
+func saveTagPosition(tree *tview.TreeView) tagPosition {
+	current := tree.GetCurrentNode()
+	e, ok := current.GetReference().(*dicom.Element)
+	if !ok {
+		return tagPosition{}
+	}
+	position := tagPosition{tag: e.Tag, ok: true}
+	if parent := getParent(tree, current); parent != nil {
+		if _, ok := parent.GetReference().(*dicom.Element); ok {
+			position.value = e
+		}
+	}
+	return position
+}
+
+// restore selects the value node of the element or the tag node, or if the tree doesn't show the tag the node of the
+// next tag shown, e.g. of a tag with the same value in all files when only differing tags are shown. Returns false if
+// there is no such node.
+func (p tagPosition) restore(tree *tview.TreeView) bool {
+	if !p.ok {
+		return false
+	}
+	var found, next *tview.TreeNode
+	var nextTag tag.Tag
+	tree.GetRoot().Walk(func(node, parent *tview.TreeNode) bool {
+		e, ok := node.GetReference().(*dicom.Element)
+		if !ok {
+			return true // root and group nodes
+		}
+		if c := compareTags(e.Tag, p.tag); c == 0 {
+			found = node
+		} else if c > 0 && (next == nil || compareTags(e.Tag, nextTag) < 0) {
+			next, nextTag = node, e.Tag
+		}
+		return false // the values of the tag
+	})
+	if found != nil && p.value != nil {
+		for _, child := range found.GetChildren() {
+			if child.GetReference() == p.value {
+				found = child
+			}
+		}
+	}
+	node := cmp.Or(found, next)
+	if node == nil {
+		return false
+	}
+	expandPathToNode(tree, node)
+	tree.SetCurrentNode(node)
+	return true
+}
+
+func walkWithPath(node *tview.TreeNode, parentPath string, visit func(node *tview.TreeNode, path string)) {
+	if node == nil {
+		return
+	}
+	path := parentPath + "/" + node.GetText()
+	visit(node, path)
+	for _, child := range node.GetChildren() {
+		walkWithPath(child, path, visit)
+	}
+}
@@ -0,0 +1,100 @@
+package main
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gdamore/tcell/v2"
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestStickyHeaders(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	tree, root := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	view := newScrollView(tree, 0)
+	screen := tcell.NewSimulationScreen("")
+	assert.NoError(screen.Init())
+	screen.SetSize(80, 4)
+	view.SetRect(0, 0, 80, 4)
+
+	fileNode := root.GetChildren()[1]
+	groups := fileNode.GetChildren()
+	lastGroup := groups[len(groups)-1]
+	elements := lastGroup.GetChildren()
+	tree.SetCurrentNode(elements[len(elements)-1])
+	view.Draw(screen)
+
+	nodes := collectAllVisible(tree)
+	assert.Greater(view.GetScrollOffset(), 0)
+	_, _, _, height := view.GetInnerRect()
+	assert.Equal([]*tview.TreeNode{fileNode, lastGroup}, view.stickyHeaders(nodes, height))
+
+	tree.SetCurrentNode(root)
+	view.Draw(screen)
+	assert.Empty(view.stickyHeaders(collectAllVisible(tree), height))
+}
+
+func TestTagPosition(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{newInstanceFixture(t, 1).entry("file1.dcm"), newInstanceFixture(t, 2).entry("file2.dcm")}
+	tree, root := sortTreeByTags("root", tview.NewTreeView(), datasets, 0)
+	sopInstanceUID, _ := datasets[1].dataset.FindElementByTag(tag.SOPInstanceUID)
+	tree.SetCurrentNode(findElementNode(root, sopInstanceUID))
+	position := saveTagPosition(tree)
+	assert.Same(sopInstanceUID, position.value)
+
+	tree, root = sortTreeByTags("root", tree, datasets, 1)
+	assert.True(position.restore(tree))
+	assert.Same(sopInstanceUID, tree.GetCurrentNode().GetReference())
+	assert.Equal(findElementNode(root, sopInstanceUID), tree.GetCurrentNode())
+
+	studyDate, _ := datasets[0].dataset.FindElementByTag(tag.StudyDate)
+	tree, root = sortTreeByTags("root", tree, datasets, 0)
+	tree.SetCurrentNode(getParent(tree, findElementNode(root, studyDate))) // tag node
+	position = saveTagPosition(tree)
+	assert.Nil(position.value)
+
+	tree, _ = sortTreeByTags("root", tree, datasets, 1)
+	assert.True(position.restore(tree)) // the same date in both files, so the next differing tag
+	instanceNumber := tree.GetCurrentNode().GetReference().(*dicom.Element)
+	assert.Equal(tag.InstanceNumber, instanceNumber.Tag)
+	assert.True(getParent(tree, tree.GetCurrentNode()).IsExpanded())
+
+	tree.SetCurrentNode(tree.GetRoot())
+	assert.False(saveTagPosition(tree).restore(tree))
+}
+
+func TestLineNumbers(t *testing.T) {
+	assert := assert.New(t)
+
+	tree, branch, _ := newMotionTree()
+	tree.SetCurrentNode(branch)
+	view := newScrollView(tree, 0)
+	view.marker = ""
+	numbers := func() []string {
+		rows := strings.Split(renderSnapshot(t, view, 20, 3), "\n")
+		prefixes := make([]string, 0, 3)
+		for _, row := range rows[:3] {
+			prefixes = append(prefixes, row[:2])
+		}
+		return prefixes
+	}
+	assert.Equal(0, view.numbers.width(3))
+
+	view.numbers = view.numbers.next()
+	assert.Equal(absoluteLineNumbers, view.numbers)
+	assert.Equal([]string{"1 ", "2 ", "3 "}, numbers())
+
+	view.numbers = view.numbers.next()
+	assert.Equal("relative", view.numbers.String())
+	assert.Equal([]string{"1 ", "2 ", "1 "}, numbers())
+	assert.Equal(3, view.numbers.width(10))
+
+	assert.Equal(noLineNumbers, view.numbers.next())
+}
@@ -0,0 +1,96 @@
+package main
+
+import (
+	"slices"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// quantityUnits are the units of the values of well-known quantitative attributes, see PS3.3
+var quantityUnits = map[tag.Tag]string{
+	tag.SliceThickness:                 "mm",
+	tag.SpacingBetweenSlices:           "mm",
+	tag.PixelSpacing:                   "mm",
+	tag.ImagerPixelSpacing:             "mm",
+	tag.ImagePositionPatient:           "mm",
+	tag.SliceLocation:                  "mm",
+	tag.TableHeight:                    "mm",
+	tag.ReconstructionDiameter:         "mm",
+	tag.DataCollectionDiameter:         "mm",
+	tag.DistanceSourceToDetector:       "mm",
+	tag.DistanceSourceToPatient:        "mm",
+	tag.KVP:                            "kV",
+	tag.XRayTubeCurrent:                "mA",
+	tag.Exposure:                       "mAs",
+	tag.ExposureTime:                   "ms",
+	tag.RepetitionTime:                 "ms",
+	tag.EchoTime:                       "ms",
+	tag.InversionTime:                  "ms",
+	tag.TriggerTime:                    "ms",
+	tag.NominalCardiacTriggerDelayTime: "ms",
+	tag.FrameTime:                      "ms",
+	tag.FlipAngle:                      "°",
+	tag.MagneticFieldStrength:          "T",
+	tag.ImagingFrequency:               "MHz",
+	tag.DiffusionBValue:                "s/mm²",
+	tag.CTDIvol:                        "mGy",
+	tag.PatientWeight:                  "kg",
+	tag.PatientSize:                    "m",
+	tag.ContrastBolusVolume:            "ml",
+	tag.RadionuclideTotalDose:          "Bq",
+	tag.RadionuclideHalfLife:           "s",
+}
+
+// hounsfieldTags are in Hounsfield units in CT images
+var hounsfieldTags = []tag.Tag{tag.WindowCenter, tag.WindowWidth, tag.RescaleIntercept}
+
+// unitOf returns the unit of the values of the attribute in a file of the modality, empty if it isn't known
+func unitOf(t tag.Tag, modality string) string {
+	if modality == "CT" && slices.Contains(hounsfieldTags, t) {
+		return "HU"
+	}
+	return quantityUnits[t]
+}
+
+// markUnits appends the unit to the values of the element nodes like '1.25 mm', in the by-tag tree to the tag nodes
+// like 'in mm' instead of every value node. The modality is taken from the file of the element.
+func markUnits(root *tview.TreeNode, datasetsWithFilename []DatasetEntry) {
+	modalities := map[*dicom.Element]string{}
+	for _, entry := range datasetsWithFilename {
+		modality := getDatasetValue(entry.dataset, tag.Modality)
+		for _, e := range entry.dataset.Elements {
+			modalities[e] = modality
+		}
+	}
+	fileModality := ""
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		switch reference := node.GetReference().(type) {
+		case *DatasetEntry:
+			fileModality = getDatasetValue(reference.dataset, tag.Modality) // nodes are visited in tree order
+		case *dicom.Element:
+			if parent != nil {
+				if e, ok := parent.GetReference().(*dicom.Element); ok && e.Tag == reference.Tag {
+					break // value node of the by-tag tree
+				}
+			}
+			modality, ok := modalities[reference]
+			if !ok {
+				modality = fileModality // within a sequence
+			}
+			unit := unitOf(reference.Tag, modality)
+			if unit == "" {
+				break
+			}
+			text := node.GetText()
+			if prefix, ok := strings.CutSuffix(text, "/"); ok && len(node.GetChildren()) > 0 {
+				node.SetText(prefix + " in " + unit + "/")
+			} else {
+				node.SetText(text + " " + unit)
+			}
+		}
+		return true
+	})
+}
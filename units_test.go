@@ -0,0 +1,61 @@
+package main
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestUnitOf(t *testing.T) {
+	assert := assert.New(t)
+
+	assert.Equal("mm", unitOf(tag.SliceThickness, ""))
+	assert.Equal("kV", unitOf(tag.KVP, "CT"))
+	assert.Equal("HU", unitOf(tag.WindowCenter, "CT"))
+	assert.Equal("", unitOf(tag.WindowCenter, "MR"))
+	assert.Equal("", unitOf(tag.PatientName, "CT"))
+}
+
+func TestMarkUnits(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newFixture(t).
+			with(tag.Modality, []string{"CT"}).
+			with(tag.SliceThickness, []string{"1.25"}).
+			with(tag.WindowCenter, []string{"40"}).
+			entry("file1.dcm"),
+		newFixture(t).
+			with(tag.Modality, []string{"MR"}).
+			with(tag.SliceThickness, []string{"3"}).
+			with(tag.WindowCenter, []string{"40"}).
+			entry("file2.dcm"),
+	}
+	elementText := func(root *tview.TreeNode, entry DatasetEntry, t tag.Tag) string {
+		e, _ := entry.dataset.FindElementByTag(t)
+		return findElementNode(root, e).GetText()
+	}
+
+	tree, _ := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	markUnits(tree.GetRoot(), datasets)
+	assert.True(strings.HasSuffix(elementText(tree.GetRoot(), datasets[0], tag.SliceThickness), ": 1.25 mm"))
+	assert.True(strings.HasSuffix(elementText(tree.GetRoot(), datasets[0], tag.WindowCenter), ": 40 HU"))
+	assert.True(strings.HasSuffix(elementText(tree.GetRoot(), datasets[1], tag.WindowCenter), ": 40"))
+	assert.True(strings.HasSuffix(elementText(tree.GetRoot(), datasets[0], tag.Modality), ": CT"))
+
+	tree, _ = sortTreeByTags("root", tview.NewTreeView(), datasets, 0)
+	markUnits(tree.GetRoot(), datasets)
+	tagTexts := make([]string, 0)
+	tree.GetRoot().Walk(func(node, parent *tview.TreeNode) bool {
+		if _, ok := node.GetReference().(*dicom.Element); ok && len(node.GetChildren()) > 0 {
+			tagTexts = append(tagTexts, node.GetText())
+		}
+		return true
+	})
+	assert.Contains(tagTexts, "\t0050 SliceThickness (DS) in mm/")
+	assert.True(strings.HasSuffix(elementText(tree.GetRoot(), datasets[1], tag.SliceThickness), " - file2.dcm"))
+}
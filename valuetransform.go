@@ -0,0 +1,212 @@
+package main
+
+import (
+	"cmp"
+	"fmt"
+	"strconv"
+	"strings"
+
+	"github.com/rivo/tview"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// valueTransform is a step of the chain mapping stored pixel values to displayed or real world values, see PS3.4 N.2
+type valueTransform struct {
+	name   string // like Rescale or VOI LUT
+	detail string
+}
+
+func (t valueTransform) String() string {
+	return t.name + ": " + t.detail
+}
+
+// valueTransforms is the pixel value transform chain of an image, real world value mappings are applied to the stored
+// values in parallel to the other steps
+type valueTransforms struct {
+	steps []valueTransform
+}
+
+// transformSources returns the element lists which may hold the transform attributes, the dataset and for enhanced
+// multi-frame images the shared functional groups
+func transformSources(dataset dicom.Dataset) [][]*dicom.Element {
+	sources := [][]*dicom.Element{dataset.Elements}
+	for _, shared := range sequenceItems(dataset.Elements, tag.SharedFunctionalGroupsSequence) {
+		sources = append(sources, sequenceItems(shared, tag.PixelValueTransformationSequence)...)
+		sources = append(sources, sequenceItems(shared, tag.FrameVOILUTSequence)...)
+		sources = append(sources, shared)
+	}
+	return sources
+}
+
+// formatLUTDescriptor returns the number of entries, first mapped value and bits of a LUT like "4096 entries from 0,
+// 16 bits"
+func formatLUTDescriptor(item []*dicom.Element) string {
+	descriptor := itemFloats(item, tag.LUTDescriptor)
+	if len(descriptor) != 3 {
+		return "invalid LUT descriptor"
+	}
+	entries := cmp.Or(descriptor[0], 65536) // 0 means 2^16 entries
+	text := fmt.Sprintf("%g entries from %g, %g bits", entries, descriptor[1], descriptor[2])
+	if explanation := itemValue(item, tag.LUTExplanation); explanation != "" {
+		text += " (" + explanation + ")"
+	}
+	return text
+}
+
+// newValueTransforms collects the transform chain of an image, false for objects without pixel data description
+func newValueTransforms(dataset dicom.Dataset) (*valueTransforms, bool) {
+	sources := transformSources(dataset)
+	value := func(t tag.Tag) string {
+		for _, source := range sources {
+			if v := itemValue(source, t); v != "" {
+				return v
+			}
+		}
+		return ""
+	}
+	floats := func(t tag.Tag) []float64 {
+		for _, source := range sources {
+			if v := itemFloats(source, t); len(v) > 0 {
+				return v
+			}
+		}
+		return nil
+	}
+	items := func(t tag.Tag) [][]*dicom.Element {
+		for _, source := range sources {
+			if v := sequenceItems(source, t); len(v) > 0 {
+				return v
+			}
+		}
+		return nil
+	}
+
+	bits, err := strconv.Atoi(value(tag.BitsStored))
+	if err != nil || bits <= 0 || bits > 32 {
+		return nil, false
+	}
+	t := &valueTransforms{}
+	low, high := 0.0, float64(int64(1)<<bits-1)
+	signedness := "unsigned"
+	if value(tag.PixelRepresentation) == "1" {
+		low, high, signedness = -float64(int64(1)<<(bits-1)), float64(int64(1)<<(bits-1)-1), "signed"
+	}
+	t.steps = append(t.steps, valueTransform{"Stored values", fmt.Sprintf("%d bits %s, %g to %g", bits, signedness, low, high)})
+	photometric := value(tag.PhotometricInterpretation)
+	if photometric != "" && !strings.HasPrefix(photometric, "MONOCHROME") {
+		t.steps = append(t.steps, valueTransform{"Color", photometric + ", no grayscale transforms"})
+		return t, true
+	}
+
+	if luts := items(tag.ModalityLUTSequence); len(luts) > 0 {
+		detail := formatLUTDescriptor(luts[0])
+		if lutType := itemValue(luts[0], tag.ModalityLUTType); lutType != "" {
+			detail += ", " + lutType
+		}
+		t.steps = append(t.steps, valueTransform{"Modality LUT", detail})
+	} else if intercept, slope := floats(tag.RescaleIntercept), floats(tag.RescaleSlope); len(intercept) > 0 || len(slope) > 0 {
+		b, m := 0.0, 1.0
+		if len(intercept) > 0 {
+			b = intercept[0]
+		}
+		if len(slope) > 0 {
+			m = slope[0]
+		}
+		unit := cmp.Or(value(tag.RescaleType), unitOf(tag.RescaleIntercept, value(tag.Modality)))
+		detail := fmt.Sprintf("slope %g, intercept %g, %g to %g", m, b, low*m+b, high*m+b)
+		if unit != "" {
+			detail += " " + unit
+		}
+		t.steps = append(t.steps, valueTransform{"Rescale", detail})
+	} else {
+		t.steps = append(t.steps, valueTransform{"Modality", "identity"})
+	}
+
+	if luts := items(tag.VOILUTSequence); len(luts) > 0 {
+		detail := formatLUTDescriptor(luts[0])
+		if len(luts) > 1 {
+			detail += fmt.Sprintf(", %d more", len(luts)-1)
+		}
+		t.steps = append(t.steps, valueTransform{"VOI LUT", detail})
+	} else if centers, widths := floats(tag.WindowCenter), floats(tag.WindowWidth); len(centers) > 0 && len(widths) > 0 {
+		detail := fmt.Sprintf("center %g, width %g", centers[0], widths[0])
+		if explanations := strings.Split(value(tag.WindowCenterWidthExplanation), `\`); explanations[0] != "" {
+			detail += " (" + explanations[0] + ")"
+		}
+		if len(centers) > 1 {
+			detail += fmt.Sprintf(", %d more", len(centers)-1)
+		}
+		detail += ", " + cmp.Or(value(tag.VOILUTFunction), "LINEAR")
+		t.steps = append(t.steps, valueTransform{"Window", detail})
+	} else {
+		t.steps = append(t.steps, valueTransform{"VOI", "none"})
+	}
+
+	shape := value(tag.PresentationLUTShape)
+	if shape == "" && photometric == "MONOCHROME1" {
+		shape = "INVERSE"
+	}
+	if shape != "" {
+		t.steps = append(t.steps, valueTransform{"Presentation LUT", shape})
+	}
+
+	for _, item := range items(tag.RealWorldValueMappingSequence) {
+		name := "Real world value mapping"
+		if label := itemValue(item, tag.LUTLabel); label != "" {
+			name += " " + label
+		}
+		first, last := itemFloats(item, tag.RealWorldValueFirstValueMapped), itemFloats(item, tag.RealWorldValueLastValueMapped)
+		var detail string
+		if slope, intercept := itemFloats(item, tag.RealWorldValueSlope), itemFloats(item, tag.RealWorldValueIntercept); len(slope) > 0 && len(intercept) > 0 {
+			detail = fmt.Sprintf("slope %g, intercept %g", slope[0], intercept[0])
+			if len(first) > 0 && len(last) > 0 {
+				detail += fmt.Sprintf(", %g to %g → %g to %g", first[0], last[0], first[0]*slope[0]+intercept[0], last[0]*slope[0]+intercept[0])
+			}
+		} else if data := itemFloats(item, tag.RealWorldValueLUTData); len(data) > 0 {
+			detail = fmt.Sprintf("LUT of %d entries", len(data))
+			if len(first) > 0 {
+				detail += fmt.Sprintf(" from %g", first[0])
+			}
+		} else {
+			detail = "no slope and intercept or LUT data"
+		}
+		if unit := cmp.Or(codeOf(item, tag.MeasurementUnitsCodeSequence, tag.CodeValue), codeOf(item, tag.MeasurementUnitsCodeSequence, tag.CodeMeaning)); unit != "" {
+			detail += " " + unit
+		}
+		t.steps = append(t.steps, valueTransform{name, detail})
+	}
+	return t, true
+}
+
+func (t *valueTransforms) String() string {
+	return "Pixel value transforms"
+}
+
+// details lists the steps for the detail view
+func (t *valueTransforms) details() string {
+	lines := []string{"Pixel value transforms, in order of application:", ""}
+	for _, step := range t.steps {
+		lines = append(lines, step.String())
+	}
+	return strings.Join(lines, "\n")
+}
+
+// addValueTransformNodes adds a collapsed node with the pixel value transform chain as first child of the file nodes
+// of images
+func addValueTransformNodes(root *tview.TreeNode) {
+	root.Walk(func(node, parent *tview.TreeNode) bool {
+		entry, ok := node.GetReference().(*DatasetEntry)
+		if !ok {
+			return true
+		}
+		if transforms, ok := newValueTransforms(entry.dataset); ok {
+			transformsNode := tview.NewTreeNode(transforms.String()).SetSelectable(true).SetReference(transforms).SetExpanded(false)
+			for _, step := range transforms.steps {
+				transformsNode.AddChild(tview.NewTreeNode(step.String()).SetSelectable(true))
+			}
+			node.SetChildren(append([]*tview.TreeNode{transformsNode}, node.GetChildren()...))
+		}
+		return false // no files below files
+	})
+}
@@ -0,0 +1,77 @@
+package main
+
+import (
+	"testing"
+
+	"github.com/rivo/tview"
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestValueTransforms(t *testing.T) {
+	assert := assert.New(t)
+
+	f := newInstanceFixture(t, 1)
+	ct := f.with(tag.BitsStored, []int{12}).
+		with(tag.PixelRepresentation, []int{0}).
+		with(tag.PhotometricInterpretation, []string{"MONOCHROME2"}).
+		with(tag.RescaleIntercept, []string{"-1024"}).
+		with(tag.RescaleSlope, []string{"1"}).
+		with(tag.WindowCenter, []string{"40", "400"}).
+		with(tag.WindowWidth, []string{"400", "2000"}).
+		with(tag.WindowCenterWidthExplanation, []string{"SOFT", "BONE"}).
+		withSequence(tag.RealWorldValueMappingSequence, []*dicom.Element{
+			f.element(tag.LUTLabel, []string{"HU"}),
+			f.element(tag.RealWorldValueFirstValueMapped, []int{0}),
+			f.element(tag.RealWorldValueLastValueMapped, []int{4095}),
+			f.element(tag.RealWorldValueSlope, []float64{1}),
+			f.element(tag.RealWorldValueIntercept, []float64{-1024}),
+			f.element(tag.MeasurementUnitsCodeSequence, [][]*dicom.Element{{f.element(tag.CodeValue, []string{"[hnsf'U]"})}}),
+		}).
+		dataset()
+
+	transforms, ok := newValueTransforms(ct)
+	assert.True(ok)
+	assert.Equal([]valueTransform{
+		{"Stored values", "12 bits unsigned, 0 to 4095"},
+		{"Rescale", "slope 1, intercept -1024, -1024 to 3071 HU"},
+		{"Window", "center 40, width 400 (SOFT), 1 more, LINEAR"},
+		{"Real world value mapping HU", "slope 1, intercept -1024, 0 to 4095 → -1024 to 3071 [hnsf'U]"},
+	}, transforms.steps)
+
+	mono1, ok := newValueTransforms(newFixture(t).
+		with(tag.BitsStored, []int{16}).
+		with(tag.PixelRepresentation, []int{1}).
+		with(tag.PhotometricInterpretation, []string{"MONOCHROME1"}).
+		dataset())
+	assert.True(ok)
+	assert.Equal("Pixel value transforms, in order of application:\n\nStored values: 16 bits signed, -32768 to 32767\n"+
+		"Modality: identity\nVOI: none\nPresentation LUT: INVERSE", mono1.details())
+
+	rgb, ok := newValueTransforms(newFixture(t).with(tag.BitsStored, []int{8}).with(tag.PhotometricInterpretation, []string{"RGB"}).dataset())
+	assert.True(ok)
+	assert.Equal(valueTransform{"Color", "RGB, no grayscale transforms"}, rgb.steps[1])
+
+	_, ok = newValueTransforms(newFixture(t).with(tag.SOPClassUID, []string{keyObjectSelectionDocument}).dataset())
+	assert.False(ok)
+}
+
+func TestAddValueTransformNodes(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newInstanceFixture(t, 1).with(tag.BitsStored, []int{12}).entry("image.dcm"),
+		newFixture(t).with(tag.SOPClassUID, []string{keyObjectSelectionDocument}).entry("ko.dcm"),
+	}
+	tree, _ := sortTreeByFilename("root", tview.NewTreeView(), datasets)
+	addValueTransformNodes(tree.GetRoot())
+
+	image, ko := tree.GetRoot().GetChildren()[0], tree.GetRoot().GetChildren()[1]
+	transformsNode := image.GetChildren()[0]
+	assert.Equal("Pixel value transforms", transformsNode.GetText())
+	assert.False(transformsNode.IsExpanded())
+	assert.Len(transformsNode.GetChildren(), 3)
+	assert.Equal("Stored values: 12 bits unsigned, 0 to 4095", transformsNode.GetChildren()[0].GetText())
+	assert.Equal(getGroupText(0x0008), ko.GetChildren()[0].GetText())
+}
@@ -0,0 +1,183 @@
+package main
+
+import (
+	"crypto/rand"
+	"fmt"
+	"math/big"
+	"regexp"
+	"slices"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// vrFormat are the constraints of a value representation, see PS3.5 6.2
+type vrFormat struct {
+	maxLength int            // per value, 0 if unlimited
+	pattern   *regexp.Regexp // a value has to match, nil for any text
+	hint      string         // shown while editing
+}
+
+var vrFormats = map[string]vrFormat{
+	"AE": {16, nil, "application entity title, max 16 characters"},
+	"AS": {4, regexp.MustCompile(`^\d{3}[DWMY]$`), "age like 042Y, units D W M Y"},
+	"CS": {16, regexp.MustCompile(`^[A-Z0-9_ ]*$`), "code string, upper case, digits, space, underscore"},
+	"DA": {8, regexp.MustCompile(`^\d{8}$`), "date YYYYMMDD"},
+	"DS": {16, regexp.MustCompile(`^ *[+-]?(\d+\.?\d*|\.\d+)([eE][+-]?\d+)? *$`), "decimal number"},
+	"DT": {26, regexp.MustCompile(`^\d{4}(\d{2}(\d{2}(\d{2}(\d{2}(\d{2}(\.\d{1,6})?)?)?)?)?)?([+-]\d{4})?$`), "date time YYYYMMDDHHMMSS.FFFFFF&ZZXX, trailing parts optional"},
+	"IS": {12, regexp.MustCompile(`^ *[+-]?\d+ *$`), "integer number"},
+	"LO": {64, nil, "text, max 64 characters"},
+	"PN": {0, nil, "person name Family^Given^Middle^Prefix^Suffix"},
+	"SH": {16, nil, "text, max 16 characters"},
+	"TM": {14, regexp.MustCompile(`^\d{2}(\d{2}(\d{2}(\.\d{1,6})?)?)?$`), "time HHMMSS.FFFFFF, trailing parts optional"},
+	"UI": {64, regexp.MustCompile(`^(0|[1-9]\d*)(\.(0|[1-9]\d*))*$`), "UID of digits and dots"},
+}
+
+// definedTerms are the allowed values of code strings, for the attributes where they are commonly checked
+var definedTerms = map[tag.Tag][]string{
+	tag.Modality: {
+		"AR", "AU", "BI", "BMD", "CR", "CT", "DG", "DOC", "DX", "ECG", "EPS", "ES", "GM", "HC", "HD", "IO", "IVOCT",
+		"IVUS", "KER", "KO", "LS", "MG", "MR", "NM", "OAM", "OCT", "OP", "OPM", "OPT", "OPV", "OSS", "OT", "PLAN", "PR",
+		"PT", "PX", "REG", "RESP", "RF", "RG", "RTDOSE", "RTIMAGE", "RTPLAN", "RTRECORD", "RTSTRUCT", "RWV", "SEG",
+		"SM", "SMR", "SR", "SRF", "STAIN", "TG", "US", "VA", "XA", "XC",
+	},
+	tag.PatientSex:                {"M", "F", "O"},
+	tag.PatientPosition:           {"HFP", "HFS", "HFDR", "HFDL", "FFDR", "FFDL", "FFP", "FFS", "LFP", "LFS", "RFP", "RFS", "AFDR", "AFDL", "PFDR", "PFDL"},
+	tag.Laterality:                {"R", "L"},
+	tag.ImageLaterality:           {"R", "L", "U", "B"},
+	tag.ConversionType:            {"DV", "DI", "DF", "WSD", "SD", "SI", "DRW", "SYN"},
+	tag.PresentationIntentType:    {"FOR PRESENTATION", "FOR PROCESSING"},
+	tag.LossyImageCompression:     {"00", "01"},
+	tag.BurnedInAnnotation:        {"YES", "NO"},
+	tag.PatientIdentityRemoved:    {"YES", "NO"},
+	tag.PhotometricInterpretation: {"MONOCHROME1", "MONOCHROME2", "PALETTE COLOR", "RGB", "YBR_FULL", "YBR_FULL_422", "YBR_PARTIAL_420", "YBR_ICT", "YBR_RCT"},
+}
+
+// validateValue checks the edited value, multiple values separated by '\', against the VR and the defined terms of
+// the attribute. Empty values are always valid.
+func validateValue(t tag.Tag, vr, value string) error {
+	if value == "" {
+		return nil
+	}
+	format, known := vrFormats[vr]
+	for i, v := range strings.Split(value, "\\") {
+		if format.maxLength > 0 && len(v) > format.maxLength {
+			return fmt.Errorf("value %d longer than %d characters", i+1, format.maxLength)
+		}
+		if known && format.pattern != nil && v != "" && !format.pattern.MatchString(v) {
+			return fmt.Errorf("value %d is no valid %s: %s", i+1, vr, format.hint)
+		}
+		if err := validateDateTime(vr, v); err != nil {
+			return fmt.Errorf("value %d: %w", i+1, err)
+		}
+		if terms, ok := definedTerms[t]; ok && vr == "CS" && v != "" && !slices.Contains(terms, v) {
+			return fmt.Errorf("value %d is none of the defined terms %s", i+1, strings.Join(terms, " "))
+		}
+	}
+	return nil
+}
+
+// validateDateTime checks the ranges of the date and time components, the syntax has been checked already
+func validateDateTime(vr, value string) error {
+	var date, clock string
+	switch vr {
+	case "DA":
+		date = value
+	case "TM":
+		clock = value
+	case "DT":
+		value, _, _ = strings.Cut(strings.Replace(value, "-", "+", 1), "+")
+		if len(value) >= 8 {
+			date, clock = value[:8], value[8:]
+		}
+	default:
+		return nil
+	}
+	if date != "" {
+		if _, err := time.Parse("20060102", date); err != nil {
+			return fmt.Errorf("invalid date %s", date)
+		}
+	}
+	limits := []int{24, 60, 60}
+	for i := 0; i < len(limits) && len(clock) >= 2*i+2; i++ {
+		if n, _ := strconv.Atoi(clock[2*i : 2*i+2]); n >= limits[i] {
+			return fmt.Errorf("invalid time %s", clock)
+		}
+	}
+	return nil
+}
+
+// formatDateTime returns a DA, TM or DT value in a readable form like 2021-03-04 13:22:05.123 +01:00, trailing zeros
+// of the fraction left out. Returns false for other VRs and invalid values.
+func formatDateTime(vr, value string) (string, bool) {
+	value = strings.TrimSpace(value)
+	if !slices.Contains([]string{"DA", "TM", "DT"}, vr) || !vrFormats[vr].pattern.MatchString(value) ||
+		validateDateTime(vr, value) != nil {
+		return "", false
+	}
+	formatTime := func(clock string) string {
+		clock, fraction, _ := strings.Cut(clock, ".")
+		parts := make([]string, 0, 3)
+		for i := 0; i+2 <= len(clock); i += 2 {
+			parts = append(parts, clock[i:i+2])
+		}
+		text := strings.Join(parts, ":")
+		if fraction = strings.TrimRight(fraction, "0"); fraction != "" {
+			text += "." + fraction
+		}
+		return text
+	}
+	switch vr {
+	case "DA":
+		return value[:4] + "-" + value[4:6] + "-" + value[6:], true
+	case "TM":
+		return formatTime(value), true
+	}
+	zone := ""
+	if i := strings.IndexAny(value, "+-"); i >= 0 {
+		value, zone = value[:i], " "+value[i:i+3]+":"+value[i+3:]
+	}
+	text := value[:4]
+	for i, separator := range []string{"-", "-"} {
+		if len(value) >= 6+2*i {
+			text += separator + value[4+2*i:6+2*i]
+		}
+	}
+	if len(value) > 8 {
+		text += " " + formatTime(value[8:])
+	}
+	return text + zone, true
+}
+
+// formatVRHint returns the format description of the VR and the defined terms of the attribute, if known
+func formatVRHint(t tag.Tag, vr string) string {
+	hint := vrFormats[vr].hint
+	if terms, ok := definedTerms[t]; ok {
+		hint = "one of " + strings.Join(terms, " ")
+	}
+	return hint
+}
+
+// currentValue returns the current date or time in the format of the VR, empty for other VRs
+func currentValue(vr string, now time.Time) string {
+	switch vr {
+	case "DA":
+		return now.Format("20060102")
+	case "TM":
+		return now.Format("150405")
+	case "DT":
+		return now.Format("20060102150405-0700")
+	}
+	return ""
+}
+
+// newUID returns a UID derived from a random UUID, see PS3.5 B.2
+func newUID() string {
+	n, err := rand.Int(rand.Reader, new(big.Int).Lsh(big.NewInt(1), 128))
+	if err != nil {
+		panic(err)
+	}
+	return "2.25." + n.String()
+}
@@ -0,0 +1,99 @@
+package main
+
+import (
+	"regexp"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func TestValidateValue(t *testing.T) {
+	assert := assert.New(t)
+
+	valid := []struct {
+		tag   tag.Tag
+		vr    string
+		value string
+	}{
+		{tag.StudyDate, "DA", "20240229"},
+		{tag.StudyTime, "TM", "2359"},
+		{tag.StudyTime, "TM", "235959.123456"},
+		{tag.AcquisitionDateTime, "DT", "20240301101500+0100"},
+		{tag.AcquisitionDateTime, "DT", "2024"},
+		{tag.SOPInstanceUID, "UI", "1.2.840.10008.0.1"},
+		{tag.Modality, "CS", "MR"},
+		{tag.ImageType, "CS", "ORIGINAL\\PRIMARY"},
+		{tag.PatientAge, "AS", "042Y"},
+		{tag.SliceThickness, "DS", "1.25"},
+		{tag.InstanceNumber, "IS", "-12"},
+		{tag.PatientName, "PN", "Doe^John"},
+		{tag.StudyDate, "DA", ""},
+	}
+	for _, v := range valid {
+		assert.NoError(validateValue(v.tag, v.vr, v.value), v.value)
+	}
+
+	invalid := []struct {
+		tag   tag.Tag
+		vr    string
+		value string
+	}{
+		{tag.StudyDate, "DA", "20230229"},
+		{tag.StudyDate, "DA", "2024-03-01"},
+		{tag.StudyTime, "TM", "2460"},
+		{tag.StudyTime, "TM", "120760"},
+		{tag.AcquisitionDateTime, "DT", "20241301"},
+		{tag.SOPInstanceUID, "UI", "1.2.03"},
+		{tag.SOPInstanceUID, "UI", "1.2.a"},
+		{tag.Modality, "CS", "XX"},
+		{tag.PatientSex, "CS", "m"},
+		{tag.ImageType, "CS", "ORIGINAL\\primary"},
+		{tag.PatientAge, "AS", "42Y"},
+		{tag.SliceThickness, "DS", "1,25"},
+		{tag.StationName, "SH", "a name longer than 16"},
+	}
+	for _, v := range invalid {
+		assert.Error(validateValue(v.tag, v.vr, v.value), v.value)
+	}
+}
+
+func TestVREditHelpers(t *testing.T) {
+	assert := assert.New(t)
+
+	uid := newUID()
+	assert.NoError(validateValue(tag.SOPInstanceUID, "UI", uid))
+	assert.Regexp(regexp.MustCompile(`^2\.25\.[1-9]\d*$`), uid)
+	assert.NotEqual(uid, newUID())
+
+	now := time.Date(2024, 3, 1, 9, 5, 7, 0, time.FixedZone("", 3600))
+	assert.Equal("20240301", currentValue("DA", now))
+	assert.Equal("090507", currentValue("TM", now))
+	assert.Equal("20240301090507+0100", currentValue("DT", now))
+	assert.Equal("", currentValue("LO", now))
+
+	assert.Equal("one of M F O", formatVRHint(tag.PatientSex, "CS"))
+	assert.Equal("date YYYYMMDD", formatVRHint(tag.StudyDate, "DA"))
+}
+
+func TestFormatDateTime(t *testing.T) {
+	assert := assert.New(t)
+
+	for _, c := range []struct{ vr, value, formatted string }{
+		{"DA", "20210304", "2021-03-04"},
+		{"TM", "132205.123000", "13:22:05.123"},
+		{"TM", "1322", "13:22"},
+		{"TM", "132205.000000", "13:22:05"},
+		{"DT", "20210304132205.5+0100", "2021-03-04 13:22:05.5 +01:00"},
+		{"DT", "202103", "2021-03"},
+	} {
+		formatted, ok := formatDateTime(c.vr, c.value)
+		assert.True(ok, c.value)
+		assert.Equal(c.formatted, formatted)
+	}
+	for _, c := range []struct{ vr, value string }{{"DA", "20211304"}, {"TM", "256000"}, {"DA", ""}, {"LO", "20210304"}} {
+		_, ok := formatDateTime(c.vr, c.value)
+		assert.False(ok, c.value)
+	}
+}
@@ -0,0 +1,175 @@
+package main
+
+import (
+	"bytes"
+	"cmp"
+	"errors"
+	"fmt"
+	"image"
+	_ "image/jpeg" // tiles of whole slide images are usually JPEG compressed
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/suyashkumar/dicom"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+// wsiSOPClass is VL Whole Slide Microscopy Image Storage
+const wsiSOPClass = "1.2.840.10008.5.1.4.1.1.77.1.6"
+
+// wsiLevel is an image of a whole slide pyramid, its frames are the tiles of the total pixel matrix
+type wsiLevel struct {
+	entry                   *DatasetEntry
+	imageType               string // VOLUME, LABEL, OVERVIEW or THUMBNAIL
+	totalColumns, totalRows int
+	tileColumns, tileRows   int
+	frames                  int
+	organization            string // DimensionOrganizationType like TILED_FULL
+}
+
+func newWSILevel(entry *DatasetEntry) (wsiLevel, error) {
+	value := func(t tag.Tag) int {
+		n, _ := strconv.Atoi(getDatasetValue(entry.dataset, t))
+		return n
+	}
+	level := wsiLevel{
+		entry:        entry,
+		totalColumns: value(tag.TotalPixelMatrixColumns),
+		totalRows:    value(tag.TotalPixelMatrixRows),
+		tileColumns:  value(tag.Columns),
+		tileRows:     value(tag.Rows),
+		frames:       cmp.Or(value(tag.NumberOfFrames), 1),
+		organization: getDatasetValue(entry.dataset, tag.DimensionOrganizationType),
+	}
+	if imageType := strings.Split(getDatasetValue(entry.dataset, tag.ImageType), `\`); len(imageType) > 2 {
+		level.imageType = imageType[2]
+	}
+	if level.tileColumns == 0 || level.tileRows == 0 {
+		return level, errors.New("no tile size")
+	}
+	level.totalColumns = cmp.Or(level.totalColumns, level.tileColumns)
+	level.totalRows = cmp.Or(level.totalRows, level.tileRows)
+	return level, nil
+}
+
+// tilesAcross and tilesDown are the size of the tile grid covering the total pixel matrix
+func (l wsiLevel) tilesAcross() int { return (l.totalColumns + l.tileColumns - 1) / l.tileColumns }
+func (l wsiLevel) tilesDown() int { return (l.totalRows + l.tileRows - 1) / l.tileRows }
+
+// tileFrame returns the frame index of the tile, for TILED_FULL organized frames in row-major order of the first
+// focal plane and optical path. False for sparse tiles and tiles outside the grid.
+func (l wsiLevel) tileFrame(column, row int) (int, bool) {
+	if column < 0 || row < 0 || column >= l.tilesAcross() || row >= l.tilesDown() {
+		return 0, false
+	}
+	if l.organization != "TILED_FULL" && l.frames != 1 {
+		return 0, false
+	}
+	index := row*l.tilesAcross() + column
+	return index, index < l.frames
+}
+
+// newWSIPyramids returns the whole slide images by series, their levels from the highest resolution down
+func newWSIPyramids(datasetsWithFilename []DatasetEntry) [][]wsiLevel {
+	levelsBySeries := map[string][]wsiLevel{}
+	series := make([]string, 0)
+	for i := range datasetsWithFilename {
+		entry := &datasetsWithFilename[i]
+		if getDatasetValue(entry.dataset, tag.SOPClassUID) != wsiSOPClass {
+			continue
+		}
+		level, err := newWSILevel(entry)
+		if err != nil {
+			continue
+		}
+		uid := getDatasetValue(entry.dataset, tag.SeriesInstanceUID)
+		if _, ok := levelsBySeries[uid]; !ok {
+			series = append(series, uid)
+		}
+		levelsBySeries[uid] = append(levelsBySeries[uid], level)
+	}
+	pyramids := make([][]wsiLevel, 0, len(series))
+	for _, uid := range series {
+		levels := levelsBySeries[uid]
+		slices.SortStableFunc(levels, func(a, b wsiLevel) int {
+			return cmp.Or(cmp.Compare(b.totalColumns*b.totalRows, a.totalColumns*a.totalRows), strings.Compare(a.entry.path, b.entry.path))
+		})
+		pyramids = append(pyramids, levels)
+	}
+	return pyramids
+}
+
+// formatWSIPyramids lists the levels of each pyramid with their tile grid and the downsampling to the first level
+func formatWSIPyramids(datasetsWithFilename []DatasetEntry) string {
+	pyramids := newWSIPyramids(datasetsWithFilename)
+	if len(pyramids) == 0 {
+		return "no whole slide images"
+	}
+	var b strings.Builder
+	for _, levels := range pyramids {
+		fmt.Fprintf(&b, "Series %s\n\n", getDatasetValue(levels[0].entry.dataset, tag.SeriesInstanceUID))
+		rows := [][]string{{"Level", "Type", "Matrix", "Tile", "Grid", "Tiles", "Frames", "Downsampling", "File"}}
+		for i, l := range levels {
+			rows = append(rows, []string{
+				fmt.Sprint(i), l.imageType,
+				fmt.Sprintf("%dx%d", l.totalColumns, l.totalRows),
+				fmt.Sprintf("%dx%d", l.tileColumns, l.tileRows),
+				fmt.Sprintf("%dx%d", l.tilesAcross(), l.tilesDown()),
+				fmt.Sprint(l.tilesAcross() * l.tilesDown()),
+				fmt.Sprint(l.frames),
+				fmt.Sprintf("%.1f", float64(levels[0].totalColumns)/float64(l.totalColumns)),
+				l.entry.filename,
+			})
+		}
+		b.WriteString(formatTable(rows) + "\n\n")
+	}
+	return strings.TrimSuffix(b.String(), "\n")
+}
+
+// decodeFrame decodes a frame of the pixel data, of huge files only this frame is read
+func decodeFrame(dataset dicom.Dataset, index int) (image.Image, error) {
+	e, err := dataset.FindElementByTag(tag.PixelData)
+	if err != nil {
+		return nil, errors.New("no pixel data")
+	}
+	if data, ok := lazyFrame(e, index); ok {
+		img, _, err := image.Decode(bytes.NewReader(data))
+		return img, err
+	}
+	if err := loadLazyValue(e); err != nil {
+		return nil, err
+	}
+	info, ok := e.Value.GetValue().(dicom.PixelDataInfo)
+	if !ok || index >= len(info.Frames) {
+		return nil, fmt.Errorf("no frame %d", index+1)
+	}
+	return info.Frames[index].GetImage()
+}
+
+// shades are the characters of increasing brightness the tile preview is drawn with
+var shades = []rune(" ░▒▓█")
+
+// renderShades draws the image scaled to the character cells with shades of its mean luminance
+func renderShades(img image.Image, columns, rows int) string {
+	bounds := img.Bounds()
+	lines := make([]string, 0, rows)
+	for row := 0; row < rows; row++ {
+		var line strings.Builder
+		for column := 0; column < columns; column++ {
+			x0, x1 := bounds.Min.X+column*bounds.Dx()/columns, bounds.Min.X+(column+1)*bounds.Dx()/columns
+			y0, y1 := bounds.Min.Y+row*bounds.Dy()/rows, bounds.Min.Y+(row+1)*bounds.Dy()/rows
+			sum, count := 0.0, 0
+			for y := y0; y < max(y1, y0+1); y++ {
+				for x := x0; x < max(x1, x0+1); x++ {
+					r, g, b, _ := img.At(x, y).RGBA()
+					sum += (0.299*float64(r) + 0.587*float64(g) + 0.114*float64(b)) / 0xffff
+					count++
+				}
+			}
+			line.WriteRune(shades[min(int(sum/float64(count)*float64(len(shades))), len(shades)-1)])
+		}
+		lines = append(lines, line.String())
+	}
+	return strings.Join(lines, "\n")
+}
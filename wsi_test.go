@@ -0,0 +1,65 @@
+package main
+
+import (
+	"image"
+	"image/color"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/suyashkumar/dicom/pkg/tag"
+)
+
+func newWSIFixture(t *testing.T, series string, totalColumns, totalRows int, imageType string) *fixture {
+	return newFixture(t).
+		with(tag.SOPClassUID, []string{wsiSOPClass}).
+		with(tag.ImageType, []string{"DERIVED", "PRIMARY", imageType, "NONE"}).
+		with(tag.SeriesInstanceUID, []string{series}).
+		with(tag.NumberOfFrames, []string{"4"}).
+		with(tag.Rows, []int{256}).
+		with(tag.Columns, []int{256}).
+		with(tag.TotalPixelMatrixColumns, []int{totalColumns}).
+		with(tag.TotalPixelMatrixRows, []int{totalRows}).
+		with(tag.DimensionOrganizationType, []string{"TILED_FULL"})
+}
+
+func TestWSIPyramids(t *testing.T) {
+	assert := assert.New(t)
+
+	datasets := []DatasetEntry{
+		newWSIFixture(t, "1.2.3", 500, 300, "VOLUME").entry("level1.dcm"),
+		newWSIFixture(t, "1.2.3", 1000, 600, "VOLUME").entry("level0.dcm"),
+		newInstanceFixture(t, 1).entry("ct.dcm"),
+	}
+	pyramids := newWSIPyramids(datasets)
+	assert.Len(pyramids, 1)
+	assert.Equal("level0.dcm", pyramids[0][0].entry.filename)
+	level := pyramids[0][1]
+	assert.Equal(2, level.tilesAcross())
+	assert.Equal(2, level.tilesDown())
+	index, ok := level.tileFrame(1, 1)
+	assert.True(ok)
+	assert.Equal(3, index)
+	_, ok = level.tileFrame(2, 0)
+	assert.False(ok)
+	_, ok = pyramids[0][0].tileFrame(3, 2) // only 4 frames of 12 tiles
+	assert.False(ok)
+
+	lines := strings.Split(formatWSIPyramids(datasets), "\n")
+	assert.Equal("Series 1.2.3", lines[0])
+	assert.Equal("Level  Type    Matrix    Tile     Grid  Tiles  Frames  Downsampling  File", lines[2])
+	assert.Equal("0      VOLUME  1000x600  256x256  4x3   12     4       1.0           level0.dcm", lines[3])
+	assert.Equal("1      VOLUME  500x300   256x256  2x2   4      4       2.0           level1.dcm", lines[4])
+	assert.Equal("no whole slide images", formatWSIPyramids(datasets[2:]))
+}
+
+func TestRenderShades(t *testing.T) {
+	assert := assert.New(t)
+
+	img := image.NewGray(image.Rect(0, 0, 4, 2))
+	img.SetGray(2, 0, color.Gray{Y: 255})
+	img.SetGray(3, 0, color.Gray{Y: 255})
+	img.SetGray(2, 1, color.Gray{Y: 255})
+	img.SetGray(3, 1, color.Gray{Y: 128})
+	assert.Equal(" █\n ▓", renderShades(img, 2, 2))
+}